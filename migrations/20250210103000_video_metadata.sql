@@ -0,0 +1,8 @@
+ALTER TABLE video
+    ADD COLUMN duration_ms BIGINT,
+    ADD COLUMN width       INT,
+    ADD COLUMN height      INT,
+    ADD COLUMN frame_rate  DOUBLE PRECISION,
+    ADD COLUMN container   VARCHAR(64),
+    ADD COLUMN video_codec VARCHAR(64),
+    ADD COLUMN audio_codec VARCHAR(64);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS temp_upload (
+    id            SERIAL PRIMARY KEY,
+    user_id       INTEGER NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    file_path     TEXT    NOT NULL,
+    upload_length BIGINT  NOT NULL,
+    upload_offset BIGINT  NOT NULL DEFAULT 0,
+    created_at    TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,87 @@
+-- Full-text search over video name, description, category names and artist username.
+-- The vector is stored on the video and kept up to date by triggers on all source tables.
+ALTER TABLE video
+    ADD COLUMN search_vector TSVECTOR NOT NULL DEFAULT ''::TSVECTOR;
+
+CREATE OR REPLACE FUNCTION video_search_vector(
+    video_name TEXT,
+    video_description TEXT,
+    video_id INTEGER,
+    video_artist_id INTEGER
+) RETURNS TSVECTOR AS
+$$
+SELECT setweight(to_tsvector('english', coalesce(video_name, '')), 'A')
+           || setweight(to_tsvector('english', coalesce((SELECT string_agg(c.name, ' ')
+                                                         FROM video_category c
+                                                                  JOIN video_category_video cv ON cv.category_id = c.id
+                                                         WHERE cv.video_id = video_search_vector.video_id), '')), 'B')
+           || setweight(to_tsvector('english', coalesce((SELECT u.username
+                                                        FROM user_table u
+                                                        WHERE u.artist_id = video_artist_id
+                                                        LIMIT 1), '')), 'B')
+           || setweight(to_tsvector('english', coalesce(video_description, '')), 'C')
+$$ LANGUAGE sql STABLE;
+
+CREATE OR REPLACE FUNCTION video_search_vector_update() RETURNS TRIGGER AS
+$$
+BEGIN
+    NEW.search_vector := video_search_vector(NEW.name, NEW.description, NEW.id, NEW.artist_id);
+    RETURN NEW;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER video_search_vector_trigger
+    BEFORE INSERT OR UPDATE OF name, description, artist_id
+    ON video
+    FOR EACH ROW
+EXECUTE FUNCTION video_search_vector_update();
+
+-- Touching the name recomputes the vector through the trigger above
+CREATE OR REPLACE FUNCTION video_category_video_search_update() RETURNS TRIGGER AS
+$$
+BEGIN
+    UPDATE video SET name = name WHERE id = coalesce(NEW.video_id, OLD.video_id);
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER video_category_video_search_trigger
+    AFTER INSERT OR DELETE
+    ON video_category_video
+    FOR EACH ROW
+EXECUTE FUNCTION video_category_video_search_update();
+
+CREATE OR REPLACE FUNCTION video_category_search_update() RETURNS TRIGGER AS
+$$
+BEGIN
+    UPDATE video
+    SET name = name
+    WHERE id IN (SELECT video_id FROM video_category_video WHERE category_id = NEW.id);
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER video_category_search_trigger
+    AFTER UPDATE OF name
+    ON video_category
+    FOR EACH ROW
+EXECUTE FUNCTION video_category_search_update();
+
+CREATE OR REPLACE FUNCTION user_table_search_update() RETURNS TRIGGER AS
+$$
+BEGIN
+    UPDATE video SET name = name WHERE artist_id = NEW.artist_id;
+    RETURN NULL;
+END
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER user_table_search_trigger
+    AFTER UPDATE OF username, artist_id
+    ON user_table
+    FOR EACH ROW
+    WHEN (NEW.artist_id IS NOT NULL)
+EXECUTE FUNCTION user_table_search_update();
+
+UPDATE video SET name = name;
+
+CREATE INDEX IF NOT EXISTS video_search_vector_idx ON video USING GIN (search_vector);
@@ -0,0 +1,6 @@
+ALTER TABLE video
+    ADD COLUMN view_count BIGINT NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS video_view_count_idx ON video (view_count DESC, id DESC);
+CREATE INDEX IF NOT EXISTS video_name_idx ON video (lower(name), id);
+CREATE INDEX IF NOT EXISTS favorite_video_id_idx ON favorite (video_id);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS video_daily_views (
+    video_id   INTEGER NOT NULL REFERENCES video(id) ON DELETE CASCADE,
+    day        DATE    NOT NULL,
+    view_count BIGINT  NOT NULL DEFAULT 0,
+    PRIMARY KEY (video_id, day)
+);
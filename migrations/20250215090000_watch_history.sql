@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS watch_history (
+    user_id     INTEGER NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    video_id    INTEGER NOT NULL REFERENCES video(id) ON DELETE CASCADE,
+    position_ms BIGINT  NOT NULL DEFAULT 0,
+    watched_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, video_id)
+);
+
+CREATE INDEX IF NOT EXISTS watch_history_user_watched_at_idx ON watch_history (user_id, watched_at DESC);
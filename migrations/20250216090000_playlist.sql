@@ -0,0 +1,39 @@
+CREATE TYPE playlist_visibility AS ENUM ('PRIVATE', 'UNLISTED', 'PUBLIC');
+
+CREATE TABLE IF NOT EXISTS playlist (
+    id          SERIAL PRIMARY KEY,
+    user_id     INTEGER             NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    name        VARCHAR(128)        NOT NULL,
+    description TEXT,
+    visibility  playlist_visibility NOT NULL DEFAULT 'PRIVATE',
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS playlist_user_id_idx ON playlist (user_id);
+
+CREATE TABLE IF NOT EXISTS playlist_item (
+    playlist_id INTEGER NOT NULL REFERENCES playlist(id) ON DELETE CASCADE,
+    video_id    INTEGER NOT NULL REFERENCES video(id) ON DELETE CASCADE,
+    position    INTEGER NOT NULL,
+    PRIMARY KEY (playlist_id, video_id)
+);
+
+CREATE INDEX IF NOT EXISTS playlist_item_position_idx ON playlist_item (playlist_id, position);
+
+-- Keeps positions of the remaining items continuous, when the video is deleted
+CREATE OR REPLACE FUNCTION playlist_item_compact_positions() RETURNS TRIGGER AS
+$$
+BEGIN
+    UPDATE playlist_item
+    SET position = position - 1
+    WHERE playlist_id = OLD.playlist_id
+      AND position > OLD.position;
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER playlist_item_compact_positions
+    AFTER DELETE
+    ON playlist_item
+    FOR EACH ROW
+EXECUTE FUNCTION playlist_item_compact_positions();
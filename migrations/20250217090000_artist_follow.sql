@@ -0,0 +1,12 @@
+ALTER TABLE video
+    ADD COLUMN IF NOT EXISTS created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW();
+
+CREATE TABLE IF NOT EXISTS artist_follow (
+    user_id     INTEGER NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    artist_id   INTEGER NOT NULL REFERENCES artist(id) ON DELETE CASCADE,
+    followed_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (user_id, artist_id)
+);
+
+CREATE INDEX IF NOT EXISTS artist_follow_artist_id_idx ON artist_follow (artist_id);
+CREATE INDEX IF NOT EXISTS video_artist_created_at_idx ON video (artist_id, created_at DESC);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS stream_key (
+    artist_id  INTEGER PRIMARY KEY REFERENCES artist(id) ON DELETE CASCADE,
+    stream_key TEXT    NOT NULL UNIQUE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE TYPE live_stream_source AS ENUM ('FILE', 'RTMP');
+ALTER TABLE live_stream ADD COLUMN source live_stream_source NOT NULL DEFAULT 'FILE';
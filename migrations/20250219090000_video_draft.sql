@@ -0,0 +1,5 @@
+-- Drafts are visible only to their artist, e.g. recordings of the live streams waiting for publishing
+ALTER TABLE video ADD COLUMN IF NOT EXISTS draft BOOLEAN NOT NULL DEFAULT FALSE;
+
+ALTER TABLE live_stream
+    ADD COLUMN IF NOT EXISTS recording_video_id INTEGER REFERENCES video(id) ON DELETE SET NULL;
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS stream_chat_message (
+    id         SERIAL PRIMARY KEY,
+    stream_id  INTEGER NOT NULL REFERENCES live_stream(id) ON DELETE CASCADE,
+    user_id    INTEGER NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    content    TEXT    NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS stream_chat_message_stream_id_idx ON stream_chat_message (stream_id, id);
@@ -0,0 +1,16 @@
+ALTER TABLE live_stream ADD COLUMN IF NOT EXISTS peak_viewers INTEGER NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS stream_health_sample (
+    id                SERIAL PRIMARY KEY,
+    stream_id         INTEGER NOT NULL REFERENCES live_stream(id) ON DELETE CASCADE,
+    resolution        TEXT    NOT NULL,
+    bitrate_kbps      INTEGER NOT NULL,
+    dropped_frames    BIGINT  NOT NULL,
+    qos_events        INTEGER NOT NULL,
+    buffering_percent INTEGER,
+    warnings          INTEGER NOT NULL,
+    recorded_at       TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS stream_health_sample_stream_id_idx
+    ON stream_health_sample (stream_id, resolution, recorded_at);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS stream_reminder (
+    stream_id  INTEGER NOT NULL REFERENCES live_stream(id) ON DELETE CASCADE,
+    user_id    INTEGER NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (stream_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS stream_reminder_user_id_idx ON stream_reminder (user_id);
+CREATE INDEX IF NOT EXISTS live_stream_scheduled_idx ON live_stream (start_time) WHERE status = 'PENDING';
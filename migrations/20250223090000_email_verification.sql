@@ -0,0 +1,10 @@
+ALTER TABLE user_table ADD COLUMN email_verified_at TIMESTAMP WITH TIME ZONE;
+
+-- Accounts created before the verification was introduced are trusted
+UPDATE user_table SET email_verified_at = now();
+
+-- Last verification link sent to the user, it throttles the resending
+CREATE TABLE IF NOT EXISTS email_verification_mail (
+    user_id INTEGER PRIMARY KEY REFERENCES user_table(id) ON DELETE CASCADE,
+    sent_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,7 @@
+-- Only the newest reset link of the user is valid, the token itself is never stored
+CREATE TABLE IF NOT EXISTS password_reset_token (
+    user_id    INTEGER PRIMARY KEY REFERENCES user_table(id) ON DELETE CASCADE,
+    token_hash TEXT                     NOT NULL UNIQUE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
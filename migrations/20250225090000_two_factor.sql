@@ -0,0 +1,18 @@
+-- TOTP secret of the user, two-factor authentication is enabled, once the user confirms it by a code
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id        INTEGER PRIMARY KEY REFERENCES user_table(id) ON DELETE CASCADE,
+    secret         TEXT                     NOT NULL,
+    enabled_at     TIMESTAMP WITH TIME ZONE,
+    -- Time step of the last accepted code, so the same code can't be used twice
+    last_used_step BIGINT                   NOT NULL DEFAULT 0
+);
+
+-- One-time codes replacing the TOTP code, when the user loses the authenticator
+CREATE TABLE IF NOT EXISTS user_recovery_code (
+    id        SERIAL PRIMARY KEY,
+    user_id   INTEGER NOT NULL REFERENCES user_table(id) ON DELETE CASCADE,
+    code_hash TEXT    NOT NULL,
+    used_at   TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS user_recovery_code_user_id_idx ON user_recovery_code (user_id);
@@ -0,0 +1,3 @@
+-- Time of the last received chunk, uploads inactive for too long are removed
+ALTER TABLE temp_upload
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW();
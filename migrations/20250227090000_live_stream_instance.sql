@@ -0,0 +1,7 @@
+-- Instance of the app, which runs the pipelines of the stream. On startup, every instance
+-- reconciles only its own orphaned streams.
+ALTER TABLE live_stream
+    ADD COLUMN IF NOT EXISTS instance_id TEXT;
+
+-- Streams running before the upgrade were started by the single instance with the default name
+UPDATE live_stream SET instance_id = 'default' WHERE status = 'RUNNING';
@@ -4,14 +4,18 @@ use crate::api::templates::admin::categories::template::AdminCategoriesTemplate;
 use crate::api::templates::admin::deals::template::AdminDealsTemplate;
 use crate::api::templates::admin::edit_deal::template::AdminEditDealTemplate;
 use crate::api::templates::admin::index::template::AdminIndexTemplate;
+use crate::api::templates::admin::locked_accounts::template::AdminLockedAccountsTemplate;
 use crate::api::templates::admin::template::AdminSectionTemplate;
 use crate::api::templates::admin::users::template::AdminUsersTemplate;
 use crate::api::templates::template::BaseTemplate;
 use crate::business::facades::artist::{ArtistFacade, ArtistFacadeTrait};
+use crate::business::facades::login_attempt::{LoginAttemptFacade, LoginAttemptFacadeTrait};
 use crate::business::facades::membership::{DealInput, MembershipFacade, MembershipFacadeTrait};
+use crate::business::facades::two_factor::{TwoFactorFacade, TwoFactorFacadeTrait};
 use crate::business::facades::user::{UserFacade, UserFacadeTrait};
 use crate::business::facades::video_category::{VideoCategoryFacade, VideoCategoryFacadeTrait};
 use crate::business::models::error::AppError;
+use crate::business::models::login_attempt::AccountUnlock;
 use crate::business::models::user::UserRole::{self, Admin};
 
 use actix_session::Session;
@@ -122,15 +126,20 @@ pub async fn add_deal(
 #[protect(any("Admin"), ty = "UserRole")]
 pub async fn get_users(
     user_facade: web::Data<UserFacade>,
+    two_factor_facade: web::Data<TwoFactorFacade>,
     htmx_request: HtmxRequest,
     session: Session,
 ) -> Result<impl Responder> {
     let users = user_facade.get_users().await?;
+    let two_factor_user_ids = two_factor_facade.list_enabled_user_ids().await?;
 
     Ok(BaseTemplate::wrap(
         htmx_request,
         session,
-        AdminSectionTemplate::wrap(AdminUsersTemplate { users }),
+        AdminSectionTemplate::wrap(AdminUsersTemplate {
+            users,
+            two_factor_user_ids,
+        }),
     ))
 }
 
@@ -170,6 +179,46 @@ pub async fn revoke_user_admin(
     Ok(response)
 }
 
+/// Disables two-factor authentication of the user, who lost the authenticator app
+#[protect(any("Admin"), ty = "UserRole")]
+pub async fn reset_user_two_factor(
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    user_id: web::Path<i32>,
+) -> Result<impl Responder> {
+    two_factor_facade.reset(*user_id).await?;
+
+    let mut response = HttpResponse::NoContent().finish();
+    add_redirect_header("/admin/users", &mut response)?;
+    Ok(response)
+}
+
+#[protect(any("Admin"), ty = "UserRole")]
+pub async fn get_locked_accounts(
+    login_attempt_facade: web::Data<LoginAttemptFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+) -> Result<impl Responder> {
+    let locked_accounts = login_attempt_facade.list_locked_accounts().await?;
+
+    Ok(BaseTemplate::wrap(
+        htmx_request,
+        session,
+        AdminSectionTemplate::wrap(AdminLockedAccountsTemplate { locked_accounts }),
+    ))
+}
+
+#[protect(any("Admin"), ty = "UserRole")]
+pub async fn unlock_account(
+    login_attempt_facade: web::Data<LoginAttemptFacade>,
+    form: web::Form<AccountUnlock>,
+) -> Result<impl Responder> {
+    login_attempt_facade.unlock_account(&form.username).await?;
+
+    let mut response = HttpResponse::NoContent().finish();
+    add_redirect_header("/admin/locked-accounts", &mut response)?;
+    Ok(response)
+}
+
 #[protect(any("Admin"), ty = "UserRole")]
 pub async fn get_admin_categories(
     category_facade: web::Data<VideoCategoryFacade>,
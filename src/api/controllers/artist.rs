@@ -1,5 +1,24 @@
+use crate::api::controllers::utils::video_utils::from_video_to_video_list;
+use crate::api::extractors::htmx_extractor::HtmxRequest;
+use crate::api::extractors::permissions_extractor::{AsInteger, AsIntegerOptional};
+use crate::api::templates::artist::template::{
+    ArtistDescriptionFormTemplate, ArtistDescriptionTemplate, ArtistTemplate, ArtistVideosTemplate,
+    FollowTemplate,
+};
+use crate::api::templates::template::BaseTemplate;
 use crate::business::facades::artist::{ArtistFacade, ArtistFacadeTrait};
-use actix_web::{web, HttpResponse, Responder};
+use crate::business::facades::playlist::{PlaylistFacade, PlaylistFacadeTrait};
+use crate::business::facades::stream::{StreamFacade, StreamFacadeTrait};
+use crate::business::facades::video::{VideoFacade, VideoFacadeTrait};
+use crate::business::models::artist_detail::{
+    ArtistDescriptionReq, ArtistVideosReq, GetArtistReq, ARTIST_VIDEOS_PAGE_SIZE,
+};
+use crate::business::models::user::UserRole::{self, Artist, Registered};
+use actix_identity::Identity;
+use actix_session::Session;
+use actix_web::{web, HttpResponse, Responder, Result};
+use actix_web_grants::protect;
+use askama_actix::TemplateToResponse;
 
 pub async fn list_artists(artist_facade: web::Data<ArtistFacade>) -> impl Responder {
     match artist_facade.list_artists().await {
@@ -7,3 +26,205 @@ pub async fn list_artists(artist_facade: web::Data<ArtistFacade>) -> impl Respon
         Err(_) => HttpResponse::InternalServerError().finish(),
     }
 }
+
+/// Follows the artist, so new videos and streams of the artist appear in the feed
+///
+/// `POST /artist/{id}/follow`
+///
+/// # Returns
+/// `FollowTemplate` - updated follow button
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn follow_artist(
+    path: web::Path<GetArtistReq>,
+    artist_facade: web::Data<ArtistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32()?;
+    artist_facade.follow_artist(user_id, path.id).await?;
+
+    let info = artist_facade
+        .get_follow_info(path.id, Some(user_id))
+        .await?;
+    Ok(FollowTemplate { info }.to_response())
+}
+
+/// Unfollows the artist
+///
+/// `DELETE /artist/{id}/follow`
+///
+/// # Returns
+/// `FollowTemplate` - updated follow button
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn unfollow_artist(
+    path: web::Path<GetArtistReq>,
+    artist_facade: web::Data<ArtistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32()?;
+    artist_facade.unfollow_artist(user_id, path.id).await?;
+
+    let info = artist_facade
+        .get_follow_info(path.id, Some(user_id))
+        .await?;
+    Ok(FollowTemplate { info }.to_response())
+}
+
+/// Returns profile page of the artist with his videos, which the user can watch, and his
+/// public playlists
+///
+/// `GET /artist/{id}`
+///
+/// # Returns
+/// `ArtistTemplate`
+#[allow(clippy::too_many_arguments)]
+pub async fn artist_profile(
+    path: web::Path<GetArtistReq>,
+    artist_facade: web::Data<ArtistFacade>,
+    video_facade: web::Data<VideoFacade>,
+    stream_facade: web::Data<StreamFacade>,
+    playlist_facade: web::Data<PlaylistFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32();
+    let profile = artist_facade.get_artist_profile(path.id).await?;
+    let playlists = playlist_facade
+        .list_public_playlists(profile.user_id, user_id)
+        .await?;
+    let info = artist_facade.get_follow_info(path.id, user_id).await?;
+    let video_count = video_facade.count_artist_videos(path.id, user_id).await?;
+    let stream_id = stream_facade
+        .get_active_stream_of_artist(path.id, user_id)
+        .await?;
+    let videos_template =
+        get_artist_videos(path.id, 0, user_id, video_facade, artist_facade).await?;
+
+    let template = ArtistTemplate {
+        follow_template: FollowTemplate { info },
+        description_template: ArtistDescriptionTemplate {
+            artist_id: profile.id,
+            description: profile.description.clone(),
+            is_owner: user_id == Some(profile.user_id),
+        },
+        videos_template,
+        video_count,
+        stream_id,
+        playlists,
+        profile,
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Returns next page of the videos of the artist
+///
+/// `GET /artist/{id}/videos?offset={offset}`
+///
+/// # Returns
+/// `ArtistVideosTemplate`
+pub async fn artist_videos(
+    path: web::Path<GetArtistReq>,
+    query: web::Query<ArtistVideosReq>,
+    artist_facade: web::Data<ArtistFacade>,
+    video_facade: web::Data<VideoFacade>,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let template = get_artist_videos(
+        path.id,
+        query.offset.unwrap_or(0),
+        identity.id_i32(),
+        video_facade,
+        artist_facade,
+    )
+    .await?;
+
+    Ok(template.to_response())
+}
+
+/// Returns description of the artist
+///
+/// `GET /artist/{id}/description`
+///
+/// # Returns
+/// `ArtistDescriptionTemplate`
+pub async fn get_description(
+    path: web::Path<GetArtistReq>,
+    artist_facade: web::Data<ArtistFacade>,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let profile = artist_facade.get_artist_profile(path.id).await?;
+
+    Ok(ArtistDescriptionTemplate {
+        artist_id: profile.id,
+        is_owner: identity.id_i32() == Some(profile.user_id),
+        description: profile.description,
+    }
+    .to_response())
+}
+
+/// Returns form for editing description of the artist
+///
+/// `GET /artist/{id}/description/edit`
+///
+/// # Returns
+/// `ArtistDescriptionFormTemplate`
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn description_form(
+    path: web::Path<GetArtistReq>,
+    artist_facade: web::Data<ArtistFacade>,
+) -> Result<impl Responder> {
+    let profile = artist_facade.get_artist_profile(path.id).await?;
+
+    Ok(ArtistDescriptionFormTemplate {
+        artist_id: profile.id,
+        description: profile.description,
+    }
+    .to_response())
+}
+
+/// Updates description of the artist. Artists can edit only their own description.
+///
+/// `PATCH /artist/{id}/description`
+///
+/// # Returns
+/// `ArtistDescriptionTemplate` - updated description
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn update_description(
+    path: web::Path<GetArtistReq>,
+    form: web::Form<ArtistDescriptionReq>,
+    artist_facade: web::Data<ArtistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let profile = artist_facade
+        .update_description(identity.id_i32()?, path.id, form.into_inner())
+        .await?;
+
+    Ok(ArtistDescriptionTemplate {
+        artist_id: profile.id,
+        description: profile.description,
+        is_owner: true,
+    }
+    .to_response())
+}
+
+async fn get_artist_videos(
+    artist_id: i32,
+    offset: i64,
+    user_id: Option<i32>,
+    video_facade: web::Data<VideoFacade>,
+    artist_facade: web::Data<ArtistFacade>,
+) -> Result<ArtistVideosTemplate> {
+    let videos = video_facade
+        .fetch_artist_videos(artist_id, offset, user_id)
+        .await?;
+    let next_offset = (videos.len() as i64 == ARTIST_VIDEOS_PAGE_SIZE)
+        .then_some(offset.max(0) + ARTIST_VIDEOS_PAGE_SIZE);
+
+    Ok(ArtistVideosTemplate {
+        artist_id,
+        videos: from_video_to_video_list(videos, artist_facade).await?,
+        first_page: offset <= 0,
+        next_offset,
+    })
+}
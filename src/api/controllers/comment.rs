@@ -4,9 +4,11 @@ use crate::api::templates::video::show::template::CommentsTemplate;
 use crate::business::facades::comment::{CommentFacade, CommentFacadeTrait};
 use crate::business::facades::user::{UserFacade, UserFacadeTrait};
 use crate::business::models::comment::{CommentCreateModel, CommentUserModel, FetchCommentsOffset};
+use crate::business::models::user::UserRole::{self, Verified};
 use actix_identity::Identity;
 use actix_web::web::Query;
 use actix_web::{web, HttpResponse, Responder, Result};
+use actix_web_grants::protect;
 use askama_actix::TemplateToResponse;
 
 pub async fn get_comments_to_video(
@@ -43,6 +45,7 @@ pub async fn get_comments_to_video(
     .to_response())
 }
 
+#[protect(any("Verified"), ty = "UserRole")]
 pub async fn create_comment(
     comment_facade: web::Data<CommentFacade>,
     form: web::Form<CommentCreateModel>,
@@ -13,6 +13,8 @@ impl ResponseError for AppError {
             AppErrorKind::Unauthorized => StatusCode::UNAUTHORIZED,
             AppErrorKind::NotFound => StatusCode::NOT_FOUND,
             AppErrorKind::AccessDenied => StatusCode::FORBIDDEN,
+            AppErrorKind::Conflict => StatusCode::CONFLICT,
+            AppErrorKind::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 
@@ -0,0 +1,74 @@
+use crate::api::controllers::utils::video_utils::from_feed_entries_to_items;
+use crate::api::extractors::htmx_extractor::HtmxRequest;
+use crate::api::extractors::permissions_extractor::AsInteger;
+use crate::api::templates::feed::template::{FeedItemsTemplate, FeedTemplate};
+use crate::api::templates::template::BaseTemplate;
+use crate::business::facades::artist::ArtistFacade;
+use crate::business::facades::feed::{FeedFacade, FeedFacadeTrait};
+use crate::business::models::feed::FeedPageReq;
+use crate::business::models::user::UserRole::{self, Registered};
+use actix_identity::Identity;
+use actix_session::Session;
+use actix_web::web::{Data, Query};
+use actix_web::{Responder, Result};
+use actix_web_grants::protect;
+use askama_actix::TemplateToResponse;
+
+/// Returns page with new videos and live streams of the followed artists
+///
+/// `GET /feed`
+///
+/// # Returns
+/// `FeedTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn feed_page(
+    feed_facade: Data<FeedFacade>,
+    artist_facade: Data<ArtistFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let items_template = get_feed_items(identity.id_i32()?, 0, feed_facade, artist_facade).await?;
+    let template = FeedTemplate { items_template };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Returns next page of the feed
+///
+/// `GET /feed/items?offset={offset}`
+///
+/// # Returns
+/// `FeedItemsTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn feed_items(
+    query: Query<FeedPageReq>,
+    feed_facade: Data<FeedFacade>,
+    artist_facade: Data<ArtistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let template = get_feed_items(
+        identity.id_i32()?,
+        query.offset.unwrap_or(0),
+        feed_facade,
+        artist_facade,
+    )
+    .await?;
+
+    Ok(template.to_response())
+}
+
+async fn get_feed_items(
+    user_id: i32,
+    offset: i64,
+    feed_facade: Data<FeedFacade>,
+    artist_facade: Data<ArtistFacade>,
+) -> Result<FeedItemsTemplate> {
+    let (entries, next_offset) = feed_facade.list_feed(user_id, offset).await?;
+
+    Ok(FeedItemsTemplate {
+        items: from_feed_entries_to_items(entries, artist_facade).await?,
+        first_page: offset == 0,
+        next_offset,
+    })
+}
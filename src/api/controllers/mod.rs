@@ -2,9 +2,13 @@ pub mod admin;
 pub mod artist;
 pub mod comment;
 pub mod error;
+pub mod feed;
 pub mod membership;
+pub mod playlist;
 pub mod stream;
+pub mod stream_chat;
 pub mod temp_file;
+pub mod two_factor;
 pub mod user;
 pub mod utils;
 pub mod video;
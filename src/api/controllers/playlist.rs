@@ -0,0 +1,388 @@
+use crate::api::controllers::utils::route_util::{add_redirect_header, build_playlist_path};
+use crate::api::controllers::utils::video_utils::from_video_to_video_list;
+use crate::api::extractors::htmx_extractor::HtmxRequest;
+use crate::api::extractors::permissions_extractor::{AsInteger, AsIntegerOptional};
+use crate::api::extractors::template_extractor::TemplateReq;
+use crate::api::templates::playlist::template::{
+    PlaylistFormTemplate, PlaylistItemAddedTemplate, PlaylistItemsTemplate, PlaylistSelectTemplate,
+    PlaylistTemplate, PlaylistsTemplate,
+};
+use crate::api::templates::template::BaseTemplate;
+use crate::api::templates::video::show::template::PlayerTemplate;
+use crate::business::facades::artist::ArtistFacade;
+use crate::business::facades::playlist::{PlaylistFacade, PlaylistFacadeTrait};
+use crate::business::facades::video::{VideoFacade, VideoFacadeTrait};
+use crate::business::facades::watch_history::{WatchHistoryFacade, WatchHistoryFacadeTrait};
+use crate::business::models::playlist::{
+    AddPlaylistItemReq, GetPlaylistReq, MovePlaylistItemReq, PlayPlaylistReq, PlaylistItemReq,
+    PlaylistItemsReq, PlaylistReq, PlaylistVisibility, SelectPlaylistReq,
+};
+use crate::business::models::user::UserRole::{self, Registered};
+use crate::configuration::models::Configuration;
+use actix_identity::Identity;
+use actix_session::Session;
+use actix_web::web::{Data, Form, Path, Query};
+use actix_web::{HttpResponse, Responder, Result};
+use actix_web_grants::protect;
+use askama_actix::TemplateToResponse;
+
+/// Returns page with all playlists of the logged-in user
+///
+/// `GET /playlist`
+///
+/// # Returns
+/// `PlaylistsTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn list_playlists(
+    playlist_facade: Data<PlaylistFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let playlists = playlist_facade
+        .list_user_playlists(identity.id_i32()?)
+        .await?;
+    let template = PlaylistsTemplate { playlists };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Returns form to create a new playlist
+///
+/// `GET /playlist/new`
+///
+/// # Returns
+/// `PlaylistFormTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn new_playlist_form(
+    htmx_request: HtmxRequest,
+    session: Session,
+) -> Result<impl Responder> {
+    let template = PlaylistFormTemplate {
+        playlist: None,
+        visibilities: PlaylistVisibility::ALL.to_vec(),
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Creates new playlist
+///
+/// `POST /playlist`
+///
+/// # Form params
+/// `PlaylistReq` - data of the playlist
+///
+/// # Returns
+/// Redirects user to the newly created playlist
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn create_playlist(
+    form: Form<PlaylistReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    template_req: TemplateReq,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let playlist = playlist_facade
+        .create_playlist(identity.id_i32()?, form.into_inner())
+        .await?;
+
+    if !template_req.return_template {
+        return Ok(HttpResponse::Created().body(playlist.id.to_string()));
+    }
+
+    let mut response = HttpResponse::Created().finish();
+    add_redirect_header(
+        build_playlist_path(playlist.id, None).as_str(),
+        &mut response,
+    )?;
+
+    Ok(response)
+}
+
+/// Returns buttons to add the video to one of the playlists of the logged-in user
+///
+/// `GET /playlist/select?video_id={video_id}`
+///
+/// # Returns
+/// `PlaylistSelectTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn select_playlist(
+    query: Query<SelectPlaylistReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let playlists = playlist_facade
+        .list_user_playlists(identity.id_i32()?)
+        .await?;
+    let template = PlaylistSelectTemplate {
+        video_id: query.video_id,
+        playlists,
+    };
+
+    Ok(template.to_response())
+}
+
+/// Returns player of the playlist. Once the video ends, the next video of the playlist
+/// is played automatically.
+///
+/// `GET /playlist/{id}?video_id={video_id}`
+///
+/// # Query params
+/// `PlayPlaylistReq` - played video, the first video is played if it's not specified
+///
+/// # Returns
+/// `PlaylistTemplate`
+#[allow(clippy::too_many_arguments)]
+pub async fn play_playlist(
+    path: Path<GetPlaylistReq>,
+    query: Query<PlayPlaylistReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    video_facade: Data<VideoFacade>,
+    watch_history_facade: Data<WatchHistoryFacade>,
+    artist_facade: Data<ArtistFacade>,
+    config: Data<Configuration>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32();
+    let playlist = playlist_facade.get_playlist(path.id, user_id).await?;
+    let videos = playlist_facade
+        .get_playlist_videos(playlist.id, user_id)
+        .await?;
+
+    let current_index = query
+        .video_id
+        .and_then(|video_id| videos.iter().position(|video| video.id == video_id))
+        .unwrap_or(0);
+
+    let mut player_template = None;
+    if let Some(video) = videos.get(current_index) {
+        let next_url = videos
+            .get(current_index + 1)
+            .map(|next| build_playlist_path(playlist.id, Some(next.id)));
+
+        let mut template = PlayerTemplate::from_saved(video.id)
+            .with_hls(video_facade.has_hls_playlist(video.id))
+            .with_view_tracking(config.app.views.threshold_seconds)
+            .with_next(next_url, query.video_id.is_some());
+        if let Some(user_id) = user_id {
+            let resume_position = watch_history_facade
+                .get_resume_position(user_id, video.id, video.duration_ms)
+                .await?;
+            template = template.with_watch_history(resume_position);
+        }
+        player_template = Some(template);
+    }
+
+    let current_video_id = videos.get(current_index).map(|video| video.id);
+    let videos = from_video_to_video_list(videos, artist_facade).await?;
+    let current_video = videos
+        .iter()
+        .find(|video| Some(video.id) == current_video_id)
+        .cloned();
+
+    let template = PlaylistTemplate {
+        items_template: PlaylistItemsTemplate {
+            playlist_id: playlist.id,
+            videos,
+            current_video_id,
+            is_owner: user_id == Some(playlist.user_id),
+        },
+        playlist,
+        current_video,
+        player_template,
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Returns form to edit the playlist
+///
+/// `GET /playlist/{id}/edit`
+///
+/// # Returns
+/// `PlaylistFormTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn edit_playlist_form(
+    path: Path<GetPlaylistReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let playlist = playlist_facade
+        .get_owned_playlist(path.id, identity.id_i32()?)
+        .await?;
+    let template = PlaylistFormTemplate {
+        playlist: Some(playlist),
+        visibilities: PlaylistVisibility::ALL.to_vec(),
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Updates the playlist
+///
+/// `PATCH /playlist/{id}`
+///
+/// # Form params
+/// `PlaylistReq` - new data of the playlist
+///
+/// # Returns
+/// Redirects user to the playlist
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn update_playlist(
+    path: Path<GetPlaylistReq>,
+    form: Form<PlaylistReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let playlist = playlist_facade
+        .update_playlist(identity.id_i32()?, path.id, form.into_inner())
+        .await?;
+
+    let mut response = HttpResponse::Ok().finish();
+    add_redirect_header(
+        build_playlist_path(playlist.id, None).as_str(),
+        &mut response,
+    )?;
+
+    Ok(response)
+}
+
+/// Deletes the playlist, videos of the playlist are not affected
+///
+/// `DELETE /playlist/{id}`
+///
+/// # Returns
+/// Redirects user to the list of the playlists
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn delete_playlist(
+    path: Path<GetPlaylistReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    playlist_facade
+        .delete_playlist(identity.id_i32()?, path.id)
+        .await?;
+
+    let mut response = HttpResponse::Ok().finish();
+    add_redirect_header("/playlist", &mut response)?;
+
+    Ok(response)
+}
+
+/// Appends the video to the end of the playlist
+///
+/// `POST /playlist/{id}/items`
+///
+/// # Form params
+/// `AddPlaylistItemReq` - added video
+///
+/// # Returns
+/// `PlaylistItemAddedTemplate` - `201 Created` if the video was added,
+/// `200 OK` if it's already in the playlist
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn add_playlist_item(
+    path: Path<GetPlaylistReq>,
+    form: Form<AddPlaylistItemReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let added = playlist_facade
+        .add_video(identity.id_i32()?, path.id, form.video_id)
+        .await?;
+    let template = PlaylistItemAddedTemplate { added };
+
+    let response = match added {
+        true => HttpResponse::Created().body(template.to_string()),
+        false => HttpResponse::Ok().body(template.to_string()),
+    };
+    Ok(response)
+}
+
+/// Removes the video from the playlist
+///
+/// `DELETE /playlist/{id}/items/{video_id}`
+///
+/// # Query params
+/// `PlaylistItemsReq` - video played on the page
+///
+/// # Returns
+/// `PlaylistItemsTemplate` - updated videos of the playlist
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn remove_playlist_item(
+    path: Path<PlaylistItemReq>,
+    query: Query<PlaylistItemsReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    artist_facade: Data<ArtistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32()?;
+    playlist_facade
+        .remove_video(user_id, path.id, path.video_id)
+        .await?;
+
+    let template = get_playlist_items(
+        path.id,
+        user_id,
+        query.current_video_id,
+        playlist_facade,
+        artist_facade,
+    )
+    .await?;
+    Ok(template.to_response())
+}
+
+/// Moves the video to another position in the playlist
+///
+/// `PATCH /playlist/{id}/items/{video_id}`
+///
+/// # Form params
+/// `MovePlaylistItemReq` - new position of the video
+///
+/// # Returns
+/// `PlaylistItemsTemplate` - reordered videos of the playlist
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn move_playlist_item(
+    path: Path<PlaylistItemReq>,
+    form: Form<MovePlaylistItemReq>,
+    playlist_facade: Data<PlaylistFacade>,
+    artist_facade: Data<ArtistFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32()?;
+    playlist_facade
+        .move_video(user_id, path.id, path.video_id, form.position)
+        .await?;
+
+    let template = get_playlist_items(
+        path.id,
+        user_id,
+        form.current_video_id,
+        playlist_facade,
+        artist_facade,
+    )
+    .await?;
+    Ok(template.to_response())
+}
+
+async fn get_playlist_items(
+    playlist_id: i32,
+    user_id: i32,
+    current_video_id: Option<i32>,
+    playlist_facade: Data<PlaylistFacade>,
+    artist_facade: Data<ArtistFacade>,
+) -> Result<PlaylistItemsTemplate> {
+    let videos = playlist_facade
+        .get_playlist_videos(playlist_id, Some(user_id))
+        .await?;
+
+    Ok(PlaylistItemsTemplate {
+        playlist_id,
+        videos: from_video_to_video_list(videos, artist_facade).await?,
+        current_video_id,
+        is_owner: true,
+    })
+}
@@ -1,19 +1,63 @@
+use crate::api::controllers::utils::request_util::client_ip;
 use crate::api::controllers::utils::route_util::{add_redirect_header, build_stream_watch_path};
+use crate::api::controllers::utils::video_utils::from_listed_streams_to_items;
 use crate::api::extractors::htmx_extractor::HtmxRequest;
 use crate::api::extractors::permissions_extractor::{AsInteger, AsIntegerOptional};
+use crate::api::templates::stream::directory::template::StreamDirectoryTemplate;
+use crate::api::templates::stream::ingest::template::{StreamIngestTemplate, StreamKeyTemplate};
+use crate::api::templates::stream::reminder::template::{
+    LiveRemindersTemplate, StreamReminderTemplate,
+};
+use crate::api::templates::stream::stats::template::StreamStatsTemplate;
 use crate::api::templates::stream::watch::template::WatchStreamTemplate;
 use crate::api::templates::template::BaseTemplate;
+use crate::business::facades::artist::ArtistFacade;
 use crate::business::facades::stream::{StreamFacade, StreamFacadeTrait};
+use crate::business::facades::stream_telemetry::{
+    StreamTelemetryFacade, StreamTelemetryFacadeTrait,
+};
 use crate::business::facades::video::{VideoFacade, VideoFacadeTrait};
 use crate::business::models::error::{AppError, AppErrorKind};
-use crate::business::models::stream::LiveStreamStart;
-use crate::business::models::user::UserRole::{self, Artist};
+use crate::business::models::stream::{
+    LiveStreamSchedule, LiveStreamSource, LiveStreamStart, LiveStreamStatus, RtmpPublishReq,
+};
+use crate::business::models::user::UserRole::{self, Artist, Registered};
+use crate::configuration::models::Configuration;
 use actix_identity::Identity;
 use actix_session::Session;
 use actix_web::web::{Data, Form, Path};
 use actix_web::{HttpRequest, HttpResponse, Responder, Result};
 use actix_web_grants::protect;
 use askama_actix::TemplateToResponse;
+use log::error;
+
+/// Returns page with the running and upcoming streams, which the user can watch
+///
+/// `GET /stream`
+///
+/// # Returns
+/// `StreamDirectoryTemplate`
+pub async fn stream_directory(
+    stream_facade: Data<StreamFacade>,
+    telemetry_facade: Data<StreamTelemetryFacade>,
+    artist_facade: Data<ArtistFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let (running, upcoming) = stream_facade.list_streams(identity.id_i32()).await?;
+
+    let mut running = from_listed_streams_to_items(running, artist_facade.clone()).await?;
+    for item in &mut running {
+        item.viewer_count = Some(telemetry_facade.count_viewers(item.stream_id).await?);
+    }
+    let template = StreamDirectoryTemplate {
+        running,
+        upcoming: from_listed_streams_to_items(upcoming, artist_facade).await?,
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
 
 /// Starts the live stream
 ///
@@ -39,6 +83,55 @@ pub async fn start_stream(
     Ok(response)
 }
 
+/// Starts the live stream, which is sent from the encoder of the artist, e.g. OBS.
+/// The stream waits until the encoder starts publishing with the stream key of the artist.
+///
+/// `POST /stream/live`
+///
+/// # Form params
+/// `LiveStreamStart` - id of the video, which gives the stream its name and visibility
+///
+/// # Returns
+/// Redirects user to the started stream
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn start_rtmp_stream(
+    Form(request): Form<LiveStreamStart>,
+    stream_facade: Data<StreamFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let stream_id = stream_facade
+        .start_rtmp_stream(request, identity.id_i32()?)
+        .await?;
+
+    let mut response = HttpResponse::Created().finish();
+    add_redirect_header(&build_stream_watch_path(stream_id), &mut response)?;
+    Ok(response)
+}
+
+/// Schedules the live stream of the video, which starts automatically at the given time
+///
+/// `POST /stream/schedule`
+///
+/// # Form params
+/// `LiveStreamSchedule` - id of the video to be streamed and the start time in RFC 3339
+///
+/// # Returns
+/// Redirects user to the countdown page of the scheduled stream
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn schedule_stream(
+    Form(request): Form<LiveStreamSchedule>,
+    stream_facade: Data<StreamFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let stream_id = stream_facade
+        .schedule_stream(request, identity.id_i32()?)
+        .await?;
+
+    let mut response = HttpResponse::Created().finish();
+    add_redirect_header(&build_stream_watch_path(stream_id), &mut response)?;
+    Ok(response)
+}
+
 /// Returns watch stream template
 ///
 /// `GET /stream/{stream_id}/watch`
@@ -58,17 +151,37 @@ pub async fn watch_stream(
         .get_stream(user_id, stream_id.into_inner())
         .await?;
 
-    let video_artist_id = video.artist_id;
+    let is_owner = video_facade
+        .is_video_owner(video.artist_id, user_id.unwrap_or(-1))
+        .await
+        .map_or(false, |_| true);
+
+    let mut ingest_template = None;
+    if is_owner
+        && stream.status == LiveStreamStatus::Pending
+        && stream.source == LiveStreamSource::Rtmp
+    {
+        let ingest = stream_facade
+            .get_stream_ingest(user_id.unwrap_or(-1))
+            .await?;
+        ingest_template = Some(StreamIngestTemplate { ingest });
+    }
+
+    let mut reminder_template = None;
+    if stream.status == LiveStreamStatus::Pending && stream.source == LiveStreamSource::File {
+        let reminders = stream_facade.get_reminders(stream.id, user_id).await?;
+        reminder_template = Some(StreamReminderTemplate { reminders });
+    }
+
     let template = BaseTemplate::wrap(
         htmx_request,
         session,
         WatchStreamTemplate {
             stream,
             video,
-            is_owner: video_facade
-                .is_video_owner(video_artist_id, user_id.unwrap_or(-1))
-                .await
-                .map_or(false, |_| true),
+            is_owner,
+            ingest_template,
+            reminder_template,
         },
     );
 
@@ -97,7 +210,7 @@ pub async fn stop_stream(
     Ok(response)
 }
 
-/// Checks if user can access the stream
+/// Checks if user can access the stream, allowed users are counted as its viewers
 ///
 /// `GET /stream/auth`
 ///
@@ -106,7 +219,9 @@ pub async fn stop_stream(
 pub async fn authenticate_stream_request(
     request: HttpRequest,
     stream_facade: Data<StreamFacade>,
+    telemetry_facade: Data<StreamTelemetryFacade>,
     identity: Option<Identity>,
+    config: Data<Configuration>,
 ) -> Result<impl Responder> {
     let headers = request.headers();
     let stream_url = headers.get("X-Original-URI").ok_or(AppError::new(
@@ -119,12 +234,162 @@ pub async fn authenticate_stream_request(
         .map_err(|_| AppError::new("Access to the stream denied!", AppErrorKind::AccessDenied))?;
     println!("1. {stream_url}");
 
-    stream_facade
-        .authenticate_stream(identity.id_i32(), stream_url)
+    let user_id = identity.id_i32();
+    let stream_id = stream_facade
+        .authenticate_stream(user_id, stream_url)
         .await
         // Map all errors to 403 Forbidden, since it's needed by Nginx plugin
         .map_err(|err| AppError::new(&err.message, AppErrorKind::AccessDenied))?;
     println!("2. {stream_url}");
 
+    // Players fetch the stream through this check, so it tells who is watching.
+    // Access is not denied, when the viewer can't be counted.
+    // The address of the player is sent by nginx, see `trusted_proxies` in the config.
+    let ip_address = client_ip(&request, &config);
+    if let Err(err) = telemetry_facade
+        .mark_viewer(stream_id, user_id, ip_address.as_deref())
+        .await
+    {
+        error!(
+            "Failed to count viewer of stream with ID: {}, {:?}",
+            stream_id, err
+        );
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Authorizes the encoder, which starts publishing the stream to nginx-rtmp (`on_publish` callback).
+/// Name of the published stream is the stream key of the artist.
+///
+/// `POST /stream/publish`
+///
+/// # Returns
+/// HTTP 200 if the stream can be published, HTTP 403 otherwise
+pub async fn publish_stream(
+    Form(request): Form<RtmpPublishReq>,
+    stream_facade: Data<StreamFacade>,
+) -> Result<impl Responder> {
+    stream_facade
+        .authorize_publish(&request.name)
+        .await
+        // Map all errors to 403 Forbidden, since any other 2xx/3xx status allows the publishing
+        .map_err(|err| AppError::new(&err.message, AppErrorKind::AccessDenied))?;
+
     Ok(HttpResponse::Ok().finish())
 }
+
+/// Returns page with the stream key of the artist and the server, where his encoder sends the stream
+///
+/// `GET /stream/key`
+///
+/// # Returns
+/// `StreamKeyTemplate`
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn stream_key_page(
+    stream_facade: Data<StreamFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let ingest = stream_facade.get_stream_ingest(identity.id_i32()?).await?;
+    let template = StreamKeyTemplate {
+        ingest_template: StreamIngestTemplate { ingest },
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Generates new stream key of the artist, the previous key stops working
+///
+/// `POST /stream/key`
+///
+/// # Returns
+/// `StreamIngestTemplate` - settings with the new key
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn regenerate_stream_key(
+    stream_facade: Data<StreamFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let ingest = stream_facade
+        .regenerate_stream_key(identity.id_i32()?)
+        .await?;
+
+    Ok(StreamIngestTemplate { ingest }.to_response())
+}
+
+/// Returns viewer count of the stream, the owner also gets health of its pipelines.
+/// It's polled by the watch page, while the stream runs.
+///
+/// `GET /stream/{stream_id}/stats`
+///
+/// # Returns
+/// `StreamStatsTemplate`
+pub async fn stream_stats(
+    stream_id: Path<i32>,
+    telemetry_facade: Data<StreamTelemetryFacade>,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let stats = telemetry_facade
+        .get_stats(stream_id.into_inner(), identity.id_i32())
+        .await?;
+
+    Ok(StreamStatsTemplate { stats }.to_response())
+}
+
+/// Notifies the user, when the scheduled stream starts
+///
+/// `POST /stream/{stream_id}/reminder`
+///
+/// # Returns
+/// `StreamReminderTemplate` - updated notify me button
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn add_reminder(
+    stream_id: Path<i32>,
+    stream_facade: Data<StreamFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let reminders = stream_facade
+        .set_reminder(stream_id.into_inner(), identity.id_i32()?, true)
+        .await?;
+
+    Ok(StreamReminderTemplate { reminders }.to_response())
+}
+
+/// The user is no longer notified, when the scheduled stream starts
+///
+/// `DELETE /stream/{stream_id}/reminder`
+///
+/// # Returns
+/// `StreamReminderTemplate` - updated notify me button
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn delete_reminder(
+    stream_id: Path<i32>,
+    stream_facade: Data<StreamFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let reminders = stream_facade
+        .set_reminder(stream_id.into_inner(), identity.id_i32()?, false)
+        .await?;
+
+    Ok(StreamReminderTemplate { reminders }.to_response())
+}
+
+/// Returns running streams, which the user asked to be notified about.
+/// It's polled by every page of the logged in user.
+///
+/// `GET /stream/reminders`
+///
+/// # Returns
+/// `LiveRemindersTemplate`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn live_reminders(
+    stream_facade: Data<StreamFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let streams = stream_facade
+        .list_live_reminders(identity.id_i32()?)
+        .await?;
+
+    Ok(LiveRemindersTemplate { streams }.to_response())
+}
@@ -0,0 +1,190 @@
+use crate::api::extractors::permissions_extractor::{AsInteger, AsIntegerOptional};
+use crate::api::templates::stream::chat::template::{ChatMessageTemplate, ChatTemplate};
+use crate::business::facades::stream_chat::{StreamChatFacade, StreamChatFacadeTrait};
+use crate::business::models::stream_chat::{
+    ChatEvent, ChatMessagePathReq, ChatMessageReq, ChatTimeoutReq,
+};
+use crate::business::models::user::UserRole::{self, Artist, Verified};
+use actix_identity::Identity;
+use actix_web::http::header::{CACHE_CONTROL, CONTENT_DISPOSITION};
+use actix_web::web::{Bytes, Data, Form, Path};
+use actix_web::{HttpResponse, Responder, Result};
+use actix_web_grants::protect;
+use askama_actix::{Template, TemplateToResponse};
+use futures::stream::{self, StreamExt};
+use log::error;
+use std::time::Duration;
+
+/// Proxies may close connections, which don't transfer any data for a while
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Returns the chat of the stream with its latest messages
+///
+/// `GET /stream/{stream_id}/chat`
+///
+/// # Returns
+/// `ChatTemplate`
+pub async fn get_chat(
+    stream_id: Path<i32>,
+    chat_facade: Data<StreamChatFacade>,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let room = chat_facade
+        .get_room(stream_id.into_inner(), identity.id_i32())
+        .await?;
+
+    Ok(ChatTemplate { room }.to_response())
+}
+
+/// Sends the message to the chat of the stream
+///
+/// `POST /stream/{stream_id}/chat`
+///
+/// # Form params
+/// `ChatMessageReq` - content of the message
+///
+/// # Returns
+/// HTTP 204, the message is delivered to the viewers by the chat events.
+/// HTTP 403 if the email of the user is not verified.
+/// HTTP 429 if the user sends messages too fast.
+#[protect(any("Verified"), ty = "UserRole")]
+pub async fn send_message(
+    stream_id: Path<i32>,
+    Form(request): Form<ChatMessageReq>,
+    chat_facade: Data<StreamChatFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    chat_facade
+        .send_message(stream_id.into_inner(), identity.id_i32()?, request)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Streams events of the chat as Server-Sent Events
+///
+/// `GET /stream/{stream_id}/chat/events`
+///
+/// # Returns
+/// `text/event-stream` with events `message` (rendered `ChatMessageTemplate`),
+/// `delete` (ID of the removed message) and `timeout` (JSON with user_id and seconds)
+pub async fn chat_events(
+    stream_id: Path<i32>,
+    chat_facade: Data<StreamChatFacade>,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let stream_id = stream_id.into_inner();
+    let events = chat_facade
+        .subscribe(stream_id, identity.id_i32())
+        .await?
+        .filter_map(move |event| async move { to_server_sent_event(stream_id, event) });
+
+    let keep_alive = stream::unfold(
+        tokio::time::interval(KEEP_ALIVE_INTERVAL),
+        |mut interval| async move {
+            interval.tick().await;
+            Some((String::from(": keep-alive\n\n"), interval))
+        },
+    );
+
+    let body = stream::select(events, keep_alive)
+        .map(|event| Ok::<_, actix_web::Error>(Bytes::from(event)));
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((CACHE_CONTROL, "no-cache"))
+        .streaming(body))
+}
+
+/// Removes the message from the chat
+///
+/// `DELETE /stream/{stream_id}/chat/{message_id}`
+///
+/// # Returns
+/// HTTP 204, the message disappears from the chat of all viewers
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn delete_message(
+    path: Path<ChatMessagePathReq>,
+    chat_facade: Data<StreamChatFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    chat_facade
+        .delete_message(path.stream_id, identity.id_i32()?, path.message_id)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Disallows the user to send messages to the chat for a while
+///
+/// `POST /stream/{stream_id}/chat/timeout`
+///
+/// # Form params
+/// `ChatTimeoutReq` - timed out user and length of the timeout in minutes
+///
+/// # Returns
+/// HTTP 204
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn timeout_user(
+    stream_id: Path<i32>,
+    Form(request): Form<ChatTimeoutReq>,
+    chat_facade: Data<StreamChatFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    chat_facade
+        .timeout_user(stream_id.into_inner(), identity.id_i32()?, request)
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Downloads all messages of the ended stream
+///
+/// `GET /stream/{stream_id}/chat/transcript`
+///
+/// # Returns
+/// Plain text attachment with one message per line
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn export_transcript(
+    stream_id: Path<i32>,
+    chat_facade: Data<StreamChatFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let stream_id = stream_id.into_inner();
+    let transcript = chat_facade
+        .export_transcript(stream_id, identity.id_i32()?)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/plain; charset=utf-8")
+        .insert_header((
+            CONTENT_DISPOSITION,
+            format!("attachment; filename=\"stream-{stream_id}-chat.txt\""),
+        ))
+        .body(transcript))
+}
+
+/// Formats the event according to the Server-Sent Events specification.
+/// Every line of the data must be prefixed separately.
+fn to_server_sent_event(stream_id: i32, event: ChatEvent) -> Option<String> {
+    let (name, data) = match event {
+        ChatEvent::Message(message) => {
+            let template = ChatMessageTemplate { stream_id, message };
+            match template.render() {
+                Ok(html) => ("message", html),
+                Err(err) => {
+                    error!("Failed to render the chat message, {:?}", err);
+                    return None;
+                }
+            }
+        }
+        ChatEvent::Delete { message_id } => ("delete", message_id.to_string()),
+        ChatEvent::Timeout { user_id, seconds } => (
+            "timeout",
+            format!("{{\"user_id\":{user_id},\"seconds\":{seconds}}}"),
+        ),
+    };
+
+    let data: String = data.lines().map(|line| format!("data: {line}\n")).collect();
+    Some(format!("event: {name}\n{data}\n"))
+}
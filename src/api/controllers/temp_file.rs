@@ -1,32 +1,43 @@
+use crate::api::controllers::utils::route_util::build_upload_path;
 use crate::api::extractors::permissions_extractor::AsInteger;
 use crate::api::extractors::template_extractor::TemplateReq;
 use crate::api::templates::video::show::template::PlayerTemplate;
 use crate::api::templates::video::upload::template::{
-    ThumbnailPreviewTemplate, ThumbnailUploadInputTemplate, VideoPreviewTemplate,
-    VideoUploadInputTemplate,
+    ThumbnailCandidatesTemplate, ThumbnailPreviewTemplate, ThumbnailUploadInputTemplate,
+    VideoPreviewTemplate, VideoUploadInputTemplate,
 };
 use crate::business::facades::temp_file::{TempFileFacade, TempFileFacadeTrait};
-use crate::business::models::temp_file::{GetFileInputTemplate, TempFileInput};
-use crate::business::models::user::UserRole::{self, Artist};
-use crate::business::models::video::{ThumbnailUploadForm, VideoUploadForm};
+use crate::business::models::error::{AppError, AppErrorKind};
+use crate::business::models::temp_file::{
+    CreateUploadReq, GetFileInputTemplate, GetUploadReq, TempFileInput, UploadStatus,
+};
+use crate::business::models::user::UserRole::{self, Artist, Verified};
+use crate::business::models::video::{
+    ThumbnailCandidatesReq, ThumbnailUploadForm, VideoUploadForm,
+};
 use crate::business::Result;
 use crate::configuration::models::Configuration;
 use actix_files::NamedFile;
 use actix_identity::Identity;
 use actix_multipart::form::MultipartForm;
-use actix_web::web::{Data, Path, Query};
-use actix_web::{HttpResponse, Responder};
+use actix_web::http::header::{CacheControl, CacheDirective, LOCATION};
+use actix_web::web::{Bytes, Data, Form, Path, Query};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, Responder};
 use actix_web_grants::protect;
 use askama::Template;
 use askama_actix::TemplateToResponse;
 
+const THUMBNAIL_CANDIDATES_COUNT: u32 = 4;
+const UPLOAD_OFFSET_HEADER: &str = "Upload-Offset";
+const UPLOAD_LENGTH_HEADER: &str = "Upload-Length";
+
 /// Creates new temporary video file
 ///
 /// `POST /temp/video`
 ///
 /// # Returns
 /// `VideoPreviewTemplate` - includes video player together with hidden input including temp_file_id
-#[protect(any("Artist"), ty = "UserRole")]
+#[protect(all("Artist", "Verified"), ty = "UserRole")]
 pub async fn post_temp_video(
     MultipartForm(form): MultipartForm<VideoUploadForm>,
     temp_file_facade: Data<TempFileFacade>,
@@ -38,7 +49,7 @@ pub async fn post_temp_video(
     let allowed_mime_types = config.app.video.accepted_mime_type.clone();
 
     temp_file_facade
-        .check_mime_type(&form.file.file, allowed_mime_types)
+        .check_mime_type(form.file.file.path(), allowed_mime_types)
         .await?;
 
     let temp_file_id = temp_file_facade
@@ -57,13 +68,118 @@ pub async fn post_temp_video(
     Ok(HttpResponse::Created().body(template.render().unwrap()))
 }
 
+/// Creates new resumable upload of the video
+///
+/// `POST /temp/upload`
+///
+/// # Form params
+/// `CreateUploadReq` - name and total size of the uploaded file in bytes
+///
+/// # Returns
+/// ID of the upload, `Location` header with the URL of the upload and `Upload-Offset` header
+#[protect(all("Artist", "Verified"), ty = "UserRole")]
+pub async fn post_upload(
+    form: Form<CreateUploadReq>,
+    temp_file_facade: Data<TempFileFacade>,
+    config: Data<Configuration>,
+    identity: Identity,
+) -> Result<HttpResponse> {
+    let max_length = config.app.file_size_limit_mb as i64 * 1024 * 1024;
+    let upload_id = temp_file_facade
+        .create_upload(form.into_inner(), max_length, identity.id_i32()?)
+        .await?;
+
+    Ok(HttpResponse::Created()
+        .insert_header((LOCATION, build_upload_path(upload_id)))
+        .insert_header((UPLOAD_OFFSET_HEADER, 0))
+        .body(upload_id.to_string()))
+}
+
+/// Returns current state of the resumable upload, so the client knows where to continue
+///
+/// `HEAD /temp/upload/{id}`
+///
+/// # Returns
+/// `Upload-Offset` and `Upload-Length` headers
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn head_upload(
+    path: Path<GetUploadReq>,
+    temp_file_facade: Data<TempFileFacade>,
+    identity: Identity,
+) -> Result<HttpResponse> {
+    let status = temp_file_facade
+        .get_upload_status(path.id, identity.id_i32()?)
+        .await?;
+
+    Ok(upload_status_response(HttpResponse::Ok(), status))
+}
+
+/// Appends chunk to the resumable upload
+///
+/// `PATCH /temp/upload/{id}`
+///
+/// # Headers
+/// `Upload-Offset` - position of the chunk in the file, must match the current offset of the upload
+///
+/// # Body
+/// Raw bytes of the chunk
+///
+/// # Returns
+/// `Upload-Offset` header with the new offset
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn patch_upload(
+    path: Path<GetUploadReq>,
+    request: HttpRequest,
+    body: Bytes,
+    temp_file_facade: Data<TempFileFacade>,
+    identity: Identity,
+) -> Result<HttpResponse> {
+    let offset = parse_upload_offset(&request)?;
+    let status = temp_file_facade
+        .append_upload_chunk(path.id, identity.id_i32()?, offset, &body)
+        .await?;
+
+    Ok(upload_status_response(HttpResponse::NoContent(), status))
+}
+
+/// Converts the complete upload to temporary video file
+///
+/// `POST /temp/upload/{id}/finalize`
+///
+/// # Returns
+/// `VideoPreviewTemplate` - includes video player together with hidden input including temp_file_id
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn finalize_upload(
+    path: Path<GetUploadReq>,
+    temp_file_facade: Data<TempFileFacade>,
+    template_req: TemplateReq,
+    config: Data<Configuration>,
+    identity: Identity,
+) -> Result<HttpResponse> {
+    let allowed_mime_types = config.app.video.accepted_mime_type.clone();
+    let temp_file_id = temp_file_facade
+        .finalize_upload(path.id, identity.id_i32()?, allowed_mime_types)
+        .await?;
+
+    if !template_req.return_template {
+        return Ok(HttpResponse::Created().body(temp_file_id.to_string()));
+    }
+
+    let template = VideoPreviewTemplate {
+        temp_file_id: Some(temp_file_id),
+        player_template: PlayerTemplate::from_temporary(temp_file_id),
+    };
+
+    Ok(HttpResponse::Created().body(template.render().unwrap()))
+}
+
 /// Creates new temporary file for thumbnail
 ///
 /// `POST /temp/thumbnail`
 ///
 /// # Returns
 /// `ThumbnailPreviewTemplate` - Thumbnail preview template together with hidden input with temp_file_id
-#[protect(any("Artist"), ty = "UserRole")]
+#[protect(all("Artist", "Verified"), ty = "UserRole")]
 pub async fn post_temp_thumbnail(
     MultipartForm(form): MultipartForm<ThumbnailUploadForm>,
     temp_file_facade: Data<TempFileFacade>,
@@ -75,7 +191,7 @@ pub async fn post_temp_thumbnail(
     let allowed_mime_types = config.app.thumbnail.accepted_mime_type.clone();
 
     temp_file_facade
-        .check_mime_type(&form.file.file, allowed_mime_types)
+        .check_mime_type(form.file.file.path(), allowed_mime_types)
         .await?;
 
     let temp_file_id = temp_file_facade
@@ -94,6 +210,39 @@ pub async fn post_temp_thumbnail(
     Ok(HttpResponse::Created().body(template.render().unwrap()))
 }
 
+/// Creates thumbnail candidates from frames of the temporary video
+///
+/// `POST /temp/thumbnail/candidates`
+///
+/// # Form params
+/// `ThumbnailCandidatesReq` - ID of the temporary video
+///
+/// # Returns
+/// `ThumbnailCandidatesTemplate` - Thumbnails, from which the artist can pick one
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn post_thumbnail_candidates(
+    form: Form<ThumbnailCandidatesReq>,
+    temp_file_facade: Data<TempFileFacade>,
+    template_req: TemplateReq,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let candidate_ids = temp_file_facade
+        .create_thumbnail_candidates(
+            form.temp_video_id,
+            identity.id_i32()?,
+            THUMBNAIL_CANDIDATES_COUNT,
+        )
+        .await?;
+
+    if !template_req.return_template {
+        return Ok(HttpResponse::Created().json(candidate_ids));
+    }
+
+    let template = ThumbnailCandidatesTemplate { candidate_ids };
+
+    Ok(HttpResponse::Created().body(template.render().unwrap()))
+}
+
 /// Get a temporary file for a preview
 ///
 /// `GET /temp/{temp_file}`
@@ -155,6 +304,27 @@ pub async fn get_input_template(
     get_upload_template(temp_file_type, config)
 }
 
+fn parse_upload_offset(request: &HttpRequest) -> Result<i64> {
+    request
+        .headers()
+        .get(UPLOAD_OFFSET_HEADER)
+        .and_then(|offset| offset.to_str().ok())
+        .and_then(|offset| offset.parse::<i64>().ok())
+        .filter(|offset| *offset >= 0)
+        .ok_or(AppError::new(
+            "Missing or invalid Upload-Offset header",
+            AppErrorKind::BadRequestError,
+        ))
+}
+
+fn upload_status_response(mut builder: HttpResponseBuilder, status: UploadStatus) -> HttpResponse {
+    builder
+        .insert_header((UPLOAD_OFFSET_HEADER, status.upload_offset))
+        .insert_header((UPLOAD_LENGTH_HEADER, status.upload_length))
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .finish()
+}
+
 fn get_upload_template(
     temp_file_type: GetFileInputTemplate,
     config: Data<Configuration>,
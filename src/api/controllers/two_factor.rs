@@ -0,0 +1,195 @@
+use crate::api::controllers::user::log_in;
+use crate::api::controllers::utils::request_util::client_ip;
+use crate::api::extractors::htmx_extractor::HtmxRequest;
+use crate::api::extractors::permissions_extractor::AsInteger;
+use crate::api::templates::template::BaseTemplate;
+use crate::api::templates::user::two_factor::template::{
+    TwoFactorChallengeTemplate, TwoFactorRecoveryCodesTemplate, TwoFactorSetupTemplate,
+    TwoFactorTemplate,
+};
+use crate::business::facades::login_attempt::{LoginAttemptFacade, LoginAttemptFacadeTrait};
+use crate::business::facades::two_factor::{TwoFactorFacade, TwoFactorFacadeTrait};
+use crate::business::facades::user::{UserFacade, UserFacadeTrait};
+use crate::business::models::error::{AppError, AppErrorKind};
+use crate::business::models::two_factor::{PendingTwoFactorLogin, TwoFactorCode};
+use crate::business::models::user::UserDetail;
+use crate::business::Result;
+use crate::configuration::models::Configuration;
+use actix_identity::Identity;
+use actix_session::Session;
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use askama_actix::TemplateToResponse;
+use chrono::Utc;
+
+const PENDING_LOGIN_KEY: &str = "pending_two_factor_login";
+/// Time, in which the user has to enter the second factor after the password
+const PENDING_LOGIN_EXPIRY_SECONDS: i64 = 5 * 60;
+/// Invalid codes, after which the user has to enter the password again
+const MAX_FAILED_ATTEMPTS: u32 = 5;
+
+/// Remembers the user, who entered the correct password, until the second factor is checked
+pub fn start_two_factor_login(session: &Session, user: &UserDetail) -> Result<()> {
+    session.insert(
+        PENDING_LOGIN_KEY,
+        PendingTwoFactorLogin {
+            user_id: user.id,
+            username: user.username.clone(),
+            expires_at: Utc::now().timestamp() + PENDING_LOGIN_EXPIRY_SECONDS,
+            failed_attempts: 0,
+        },
+    )?;
+
+    Ok(())
+}
+
+fn get_pending_login(session: &Session) -> Option<PendingTwoFactorLogin> {
+    session
+        .get::<PendingTwoFactorLogin>(PENDING_LOGIN_KEY)
+        .unwrap_or(None)
+        .filter(|pending_login| pending_login.expires_at > Utc::now().timestamp())
+}
+
+/// `GET /user/two-factor`
+pub async fn two_factor_settings(
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let status = two_factor_facade.get_status(identity.id_i32()?).await?;
+
+    Ok(BaseTemplate::wrap(htmx_request, session, TwoFactorTemplate { status }).to_response())
+}
+
+/// Generates a new secret and shows it as a QR code
+///
+/// `POST /user/two-factor/enable`
+pub async fn enable_two_factor(
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let enrolment = two_factor_facade
+        .start_enrolment(identity.id_i32()?)
+        .await?;
+
+    Ok(
+        BaseTemplate::wrap(htmx_request, session, TwoFactorSetupTemplate { enrolment })
+            .to_response(),
+    )
+}
+
+/// Enables two-factor authentication by the first code from the authenticator app
+///
+/// `POST /user/two-factor/confirm`
+pub async fn confirm_two_factor(
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+    form: web::Form<TwoFactorCode>,
+) -> Result<impl Responder> {
+    let recovery_codes = two_factor_facade
+        .confirm_enrolment(identity.id_i32()?, &form.code)
+        .await?;
+
+    Ok(BaseTemplate::wrap(
+        htmx_request,
+        session,
+        TwoFactorRecoveryCodesTemplate { recovery_codes },
+    )
+    .to_response())
+}
+
+/// `POST /user/two-factor/disable`
+pub async fn disable_two_factor(
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    identity: Identity,
+    form: web::Form<TwoFactorCode>,
+) -> Result<impl Responder> {
+    two_factor_facade
+        .disable(identity.id_i32()?, &form.code)
+        .await?;
+
+    Ok(HttpResponse::Ok()
+        .append_header(("HX-Redirect", "/user/two-factor"))
+        .finish())
+}
+
+/// Asks for the second factor of the login, which passed the password check
+///
+/// `GET /user/login/two-factor`
+pub async fn two_factor_challenge_form(
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+) -> impl Responder {
+    if identity.is_some() || get_pending_login(&session).is_none() {
+        return HttpResponse::SeeOther()
+            .append_header(("Location", "/"))
+            .finish();
+    }
+
+    BaseTemplate::wrap(htmx_request, session, TwoFactorChallengeTemplate {}).to_response()
+}
+
+/// `POST /user/login/two-factor`
+pub async fn two_factor_challenge(
+    user_facade: web::Data<UserFacade>,
+    login_attempt_facade: web::Data<LoginAttemptFacade>,
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    config: web::Data<Configuration>,
+    session: Session,
+    request: HttpRequest,
+    form: web::Form<TwoFactorCode>,
+) -> Result<impl Responder> {
+    let Some(mut pending_login) = get_pending_login(&session) else {
+        session.remove(PENDING_LOGIN_KEY);
+        return Err(AppError::new(
+            "The login has expired, log in with your password again",
+            AppErrorKind::BadRequestError,
+        ));
+    };
+    let ip_address = client_ip(&request, &config);
+    login_attempt_facade
+        .check_allowed(&pending_login.username, ip_address.as_deref())
+        .await?;
+
+    if let Err(error) = two_factor_facade
+        .verify(pending_login.user_id, &form.code)
+        .await
+    {
+        if matches!(error.error, AppErrorKind::BadRequestError) {
+            login_attempt_facade
+                .record_failure(&pending_login.username, ip_address.as_deref())
+                .await?;
+        }
+        pending_login.failed_attempts += 1;
+        if pending_login.failed_attempts >= MAX_FAILED_ATTEMPTS {
+            session.remove(PENDING_LOGIN_KEY);
+            return Err(AppError::new(
+                "Too many invalid codes, log in with your password again",
+                AppErrorKind::TooManyRequests,
+            ));
+        }
+
+        session.insert(PENDING_LOGIN_KEY, pending_login)?;
+        return Err(error);
+    }
+
+    session.remove(PENDING_LOGIN_KEY);
+    let user = user_facade
+        .get_user_detail(pending_login.user_id)
+        .await?
+        .ok_or(AppError::new(
+            "User does not exist",
+            AppErrorKind::BadRequestError,
+        ))?;
+    login_attempt_facade.record_success(&user.username).await?;
+    log_in(&user_facade, &session, &request, &config, &user).await?;
+
+    Ok(HttpResponse::Ok()
+        .append_header(("HX-Redirect", "/"))
+        .finish())
+}
@@ -1,28 +1,47 @@
-use crate::api::controllers::utils::video_utils::from_video_to_video_list;
+use crate::api::controllers::two_factor::start_two_factor_login;
+use crate::api::controllers::utils::request_util::client_ip;
+use crate::api::controllers::utils::video_utils::{
+    from_video_to_video_list, from_watched_videos_to_history,
+};
 use crate::api::extractors::htmx_extractor::HtmxRequest;
-use crate::api::extractors::permissions_extractor::AsInteger;
+use crate::api::extractors::permissions_extractor::{AsInteger, AsIntegerOptional};
 use crate::api::templates::template::BaseTemplate;
 use crate::api::templates::user::delete::template::DeleteTemplate;
 use crate::api::templates::user::detail::template::UserDetailTemplate;
+use crate::api::templates::user::history::template::{HistoryItemsTemplate, HistoryTemplate};
 use crate::api::templates::user::liked_videos::template::LikedVideosTemplate;
 use crate::api::templates::user::logged_in::template::UserLoggedInTemplate;
 use crate::api::templates::user::login::template::UserLoginTemplate;
 use crate::api::templates::user::password_change::template::PasswordChangeTemplate;
+use crate::api::templates::user::password_forgot::template::PasswordForgotTemplate;
+use crate::api::templates::user::password_reset::template::PasswordResetTemplate;
 use crate::api::templates::user::register::template::UserRegisterTemplate;
+use crate::api::templates::user::sessions::template::SessionsTemplate;
 use crate::api::templates::user::validation::template::ValidationTemplate;
+use crate::api::templates::user::verification::template::EmailVerificationTemplate;
 use crate::api::templates::video::list::template::VideosTemplate;
 use crate::business::facades::artist::ArtistFacade;
+use crate::business::facades::login_attempt::{LoginAttemptFacade, LoginAttemptFacadeTrait};
+use crate::business::facades::two_factor::{TwoFactorFacade, TwoFactorFacadeTrait};
 use crate::business::facades::user::{UserFacade, UserFacadeTrait};
 use crate::business::facades::video::{VideoFacade, VideoFacadeTrait};
+use crate::business::facades::watch_history::{WatchHistoryFacade, WatchHistoryFacadeTrait};
+use crate::business::models::error::{AppError, AppErrorKind};
 use crate::business::models::user::UserRole::{self, Registered};
 use crate::business::models::user::{
-    EmailQuery, ProfilePictureUpdate, UserDetailUpdate, UserLogin, UserPasswordUpdate,
-    UserRegisterMultipart, UserSessionData, UsernameQuery,
+    EmailQuery, PasswordReset, PasswordResetRequest, ProfilePictureUpdate, TokenQuery, UserDetail,
+    UserDetailUpdate, UserLogin, UserPasswordUpdate, UserRegisterMultipart, UserSessionData,
+    UsernameQuery,
 };
+use crate::business::models::watch_history::{HistoryEntryReq, HistoryPageReq};
 use crate::business::Result;
+use crate::configuration::models::Configuration;
+use crate::persistence::entities::session::SessionInfo;
+use crate::persistence::repositories::session::SESSION_INFO_KEY;
 use actix_identity::Identity;
 use actix_multipart::form::MultipartForm;
 use actix_session::Session;
+use actix_web::http::{header, StatusCode};
 use actix_web::{web, HttpMessage, HttpRequest, HttpResponse, Responder};
 use actix_web_grants::protect;
 use askama_actix::TemplateToResponse;
@@ -48,6 +67,7 @@ pub async fn register_form(
 
 pub async fn register_user(
     user_facade: web::Data<UserFacade>,
+    config: web::Data<Configuration>,
     session: Session,
     request: HttpRequest,
     identity: Option<Identity>,
@@ -61,16 +81,7 @@ pub async fn register_user(
 
     let user = user_facade.register(user_register).await?;
 
-    Identity::login(&request.extensions(), user.id.to_string())?;
-
-    session.insert(
-        "user_session_data",
-        UserSessionData {
-            profile_picture_path: user.profile_picture_path.clone(),
-
-            roles: user_facade.get_permissions(user.id).await?,
-        },
-    )?;
+    log_in(&user_facade, &session, &request, &config, &user).await?;
 
     Ok(UserLoggedInTemplate {
         profile_picture_path: user.profile_picture_path,
@@ -91,8 +102,14 @@ pub async fn login_form(
     }
 }
 
+/// Logs the user in, or asks for the second factor, if the user has it enabled.
+/// Failed logins lock the username and the IP address for a while.
+#[allow(clippy::too_many_arguments)]
 pub async fn login(
     user_facade: web::Data<UserFacade>,
+    two_factor_facade: web::Data<TwoFactorFacade>,
+    login_attempt_facade: web::Data<LoginAttemptFacade>,
+    config: web::Data<Configuration>,
     session: Session,
     request: HttpRequest,
     identity: Option<Identity>,
@@ -104,8 +121,49 @@ pub async fn login(
             .finish());
     }
 
-    let user = user_facade.login(user_login.into_inner()).await?;
+    let user_login = user_login.into_inner();
+    let ip_address = client_ip(&request, &config);
+    login_attempt_facade
+        .check_allowed(&user_login.username, ip_address.as_deref())
+        .await?;
+
+    let user = match user_facade.login(user_login.clone()).await {
+        Ok(user) => user,
+        Err(error) => {
+            if matches!(error.error, AppErrorKind::BadRequestError) {
+                login_attempt_facade
+                    .record_failure(&user_login.username, ip_address.as_deref())
+                    .await?;
+            }
+            return Err(error);
+        }
+    };
+
+    if two_factor_facade.is_enabled(user.id).await? {
+        start_two_factor_login(&session, &user)?;
+
+        return Ok(HttpResponse::Ok()
+            .append_header(("HX-Redirect", "/user/login/two-factor"))
+            .finish());
+    }
+
+    login_attempt_facade.record_success(&user.username).await?;
+    log_in(&user_facade, &session, &request, &config, &user).await?;
 
+    Ok(UserLoggedInTemplate {
+        profile_picture_path: user.profile_picture_path,
+    }
+    .to_response())
+}
+
+/// Starts the session of the user, who passed all checks
+pub async fn log_in(
+    user_facade: &UserFacade,
+    session: &Session,
+    request: &HttpRequest,
+    config: &Configuration,
+    user: &UserDetail,
+) -> Result<()> {
     Identity::login(&request.extensions(), user.id.to_string())?;
 
     session.insert(
@@ -117,10 +175,23 @@ pub async fn login(
         },
     )?;
 
-    Ok(UserLoggedInTemplate {
-        profile_picture_path: user.profile_picture_path,
-    }
-    .to_response())
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(String::from);
+    let ip_address = client_ip(request, config);
+    session.insert(SESSION_INFO_KEY, SessionInfo::new(user_agent, ip_address))?;
+
+    Ok(())
+}
+
+/// Returns the public ID of the session of the request
+fn current_session_id(session: &Session) -> Option<String> {
+    session
+        .get::<SessionInfo>(SESSION_INFO_KEY)
+        .unwrap_or(None)
+        .map(|session_info| session_info.id)
 }
 
 pub async fn user_detail(
@@ -194,7 +265,11 @@ pub async fn change_password(
     user_password_update: web::Form<UserPasswordUpdate>,
 ) -> Result<impl Responder> {
     user_facade
-        .change_password(identity.id_i32()?, user_password_update.into_inner())
+        .change_password(
+            identity.id_i32()?,
+            current_session_id(&session).as_deref(),
+            user_password_update.into_inner(),
+        )
         .await?;
 
     let user_session_data = session
@@ -214,6 +289,72 @@ pub async fn change_password(
     .to_response())
 }
 
+pub async fn forgot_password_form(htmx_request: HtmxRequest, session: Session) -> impl Responder {
+    BaseTemplate::wrap(
+        htmx_request,
+        session,
+        PasswordForgotTemplate { sent: false },
+    )
+    .to_response()
+}
+
+/// Sends the password reset link, the response is the same for unknown emails
+///
+/// `POST /user/forgot-password`
+pub async fn forgot_password(
+    user_facade: web::Data<UserFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    password_reset_request: web::Form<PasswordResetRequest>,
+) -> Result<impl Responder> {
+    user_facade
+        .request_password_reset(&password_reset_request.email)
+        .await?;
+
+    Ok(
+        BaseTemplate::wrap(htmx_request, session, PasswordForgotTemplate { sent: true })
+            .to_response(),
+    )
+}
+
+/// `GET /user/reset-password?token={token}`
+pub async fn reset_password_form(
+    htmx_request: HtmxRequest,
+    session: Session,
+    query: web::Query<TokenQuery>,
+) -> impl Responder {
+    let template = PasswordResetTemplate {
+        token: Some(query.into_inner().token),
+    };
+
+    BaseTemplate::wrap(htmx_request, session, template).to_response()
+}
+
+/// Sets the new password by the token from the reset link
+///
+/// `POST /user/reset-password`
+pub async fn reset_password(
+    user_facade: web::Data<UserFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+    password_reset: web::Form<PasswordReset>,
+) -> Result<impl Responder> {
+    user_facade
+        .reset_password(password_reset.into_inner())
+        .await?;
+
+    // All sessions of the user were deleted, the current one must not be saved again
+    if let Some(identity) = identity {
+        identity.logout();
+    }
+
+    Ok(
+        BaseTemplate::wrap(htmx_request, session, PasswordResetTemplate { token: None })
+            .to_response(),
+    )
+}
+
 pub async fn delete_form(
     htmx_request: HtmxRequest,
     session: Session,
@@ -279,6 +420,7 @@ pub async fn liked_videos(
     let serialized_videos = from_video_to_video_list(videos, artist_facade).await?;
     let template = VideosTemplate {
         videos: serialized_videos,
+        next_page: None,
     };
 
     Ok(template.to_response())
@@ -318,6 +460,84 @@ pub async fn like_video(
         .finish())
 }
 
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn history_page(
+    watch_history_facade: web::Data<WatchHistoryFacade>,
+    artist_facade: web::Data<ArtistFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let items_template =
+        get_history_items(identity.id_i32()?, 0, watch_history_facade, artist_facade).await?;
+    let template = HistoryTemplate { items_template };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn history_items(
+    watch_history_facade: web::Data<WatchHistoryFacade>,
+    artist_facade: web::Data<ArtistFacade>,
+    identity: Identity,
+    query: web::Query<HistoryPageReq>,
+) -> Result<impl Responder> {
+    let template = get_history_items(
+        identity.id_i32()?,
+        query.offset.unwrap_or(0),
+        watch_history_facade,
+        artist_facade,
+    )
+    .await?;
+
+    Ok(template.to_response())
+}
+
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn delete_history_entry(
+    watch_history_facade: web::Data<WatchHistoryFacade>,
+    identity: Identity,
+    path: web::Path<HistoryEntryReq>,
+) -> Result<impl Responder> {
+    watch_history_facade
+        .delete_entry(identity.id_i32()?, path.video_id)
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn clear_history(
+    watch_history_facade: web::Data<WatchHistoryFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    watch_history_facade
+        .clear_history(identity.id_i32()?)
+        .await?;
+
+    let template = HistoryItemsTemplate {
+        items: Vec::new(),
+        first_page: true,
+        next_offset: None,
+    };
+    Ok(template.to_response())
+}
+
+async fn get_history_items(
+    user_id: i32,
+    offset: i64,
+    watch_history_facade: web::Data<WatchHistoryFacade>,
+    artist_facade: web::Data<ArtistFacade>,
+) -> Result<HistoryItemsTemplate> {
+    let (history, next_offset) = watch_history_facade.list_history(user_id, offset).await?;
+
+    Ok(HistoryItemsTemplate {
+        items: from_watched_videos_to_history(history, artist_facade).await?,
+        first_page: offset == 0,
+        next_offset,
+    })
+}
+
 pub async fn validate_username(
     user_facade: web::Data<UserFacade>,
     username_query: web::Query<UsernameQuery>,
@@ -350,6 +570,121 @@ pub async fn validate_email(
     validate_email_template.to_response()
 }
 
+/// Verifies the email by the link sent to the user
+///
+/// `GET /user/verify?token={token}`
+pub async fn verify_email(
+    user_facade: web::Data<UserFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+    query: web::Query<TokenQuery>,
+) -> Result<impl Responder> {
+    let (template, status) = match user_facade.verify_email(&query.token).await {
+        Ok(user) => {
+            // The link can be opened in the browser, where the user is logged in,
+            // so the new role is applied without logging in again
+            if identity.id_i32() == Some(user.id) {
+                session.insert(
+                    "user_session_data",
+                    UserSessionData {
+                        profile_picture_path: user.profile_picture_path,
+                        roles: user_facade.get_permissions(user.id).await?,
+                    },
+                )?;
+            }
+            let template = EmailVerificationTemplate {
+                error_message: None,
+            };
+            (template, StatusCode::OK)
+        }
+        Err(AppError {
+            message,
+            error: AppErrorKind::BadRequestError,
+        }) => {
+            let template = EmailVerificationTemplate {
+                error_message: Some(message),
+            };
+            (template, StatusCode::BAD_REQUEST)
+        }
+        Err(err) => return Err(err),
+    };
+
+    let mut response = BaseTemplate::wrap(htmx_request, session, template).to_response();
+    *response.status_mut() = status;
+    Ok(response)
+}
+
+/// Sends a new verification link to the email of the user
+///
+/// `POST /user/verify/resend`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn resend_verification(
+    user_facade: web::Data<UserFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    user_facade.resend_verification(identity.id_i32()?).await?;
+
+    Ok(HttpResponse::Ok().body("Verification link was sent to your email."))
+}
+
+/// Sessions of the user, which are shown on the account page
+///
+/// `GET /user/sessions`
+pub async fn user_sessions(
+    user_facade: web::Data<UserFacade>,
+    session: Session,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let sessions = user_facade
+        .list_sessions(identity.id_i32()?, current_session_id(&session).as_deref())
+        .await?;
+
+    Ok(SessionsTemplate { sessions }.to_response())
+}
+
+/// Logs the user out of the session, e.g. on a lost device
+///
+/// `POST /user/sessions/{session_id}/logout`
+pub async fn log_out_session(
+    user_facade: web::Data<UserFacade>,
+    session: Session,
+    identity: Identity,
+    session_id: web::Path<String>,
+) -> Result<impl Responder> {
+    let user_id = identity.id_i32()?;
+    let current_session_id = current_session_id(&session);
+    user_facade.log_out_session(user_id, &session_id).await?;
+
+    // The current session was deleted, it must not be saved again
+    if current_session_id.as_deref() == Some(session_id.as_str()) {
+        identity.logout();
+        return Ok(HttpResponse::Ok()
+            .append_header(("HX-Redirect", "/"))
+            .finish());
+    }
+
+    let sessions = user_facade
+        .list_sessions(user_id, current_session_id.as_deref())
+        .await?;
+
+    Ok(SessionsTemplate { sessions }.to_response())
+}
+
+/// `POST /user/sessions/logout-all`
+pub async fn log_out_everywhere(
+    user_facade: web::Data<UserFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    user_facade.log_out_everywhere(identity.id_i32()?).await?;
+
+    identity.logout();
+
+    Ok(HttpResponse::Ok()
+        .append_header(("HX-Redirect", "/"))
+        .finish())
+}
+
 pub async fn logout(user: Identity) -> impl Responder {
     user.logout();
     HttpResponse::SeeOther()
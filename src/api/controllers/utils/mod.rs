@@ -1,3 +1,4 @@
 pub mod comment_utils;
+pub mod request_util;
 pub mod route_util;
 pub mod video_utils;
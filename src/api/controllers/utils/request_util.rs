@@ -0,0 +1,30 @@
+use crate::configuration::models::Configuration;
+use actix_web::HttpRequest;
+use std::net::IpAddr;
+
+/// Header, in which the trusted proxies pass the address of the client
+const REAL_IP_HEADER: &str = "X-Real-IP";
+
+/// Returns IP address of the client, who sent the request.
+///
+/// Any client can send the `X-Real-IP` header, so it's read only on the requests from
+/// the trusted proxies in the configuration. Requests from a trusted proxy without a valid
+/// header have no address, otherwise all clients of the proxy would share its address.
+pub fn client_ip(request: &HttpRequest, config: &Configuration) -> Option<String> {
+    let peer_ip = request.peer_addr()?.ip().to_canonical();
+    let from_proxy = config
+        .app
+        .trusted_proxies
+        .iter()
+        .any(|network| network.contains(peer_ip));
+    if !from_proxy {
+        return Some(peer_ip.to_string());
+    }
+
+    request
+        .headers()
+        .get(REAL_IP_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<IpAddr>().ok())
+        .map(|ip| ip.to_canonical().to_string())
+}
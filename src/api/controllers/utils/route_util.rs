@@ -6,6 +6,10 @@ pub fn build_get_temp_path(file_id: i32) -> String {
     format!("/temp/{file_id}")
 }
 
+pub fn build_upload_path(upload_id: i32) -> String {
+    format!("/temp/upload/{upload_id}")
+}
+
 pub fn build_get_video_path(video_id: i32) -> (String, String) {
     (
         format!("/video/{video_id}"),
@@ -13,9 +17,29 @@ pub fn build_get_video_path(video_id: i32) -> (String, String) {
     )
 }
 
+pub fn build_hls_playlist_path(video_id: i32) -> String {
+    format!("/video/{video_id}/hls/master.m3u8")
+}
+
+pub fn build_view_path(video_id: i32) -> String {
+    format!("/video/{video_id}/view")
+}
+
+pub fn build_position_path(video_id: i32) -> String {
+    format!("/video/{video_id}/position")
+}
+
 pub fn build_watch_path(video_id: i32) -> String {
     format!("/video/{}/watch", video_id)
 }
+
+pub fn build_playlist_path(playlist_id: i32, video_id: Option<i32>) -> String {
+    match video_id {
+        Some(video_id) => format!("/playlist/{playlist_id}?video_id={video_id}"),
+        None => format!("/playlist/{playlist_id}"),
+    }
+}
+
 pub fn build_stream_watch_path(stream_id: i32) -> String {
     format!("/stream/{stream_id}/watch")
 }
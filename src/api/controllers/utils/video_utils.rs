@@ -1,10 +1,18 @@
 use crate::api::controllers::utils::route_util::build_get_video_path;
 use crate::business::facades::artist::{ArtistFacade, ArtistFacadeTrait};
-use crate::business::models::video::VideoList;
+use crate::business::models::feed::FeedItem;
+use crate::business::models::stream::StreamListItem;
+use crate::business::models::video::VideoVisibility as VideoVisibilityDto;
+use crate::business::models::video::{highlight_to_html, VideoList, VideoSearchResult};
+use crate::business::models::watch_history::WatchHistoryItem;
 use crate::business::Result;
-use crate::persistence::entities::video::Video;
+use crate::persistence::entities::feed::FeedEntry;
+use crate::persistence::entities::stream::ListedStream;
+use crate::persistence::entities::video::{Video, VideoSearchHit};
+use crate::persistence::entities::watch_history::WatchedVideo;
 use actix_web::web::Data;
 use anyhow::Error;
+use std::collections::HashMap;
 
 pub fn parse_option_string(input: Option<String>) -> Result<Option<Vec<i32>>, Error> {
     if let Some(s) = input {
@@ -44,6 +52,9 @@ pub async fn from_video_to_video_list(
                     artist_name: artist.name.clone(),
                     thumbnail_path,
                     name: video.name.clone(),
+                    duration_ms: video.duration_ms,
+                    height: video.height,
+                    draft: video.draft,
                 })
             }
         }
@@ -51,3 +62,136 @@ pub async fn from_video_to_video_list(
 
     Ok(serialized_videos)
 }
+
+pub async fn from_search_hits_to_results(
+    hits: Vec<VideoSearchHit>,
+    artist_facade: Data<ArtistFacade>,
+) -> Result<Vec<VideoSearchResult>> {
+    let mut highlights = HashMap::with_capacity(hits.len());
+    let mut videos = Vec::with_capacity(hits.len());
+    for hit in hits {
+        highlights.insert(hit.video.id, (hit.name_highlight, hit.description_snippet));
+        videos.push(hit.video);
+    }
+
+    let video_list = from_video_to_video_list(videos, artist_facade).await?;
+    let results = video_list
+        .into_iter()
+        .filter_map(|video| {
+            let (name_highlight, snippet) = highlights.remove(&video.id)?;
+            Some(VideoSearchResult {
+                video,
+                name_html: highlight_to_html(&name_highlight),
+                snippet_html: snippet.as_deref().map(highlight_to_html),
+            })
+        })
+        .collect();
+
+    Ok(results)
+}
+
+pub async fn from_watched_videos_to_history(
+    watched_videos: Vec<WatchedVideo>,
+    artist_facade: Data<ArtistFacade>,
+) -> Result<Vec<WatchHistoryItem>> {
+    let mut positions = HashMap::with_capacity(watched_videos.len());
+    let mut videos = Vec::with_capacity(watched_videos.len());
+    for watched in watched_videos {
+        positions.insert(watched.video.id, (watched.position_ms, watched.watched_at));
+        videos.push(watched.video);
+    }
+
+    let video_list = from_video_to_video_list(videos, artist_facade).await?;
+    let items = video_list
+        .into_iter()
+        .filter_map(|video| {
+            let (position_ms, watched_at) = positions.remove(&video.id)?;
+            Some(WatchHistoryItem {
+                video,
+                position_ms,
+                watched_at,
+            })
+        })
+        .collect();
+
+    Ok(items)
+}
+
+pub async fn from_feed_entries_to_items(
+    entries: Vec<FeedEntry>,
+    artist_facade: Data<ArtistFacade>,
+) -> Result<Vec<FeedItem>> {
+    // The same video can be both uploaded and streamed
+    let mut details = Vec::with_capacity(entries.len());
+    let mut videos = Vec::with_capacity(entries.len());
+    for entry in entries {
+        details.push((entry.video.id, entry.stream_id, entry.published_at));
+        if !videos
+            .iter()
+            .any(|video: &Video| video.id == entry.video.id)
+        {
+            videos.push(entry.video);
+        }
+    }
+
+    let video_list: HashMap<i32, VideoList> = from_video_to_video_list(videos, artist_facade)
+        .await?
+        .into_iter()
+        .map(|video| (video.id, video))
+        .collect();
+    let items = details
+        .into_iter()
+        .filter_map(|(video_id, stream_id, published_at)| {
+            Some(FeedItem {
+                video: video_list.get(&video_id)?.clone(),
+                stream_id,
+                published_at,
+            })
+        })
+        .collect();
+
+    Ok(items)
+}
+
+pub async fn from_listed_streams_to_items(
+    streams: Vec<ListedStream>,
+    artist_facade: Data<ArtistFacade>,
+) -> Result<Vec<StreamListItem>> {
+    // The same video can be streamed more times
+    let mut details = Vec::with_capacity(streams.len());
+    let mut videos = Vec::with_capacity(streams.len());
+    for stream in streams {
+        details.push((
+            stream.video.id,
+            stream.stream_id,
+            VideoVisibilityDto::from(&stream.video.visibility),
+            stream.start_time,
+        ));
+        if !videos
+            .iter()
+            .any(|video: &Video| video.id == stream.video.id)
+        {
+            videos.push(stream.video);
+        }
+    }
+
+    let video_list: HashMap<i32, VideoList> = from_video_to_video_list(videos, artist_facade)
+        .await?
+        .into_iter()
+        .map(|video| (video.id, video))
+        .collect();
+    let items = details
+        .into_iter()
+        .filter_map(|(video_id, stream_id, visibility, start_time)| {
+            Some(StreamListItem {
+                stream_id,
+                video: video_list.get(&video_id)?.clone(),
+                visibility,
+                start_time,
+                viewer_count: None,
+            })
+        })
+        .collect();
+
+    Ok(items)
+}
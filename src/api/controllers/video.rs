@@ -1,38 +1,47 @@
+use crate::api::controllers::utils::request_util::client_ip;
 use crate::api::controllers::utils::route_util::{add_redirect_header, build_watch_path};
-use crate::api::controllers::utils::video_utils::{from_video_to_video_list, parse_option_string};
+use crate::api::controllers::utils::video_utils::{
+    from_search_hits_to_results, from_video_to_video_list, parse_option_string,
+};
 use crate::api::extractors::htmx_extractor::HtmxRequest;
 use crate::api::extractors::permissions_extractor::{AsInteger, AsIntegerOptional};
 use crate::api::extractors::template_extractor::TemplateReq;
+use crate::api::templates::artist::template::FollowTemplate;
 use crate::api::templates::template::BaseTemplate;
 use crate::api::templates::video::edit::template::EditVideoTemplate;
 use crate::api::templates::video::list::template::{
     IndexTemplate, VideoGridTemplate, VideosTemplate,
 };
+use crate::api::templates::video::search::template::{SearchResultsTemplate, SearchTemplate};
 use crate::api::templates::video::show::template::{
-    PlayerTemplate, ShowVideoTemplate, VideoNotAllowed,
+    PlayerTemplate, ShowVideoTemplate, VideoNotAllowed, ViewStatsTemplate,
 };
 use crate::api::templates::video::upload::template::{
     ThumbnailPreviewTemplate, ThumbnailUploadInputTemplate, VideoPreviewTemplate,
     VideoUploadInputTemplate, VideoUploadTemplate,
 };
-use crate::business::facades::artist::ArtistFacade;
+use crate::business::facades::artist::{ArtistFacade, ArtistFacadeTrait};
 use crate::business::facades::user::{UserFacade, UserFacadeTrait};
 use crate::business::facades::video::{VideoFacade, VideoFacadeTrait};
 use crate::business::facades::video_category::{VideoCategoryFacade, VideoCategoryFacadeTrait};
+use crate::business::facades::view::{ViewFacade, ViewFacadeTrait};
+use crate::business::facades::watch_history::{WatchHistoryFacade, WatchHistoryFacadeTrait};
 use crate::business::models::error::MapToAppError;
-use crate::business::models::user::UserRole::{self, Artist};
+use crate::business::models::user::UserRole::{self, Artist, Registered, Verified};
 use crate::business::models::video::{
-    FetchVideoByFilters, GetVideoByIdReq, VideoEditReq, VideoList, VideoUploadReq,
+    FetchVideoByFilters, GetHlsFileReq, GetVideoByIdReq, SearchVideosReq, VideoEditReq, VideoList,
+    VideoSort, VideoUploadReq, SEARCH_PAGE_SIZE, VIDEO_PAGE_SIZE,
 };
+use crate::business::models::watch_history::SavePositionReq;
 use crate::configuration::models::Configuration;
 use actix_files::NamedFile;
 use actix_identity::Identity;
 use actix_session::Session;
-use actix_web::web::{Data, Path, Query};
-use actix_web::{HttpResponse, Responder, Result};
+use actix_web::web::{Data, Form, Path, Query};
+use actix_web::{HttpRequest, HttpResponse, Responder, Result};
 use actix_web_grants::protect;
 use askama_actix::TemplateToResponse;
-use serde_qs::actix::QsForm;
+use serde_qs::actix::{QsForm, QsQuery};
 
 /// Creates new video
 ///
@@ -43,7 +52,7 @@ use serde_qs::actix::QsForm;
 ///
 /// # Returns
 /// Redirects user to the newly created video
-#[protect(any("Artist"), ty = "UserRole")]
+#[protect(all("Artist", "Verified"), ty = "UserRole")]
 pub async fn create_video(
     form: QsForm<VideoUploadReq>,
     video_facade: Data<VideoFacade>,
@@ -75,12 +84,14 @@ pub async fn create_video(
 ///
 /// # Returns
 /// Redirects user to the patched video
+#[allow(clippy::too_many_arguments)]
 #[protect(any("Artist"), ty = "UserRole")]
 pub async fn patch_video(
     path: Path<GetVideoByIdReq>,
     form: QsForm<VideoEditReq>,
     video_facade: Data<VideoFacade>,
     user_facade: Data<UserFacade>,
+    artist_facade: Data<ArtistFacade>,
     identity: Identity,
     session: Session,
 ) -> Result<impl Responder> {
@@ -90,9 +101,14 @@ pub async fn patch_video(
         .await?;
 
     let video_artist_id = video.artist_id;
+    let follow_info = artist_facade
+        .get_follow_info(video_artist_id, Some(user_id))
+        .await?;
     let template = ShowVideoTemplate {
         video,
-        player_template: PlayerTemplate::from_saved(path.id),
+        player_template: PlayerTemplate::from_saved(path.id)
+            .with_hls(video_facade.has_hls_playlist(path.id)),
+        follow_template: FollowTemplate { info: follow_info },
         session,
         user_id,
         is_liked: user_facade.is_liked_already(user_id, path.id).await?,
@@ -130,6 +146,28 @@ pub async fn delete_video(
     Ok(response)
 }
 
+/// Publishes the draft video, so other users can watch it
+///
+/// `POST /video/{id}/publish`
+///
+/// # Returns
+/// Redirects user to the published video
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn publish_video(
+    path: Path<GetVideoByIdReq>,
+    video_facade: Data<VideoFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    video_facade
+        .publish_video(identity.id_i32()?, path.id)
+        .await?;
+
+    let mut response = HttpResponse::NoContent().finish();
+
+    add_redirect_header(&build_watch_path(path.id), &mut response)?;
+    Ok(response)
+}
+
 /// Returns video file
 ///
 /// `GET /video/{id}`
@@ -147,6 +185,23 @@ pub async fn get_video(
     Ok(file)
 }
 
+/// Returns HLS playlist or segment of the transcoded video
+///
+/// `GET /video/{id}/hls/{file}`
+///
+/// # Returns
+/// File with the playlist or the segment
+pub async fn get_hls_file(
+    request: Path<GetHlsFileReq>,
+    video_facade: Data<VideoFacade>,
+    identity: Option<Identity>,
+) -> Result<NamedFile> {
+    let file = video_facade
+        .get_hls_file(request.id, identity.id_i32(), request.file.as_str())
+        .await?;
+    Ok(file)
+}
+
 /// Returns thumbnail file
 ///
 /// `GET /thumbnail/{id}`
@@ -168,10 +223,14 @@ pub async fn get_thumbnail(
 ///
 /// # Returns
 /// `ShowVideoTemplate` - template with video view
+#[allow(clippy::too_many_arguments)]
 pub async fn watch_video(
     req: Path<GetVideoByIdReq>,
     video_facade: Data<VideoFacade>,
     user_facade: Data<UserFacade>,
+    watch_history_facade: Data<WatchHistoryFacade>,
+    artist_facade: Data<ArtistFacade>,
+    config: Data<Configuration>,
     htmx_request: HtmxRequest,
     session: Session,
     identity: Option<Identity>,
@@ -184,9 +243,24 @@ pub async fn watch_video(
     let video_id = video.id;
     let video_artist_id = video.artist_id;
 
+    let mut player_template = PlayerTemplate::from_saved(video_id)
+        .with_hls(video_facade.has_hls_playlist(video_id))
+        .with_view_tracking(config.app.views.threshold_seconds);
+    if let Some(user_id) = user_id {
+        let resume_position = watch_history_facade
+            .get_resume_position(user_id, video_id, video.duration_ms)
+            .await?;
+        player_template = player_template.with_watch_history(resume_position);
+    }
+
+    let follow_info = artist_facade
+        .get_follow_info(video_artist_id, user_id)
+        .await?;
+
     let template = ShowVideoTemplate {
         video,
-        player_template: PlayerTemplate::from_saved(video_id),
+        player_template,
+        follow_template: FollowTemplate { info: follow_info },
         session: session.clone(),
         user_id: user_id.unwrap_or(-1),
         is_liked: user_facade
@@ -201,6 +275,70 @@ pub async fn watch_video(
     Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
 }
 
+/// Records the view of the video, once the viewer played enough of it.
+/// Repeated views of the same viewer are counted only once within the configured window.
+///
+/// `POST /video/{id}/view`
+///
+/// # Returns
+/// `204 No Content` even if the view was not counted
+pub async fn record_view(
+    request: Path<GetVideoByIdReq>,
+    view_facade: Data<ViewFacade>,
+    identity: Option<Identity>,
+    http_request: HttpRequest,
+    config: Data<Configuration>,
+) -> Result<impl Responder> {
+    let ip_address = client_ip(&http_request, &config);
+    view_facade
+        .record_view(request.id, identity.id_i32(), ip_address.as_deref())
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Saves the position of the playback to the watch history of the user
+///
+/// `POST /video/{id}/position`
+///
+/// # Form params
+/// `SavePositionReq` - current position of the playback
+///
+/// # Returns
+/// `204 No Content`
+#[protect(any("Registered"), ty = "UserRole")]
+pub async fn save_position(
+    request: Path<GetVideoByIdReq>,
+    form: Form<SavePositionReq>,
+    watch_history_facade: Data<WatchHistoryFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    watch_history_facade
+        .save_position(identity.id_i32()?, request.id, form.into_inner())
+        .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Returns view statistics of the video to its artist
+///
+/// `GET /video/{id}/stats`
+///
+/// # Returns
+/// `ViewStatsTemplate` - total views and views of the last days
+#[protect(any("Artist"), ty = "UserRole")]
+pub async fn view_stats(
+    request: Path<GetVideoByIdReq>,
+    view_facade: Data<ViewFacade>,
+    identity: Identity,
+) -> Result<impl Responder> {
+    let stats = view_facade
+        .get_view_stats(request.id, identity.id_i32()?)
+        .await?;
+
+    Ok(ViewStatsTemplate { stats }.to_response())
+}
+
 pub async fn main_page(
     category_facade: Data<VideoCategoryFacade>,
     session: Session,
@@ -216,6 +354,7 @@ pub async fn main_page(
     let template = IndexTemplate {
         videos_template: VideoGridTemplate {},
         categories,
+        sorts: VideoSort::ALL.to_vec(),
     };
 
     BaseTemplate::wrap(htmx_request, session, template).to_response()
@@ -228,8 +367,17 @@ pub async fn list_videos(
 ) -> Result<impl Responder> {
     let serialized_videos = get_videos(video_facade, artist_facade, req.clone()).await?;
 
+    let has_next_page = serialized_videos.len() as i64 == VIDEO_PAGE_SIZE;
+    let next_page = FetchVideoByFilters {
+        after: serialized_videos
+            .last()
+            .filter(|_| has_next_page)
+            .map(|video| video.id),
+        ..req.into_inner()
+    };
     let template = VideosTemplate {
         videos: serialized_videos,
+        next_page: Some(next_page),
     };
 
     Ok(template.to_response())
@@ -240,24 +388,94 @@ pub async fn get_videos(
     artist_facade: Data<ArtistFacade>,
     req: Query<FetchVideoByFilters>,
 ) -> Result<Vec<VideoList>> {
-    let offset = req.offset;
     let filter: Option<Vec<i32>> = parse_option_string(req.filter.clone()).app_error("filter")?;
-    let ord = req.ord.as_deref();
 
-    let videos = video_facade.fetch_videos(ord, filter, offset).await?;
+    let videos = video_facade
+        .fetch_videos(req.sort, filter, req.after)
+        .await?;
 
     let serialized_videos = from_video_to_video_list(videos, artist_facade).await?;
 
     Ok(serialized_videos)
 }
 
+/// Returns page with results of the full-text search
+///
+/// `GET /video/search`
+///
+/// # Query params
+/// `SearchVideosReq` - searched text and categories, which the videos must belong to
+///
+/// # Returns
+/// `SearchTemplate` - the most relevant videos together with category filter
+pub async fn search_videos(
+    query: QsQuery<SearchVideosReq>,
+    video_facade: Data<VideoFacade>,
+    artist_facade: Data<ArtistFacade>,
+    category_facade: Data<VideoCategoryFacade>,
+    htmx_request: HtmxRequest,
+    session: Session,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let req = query.into_inner();
+    let results_template =
+        get_search_results(&req, video_facade, artist_facade, identity.id_i32()).await?;
+    let template = SearchTemplate {
+        query: req.q,
+        categories: category_facade.list_categories().await?,
+        selected_category_ids: req.category_ids.unwrap_or_default(),
+        results_template,
+    };
+
+    Ok(BaseTemplate::wrap(htmx_request, session, template).to_response())
+}
+
+/// Returns next page of the search results
+///
+/// `GET /video/search/results`
+///
+/// # Query params
+/// `SearchVideosReq` - searched text, categories and offset of the page
+///
+/// # Returns
+/// `SearchResultsTemplate` - found videos with highlighted matches
+pub async fn search_results(
+    query: QsQuery<SearchVideosReq>,
+    video_facade: Data<VideoFacade>,
+    artist_facade: Data<ArtistFacade>,
+    identity: Option<Identity>,
+) -> Result<impl Responder> {
+    let template =
+        get_search_results(&query, video_facade, artist_facade, identity.id_i32()).await?;
+    Ok(template.to_response())
+}
+
+async fn get_search_results(
+    req: &SearchVideosReq,
+    video_facade: Data<VideoFacade>,
+    artist_facade: Data<ArtistFacade>,
+    user_id: Option<i32>,
+) -> Result<SearchResultsTemplate> {
+    let offset = req.offset.unwrap_or(0);
+    let hits = video_facade
+        .search_videos(&req.q, req.category_ids.clone(), offset, user_id)
+        .await?;
+    let has_next_page = hits.len() as i64 == SEARCH_PAGE_SIZE;
+
+    Ok(SearchResultsTemplate {
+        results: from_search_hits_to_results(hits, artist_facade).await?,
+        first_page: offset == 0,
+        next_offset: has_next_page.then_some(offset + SEARCH_PAGE_SIZE),
+    })
+}
+
 /// Returns template with create new video form
 ///
 /// `GET /video/new`
 ///
 /// # Returns
 /// `VideoUploadTemplate`
-#[protect(any("Artist"), ty = "UserRole")]
+#[protect(all("Artist", "Verified"), ty = "UserRole")]
 pub async fn upload_video_template(
     htmx_request: HtmxRequest,
     session: Session,
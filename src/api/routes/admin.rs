@@ -2,8 +2,9 @@ use actix_web::web;
 
 use crate::api::controllers::admin::{
     add_category, add_deal, delete_category, delete_deal, edit_deal, get_admin_add_deal_form,
-    get_admin_categories, get_admin_deals, get_admin_edit_deal_form, get_admin_section, get_users,
-    make_user_admin, make_user_artist, revoke_user_admin,
+    get_admin_categories, get_admin_deals, get_admin_edit_deal_form, get_admin_section,
+    get_locked_accounts, get_users, make_user_admin, make_user_artist, reset_user_two_factor,
+    revoke_user_admin, unlock_account,
 };
 
 pub fn admin_routes(cfg: &mut web::ServiceConfig) {
@@ -29,6 +30,12 @@ pub fn admin_routes(cfg: &mut web::ServiceConfig) {
                 "/users/{user_id}/revoke-admin",
                 web::post().to(revoke_user_admin),
             )
+            .route(
+                "/users/{user_id}/reset-two-factor",
+                web::post().to(reset_user_two_factor),
+            )
+            .route("/locked-accounts", web::get().to(get_locked_accounts))
+            .route("/locked-accounts/unlock", web::post().to(unlock_account))
             .route("/categories", web::get().to(get_admin_categories))
             .route("/categories", web::post().to(add_category))
             .route(
@@ -0,0 +1,18 @@
+use crate::api::controllers::artist::{
+    artist_profile, artist_videos, description_form, follow_artist, get_description,
+    unfollow_artist, update_description,
+};
+use actix_web::web;
+
+pub fn artist_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/artist")
+            .route("/{id}", web::get().to(artist_profile))
+            .route("/{id}/videos", web::get().to(artist_videos))
+            .route("/{id}/description", web::get().to(get_description))
+            .route("/{id}/description", web::patch().to(update_description))
+            .route("/{id}/description/edit", web::get().to(description_form))
+            .route("/{id}/follow", web::post().to(follow_artist))
+            .route("/{id}/follow", web::delete().to(unfollow_artist)),
+    );
+}
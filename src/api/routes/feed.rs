@@ -0,0 +1,10 @@
+use crate::api::controllers::feed::{feed_items, feed_page};
+use actix_web::web;
+
+pub fn feed_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/feed")
+            .route("/items", web::get().to(feed_items))
+            .route("", web::get().to(feed_page)),
+    );
+}
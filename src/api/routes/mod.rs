@@ -1,6 +1,9 @@
 pub mod admin;
+pub mod artist;
 pub mod comment;
+pub mod feed;
 pub mod membership;
+pub mod playlist;
 pub mod stream;
 pub mod temp_file;
 pub mod user;
@@ -0,0 +1,29 @@
+use crate::api::controllers::playlist::{
+    add_playlist_item, create_playlist, delete_playlist, edit_playlist_form, list_playlists,
+    move_playlist_item, new_playlist_form, play_playlist, remove_playlist_item, select_playlist,
+    update_playlist,
+};
+use actix_web::web;
+
+pub fn playlist_routes(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/playlist")
+            .route("/new", web::get().to(new_playlist_form))
+            .route("/select", web::get().to(select_playlist))
+            .route("/{id}", web::get().to(play_playlist))
+            .route("/{id}", web::patch().to(update_playlist))
+            .route("/{id}", web::delete().to(delete_playlist))
+            .route("/{id}/edit", web::get().to(edit_playlist_form))
+            .route("/{id}/items", web::post().to(add_playlist_item))
+            .route(
+                "/{id}/items/{video_id}",
+                web::patch().to(move_playlist_item),
+            )
+            .route(
+                "/{id}/items/{video_id}",
+                web::delete().to(remove_playlist_item),
+            )
+            .route("", web::post().to(create_playlist))
+            .route("", web::get().to(list_playlists)),
+    );
+}
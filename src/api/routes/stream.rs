@@ -1,14 +1,41 @@
 use crate::api::controllers::stream::{
-    authenticate_stream_request, start_stream, stop_stream, watch_stream,
+    add_reminder, authenticate_stream_request, delete_reminder, live_reminders, publish_stream,
+    regenerate_stream_key, schedule_stream, start_rtmp_stream, start_stream, stop_stream,
+    stream_directory, stream_key_page, stream_stats, watch_stream,
+};
+use crate::api::controllers::stream_chat::{
+    chat_events, delete_message, export_transcript, get_chat, send_message, timeout_user,
 };
 use actix_web::web;
 
 pub fn stream_routes(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/stream")
+            .route("", web::get().to(stream_directory))
             .route("/auth", web::get().to(authenticate_stream_request))
             .route("/start", web::post().to(start_stream))
+            .route("/live", web::post().to(start_rtmp_stream))
+            .route("/schedule", web::post().to(schedule_stream))
+            .route("/reminders", web::get().to(live_reminders))
+            .route("/publish", web::post().to(publish_stream))
+            .route("/key", web::get().to(stream_key_page))
+            .route("/key", web::post().to(regenerate_stream_key))
             .route("/{stream_id}/watch", web::get().to(watch_stream))
-            .route("/{stream_id}/stop", web::delete().to(stop_stream)),
+            .route("/{stream_id}/stop", web::delete().to(stop_stream))
+            .route("/{stream_id}/stats", web::get().to(stream_stats))
+            .route("/{stream_id}/reminder", web::post().to(add_reminder))
+            .route("/{stream_id}/reminder", web::delete().to(delete_reminder))
+            .route("/{stream_id}/chat", web::get().to(get_chat))
+            .route("/{stream_id}/chat", web::post().to(send_message))
+            .route("/{stream_id}/chat/events", web::get().to(chat_events))
+            .route("/{stream_id}/chat/timeout", web::post().to(timeout_user))
+            .route(
+                "/{stream_id}/chat/transcript",
+                web::get().to(export_transcript),
+            )
+            .route(
+                "/{stream_id}/chat/{message_id}",
+                web::delete().to(delete_message),
+            ),
     );
 }
@@ -1,5 +1,6 @@
 use crate::api::controllers::temp_file::{
-    delete_temp_file, get_input_template, get_temp_file, post_temp_thumbnail, post_temp_video,
+    delete_temp_file, finalize_upload, get_input_template, get_temp_file, head_upload,
+    patch_upload, post_temp_thumbnail, post_temp_video, post_thumbnail_candidates, post_upload,
 };
 use actix_web::web;
 
@@ -10,6 +11,14 @@ pub fn temp_file_routes(cfg: &mut web::ServiceConfig) {
             .route("/{temp_file}", web::get().to(get_temp_file))
             .route("/video", web::post().to(post_temp_video))
             .route("/{temp_file}", web::delete().to(delete_temp_file))
-            .route("/thumbnail", web::post().to(post_temp_thumbnail)),
+            .route("/thumbnail", web::post().to(post_temp_thumbnail))
+            .route(
+                "/thumbnail/candidates",
+                web::post().to(post_thumbnail_candidates),
+            )
+            .route("/upload", web::post().to(post_upload))
+            .route("/upload/{id}", web::head().to(head_upload))
+            .route("/upload/{id}", web::patch().to(patch_upload))
+            .route("/upload/{id}/finalize", web::post().to(finalize_upload)),
     );
 }
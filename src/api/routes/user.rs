@@ -1,7 +1,14 @@
+use crate::api::controllers::two_factor::{
+    confirm_two_factor, disable_two_factor, enable_two_factor, two_factor_challenge,
+    two_factor_challenge_form, two_factor_settings,
+};
 use crate::api::controllers::user::{
-    change_password, change_password_form, delete, delete_form, like_video, liked_videos,
-    likes_page, login, login_form, logout, profile_picture_update, register_form, register_user,
-    user_detail, user_update, validate_email, validate_username,
+    change_password, change_password_form, clear_history, delete, delete_form,
+    delete_history_entry, forgot_password, forgot_password_form, history_items, history_page,
+    like_video, liked_videos, likes_page, log_out_everywhere, log_out_session, login, login_form,
+    logout, profile_picture_update, register_form, register_user, resend_verification,
+    reset_password, reset_password_form, user_detail, user_sessions, user_update, validate_email,
+    validate_username, verify_email,
 };
 use actix_web::web;
 use actix_web::web::scope;
@@ -18,18 +25,46 @@ pub fn user_routes(cfg: &mut web::ServiceConfig) {
             .route("/register", web::post().to(register_user))
             .route("/login", web::get().to(login_form))
             .route("/login", web::post().to(login))
+            .route(
+                "/login/two-factor",
+                web::get().to(two_factor_challenge_form),
+            )
+            .route("/login/two-factor", web::post().to(two_factor_challenge))
             .route("/logout", web::get().to(logout))
+            .route("/verify", web::get().to(verify_email))
+            .route("/verify/resend", web::post().to(resend_verification))
             .route("/account", web::get().to(user_detail))
             .route("/account/edit", web::post().to(user_update))
             .route(
                 "/account/profile-picture-update",
                 web::post().to(profile_picture_update),
             )
+            .route("/sessions", web::get().to(user_sessions))
+            .route("/sessions/logout-all", web::post().to(log_out_everywhere))
+            .route(
+                "/sessions/{session_id}/logout",
+                web::post().to(log_out_session),
+            )
             .route("/likes", web::get().to(likes_page))
             .route("/liked-videos", web::get().to(liked_videos))
             .route("/like/{video_id}", web::post().to(like_video))
+            .route("/history", web::get().to(history_page))
+            .route("/history", web::delete().to(clear_history))
+            .route("/history/items", web::get().to(history_items))
+            .route(
+                "/history/{video_id}",
+                web::delete().to(delete_history_entry),
+            )
             .route("/change-password", web::get().to(change_password_form))
             .route("/change-password", web::post().to(change_password))
+            .route("/forgot-password", web::get().to(forgot_password_form))
+            .route("/forgot-password", web::post().to(forgot_password))
+            .route("/reset-password", web::get().to(reset_password_form))
+            .route("/reset-password", web::post().to(reset_password))
+            .route("/two-factor", web::get().to(two_factor_settings))
+            .route("/two-factor/enable", web::post().to(enable_two_factor))
+            .route("/two-factor/confirm", web::post().to(confirm_two_factor))
+            .route("/two-factor/disable", web::post().to(disable_two_factor))
             .route("/delete", web::get().to(delete_form))
             .route("/delete", web::post().to(delete)),
     );
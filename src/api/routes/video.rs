@@ -1,6 +1,7 @@
 use crate::api::controllers::video::{
-    create_video, delete_video, edit_video_template, get_thumbnail, get_video, list_videos,
-    main_page, patch_video, upload_video_template, watch_video,
+    create_video, delete_video, edit_video_template, get_hls_file, get_thumbnail, get_video,
+    list_videos, main_page, patch_video, publish_video, record_view, save_position, search_results,
+    search_videos, upload_video_template, view_stats, watch_video,
 };
 use actix_web::web;
 
@@ -9,11 +10,18 @@ pub fn video_routes(cfg: &mut web::ServiceConfig) {
         .service(
             web::scope("/video")
                 .route("/new", web::get().to(upload_video_template))
+                .route("/search", web::get().to(search_videos))
+                .route("/search/results", web::get().to(search_results))
                 .route("{id}", web::get().to(get_video))
                 .route("{id}", web::patch().to(patch_video))
                 .route("{id}", web::delete().to(delete_video))
                 .route("/{id}/edit", web::get().to(edit_video_template))
                 .route("/{id}/watch", web::get().to(watch_video))
+                .route("/{id}/hls/{file:.*}", web::get().to(get_hls_file))
+                .route("/{id}/view", web::post().to(record_view))
+                .route("/{id}/stats", web::get().to(view_stats))
+                .route("/{id}/position", web::post().to(save_position))
+                .route("/{id}/publish", web::post().to(publish_video))
                 .route("", web::post().to(create_video))
                 .route("", web::get().to(list_videos)),
         )
@@ -0,0 +1,9 @@
+use askama_actix::Template;
+
+use crate::business::models::login_attempt::LockedAccount;
+
+#[derive(Template)]
+#[template(path = "admin/locked_accounts/index.html")]
+pub struct AdminLockedAccountsTemplate {
+    pub locked_accounts: Vec<LockedAccount>,
+}
@@ -2,5 +2,6 @@ pub mod categories;
 pub mod deals;
 pub mod edit_deal;
 pub mod index;
+pub mod locked_accounts;
 pub mod template;
 pub mod users;
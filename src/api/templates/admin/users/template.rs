@@ -1,4 +1,5 @@
 use askama_actix::Template;
+use std::collections::HashSet;
 
 use crate::business::models::user::UserDetail;
 
@@ -6,4 +7,12 @@ use crate::business::models::user::UserDetail;
 #[template(path = "admin/users/index.html")]
 pub struct AdminUsersTemplate {
     pub users: Vec<UserDetail>,
+    /// Users, who have two-factor authentication enabled
+    pub two_factor_user_ids: HashSet<i32>,
+}
+
+impl AdminUsersTemplate {
+    pub fn has_two_factor(&self, user_id: &i32) -> bool {
+        self.two_factor_user_ids.contains(user_id)
+    }
 }
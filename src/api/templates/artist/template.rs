@@ -0,0 +1,50 @@
+use crate::business::models::artist_detail::{ArtistFollowInfo, ArtistProfile};
+use crate::business::models::playlist::PlaylistOverview;
+use crate::business::models::video::VideoList;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "artist/follow.html")]
+pub struct FollowTemplate {
+    pub info: ArtistFollowInfo,
+}
+
+#[derive(Template)]
+#[template(path = "artist/index.html")]
+pub struct ArtistTemplate {
+    pub profile: ArtistProfile,
+    pub follow_template: FollowTemplate,
+    pub description_template: ArtistDescriptionTemplate,
+    pub videos_template: ArtistVideosTemplate,
+    pub video_count: i64,
+    /// Running live stream of the artist, which the user can watch
+    pub stream_id: Option<i32>,
+    /// Playlists, which the artist listed publicly
+    pub playlists: Vec<PlaylistOverview>,
+}
+
+#[derive(Template)]
+#[template(path = "artist/description.html")]
+pub struct ArtistDescriptionTemplate {
+    pub artist_id: i32,
+    pub description: Option<String>,
+    /// The artist can edit his own description
+    pub is_owner: bool,
+}
+
+#[derive(Template)]
+#[template(path = "artist/description_form.html")]
+pub struct ArtistDescriptionFormTemplate {
+    pub artist_id: i32,
+    pub description: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "artist/videos.html")]
+pub struct ArtistVideosTemplate {
+    pub artist_id: i32,
+    pub videos: Vec<VideoList>,
+    pub first_page: bool,
+    /// Offset of the next page, `None` if there are no more videos
+    pub next_offset: Option<i64>,
+}
@@ -0,0 +1,17 @@
+use crate::business::models::feed::FeedItem;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "feed/index.html")]
+pub struct FeedTemplate {
+    pub items_template: FeedItemsTemplate,
+}
+
+#[derive(Template)]
+#[template(path = "feed/items.html")]
+pub struct FeedItemsTemplate {
+    pub items: Vec<FeedItem>,
+    pub first_page: bool,
+    /// Offset of the next page, `None` if there are no more items
+    pub next_offset: Option<i64>,
+}
@@ -1,5 +1,8 @@
 pub mod admin;
+pub mod artist;
+pub mod feed;
 pub mod membership;
+pub mod playlist;
 pub mod stream;
 pub mod template;
 pub mod user;
@@ -0,0 +1,66 @@
+use crate::api::templates::video::show::template::PlayerTemplate;
+use crate::business::models::playlist::{Playlist, PlaylistOverview, PlaylistVisibility};
+use crate::business::models::video::VideoList;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "playlist/index.html")]
+pub struct PlaylistsTemplate {
+    pub playlists: Vec<PlaylistOverview>,
+}
+
+#[derive(Template)]
+#[template(path = "playlist/form.html")]
+pub struct PlaylistFormTemplate {
+    /// Edited playlist, `None` when a new playlist is created
+    pub playlist: Option<Playlist>,
+    pub visibilities: Vec<PlaylistVisibility>,
+}
+
+impl PlaylistFormTemplate {
+    pub fn is_selected(&self, visibility: &PlaylistVisibility) -> bool {
+        match &self.playlist {
+            Some(playlist) => playlist.visibility == *visibility,
+            None => *visibility == PlaylistVisibility::Private,
+        }
+    }
+}
+
+#[derive(Template)]
+#[template(path = "playlist/show.html")]
+pub struct PlaylistTemplate {
+    pub playlist: Playlist,
+    /// Currently played video, `None` if the playlist is empty
+    pub current_video: Option<VideoList>,
+    pub player_template: Option<PlayerTemplate>,
+    pub items_template: PlaylistItemsTemplate,
+}
+
+#[derive(Template)]
+#[template(path = "playlist/items.html")]
+pub struct PlaylistItemsTemplate {
+    pub playlist_id: i32,
+    pub videos: Vec<VideoList>,
+    pub current_video_id: Option<i32>,
+    /// Owner can reorder and remove the videos
+    pub is_owner: bool,
+}
+
+impl PlaylistItemsTemplate {
+    pub fn is_current(&self, video: &VideoList) -> bool {
+        self.current_video_id == Some(video.id)
+    }
+}
+
+#[derive(Template)]
+#[template(path = "playlist/select.html")]
+pub struct PlaylistSelectTemplate {
+    pub video_id: i32,
+    pub playlists: Vec<PlaylistOverview>,
+}
+
+#[derive(Template)]
+#[template(path = "playlist/added.html")]
+pub struct PlaylistItemAddedTemplate {
+    pub added: bool,
+}
@@ -0,0 +1,16 @@
+use crate::business::models::stream_chat::{ChatMessage, ChatRoom};
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "stream/chat/chat.html")]
+pub struct ChatTemplate {
+    pub room: ChatRoom,
+}
+
+/// Single message of the chat, which is sent to the viewers as it arrives
+#[derive(Template)]
+#[template(path = "stream/chat/message.html")]
+pub struct ChatMessageTemplate {
+    pub stream_id: i32,
+    pub message: ChatMessage,
+}
@@ -0,0 +1,11 @@
+use crate::business::models::stream::StreamListItem;
+#[allow(unused_imports)] // Used in index.html template
+use crate::business::models::video::VideoVisibility;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "stream/directory/index.html")]
+pub struct StreamDirectoryTemplate {
+    pub running: Vec<StreamListItem>,
+    pub upcoming: Vec<StreamListItem>,
+}
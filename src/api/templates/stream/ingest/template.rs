@@ -0,0 +1,14 @@
+use crate::business::models::stream::StreamIngest;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "stream/ingest/ingest.html")]
+pub struct StreamIngestTemplate {
+    pub ingest: StreamIngest,
+}
+
+#[derive(Template)]
+#[template(path = "stream/ingest/key.html")]
+pub struct StreamKeyTemplate {
+    pub ingest_template: StreamIngestTemplate,
+}
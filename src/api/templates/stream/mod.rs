@@ -1 +1,6 @@
+pub mod chat;
+pub mod directory;
+pub mod ingest;
+pub mod reminder;
+pub mod stats;
 pub mod watch;
@@ -0,0 +1,14 @@
+use crate::business::models::stream::{RemindedStream, StreamReminders};
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "stream/reminder/reminder.html")]
+pub struct StreamReminderTemplate {
+    pub reminders: StreamReminders,
+}
+
+#[derive(Template)]
+#[template(path = "stream/reminder/live.html")]
+pub struct LiveRemindersTemplate {
+    pub streams: Vec<RemindedStream>,
+}
@@ -0,0 +1,8 @@
+use crate::business::models::stream_telemetry::StreamStats;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "stream/stats/stats.html")]
+pub struct StreamStatsTemplate {
+    pub stats: StreamStats,
+}
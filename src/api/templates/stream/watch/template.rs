@@ -1,6 +1,8 @@
+use crate::api::templates::stream::ingest::template::StreamIngestTemplate;
+use crate::api::templates::stream::reminder::template::StreamReminderTemplate;
 use crate::business::models::stream::LiveStream;
 #[allow(unused_imports)] // Used in stream.html template
-use crate::business::models::stream::LiveStreamStatus;
+use crate::business::models::stream::{LiveStreamSource, LiveStreamStatus};
 use crate::business::models::video::Video;
 use askama_actix::Template;
 
@@ -10,4 +12,8 @@ pub struct WatchStreamTemplate {
     pub stream: LiveStream,
     pub video: Video,
     pub is_owner: bool,
+    /// Settings of the encoder, shown to the owner while the stream waits for it
+    pub ingest_template: Option<StreamIngestTemplate>,
+    /// Notify me button, shown while the scheduled stream counts down to its start
+    pub reminder_template: Option<StreamReminderTemplate>,
 }
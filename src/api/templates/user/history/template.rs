@@ -0,0 +1,17 @@
+use crate::business::models::watch_history::WatchHistoryItem;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "user/history/index.html")]
+pub struct HistoryTemplate {
+    pub items_template: HistoryItemsTemplate,
+}
+
+#[derive(Template)]
+#[template(path = "user/history/items.html")]
+pub struct HistoryItemsTemplate {
+    pub items: Vec<WatchHistoryItem>,
+    pub first_page: bool,
+    /// Offset of the next page, `None` if there are no more videos
+    pub next_offset: Option<i64>,
+}
@@ -1,8 +1,14 @@
 pub mod delete;
 pub mod detail;
+pub mod history;
 pub mod liked_videos;
 pub mod logged_in;
 pub mod login;
 pub mod password_change;
+pub mod password_forgot;
+pub mod password_reset;
 pub mod register;
+pub mod sessions;
+pub mod two_factor;
 pub mod validation;
+pub mod verification;
@@ -0,0 +1,8 @@
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "user/password_forgot/index.html")]
+pub struct PasswordForgotTemplate {
+    /// Whether the reset link was requested, the page doesn't tell if the email is registered
+    pub sent: bool,
+}
@@ -0,0 +1,8 @@
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "user/password_reset/index.html")]
+pub struct PasswordResetTemplate {
+    /// Token from the reset link, `None` once the password was reset
+    pub token: Option<String>,
+}
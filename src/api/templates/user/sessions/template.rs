@@ -0,0 +1,9 @@
+use crate::business::models::session::ActiveSession;
+use askama_actix::Template;
+
+/// Sessions of the user on the account page
+#[derive(Template)]
+#[template(path = "user/sessions/index.html")]
+pub struct SessionsTemplate {
+    pub sessions: Vec<ActiveSession>,
+}
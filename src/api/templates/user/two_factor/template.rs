@@ -0,0 +1,25 @@
+use crate::business::models::two_factor::{TotpEnrolment, TwoFactorStatus};
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "user/two_factor/index.html")]
+pub struct TwoFactorTemplate {
+    pub status: TwoFactorStatus,
+}
+
+#[derive(Template)]
+#[template(path = "user/two_factor/setup.html")]
+pub struct TwoFactorSetupTemplate {
+    pub enrolment: TotpEnrolment,
+}
+
+#[derive(Template)]
+#[template(path = "user/two_factor/recovery_codes.html")]
+pub struct TwoFactorRecoveryCodesTemplate {
+    /// Plaintext codes, which are shown only once after the setup
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Template)]
+#[template(path = "user/two_factor/challenge.html")]
+pub struct TwoFactorChallengeTemplate {}
@@ -0,0 +1,8 @@
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "user/verification/index.html")]
+pub struct EmailVerificationTemplate {
+    /// Why the email was not verified, `None` when it was verified
+    pub error_message: Option<String>,
+}
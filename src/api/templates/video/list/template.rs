@@ -1,4 +1,4 @@
-use crate::business::models::video::VideoList;
+use crate::business::models::video::{FetchVideoByFilters, VideoList, VideoSort};
 use crate::business::models::video_category::VideoCategory;
 use askama_actix::Template;
 
@@ -7,6 +7,7 @@ use askama_actix::Template;
 pub struct IndexTemplate<T: Template> {
     pub videos_template: T,
     pub categories: Vec<VideoCategory>,
+    pub sorts: Vec<VideoSort>,
 }
 
 #[derive(Template)]
@@ -17,4 +18,17 @@ pub struct VideoGridTemplate {}
 #[template(path = "video/list/videos.html")]
 pub struct VideosTemplate {
     pub videos: Vec<VideoList>,
+    /// Request for the next page, which is used by the "Load More" button.
+    /// The button is not rendered, when it's `None`.
+    pub next_page: Option<FetchVideoByFilters>,
+}
+
+impl VideosTemplate {
+    /// Serializes request for the next page, so it can be used in `hx-vals`
+    pub fn next_page_vals(&self) -> String {
+        self.next_page
+            .as_ref()
+            .and_then(|next_page| serde_json::to_string(next_page).ok())
+            .unwrap_or_default()
+    }
 }
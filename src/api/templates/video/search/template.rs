@@ -0,0 +1,21 @@
+use crate::business::models::video::VideoSearchResult;
+use crate::business::models::video_category::VideoCategory;
+use askama_actix::Template;
+
+#[derive(Template)]
+#[template(path = "video/search/search.html")]
+pub struct SearchTemplate {
+    pub query: String,
+    pub categories: Vec<VideoCategory>,
+    pub selected_category_ids: Vec<i32>,
+    pub results_template: SearchResultsTemplate,
+}
+
+#[derive(Template)]
+#[template(path = "video/search/results.html")]
+pub struct SearchResultsTemplate {
+    pub results: Vec<VideoSearchResult>,
+    pub first_page: bool,
+    /// Offset of the next page, `None` if there are no more results
+    pub next_offset: Option<i64>,
+}
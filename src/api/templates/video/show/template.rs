@@ -1,7 +1,12 @@
-use crate::api::controllers::utils::route_util::{build_get_temp_path, build_get_video_path};
+use crate::api::controllers::utils::route_util::{
+    build_get_temp_path, build_get_video_path, build_hls_playlist_path, build_position_path,
+    build_view_path,
+};
 use crate::api::extractors::permissions_extractor::IsRole;
+use crate::api::templates::artist::template::FollowTemplate;
 use crate::business::models::comment::CommentUserModel;
-use crate::business::models::video::Video;
+use crate::business::models::video::{format_duration, Video};
+use crate::business::models::view::VideoViewStats;
 use actix_session::Session;
 use askama_actix::Template;
 
@@ -10,6 +15,7 @@ use askama_actix::Template;
 pub struct ShowVideoTemplate<T: Template> {
     pub video: Video,
     pub player_template: T,
+    pub follow_template: FollowTemplate,
     pub session: Session,
     pub user_id: i32,
     pub is_liked: bool,
@@ -19,26 +25,88 @@ pub struct ShowVideoTemplate<T: Template> {
 #[derive(Template)]
 #[template(path = "video/show/player.html")]
 pub struct PlayerTemplate {
+    video_id: Option<i32>,
     video_path: String,
+    hls_path: Option<String>,
     thumbnail_path: Option<String>,
+    view_url: Option<String>,
+    view_threshold_seconds: u32,
+    position_url: Option<String>,
+    resume_position_ms: Option<i64>,
+    next_url: Option<String>,
+    autoplay: bool,
 }
 
 impl PlayerTemplate {
     pub fn from_saved(video_id: i32) -> Self {
         let (video_path, thumbnail_path) = build_get_video_path(video_id);
         Self {
+            video_id: Some(video_id),
             video_path,
+            hls_path: None,
             thumbnail_path: Some(thumbnail_path),
+            view_url: None,
+            view_threshold_seconds: 0,
+            position_url: None,
+            resume_position_ms: None,
+            next_url: None,
+            autoplay: false,
         }
     }
 
     pub fn from_temporary(temp_id: i32) -> Self {
         let video_path = build_get_temp_path(temp_id);
         Self {
+            video_id: None,
             video_path,
+            hls_path: None,
             thumbnail_path: None,
+            view_url: None,
+            view_threshold_seconds: 0,
+            position_url: None,
+            resume_position_ms: None,
+            next_url: None,
+            autoplay: false,
         }
     }
+
+    /// Plays HLS renditions of the saved video, if they are available.
+    /// The original file is used as a fallback.
+    pub fn with_hls(mut self, hls_available: bool) -> Self {
+        self.hls_path = match (hls_available, self.video_id) {
+            (true, Some(video_id)) => Some(build_hls_playlist_path(video_id)),
+            _ => None,
+        };
+        self
+    }
+
+    /// Records the view of the saved video, once the given number of seconds is played.
+    /// Shorter videos have to be played at least to the half.
+    pub fn with_view_tracking(mut self, threshold_seconds: u32) -> Self {
+        self.view_url = self.video_id.map(build_view_path);
+        self.view_threshold_seconds = threshold_seconds;
+        self
+    }
+
+    /// Saves the position of the playback to the watch history of the logged-in user
+    /// and offers to continue from the previously saved position.
+    pub fn with_watch_history(mut self, resume_position_ms: Option<i64>) -> Self {
+        self.position_url = self.video_id.map(build_position_path);
+        self.resume_position_ms = self.video_id.and(resume_position_ms);
+        self
+    }
+
+    /// Loads the given page into the content once the video ends, which plays the next
+    /// video of the playlist. The video starts automatically if `autoplay` is set.
+    pub fn with_next(mut self, next_url: Option<String>, autoplay: bool) -> Self {
+        self.next_url = next_url;
+        self.autoplay = autoplay;
+        self
+    }
+
+    pub fn formatted_resume_position(&self) -> Option<String> {
+        self.resume_position_ms.map(format_duration)
+    }
 }
 
 #[derive(Template)]
@@ -47,6 +115,12 @@ pub struct CommentsTemplate {
     pub comments: Vec<CommentUserModel>,
 }
 
+#[derive(Template)]
+#[template(path = "video/show/view_stats.html")]
+pub struct ViewStatsTemplate {
+    pub stats: VideoViewStats,
+}
+
 #[derive(Template)]
 #[template(path = "video/show/video_not_allowed.html")]
 pub struct VideoNotAllowed {}
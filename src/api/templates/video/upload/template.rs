@@ -15,12 +15,17 @@ pub struct VideoUploadTemplate<V: Template, T: Template> {
 #[template(path = "video/upload/inputs/upload_video.html")]
 pub struct VideoUploadInputTemplate {
     pub accepted_mimetype: String,
+    pub chunk_size: i32,
 }
 
 impl VideoUploadInputTemplate {
     pub fn new(config: Arc<Configuration>) -> Self {
         let accepted_mimetype = config.app.video.accepted_mime_type.join(",");
-        Self { accepted_mimetype }
+        let chunk_size = config.app.upload_chunk_size_mb * 1024 * 1024;
+        Self {
+            accepted_mimetype,
+            chunk_size,
+        }
     }
 }
 
@@ -50,3 +55,9 @@ pub struct ThumbnailPreviewTemplate {
     pub temp_file_id: Option<i32>,
     pub file_path: String,
 }
+
+#[derive(Template)]
+#[template(path = "video/upload/inputs/thumbnail_candidates.html")]
+pub struct ThumbnailCandidatesTemplate {
+    pub candidate_ids: Vec<i32>,
+}
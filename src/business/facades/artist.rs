@@ -1,6 +1,9 @@
 use crate::business::mappers::generic::ToMappedList;
-use crate::business::models::artist_detail::{ArtistDetail, ArtistName};
-use crate::business::models::error::{AppErrorKind, MapToAppError};
+use crate::business::models::artist_detail::{
+    ArtistDescriptionReq, ArtistDetail, ArtistFollowInfo, ArtistName, ArtistProfile,
+};
+use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
+use crate::business::validation::validatable::{EmptyContext, Validatable};
 use crate::business::Result;
 use crate::persistence::entities::artist::Artist;
 use crate::persistence::repositories::artist::ArtistRepoTrait;
@@ -19,6 +22,22 @@ pub trait ArtistFacadeTrait {
         tx: Option<&mut Transaction<'_, Postgres>>,
     ) -> Result<Artist>;
     async fn make_user_artist(&self, user_id: i32) -> Result<()>;
+    async fn follow_artist(&self, user_id: i32, artist_id: i32) -> Result<()>;
+    async fn unfollow_artist(&self, user_id: i32, artist_id: i32) -> Result<()>;
+    /// Returns number of the followers of the artist and whether the user follows the artist
+    async fn get_follow_info(
+        &self,
+        artist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<ArtistFollowInfo>;
+    async fn get_artist_profile(&self, artist_id: i32) -> Result<ArtistProfile>;
+    /// Updates description of the artist, only the artist can edit his own description
+    async fn update_description(
+        &self,
+        user_id: i32,
+        artist_id: i32,
+        req: ArtistDescriptionReq,
+    ) -> Result<ArtistProfile>;
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +49,14 @@ impl ArtistFacade {
     pub fn new(artist_repository: Arc<dyn ArtistRepoTrait + Send + Sync>) -> Self {
         Self { artist_repository }
     }
+
+    async fn get_artist_name(&self, artist_id: i32) -> Result<ArtistName> {
+        self.artist_repository
+            .fetch_artists_names_by_id(vec![artist_id])
+            .await?
+            .pop()
+            .ok_or_else(|| AppError::new("Artist doesn't exist", AppErrorKind::NotFound))
+    }
 }
 
 #[async_trait]
@@ -79,4 +106,91 @@ impl ArtistFacadeTrait for ArtistFacade {
 
         Ok(())
     }
+
+    async fn follow_artist(&self, user_id: i32, artist_id: i32) -> Result<()> {
+        let artist = self.get_artist_name(artist_id).await?;
+        if artist.user_id == user_id {
+            return Err(AppError::new(
+                "You can't follow yourself",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        self.artist_repository
+            .follow_artist(user_id, artist_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn unfollow_artist(&self, user_id: i32, artist_id: i32) -> Result<()> {
+        self.get_artist_name(artist_id).await?;
+        self.artist_repository
+            .unfollow_artist(user_id, artist_id)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_follow_info(
+        &self,
+        artist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<ArtistFollowInfo> {
+        let artist = self.get_artist_name(artist_id).await?;
+        let follower_count = self.artist_repository.get_follower_count(artist_id).await?;
+        let is_following = match user_id {
+            Some(user_id) => {
+                self.artist_repository
+                    .is_following(user_id, artist_id)
+                    .await?
+            }
+            None => false,
+        };
+
+        Ok(ArtistFollowInfo {
+            artist_id,
+            can_follow: user_id.is_some_and(|user_id| user_id != artist.user_id),
+            name: artist.name,
+            follower_count,
+            is_following,
+        })
+    }
+
+    async fn get_artist_profile(&self, artist_id: i32) -> Result<ArtistProfile> {
+        let profile = self
+            .artist_repository
+            .get_artist_profile(artist_id)
+            .await?
+            .ok_or_else(|| AppError::new("Artist doesn't exist", AppErrorKind::NotFound))?;
+
+        Ok(ArtistProfile::from(profile))
+    }
+
+    async fn update_description(
+        &self,
+        user_id: i32,
+        artist_id: i32,
+        req: ArtistDescriptionReq,
+    ) -> Result<ArtistProfile> {
+        req.validate_model(&EmptyContext::new())
+            .await
+            .app_error_kind("Validation failed", AppErrorKind::BadRequestError)?;
+
+        let artist = self.get_artist_internal(user_id, None).await?;
+        if artist.id != artist_id {
+            return Err(AppError::new(
+                "You can edit only your own profile",
+                AppErrorKind::AccessDenied,
+            ));
+        }
+
+        let description = req
+            .description
+            .map(|description| description.trim().to_string())
+            .filter(|description| !description.is_empty());
+        self.artist_repository
+            .update_description(artist_id, description)
+            .await?;
+
+        self.get_artist_profile(artist_id).await
+    }
 }
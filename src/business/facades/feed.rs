@@ -0,0 +1,52 @@
+use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::models::feed::FEED_PAGE_SIZE;
+use crate::business::Result;
+use crate::persistence::entities::feed::FeedEntry;
+use crate::persistence::repositories::feed::FeedRepo;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait FeedFacadeTrait {
+    /// Returns one page of the videos and live streams of the followed artists.
+    /// Videos, which the user is not allowed to watch, are skipped.
+    ///
+    /// # Returns
+    /// Tuple with:
+    /// - Entries of the page, the most recently published first
+    /// - Offset of the next page, `None` if there are no more entries
+    async fn list_feed(&self, user_id: i32, offset: i64) -> Result<(Vec<FeedEntry>, Option<i64>)>;
+}
+
+pub struct FeedFacade {
+    feed_repo: Arc<dyn FeedRepo + Send + Sync>,
+    video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+}
+
+impl FeedFacade {
+    pub fn new(
+        feed_repo: Arc<dyn FeedRepo + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    ) -> Self {
+        Self {
+            feed_repo,
+            video_facade,
+        }
+    }
+}
+
+#[async_trait]
+impl FeedFacadeTrait for FeedFacade {
+    async fn list_feed(&self, user_id: i32, offset: i64) -> Result<(Vec<FeedEntry>, Option<i64>)> {
+        let offset = offset.max(0);
+        let access = self.video_facade.get_video_access(Some(user_id)).await?;
+        let entries = self
+            .feed_repo
+            .list_feed(user_id, &access, offset, FEED_PAGE_SIZE)
+            .await?;
+        let next_offset =
+            (entries.len() as i64 == FEED_PAGE_SIZE).then_some(offset + FEED_PAGE_SIZE);
+
+        Ok((entries, next_offset))
+    }
+}
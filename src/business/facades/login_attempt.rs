@@ -0,0 +1,142 @@
+use crate::business::models::error::{AppError, AppErrorKind};
+use crate::business::models::login_attempt::LockedAccount;
+use crate::business::Result;
+use crate::configuration::models::Configuration;
+use crate::persistence::entities::login_attempt::LoginSubject;
+use crate::persistence::repositories::login_attempt::LoginAttemptRepo;
+use async_trait::async_trait;
+use log::{info, warn};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[async_trait]
+pub trait LoginAttemptFacadeTrait {
+    /// Fails, if the username or the IP address is locked after too many failed logins.
+    /// Unknown usernames are locked too, so the lock doesn't reveal which accounts exist.
+    async fn check_allowed(&self, username: &str, ip_address: Option<&str>) -> Result<()>;
+    /// Counts the failed login and locks the username or the IP address, once it has
+    /// too many of them. Every further failure doubles the lock.
+    async fn record_failure(&self, username: &str, ip_address: Option<&str>) -> Result<()>;
+    /// Forgets the failed logins of the user, who logged in
+    async fn record_success(&self, username: &str) -> Result<()>;
+    async fn list_locked_accounts(&self) -> Result<Vec<LockedAccount>>;
+    async fn unlock_account(&self, username: &str) -> Result<()>;
+}
+
+pub struct LoginAttemptFacade {
+    login_attempt_repo: Arc<dyn LoginAttemptRepo + Send + Sync>,
+    app_configuration: Arc<Configuration>,
+}
+
+impl LoginAttemptFacade {
+    pub fn new(
+        login_attempt_repo: Arc<dyn LoginAttemptRepo + Send + Sync>,
+        app_configuration: Arc<Configuration>,
+    ) -> Self {
+        Self {
+            login_attempt_repo,
+            app_configuration,
+        }
+    }
+
+    fn subjects<'a>(username: &'a str, ip_address: Option<&'a str>) -> Vec<LoginSubject<'a>> {
+        let mut subjects = vec![LoginSubject::Username(username)];
+        if let Some(ip_address) = ip_address {
+            subjects.push(LoginSubject::IpAddress(ip_address));
+        }
+
+        subjects
+    }
+
+    /// Returns the lock for the number of failures, `None` if there are not enough of them
+    fn lockout_duration(&self, failures: u32, max_failures: u32) -> Option<Duration> {
+        let config = &self.app_configuration.app.login;
+        let doublings = failures.checked_sub(max_failures)?.min(31);
+        let lockout_seconds = config
+            .lockout_seconds
+            .saturating_mul(1 << doublings)
+            .min(config.max_lockout_seconds);
+
+        Some(Duration::from_secs(lockout_seconds))
+    }
+}
+
+#[async_trait]
+impl LoginAttemptFacadeTrait for LoginAttemptFacade {
+    async fn check_allowed(&self, username: &str, ip_address: Option<&str>) -> Result<()> {
+        for subject in Self::subjects(username, ip_address) {
+            if let Some(remaining) = self.login_attempt_repo.get_lock(&subject).await? {
+                return Err(AppError::new(
+                    &format!(
+                        "Too many failed login attempts, try again in {} seconds",
+                        remaining.as_secs()
+                    ),
+                    AppErrorKind::TooManyRequests,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_failure(&self, username: &str, ip_address: Option<&str>) -> Result<()> {
+        let config = &self.app_configuration.app.login;
+        let window = Duration::from_secs(config.failure_window_minutes * 60);
+
+        for subject in Self::subjects(username, ip_address) {
+            let max_failures = match subject {
+                LoginSubject::Username(_) => config.max_failures_per_user,
+                LoginSubject::IpAddress(_) => config.max_failures_per_ip,
+            };
+            let failures = self
+                .login_attempt_repo
+                .add_failure(&subject, window)
+                .await?;
+
+            if let Some(duration) = self.lockout_duration(failures, max_failures) {
+                self.login_attempt_repo.lock(&subject, duration).await?;
+                match subject {
+                    LoginSubject::Username(username) => warn!(
+                        "Login of {username} was locked for {}s after {failures} failures",
+                        duration.as_secs()
+                    ),
+                    LoginSubject::IpAddress(ip_address) => warn!(
+                        "Login from {ip_address} was locked for {}s after {failures} failures",
+                        duration.as_secs()
+                    ),
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn record_success(&self, username: &str) -> Result<()> {
+        self.login_attempt_repo
+            .reset_failures(&LoginSubject::Username(username))
+            .await?;
+
+        Ok(())
+    }
+
+    async fn list_locked_accounts(&self) -> Result<Vec<LockedAccount>> {
+        let locked_accounts = self.login_attempt_repo.list_locked_accounts().await?;
+
+        Ok(locked_accounts
+            .into_iter()
+            .map(LockedAccount::from)
+            .collect())
+    }
+
+    async fn unlock_account(&self, username: &str) -> Result<()> {
+        if !self.login_attempt_repo.unlock_account(username).await? {
+            return Err(AppError::new(
+                "The account is not locked",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        info!("Login of {username} was unlocked");
+        Ok(())
+    }
+}
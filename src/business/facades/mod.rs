@@ -1,8 +1,16 @@
 pub mod artist;
 pub mod comment;
+pub mod feed;
+pub mod login_attempt;
 pub mod membership;
+pub mod playlist;
 pub mod stream;
+pub mod stream_chat;
+pub mod stream_telemetry;
 pub mod temp_file;
+pub mod two_factor;
 pub mod user;
 pub mod video;
 pub mod video_category;
+pub mod view;
+pub mod watch_history;
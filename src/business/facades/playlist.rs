@@ -0,0 +1,261 @@
+use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::mappers::generic::ToMappedList;
+use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
+use crate::business::models::playlist::{Playlist, PlaylistOverview, PlaylistReq};
+use crate::business::validation::validatable::{EmptyContext, Validatable};
+use crate::business::Result;
+use crate::persistence::entities::playlist::{
+    Playlist as PlaylistEntity, PlaylistOverview as PlaylistOverviewEntity,
+    PlaylistVisibility as PlaylistVisibilityEntity,
+};
+use crate::persistence::entities::video::Video;
+use crate::persistence::repositories::playlist::PlaylistRepo;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait PlaylistFacadeTrait {
+    async fn create_playlist(&self, user_id: i32, req: PlaylistReq) -> Result<Playlist>;
+    async fn update_playlist(
+        &self,
+        user_id: i32,
+        playlist_id: i32,
+        req: PlaylistReq,
+    ) -> Result<Playlist>;
+    async fn delete_playlist(&self, user_id: i32, playlist_id: i32) -> Result<()>;
+    /// Returns the playlist, if the user can see it. Private playlists are visible
+    /// only to their owner, unlisted and public playlists to everyone.
+    async fn get_playlist(&self, playlist_id: i32, user_id: Option<i32>) -> Result<Playlist>;
+    /// Returns the playlist only if it's owned by the user
+    async fn get_owned_playlist(&self, playlist_id: i32, user_id: i32) -> Result<Playlist>;
+    /// Returns all playlists of the user, including the private ones
+    async fn list_user_playlists(&self, user_id: i32) -> Result<Vec<PlaylistOverview>>;
+    /// Returns playlists of the user, which are listed publicly. Only the videos,
+    /// which the viewer can watch, are counted.
+    async fn list_public_playlists(
+        &self,
+        user_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<PlaylistOverview>>;
+    /// Returns videos of the playlist in their order. Videos, which the user
+    /// is not allowed to watch, are skipped.
+    async fn get_playlist_videos(
+        &self,
+        playlist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<Vec<Video>>;
+    /// Appends the video to the end of the playlist
+    ///
+    /// # Returns
+    /// `false` if the video is already in the playlist
+    async fn add_video(&self, user_id: i32, playlist_id: i32, video_id: i32) -> Result<bool>;
+    async fn remove_video(&self, user_id: i32, playlist_id: i32, video_id: i32) -> Result<()>;
+    /// Moves the video to the given position in the playlist, counted from 0
+    async fn move_video(
+        &self,
+        user_id: i32,
+        playlist_id: i32,
+        video_id: i32,
+        position: i32,
+    ) -> Result<()>;
+}
+
+pub struct PlaylistFacade {
+    playlist_repo: Arc<dyn PlaylistRepo + Send + Sync>,
+    video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+}
+
+impl PlaylistFacade {
+    pub fn new(
+        playlist_repo: Arc<dyn PlaylistRepo + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    ) -> Self {
+        Self {
+            playlist_repo,
+            video_facade,
+        }
+    }
+
+    fn not_found() -> AppError {
+        AppError::new("Playlist doesn't exist", AppErrorKind::NotFound)
+    }
+
+    async fn validate(req: &PlaylistReq) -> Result<()> {
+        req.validate_model(&EmptyContext::new())
+            .await
+            .app_error_kind("Validation failed", AppErrorKind::BadRequestError)
+    }
+
+    fn to_entity(id: i32, user_id: i32, req: PlaylistReq) -> PlaylistEntity {
+        PlaylistEntity {
+            id,
+            user_id,
+            name: req.name.trim().to_string(),
+            description: req
+                .description
+                .filter(|description| !description.trim().is_empty()),
+            visibility: PlaylistVisibilityEntity::from(&req.visibility),
+        }
+    }
+
+    fn to_overviews(playlists: Vec<PlaylistOverviewEntity>) -> Vec<PlaylistOverview> {
+        playlists.to_mapped_list(|playlist| PlaylistOverview::from(&playlist))
+    }
+}
+
+#[async_trait]
+impl PlaylistFacadeTrait for PlaylistFacade {
+    async fn create_playlist(&self, user_id: i32, req: PlaylistReq) -> Result<Playlist> {
+        Self::validate(&req).await?;
+        let playlist = self
+            .playlist_repo
+            .create_playlist(Self::to_entity(-1, user_id, req))
+            .await?;
+
+        Ok(Playlist::from(&playlist))
+    }
+
+    async fn update_playlist(
+        &self,
+        user_id: i32,
+        playlist_id: i32,
+        req: PlaylistReq,
+    ) -> Result<Playlist> {
+        Self::validate(&req).await?;
+        let playlist = self
+            .playlist_repo
+            .update_playlist(Self::to_entity(playlist_id, user_id, req))
+            .await?
+            .ok_or_else(Self::not_found)?;
+
+        Ok(Playlist::from(&playlist))
+    }
+
+    async fn delete_playlist(&self, user_id: i32, playlist_id: i32) -> Result<()> {
+        if !self
+            .playlist_repo
+            .delete_playlist(playlist_id, user_id)
+            .await?
+        {
+            return Err(Self::not_found());
+        }
+        Ok(())
+    }
+
+    async fn get_playlist(&self, playlist_id: i32, user_id: Option<i32>) -> Result<Playlist> {
+        let playlist = self
+            .playlist_repo
+            .get_playlist(playlist_id)
+            .await?
+            .ok_or_else(Self::not_found)?;
+
+        let is_owner = user_id == Some(playlist.user_id);
+        if playlist.visibility == PlaylistVisibilityEntity::Private && !is_owner {
+            return Err(Self::not_found());
+        }
+        Ok(Playlist::from(&playlist))
+    }
+
+    async fn get_owned_playlist(&self, playlist_id: i32, user_id: i32) -> Result<Playlist> {
+        let playlist = self.get_playlist(playlist_id, Some(user_id)).await?;
+        if playlist.user_id != user_id {
+            return Err(Self::not_found());
+        }
+        Ok(playlist)
+    }
+
+    async fn list_user_playlists(&self, user_id: i32) -> Result<Vec<PlaylistOverview>> {
+        let access = self.video_facade.get_video_access(Some(user_id)).await?;
+        let playlists = self
+            .playlist_repo
+            .list_playlists(
+                user_id,
+                &[
+                    PlaylistVisibilityEntity::Private,
+                    PlaylistVisibilityEntity::Unlisted,
+                    PlaylistVisibilityEntity::Public,
+                ],
+                &access,
+            )
+            .await?;
+        Ok(Self::to_overviews(playlists))
+    }
+
+    async fn list_public_playlists(
+        &self,
+        user_id: i32,
+        viewer_id: Option<i32>,
+    ) -> Result<Vec<PlaylistOverview>> {
+        let access = self.video_facade.get_video_access(viewer_id).await?;
+        let playlists = self
+            .playlist_repo
+            .list_playlists(user_id, &[PlaylistVisibilityEntity::Public], &access)
+            .await?;
+        Ok(Self::to_overviews(playlists))
+    }
+
+    async fn get_playlist_videos(
+        &self,
+        playlist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<Vec<Video>> {
+        self.get_playlist(playlist_id, user_id).await?;
+        // The playlist can be visible to more users than some of its videos
+        let access = self.video_facade.get_video_access(user_id).await?;
+        let playlist_videos = self
+            .playlist_repo
+            .get_playlist_videos(playlist_id, &access)
+            .await?;
+
+        Ok(playlist_videos
+            .into_iter()
+            .map(|playlist_video| playlist_video.video)
+            .collect())
+    }
+
+    async fn add_video(&self, user_id: i32, playlist_id: i32, video_id: i32) -> Result<bool> {
+        self.get_owned_playlist(playlist_id, user_id).await?;
+        self.video_facade
+            .get_video_entity(video_id, Some(user_id))
+            .await?;
+
+        let added = self.playlist_repo.add_video(playlist_id, video_id).await?;
+        Ok(added)
+    }
+
+    async fn remove_video(&self, user_id: i32, playlist_id: i32, video_id: i32) -> Result<()> {
+        self.get_owned_playlist(playlist_id, user_id).await?;
+        if !self
+            .playlist_repo
+            .remove_video(playlist_id, video_id)
+            .await?
+        {
+            return Err(AppError::new(
+                "Video is not in the playlist",
+                AppErrorKind::NotFound,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn move_video(
+        &self,
+        user_id: i32,
+        playlist_id: i32,
+        video_id: i32,
+        position: i32,
+    ) -> Result<()> {
+        self.get_owned_playlist(playlist_id, user_id).await?;
+        if !self
+            .playlist_repo
+            .move_video(playlist_id, video_id, position)
+            .await?
+        {
+            return Err(AppError::new(
+                "Video is not in the playlist",
+                AppErrorKind::NotFound,
+            ));
+        }
+        Ok(())
+    }
+}
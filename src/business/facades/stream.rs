@@ -1,29 +1,52 @@
+use crate::business::facades::artist::ArtistFacadeTrait;
 use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::mappers::generic::ToMappedList;
 use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
 use crate::business::models::stream::{
-    CompoundStreamInfo, LiveStream as LiveStreamDto, LiveStreamStart, StreamStorage,
+    CompoundStreamInfo, LiveStream as LiveStreamDto, LiveStreamSchedule, LiveStreamStart,
+    RemindedStream, StreamIngest, StreamReminders, StreamStorage,
 };
 use crate::business::models::video::Video;
 use crate::business::{models, Result};
 use crate::configuration::models::Configuration;
 use crate::persistence::entities::error::MapToDatabaseError;
-use crate::persistence::entities::stream::{LiveStream, LiveStreamStatus};
+use crate::persistence::entities::stream::{
+    ListedStream, LiveStream, LiveStreamSource, LiveStreamStatus,
+};
+use crate::persistence::entities::video::Video as VideoEntity;
 use crate::persistence::repositories::stream::StreamRepoTrait;
 use crate::streamer;
-use crate::streamer::types::{CompoundStreamInfoTrait, StreamResolution, StreamStorageTrait};
+use crate::streamer::types::{
+    CompoundStreamInfoTrait, StreamResolution, StreamSource, StreamStorageTrait,
+};
 use async_trait::async_trait;
+use chrono::Local;
 use gstreamer::Pipeline;
 use lazy_static::lazy_static;
+use log::{error, info, warn};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
 use regex::Regex;
 use std::ops::Deref;
-use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use tokio::runtime::Runtime;
+use tokio::time::MissedTickBehavior;
+use uuid::Uuid;
 
 const NGINX_HLS_URL_KEY: &str = "NGINX_HLS_URL";
 const STREAM_PREFIX_KEY: &str = "STREAM_PATH_PREFIX";
+/// RTMP application, from which the app reads the streams of the encoders
+const RTMP_INGEST_URL_KEY: &str = "RTMP_INGEST_URL";
+/// The same application as seen by the artists, which publish the streams to it
+const RTMP_INGEST_PUBLIC_URL_KEY: &str = "RTMP_INGEST_PUBLIC_URL";
+/// Name of this instance of the app, every instance sharing the database must have its own
+const INSTANCE_ID_KEY: &str = "INSTANCE_ID";
+const DEFAULT_INSTANCE_ID: &str = "default";
+const STREAM_KEY_LENGTH: usize = 32;
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
 
 lazy_static! {
     static ref STREAM_PREFIX: String =
@@ -42,6 +65,17 @@ pub trait StreamFacadeTrait {
     /// # Returns
     /// `i32` - stream ID of created stream
     async fn start_stream(&self, live_stream: LiveStreamStart, user_id: i32) -> Result<i32>;
+    /// Creates stream of the given video, which waits until the artist starts sending
+    /// the stream from his encoder, e.g. OBS
+    ///
+    /// # Returns
+    /// `i32` - stream ID of created stream
+    async fn start_rtmp_stream(&self, live_stream: LiveStreamStart, user_id: i32) -> Result<i32>;
+    /// Schedules stream of the given video, which starts automatically at the given time
+    ///
+    /// # Returns
+    /// `i32` - stream ID of the scheduled stream
+    async fn schedule_stream(&self, schedule: LiveStreamSchedule, user_id: i32) -> Result<i32>;
     /// Gets live stream by it's ID
     ///
     /// # Returns
@@ -57,7 +91,61 @@ pub trait StreamFacadeTrait {
     ///
     /// # Params
     /// `stream_url` - e.g. /hls/stream-3.m3u8 (stream-{id}.m3u8)
-    async fn authenticate_stream(&self, user_id: Option<i32>, stream_url: &str) -> Result<()>;
+    ///
+    /// # Returns
+    /// ID of the stream, which the user can access
+    async fn authenticate_stream(&self, user_id: Option<i32>, stream_url: &str) -> Result<i32>;
+    /// Returns ID of the running stream of the artist, if the user can watch it
+    async fn get_active_stream_of_artist(
+        &self,
+        artist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<Option<i32>>;
+    /// Returns where the artist sends the stream from his encoder.
+    /// The stream key is generated, if the artist doesn't have any yet.
+    async fn get_stream_ingest(&self, user_id: i32) -> Result<StreamIngest>;
+    /// Generates new stream key of the artist, the previous key stops working
+    async fn regenerate_stream_key(&self, user_id: i32) -> Result<StreamIngest>;
+    /// Nginx asks for authorization when the encoder starts publishing the stream.
+    /// Publishing is allowed only if the owner of the stream key has a stream waiting
+    /// for the encoder, the incoming feed is then transcoded into the stream.
+    async fn authorize_publish(&self, stream_key: &str) -> Result<()>;
+    /// Pipelines of the streams live only in the memory, so the streams running before
+    /// the restart of the app are orphaned. They are started again, if it's enabled
+    /// in the configuration, otherwise they are marked as ended. Only the streams started
+    /// by this instance are reconciled, the streams of the other instances keep running.
+    /// Should be called once on startup, before any stream is started.
+    async fn reconcile_streams(&self) -> Result<()>;
+    /// Ends all running streams, so their recordings are finished. Streams, which don't end
+    /// within the configured timeout, are stopped immediately.
+    /// Should be called on shutdown of the app.
+    async fn shutdown_streams(&self) -> Result<()>;
+    /// Returns notifications of the scheduled stream, if the user can watch it
+    async fn get_reminders(&self, stream_id: i32, user_id: Option<i32>) -> Result<StreamReminders>;
+    /// Sets whether the user is notified, when the scheduled stream starts
+    async fn set_reminder(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        enabled: bool,
+    ) -> Result<StreamReminders>;
+    /// Returns running streams, which the user asked to be notified about
+    async fn list_live_reminders(&self, user_id: i32) -> Result<Vec<RemindedStream>>;
+    /// Returns running and upcoming scheduled streams, which the user can watch
+    ///
+    /// # Returns
+    /// Tuple with:
+    /// - Running streams, the earliest started first
+    /// - Scheduled streams, which didn't start yet, the soonest first
+    async fn list_streams(
+        &self,
+        user_id: Option<i32>,
+    ) -> Result<(Vec<ListedStream>, Vec<ListedStream>)>;
+    /// Starts the scheduled streams, whose start time has come
+    ///
+    /// # Returns
+    /// Number of the started streams
+    async fn start_due_streams(&self) -> Result<usize>;
 }
 
 /// Proxy for calling gstreamer_controller functions. It allows mocking the streamer in integration tests
@@ -70,6 +158,9 @@ pub trait GStreamerProxyTrait {
     ) -> anyhow::Result<Vec<JoinHandle<()>>>;
 
     fn stop_stream(&self, pipeline: &Pipeline) -> anyhow::Result<()>;
+
+    /// Stops the pipeline immediately, the unsent data and the end of the recording are lost
+    fn kill_stream(&self, pipeline: &Pipeline) -> anyhow::Result<()>;
 }
 
 pub struct GStreamerProxy {}
@@ -85,21 +176,32 @@ impl GStreamerProxyTrait for GStreamerProxy {
     }
 
     fn stop_stream(&self, pipeline: &Pipeline) -> anyhow::Result<()> {
+        // The pipeline stops itself after the end of the stream, so the recording is finished
+        streamer::gstreamer_controller::end_stream(pipeline)
+    }
+
+    fn kill_stream(&self, pipeline: &Pipeline) -> anyhow::Result<()> {
         streamer::gstreamer_controller::stop_stream(pipeline)
     }
 }
 
 pub struct StreamFacade {
     video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    artist_facade: Arc<dyn ArtistFacadeTrait + Send + Sync>,
     stream_storage: Arc<StreamStorage>,
     stream_repo: Arc<dyn StreamRepoTrait + Send + Sync>,
     gstreamer_proxy: Arc<dyn GStreamerProxyTrait + Send + Sync>,
     app_configuration: Arc<Configuration>,
+    /// Name of this instance of the app, which is recorded on the streams it runs
+    instance_id: String,
+    /// Threads, which wait for the pipelines of the stream and finish it, by stream ID
+    finishing_threads: Mutex<Vec<(String, JoinHandle<Result<()>>)>>,
 }
 
 impl StreamFacade {
     pub fn new(
         video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+        artist_facade: Arc<dyn ArtistFacadeTrait + Send + Sync>,
         stream_storage: Arc<StreamStorage>,
         stream_repo: Arc<dyn StreamRepoTrait + Send + Sync>,
         gstreamer_proxy: Option<Arc<dyn GStreamerProxyTrait + Send + Sync>>,
@@ -107,16 +209,50 @@ impl StreamFacade {
     ) -> Self {
         Self {
             video_facade,
+            artist_facade,
             stream_storage,
             stream_repo,
             gstreamer_proxy: gstreamer_proxy.unwrap_or(Arc::new(GStreamerProxy {})),
             app_configuration,
+            instance_id: dotenvy::var(INSTANCE_ID_KEY).unwrap_or(String::from(DEFAULT_INSTANCE_ID)),
+            finishing_threads: Mutex::new(Vec::new()),
         }
     }
 
-    fn create_stream(&self, stream_info: Arc<CompoundStreamInfo>) -> Result<String> {
+    /// Periodically starts the scheduled streams in the background task
+    pub fn start_scheduler(stream_facade: Arc<StreamFacade>) {
+        let period = Duration::from_secs(
+            stream_facade
+                .app_configuration
+                .app
+                .stream
+                .scheduler_interval_seconds
+                .max(1),
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(err) = stream_facade.start_due_streams().await {
+                    error!("Failed to start the scheduled streams, {:?}", err);
+                }
+            }
+        });
+    }
+
+    async fn create_stream(&self, stream_info: Arc<CompoundStreamInfo>) -> Result<String> {
         let stream_url = self.create_stream_url(stream_info.stream_id.clone())?;
+        let stream_id = stream_info
+            .stream_id
+            .parse()
+            .app_error("Stream ID has unexpected format")?;
+        self.stream_repo
+            .set_instance(stream_id, &self.instance_id)
+            .await?;
         let stream_repo = self.stream_repo.clone();
+        let video_facade = self.video_facade.clone();
         let info = stream_info.clone();
 
         let stream_storage = self.stream_storage.clone();
@@ -125,19 +261,81 @@ impl StreamFacade {
             .gstreamer_proxy
             .create_streams(stream_storage, stream_info.clone())
             .app_error("Failed to create streams")?;
-        thread::spawn::<_, Result<()>>(move || {
+        let finishing_thread = thread::spawn::<_, Result<()>>(move || {
             for handle in handles {
                 handle.join().app_error("Failed to end the stream")?;
             }
 
             let runtime = Runtime::new().app_error("Failed to end the stream")?;
-            runtime.block_on(Self::mark_stream_as_ended(info, stream_repo))?;
+            runtime.block_on(Self::mark_stream_as_ended(
+                info.clone(),
+                stream_repo.clone(),
+            ))?;
+            runtime.block_on(Self::save_recording(info, stream_repo, video_facade))?;
             Ok(())
         });
 
+        let mut finishing_threads = self.finishing_threads.lock().unwrap();
+        finishing_threads.retain(|(_stream_id, thread)| !thread.is_finished());
+        finishing_threads.push((stream_info.stream_id.clone(), finishing_thread));
+
         Ok(stream_url)
     }
 
+    /// Registers the recording of the ended stream as a draft video of the artist
+    async fn save_recording(
+        stream_info: Arc<CompoundStreamInfo>,
+        stream_repo: Arc<dyn StreamRepoTrait + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    ) -> Result<()> {
+        let Some(record_path) = stream_info.record_path.clone() else {
+            return Ok(());
+        };
+        let recorded = tokio::fs::metadata(&record_path)
+            .await
+            .is_ok_and(|metadata| metadata.len() > 0);
+        if !recorded {
+            tokio::fs::remove_file(&record_path).await.ok();
+            return Ok(());
+        }
+
+        let stream_id = stream_info
+            .stream_id
+            .parse()
+            .app_error("Stream ID has unexpected format")?;
+        let streamed_video = stream_repo.get_streamed_video(stream_id).await?;
+        let recording = match video_facade
+            .save_recording(&streamed_video, record_path.clone())
+            .await
+        {
+            Ok(recording) => recording,
+            Err(err) => {
+                error!(
+                    "Failed to save recording {} of stream with ID: {}, {:?}",
+                    record_path, stream_id, err
+                );
+                return Err(err);
+            }
+        };
+        stream_repo.set_recording(stream_id, recording.id).await?;
+        info!(
+            "Recording of stream with ID: {} was saved as video with ID: {}",
+            stream_id, recording.id
+        );
+
+        Ok(())
+    }
+
+    /// Returns path, where the stream is recorded, or `None` if the recording is disabled
+    fn create_record_path(&self) -> Option<String> {
+        if !self.app_configuration.app.stream.record {
+            return None;
+        }
+
+        let (video_dir, _) = self.video_facade.get_video_thumbnail_dirs();
+        Some(format!("{video_dir}/{}.mp4", Uuid::new_v4()))
+    }
+
     async fn mark_stream_as_ended(
         stream_info: Arc<CompoundStreamInfo>,
         pg_stream_repo: Arc<dyn StreamRepoTrait + Send + Sync>,
@@ -163,37 +361,173 @@ impl StreamFacade {
 
         Ok(url)
     }
-}
 
-#[async_trait]
-impl StreamFacadeTrait for StreamFacade {
-    async fn start_stream(&self, live_stream: LiveStreamStart, user_id: i32) -> Result<i32> {
+    /// Composes URL, from which the app reads the feed published with the stream key
+    fn create_ingest_url(stream_key: &str) -> Result<String> {
+        let server_url =
+            dotenvy::var(RTMP_INGEST_URL_KEY).app_error("Stream is wrongly configured")?;
+
+        Ok(format!("{}/{stream_key}", server_url.trim_end_matches('/')))
+    }
+
+    fn generate_stream_key() -> String {
+        rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(STREAM_KEY_LENGTH)
+            .map(char::from)
+            .collect()
+    }
+
+    async fn get_owned_video(&self, video_id: i32, user_id: i32) -> Result<VideoEntity> {
         let video = self
             .video_facade
-            .get_video_entity(live_stream.video_id, Some(user_id))
+            .get_video_entity(video_id, Some(user_id))
             .await?;
 
         self.video_facade
             .is_video_owner(video.artist_id, user_id)
             .await?;
+        Ok(video)
+    }
+
+    /// Skips the streams of the videos, which the user is not allowed to watch
+    async fn filter_visible(
+        &self,
+        streams: Vec<ListedStream>,
+        user_id: Option<i32>,
+    ) -> Vec<ListedStream> {
+        let mut visible_streams = Vec::with_capacity(streams.len());
+        for stream in streams {
+            if self
+                .video_facade
+                .check_permissions(&stream.video, user_id)
+                .await
+                .is_ok()
+            {
+                visible_streams.push(stream);
+            }
+        }
+        visible_streams
+    }
+
+    fn get_resolutions(&self) -> Vec<StreamResolution> {
+        StreamResolution::from_profiles(&self.app_configuration.app.stream.profiles)
+    }
+
+    async fn set_new_stream_key(&self, artist_id: i32) -> Result<String> {
+        let stream_key = Self::generate_stream_key();
+        self.stream_repo
+            .set_stream_key(artist_id, &stream_key)
+            .await?;
+
+        Ok(stream_key)
+    }
+
+    /// Starts the pipelines of the orphaned stream again, the stream of the file starts from
+    /// the beginning and the stream from the encoder waits until the encoder reconnects
+    async fn restart_stream(&self, stream: &LiveStream) -> Result<()> {
+        if stream.source == LiveStreamSource::Rtmp {
+            self.stream_repo
+                .change_status(stream.id, LiveStreamStatus::Pending)
+                .await?;
+            return Ok(());
+        }
+
+        self.start_file_stream(stream.id).await
+    }
+
+    /// Starts the pipelines, which stream the video of the stream
+    async fn start_file_stream(&self, stream_id: i32) -> Result<()> {
+        let video = self.stream_repo.get_streamed_video(stream_id).await?;
+        let resolutions = StreamResolution::filter_by_source(self.get_resolutions(), video.height);
+        let stream_info = CompoundStreamInfo::new(
+            stream_id.to_string(),
+            StreamSource::File(video.file_path),
+            resolutions,
+            self.create_record_path(),
+        );
+        self.create_stream(Arc::new(stream_info)).await?;
+        Ok(())
+    }
+
+    async fn mark_as_ended(&self, stream_id: &str) -> Result<()> {
+        let stream_id = stream_id
+            .parse()
+            .app_error("Stream ID has unexpected format")?;
+        self.stream_repo
+            .change_status(stream_id, LiveStreamStatus::Ended)
+            .await?;
+        Ok(())
+    }
+
+    /// Returns IDs of the streams, which are not finished yet
+    fn get_unfinished_streams(&self) -> Vec<String> {
+        let finishing_threads = self.finishing_threads.lock().unwrap();
+        finishing_threads
+            .iter()
+            .filter(|(_stream_id, thread)| !thread.is_finished())
+            .map(|(stream_id, _thread)| stream_id.clone())
+            .collect()
+    }
+
+    fn to_ingest(stream_key: String) -> Result<StreamIngest> {
+        Ok(StreamIngest {
+            server_url: dotenvy::var(RTMP_INGEST_PUBLIC_URL_KEY)
+                .app_error("Stream is wrongly configured")?,
+            stream_key,
+        })
+    }
+}
+
+#[async_trait]
+impl StreamFacadeTrait for StreamFacade {
+    async fn start_stream(&self, live_stream: LiveStreamStart, user_id: i32) -> Result<i32> {
+        let video = self.get_owned_video(live_stream.video_id, user_id).await?;
 
         let stream_id = self
             .stream_repo
             .add_stream(LiveStream::from(&live_stream))
             .await?;
 
-        let resolutions: Result<Vec<StreamResolution>> = self
-            .app_configuration
-            .app
-            .stream
-            .resolutions
-            .iter()
-            .map(|res| StreamResolution::from_str(res))
-            .collect();
+        let resolutions = StreamResolution::filter_by_source(self.get_resolutions(), video.height);
+
+        let stream_info = CompoundStreamInfo::new(
+            stream_id.to_string(),
+            StreamSource::File(video.file_path),
+            resolutions,
+            self.create_record_path(),
+        );
+        self.create_stream(Arc::new(stream_info)).await?;
+        Ok(stream_id)
+    }
+
+    async fn start_rtmp_stream(&self, live_stream: LiveStreamStart, user_id: i32) -> Result<i32> {
+        self.get_owned_video(live_stream.video_id, user_id).await?;
+
+        let stream_id = self
+            .stream_repo
+            .add_stream(LiveStream {
+                status: LiveStreamStatus::Pending,
+                source: LiveStreamSource::Rtmp,
+                ..LiveStream::from(&live_stream)
+            })
+            .await?;
+        Ok(stream_id)
+    }
+
+    async fn schedule_stream(&self, schedule: LiveStreamSchedule, user_id: i32) -> Result<i32> {
+        self.get_owned_video(schedule.video_id, user_id).await?;
+        if schedule.start_time <= Local::now() {
+            return Err(AppError::new(
+                "The stream must be scheduled to the future",
+                AppErrorKind::BadRequestError,
+            ));
+        }
 
-        let stream_info =
-            CompoundStreamInfo::new(stream_id.to_string(), video.file_path, resolutions?);
-        self.create_stream(Arc::new(stream_info))?;
+        let stream_id = self
+            .stream_repo
+            .add_stream(LiveStream::from(&schedule))
+            .await?;
         Ok(stream_id)
     }
 
@@ -226,7 +560,22 @@ impl StreamFacadeTrait for StreamFacade {
             .is_video_owner(video.artist_id, user_id)
             .await?;
 
-        self.stream_storage
+        // Pending streams have no pipelines yet, so they are only cancelled.
+        // If the stream started meanwhile, its pipelines are stopped below.
+        let cancelled = self
+            .stream_repo
+            .change_status_if(
+                stream_id,
+                LiveStreamStatus::Pending,
+                LiveStreamStatus::Ended,
+            )
+            .await?;
+        if cancelled {
+            return Ok(());
+        }
+
+        let stopped = self
+            .stream_storage
             .run_on(&stream_id.to_string(), |stream| {
                 let (_info, pipelines) = stream;
                 for pipeline in pipelines {
@@ -236,7 +585,18 @@ impl StreamFacadeTrait for StreamFacade {
                 }
 
                 Ok(())
-            })?;
+            });
+        // The stream has no pipelines in this instance, e.g. they already finished
+        // or the app was restarted, so there is nothing to stop
+        if let Err(err) = stopped {
+            if !matches!(err.error, AppErrorKind::NotFound) {
+                return Err(err);
+            }
+            info!(
+                "Stream with ID: {} has no running pipelines, it's only marked as ended",
+                stream_id
+            );
+        }
 
         self.stream_repo
             .change_status(stream_id, LiveStreamStatus::Ended)
@@ -244,7 +604,7 @@ impl StreamFacadeTrait for StreamFacade {
         Ok(())
     }
 
-    async fn authenticate_stream(&self, user_id: Option<i32>, stream_url: &str) -> Result<()> {
+    async fn authenticate_stream(&self, user_id: Option<i32>, stream_url: &str) -> Result<i32> {
         let err = AppError::new("Failed to parse stream URL", AppErrorKind::AccessDenied);
         let regex;
         if stream_url.ends_with(".m3u8") {
@@ -266,6 +626,484 @@ impl StreamFacadeTrait for StreamFacade {
 
         let video = self.stream_repo.get_streamed_video(stream_id).await?;
         self.video_facade.check_permissions(&video, user_id).await?;
+        Ok(stream_id)
+    }
+
+    async fn get_active_stream_of_artist(
+        &self,
+        artist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<Option<i32>> {
+        let Some(stream) = self
+            .stream_repo
+            .get_active_stream_of_artist(artist_id)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let video = self.stream_repo.get_streamed_video(stream.id).await?;
+        if self
+            .video_facade
+            .check_permissions(&video, user_id)
+            .await
+            .is_err()
+        {
+            return Ok(None);
+        }
+        Ok(Some(stream.id))
+    }
+
+    async fn get_stream_ingest(&self, user_id: i32) -> Result<StreamIngest> {
+        let artist = self
+            .artist_facade
+            .get_artist_internal(user_id, None)
+            .await?;
+        let stream_key = match self.stream_repo.get_stream_key(artist.id).await? {
+            Some(stream_key) => stream_key,
+            None => self.set_new_stream_key(artist.id).await?,
+        };
+
+        Self::to_ingest(stream_key)
+    }
+
+    async fn regenerate_stream_key(&self, user_id: i32) -> Result<StreamIngest> {
+        let artist = self
+            .artist_facade
+            .get_artist_internal(user_id, None)
+            .await?;
+        let stream_key = self.set_new_stream_key(artist.id).await?;
+
+        Self::to_ingest(stream_key)
+    }
+
+    async fn authorize_publish(&self, stream_key: &str) -> Result<()> {
+        let denied = || {
+            AppError::new(
+                "Publishing of the stream denied",
+                AppErrorKind::AccessDenied,
+            )
+        };
+        let artist_id = self
+            .stream_repo
+            .get_artist_by_stream_key(stream_key)
+            .await?
+            .ok_or_else(denied)?;
+        let stream = self
+            .stream_repo
+            .get_waiting_rtmp_stream(artist_id)
+            .await?
+            .ok_or_else(denied)?;
+
+        // Nginx lets the pipeline subscribe before the publishing is allowed,
+        // the feed is delivered to it as soon as the encoder starts sending it
+        let source = StreamSource::Rtmp(Self::create_ingest_url(stream_key)?);
+        let stream_info = CompoundStreamInfo::new(
+            stream.id.to_string(),
+            source,
+            self.get_resolutions(),
+            self.create_record_path(),
+        );
+
+        self.stream_repo
+            .change_status(stream.id, LiveStreamStatus::Running)
+            .await?;
+        if let Err(err) = self.create_stream(Arc::new(stream_info)).await {
+            self.stream_repo
+                .change_status(stream.id, LiveStreamStatus::Ended)
+                .await?;
+            return Err(err);
+        }
         Ok(())
     }
+
+    async fn reconcile_streams(&self) -> Result<()> {
+        let running_streams = self
+            .stream_repo
+            .list_instance_streams(&self.instance_id, LiveStreamStatus::Running)
+            .await?;
+        let active_streams = self.stream_storage.stream_ids();
+
+        for stream in running_streams {
+            if active_streams.contains(&stream.id.to_string()) {
+                continue;
+            }
+
+            if self.app_configuration.app.stream.restart_orphaned {
+                match self.restart_stream(&stream).await {
+                    Ok(()) => {
+                        info!("Orphaned stream with ID: {} was restarted", stream.id);
+                        continue;
+                    }
+                    Err(err) => error!(
+                        "Failed to restart orphaned stream with ID: {}, {:?}",
+                        stream.id, err
+                    ),
+                }
+            }
+
+            self.stream_repo
+                .change_status(stream.id, LiveStreamStatus::Ended)
+                .await?;
+            info!("Orphaned stream with ID: {} was marked as ended", stream.id);
+        }
+
+        Ok(())
+    }
+
+    async fn shutdown_streams(&self) -> Result<()> {
+        let stream_ids = self.stream_storage.stream_ids();
+        for stream_id in &stream_ids {
+            let ended = self.stream_storage.run_on(stream_id, |(_info, pipelines)| {
+                for pipeline in pipelines {
+                    self.gstreamer_proxy
+                        .stop_stream(pipeline)
+                        .app_error("Failed to stop the stream")?;
+                }
+                Ok(())
+            });
+            if let Err(err) = ended {
+                error!("Failed to end stream with ID: {}, {:?}", stream_id, err);
+            }
+        }
+
+        let timeout =
+            Duration::from_secs(self.app_configuration.app.stream.shutdown_timeout_seconds);
+        let deadline = Instant::now() + timeout;
+        while !self.get_unfinished_streams().is_empty() && Instant::now() < deadline {
+            tokio::time::sleep(SHUTDOWN_POLL_INTERVAL).await;
+        }
+
+        for stream_id in self.get_unfinished_streams() {
+            warn!(
+                "Stream with ID: {} didn't end in time, it's stopped immediately",
+                stream_id
+            );
+            self.stream_storage
+                .run_on(&stream_id, |(_info, pipelines)| {
+                    for pipeline in pipelines {
+                        self.gstreamer_proxy
+                            .kill_stream(pipeline)
+                            .app_error("Failed to stop the stream")?;
+                    }
+                    Ok(())
+                })
+                .ok();
+
+            if let Err(err) = self.mark_as_ended(&stream_id).await {
+                error!("Failed to end stream with ID: {}, {:?}", stream_id, err);
+            }
+        }
+
+        info!("{} streams were ended on shutdown", stream_ids.len());
+        Ok(())
+    }
+
+    async fn get_reminders(&self, stream_id: i32, user_id: Option<i32>) -> Result<StreamReminders> {
+        self.get_stream(user_id, stream_id).await?;
+        let is_reminded = match user_id {
+            Some(user_id) => self.stream_repo.has_reminder(stream_id, user_id).await?,
+            None => false,
+        };
+
+        Ok(StreamReminders {
+            stream_id,
+            can_remind: user_id.is_some(),
+            is_reminded,
+            reminder_count: self.stream_repo.count_reminders(stream_id).await?,
+        })
+    }
+
+    async fn set_reminder(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        enabled: bool,
+    ) -> Result<StreamReminders> {
+        let (_, stream) = self.get_stream(Some(user_id), stream_id).await?;
+        if enabled && stream.status != models::stream::LiveStreamStatus::Pending {
+            return Err(AppError::new(
+                "The stream has already started",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        match enabled {
+            true => self.stream_repo.add_reminder(stream_id, user_id).await?,
+            false => self.stream_repo.delete_reminder(stream_id, user_id).await?,
+        }
+        self.get_reminders(stream_id, Some(user_id)).await
+    }
+
+    async fn list_live_reminders(&self, user_id: i32) -> Result<Vec<RemindedStream>> {
+        let streams = self
+            .stream_repo
+            .list_reminded_streams(user_id, LiveStreamStatus::Running)
+            .await?;
+
+        Ok(streams.to_mapped_list(|stream| RemindedStream::from(&stream)))
+    }
+
+    async fn list_streams(
+        &self,
+        user_id: Option<i32>,
+    ) -> Result<(Vec<ListedStream>, Vec<ListedStream>)> {
+        let running = self
+            .stream_repo
+            .list_streams_with_video(LiveStreamStatus::Running)
+            .await?;
+        // Streams from the encoder are pending only until the encoder connects
+        let upcoming = self
+            .stream_repo
+            .list_streams_with_video(LiveStreamStatus::Pending)
+            .await?
+            .into_iter()
+            .filter(|stream| stream.source == LiveStreamSource::File)
+            .collect();
+
+        Ok((
+            self.filter_visible(running, user_id).await,
+            self.filter_visible(upcoming, user_id).await,
+        ))
+    }
+
+    async fn start_due_streams(&self) -> Result<usize> {
+        let due_streams = self.stream_repo.list_due_streams(Local::now()).await?;
+
+        let mut started = 0;
+        for stream in due_streams {
+            // Another instance of the app could have started the stream already
+            let claimed = self
+                .stream_repo
+                .change_status_if(
+                    stream.id,
+                    LiveStreamStatus::Pending,
+                    LiveStreamStatus::Running,
+                )
+                .await?;
+            if !claimed {
+                continue;
+            }
+
+            match self.start_file_stream(stream.id).await {
+                Ok(()) => {
+                    info!("Scheduled stream with ID: {} was started", stream.id);
+                    started += 1;
+                }
+                Err(err) => {
+                    error!(
+                        "Failed to start scheduled stream with ID: {}, {:?}",
+                        stream.id, err
+                    );
+                    self.stream_repo
+                        .change_status(stream.id, LiveStreamStatus::Ended)
+                        .await?;
+                }
+            }
+        }
+
+        Ok(started)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::business::facades::artist::ArtistFacade;
+    use crate::business::facades::temp_file::TempFileFacade;
+    use crate::business::facades::user::UserFacade;
+    use crate::business::facades::video::VideoFacade;
+    use crate::business::facades::video_category::VideoCategoryFacade;
+    use crate::common::tests::media::MediaProxyMock;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::common::tests::stream::StreamProxyMock;
+    use crate::mailer::file::FileMailer;
+    use crate::persistence::entities::video::VideoVisibility;
+    use crate::persistence::repositories::artist::ArtistRepository;
+    use crate::persistence::repositories::stream::PgStreamRepo;
+    use crate::persistence::repositories::temp_file::PgTempFileRepo;
+    use crate::persistence::repositories::unit_of_work::PostgresUnitOfWork;
+    use crate::persistence::repositories::user::UserRepository;
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use crate::persistence::repositories::video_category::VideoCategoryRepository;
+    use crate::streamer::gstreamer_controller::init_gstreamer;
+    use crate::{get_secret_key, init_configuration, setup_redis_pool};
+    use test_context::test_context;
+
+    const OTHER_INSTANCE_ID: &str = "other";
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn reconcile_ends_orphaned_stream(ctx: &mut AsyncContext) -> Result<()> {
+        let facade = create_facade(ctx, Arc::new(StreamProxyMock::default()), |_| {}).await;
+        let orphaned_id = create_running_stream(ctx, &facade.instance_id).await?;
+        let foreign_id = create_running_stream(ctx, OTHER_INSTANCE_ID).await?;
+
+        facade.reconcile_streams().await?;
+
+        assert_eq!(
+            get_status(&facade, orphaned_id).await?,
+            LiveStreamStatus::Ended
+        );
+        assert_eq!(
+            get_status(&facade, foreign_id).await?,
+            LiveStreamStatus::Running,
+            "Stream of another instance was reconciled"
+        );
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn reconcile_restarts_orphaned_stream(ctx: &mut AsyncContext) -> Result<()> {
+        let proxy = Arc::new(StreamProxyMock::stalled());
+        let facade = create_facade(ctx, proxy.clone(), |config| {
+            config.app.stream.restart_orphaned = true
+        })
+        .await;
+        let orphaned_id = create_running_stream(ctx, &facade.instance_id).await?;
+
+        facade.reconcile_streams().await?;
+
+        assert_eq!(
+            get_status(&facade, orphaned_id).await?,
+            LiveStreamStatus::Running
+        );
+        assert!(
+            facade
+                .stream_storage
+                .stream_ids()
+                .contains(&orphaned_id.to_string()),
+            "Pipelines of the orphaned stream were not started"
+        );
+        proxy
+            .kill_stream(&Pipeline::new())
+            .app_error("Failed to stop the stream")?;
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn shutdown_kills_stalled_stream(ctx: &mut AsyncContext) -> Result<()> {
+        let proxy = Arc::new(StreamProxyMock::stalled());
+        let facade = create_facade(ctx, proxy.clone(), |config| {
+            config.app.stream.shutdown_timeout_seconds = 0
+        })
+        .await;
+        let stream_id = create_running_stream(ctx, &facade.instance_id).await?;
+        facade.start_file_stream(stream_id).await?;
+
+        facade.shutdown_streams().await?;
+
+        assert!(proxy.was_killed(), "Stalled stream was not killed");
+        assert_eq!(
+            get_status(&facade, stream_id).await?,
+            LiveStreamStatus::Ended
+        );
+        Ok(())
+    }
+
+    async fn create_facade(
+        ctx: &AsyncContext,
+        gstreamer_proxy: Arc<StreamProxyMock>,
+        configure: impl FnOnce(&mut Configuration),
+    ) -> StreamFacade {
+        init_gstreamer().expect("Failed to initialize GStreamer");
+        let mut app_config = init_configuration().expect("Failed to load test-config.yaml");
+        configure(&mut app_config);
+        let app_config = Arc::new(app_config);
+
+        let unit_of_work = Arc::new(PostgresUnitOfWork::new(ctx.pg_pool.clone()));
+        let redis_pool = setup_redis_pool().await.unwrap();
+        let mailer = Arc::new(
+            FileMailer::new(&app_config.app.mail.from, &ctx.get_mail_dir())
+                .expect("Failed to create the mailer"),
+        );
+        let user_facade = Arc::new(UserFacade::new(
+            Arc::new(UserRepository::new(ctx.pg_pool.clone())),
+            Arc::new(ctx.create_user_session_repo(redis_pool)),
+            mailer,
+            app_config.clone(),
+            get_secret_key().signing().to_vec(),
+        ));
+        let artist_facade = Arc::new(ArtistFacade::new(Arc::new(ArtistRepository::new(
+            ctx.pg_pool.clone(),
+        ))));
+        let media_proxy_mock = Arc::new(MediaProxyMock {});
+        let temp_file_facade = Arc::new(TempFileFacade::new(
+            Arc::new(PgTempFileRepo::new(ctx.pg_pool.clone())),
+            unit_of_work.clone(),
+            format!("{}/temp", ctx.test_folders_root),
+            Some(media_proxy_mock.clone()),
+        ));
+        let video_facade = Arc::new(VideoFacade::new(
+            temp_file_facade,
+            Arc::new(PgVideoRepo::new(ctx.pg_pool.clone())),
+            artist_facade.clone(),
+            user_facade,
+            Arc::new(VideoCategoryFacade::new(Arc::new(
+                VideoCategoryRepository::new(ctx.pg_pool.clone()),
+            ))),
+            unit_of_work,
+            format!("{}/videos", ctx.test_folders_root),
+            format!("{}/thumbnails", ctx.test_folders_root),
+            format!("{}/hls", ctx.test_folders_root),
+            Some(media_proxy_mock),
+            app_config.clone(),
+        ));
+
+        StreamFacade::new(
+            video_facade,
+            artist_facade,
+            Arc::new(StreamStorage::default()),
+            Arc::new(PgStreamRepo::new(ctx.pg_pool.clone())),
+            Some(gstreamer_proxy),
+            app_config,
+        )
+    }
+
+    /// Creates stream, which was running on the given instance before the restart of the app
+    async fn create_running_stream(ctx: &AsyncContext, instance_id: &str) -> Result<i32> {
+        let video = VideoEntity {
+            id: -1,
+            artist_id: 1,
+            visibility: VideoVisibility::All,
+            name: String::from("Streamed video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+        let mut tx = ctx.pg_pool.begin().await.db_error("Failed to begin")?;
+        let video = PgVideoRepo::new(ctx.pg_pool.clone())
+            .save_video(video, &mut tx)
+            .await?;
+        tx.commit().await.db_error("Failed to commit")?;
+
+        let stream_repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let stream_id = stream_repo
+            .add_stream(LiveStream {
+                id: -1,
+                video_id: video.id,
+                start_time: Local::now(),
+                status: LiveStreamStatus::Running,
+                source: LiveStreamSource::File,
+                recording_video_id: None,
+            })
+            .await?;
+        stream_repo.set_instance(stream_id, instance_id).await?;
+        Ok(stream_id)
+    }
+
+    async fn get_status(facade: &StreamFacade, stream_id: i32) -> Result<LiveStreamStatus> {
+        let stream = facade.stream_repo.get_stream(stream_id).await?.unwrap();
+        Ok(stream.status)
+    }
 }
@@ -0,0 +1,257 @@
+use crate::business::facades::stream::StreamFacadeTrait;
+use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::mappers::generic::ToMappedList;
+use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
+use crate::business::models::stream::{LiveStream, LiveStreamStatus};
+use crate::business::models::stream_chat::{
+    ChatEvent, ChatMessage, ChatMessageReq, ChatRoom, ChatTimeoutReq,
+};
+use crate::business::models::video::Video;
+use crate::business::validation::validatable::{EmptyContext, Validatable};
+use crate::business::Result;
+use crate::configuration::models::Configuration;
+use crate::persistence::entities::stream_chat::ChatEvent as ChatEventEntity;
+use crate::persistence::repositories::stream_chat::{ChatBroker, StreamChatRepo};
+use async_trait::async_trait;
+use futures::stream::{BoxStream, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[async_trait]
+pub trait StreamChatFacadeTrait {
+    /// Returns the chat of the stream with its latest messages, if the user can watch the stream
+    async fn get_room(&self, stream_id: i32, user_id: Option<i32>) -> Result<ChatRoom>;
+    /// Saves the message and delivers it to all viewers of the stream.
+    /// Messages can be sent only until the stream ends.
+    async fn send_message(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        req: ChatMessageReq,
+    ) -> Result<ChatMessage>;
+    /// Returns events of the chat, which happen after the subscription
+    async fn subscribe(
+        &self,
+        stream_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<BoxStream<'static, ChatEvent>>;
+    /// Removes the message from the chat, allowed only to the owner of the stream
+    async fn delete_message(&self, stream_id: i32, user_id: i32, message_id: i32) -> Result<()>;
+    /// Disallows the user to send messages for the given time,
+    /// allowed only to the owner of the stream
+    async fn timeout_user(&self, stream_id: i32, user_id: i32, req: ChatTimeoutReq) -> Result<()>;
+    /// Returns the whole chat as plain text, one message per line.
+    /// The transcript is available to the owner of the stream after the stream ends.
+    async fn export_transcript(&self, stream_id: i32, user_id: i32) -> Result<String>;
+}
+
+pub struct StreamChatFacade {
+    stream_facade: Arc<dyn StreamFacadeTrait + Send + Sync>,
+    video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    chat_repo: Arc<dyn StreamChatRepo + Send + Sync>,
+    chat_broker: Arc<dyn ChatBroker + Send + Sync>,
+    config: Arc<Configuration>,
+}
+
+impl StreamChatFacade {
+    pub fn new(
+        stream_facade: Arc<dyn StreamFacadeTrait + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+        chat_repo: Arc<dyn StreamChatRepo + Send + Sync>,
+        chat_broker: Arc<dyn ChatBroker + Send + Sync>,
+        config: Arc<Configuration>,
+    ) -> Self {
+        Self {
+            stream_facade,
+            video_facade,
+            chat_repo,
+            chat_broker,
+            config,
+        }
+    }
+
+    async fn is_owner(&self, video: &Video, user_id: Option<i32>) -> bool {
+        self.video_facade
+            .is_video_owner(video.artist_id, user_id.unwrap_or(-1))
+            .await
+            .is_ok()
+    }
+
+    /// Returns the stream, if the user is its owner
+    async fn get_owned_stream(&self, stream_id: i32, user_id: i32) -> Result<LiveStream> {
+        let (video, stream) = self
+            .stream_facade
+            .get_stream(Some(user_id), stream_id)
+            .await?;
+        if !self.is_owner(&video, Some(user_id)).await {
+            return Err(AppError::new(
+                "Only the owner of the stream can moderate the chat",
+                AppErrorKind::AccessDenied,
+            ));
+        }
+        Ok(stream)
+    }
+
+    async fn publish(&self, stream_id: i32, event: ChatEventEntity) -> Result<()> {
+        self.chat_broker.publish(stream_id, &event).await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StreamChatFacadeTrait for StreamChatFacade {
+    async fn get_room(&self, stream_id: i32, user_id: Option<i32>) -> Result<ChatRoom> {
+        let (video, stream) = self.stream_facade.get_stream(user_id, stream_id).await?;
+        let messages = self
+            .chat_repo
+            .list_recent_messages(stream_id, self.config.app.stream.chat.history_size)
+            .await?;
+
+        Ok(ChatRoom {
+            stream_id,
+            messages: messages.to_mapped_list(|message| ChatMessage::from(&message)),
+            user_id,
+            is_owner: self.is_owner(&video, user_id).await,
+            is_open: stream.status != LiveStreamStatus::Ended,
+        })
+    }
+
+    async fn send_message(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        req: ChatMessageReq,
+    ) -> Result<ChatMessage> {
+        req.validate_model(&EmptyContext::new())
+            .await
+            .app_error_kind("Invalid message", AppErrorKind::BadRequestError)?;
+
+        let (_, stream) = self
+            .stream_facade
+            .get_stream(Some(user_id), stream_id)
+            .await?;
+        if stream.status == LiveStreamStatus::Ended {
+            return Err(AppError::new(
+                "The stream has already ended",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        if let Some(remaining) = self.chat_broker.get_timeout(stream_id, user_id).await? {
+            return Err(AppError::new(
+                &format!(
+                    "You are timed out for another {} seconds",
+                    remaining.as_secs()
+                ),
+                AppErrorKind::AccessDenied,
+            ));
+        }
+
+        let chat_config = &self.config.app.stream.chat;
+        if !self
+            .chat_broker
+            .check_rate_limit(
+                stream_id,
+                user_id,
+                chat_config.message_limit,
+                Duration::from_secs(chat_config.rate_window_seconds),
+            )
+            .await?
+        {
+            return Err(AppError::new(
+                "You are sending messages too fast",
+                AppErrorKind::TooManyRequests,
+            ));
+        }
+
+        let message = self
+            .chat_repo
+            .add_message(stream_id, user_id, req.content.trim())
+            .await?;
+        let dto = ChatMessage::from(&message);
+        self.publish(stream_id, ChatEventEntity::Message(message))
+            .await?;
+
+        Ok(dto)
+    }
+
+    async fn subscribe(
+        &self,
+        stream_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<BoxStream<'static, ChatEvent>> {
+        self.stream_facade.get_stream(user_id, stream_id).await?;
+        let events = self.chat_broker.subscribe(stream_id).await?;
+
+        Ok(events.map(|event| ChatEvent::from(&event)).boxed())
+    }
+
+    async fn delete_message(&self, stream_id: i32, user_id: i32, message_id: i32) -> Result<()> {
+        self.get_owned_stream(stream_id, user_id).await?;
+        if !self.chat_repo.delete_message(stream_id, message_id).await? {
+            return Err(AppError::new(
+                "Message doesn't exist",
+                AppErrorKind::NotFound,
+            ));
+        }
+
+        self.publish(stream_id, ChatEventEntity::Delete { message_id })
+            .await
+    }
+
+    async fn timeout_user(&self, stream_id: i32, user_id: i32, req: ChatTimeoutReq) -> Result<()> {
+        req.validate_model(&EmptyContext::new())
+            .await
+            .app_error_kind("Invalid timeout", AppErrorKind::BadRequestError)?;
+        self.get_owned_stream(stream_id, user_id).await?;
+
+        if req.user_id == user_id {
+            return Err(AppError::new(
+                "You can't time out yourself",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        let minutes = req
+            .minutes
+            .min(self.config.app.stream.chat.max_timeout_minutes);
+        let duration = Duration::from_secs(minutes * 60);
+        self.chat_broker
+            .set_timeout(stream_id, req.user_id, duration)
+            .await?;
+
+        self.publish(
+            stream_id,
+            ChatEventEntity::Timeout {
+                user_id: req.user_id,
+                seconds: duration.as_secs(),
+            },
+        )
+        .await
+    }
+
+    async fn export_transcript(&self, stream_id: i32, user_id: i32) -> Result<String> {
+        let stream = self.get_owned_stream(stream_id, user_id).await?;
+        if stream.status != LiveStreamStatus::Ended {
+            return Err(AppError::new(
+                "The transcript is available after the stream ends",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        let messages = self.chat_repo.list_messages(stream_id).await?;
+        let transcript = messages
+            .iter()
+            .map(|message| {
+                format!(
+                    "[{}] {}: {}\n",
+                    message.created_at.format("%Y-%m-%d %H:%M:%S"),
+                    message.username,
+                    message.content
+                )
+            })
+            .collect();
+
+        Ok(transcript)
+    }
+}
@@ -0,0 +1,211 @@
+use crate::business::facades::stream::StreamFacadeTrait;
+use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::mappers::generic::ToMappedList;
+use crate::business::models::error::MapToAppError;
+use crate::business::models::stream::{LiveStreamStatus, StreamStorage};
+use crate::business::models::stream_telemetry::{StreamHealth, StreamHealthSummary, StreamStats};
+use crate::business::Result;
+use crate::configuration::models::Configuration;
+use crate::persistence::entities::stream_telemetry::StreamHealthSample;
+use crate::persistence::repositories::stream_telemetry::{StreamTelemetryRepo, ViewerTracker};
+use async_trait::async_trait;
+use log::error;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+#[async_trait]
+pub trait StreamTelemetryFacadeTrait {
+    /// Counts the viewer as watching the stream. Players fetch the playlists and segments
+    /// of the stream all the time, so the viewer is counted until he stops watching.
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - ID of the logged-in viewer
+    /// * `ip_address` - IP address of the viewer, used when the viewer is not logged in
+    async fn mark_viewer(
+        &self,
+        stream_id: i32,
+        user_id: Option<i32>,
+        ip_address: Option<&str>,
+    ) -> Result<()>;
+    /// Returns viewer count of the stream. The owner of the stream also gets
+    /// the health of its pipelines.
+    async fn get_stats(&self, stream_id: i32, user_id: Option<i32>) -> Result<StreamStats>;
+    /// Returns number of the viewers, who are watching the stream right now.
+    /// Access of the user to the stream is not checked.
+    async fn count_viewers(&self, stream_id: i32) -> Result<u64>;
+    /// Writes health of the pipelines reported since the previous call to the database,
+    /// together with the peak viewer counts of their streams
+    ///
+    /// # Returns
+    /// Number of the written samples
+    async fn record_health(&self) -> Result<usize>;
+}
+
+pub struct StreamTelemetryFacade {
+    stream_facade: Arc<dyn StreamFacadeTrait + Send + Sync>,
+    video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    telemetry_repo: Arc<dyn StreamTelemetryRepo + Send + Sync>,
+    viewer_tracker: Arc<dyn ViewerTracker + Send + Sync>,
+    stream_storage: Arc<StreamStorage>,
+    app_configuration: Arc<Configuration>,
+}
+
+impl StreamTelemetryFacade {
+    pub fn new(
+        stream_facade: Arc<dyn StreamFacadeTrait + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+        telemetry_repo: Arc<dyn StreamTelemetryRepo + Send + Sync>,
+        viewer_tracker: Arc<dyn ViewerTracker + Send + Sync>,
+        stream_storage: Arc<StreamStorage>,
+        app_configuration: Arc<Configuration>,
+    ) -> Self {
+        Self {
+            stream_facade,
+            video_facade,
+            telemetry_repo,
+            viewer_tracker,
+            stream_storage,
+            app_configuration,
+        }
+    }
+
+    /// Periodically writes health of the running streams to the database in the background task
+    pub fn start_recording(telemetry_facade: Arc<StreamTelemetryFacade>) {
+        let period = Duration::from_secs(
+            telemetry_facade
+                .app_configuration
+                .app
+                .stream
+                .telemetry
+                .record_interval_seconds
+                .max(1),
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(err) = telemetry_facade.record_health().await {
+                    error!("Failed to record health of the streams, {:?}", err);
+                }
+            }
+        });
+    }
+
+    fn viewer_window(&self) -> Duration {
+        Duration::from_secs(
+            self.app_configuration
+                .app
+                .stream
+                .telemetry
+                .viewer_window_seconds,
+        )
+    }
+}
+
+#[async_trait]
+impl StreamTelemetryFacadeTrait for StreamTelemetryFacade {
+    async fn mark_viewer(
+        &self,
+        stream_id: i32,
+        user_id: Option<i32>,
+        ip_address: Option<&str>,
+    ) -> Result<()> {
+        let viewer = match (user_id, ip_address) {
+            (Some(user_id), _) => format!("user:{user_id}"),
+            (None, Some(ip_address)) => format!("ip:{ip_address}"),
+            (None, None) => return Ok(()),
+        };
+
+        self.viewer_tracker
+            .mark_viewer(stream_id, &viewer, self.viewer_window())
+            .await?;
+        Ok(())
+    }
+
+    async fn get_stats(&self, stream_id: i32, user_id: Option<i32>) -> Result<StreamStats> {
+        let (video, stream) = self.stream_facade.get_stream(user_id, stream_id).await?;
+        let is_owner = self
+            .video_facade
+            .is_video_owner(video.artist_id, user_id.unwrap_or(-1))
+            .await
+            .is_ok();
+        let is_running = stream.status == LiveStreamStatus::Running;
+
+        let viewer_count = match is_running {
+            true => {
+                self.viewer_tracker
+                    .count_viewers(stream_id, self.viewer_window())
+                    .await?
+            }
+            false => 0,
+        };
+
+        let mut health = Vec::new();
+        let mut health_summary = Vec::new();
+        if is_owner && is_running {
+            health = self
+                .telemetry_repo
+                .list_latest_health(stream_id)
+                .await?
+                .to_mapped_list(|sample| StreamHealth::from(&sample));
+        } else if is_owner && stream.status == LiveStreamStatus::Ended {
+            health_summary = self
+                .telemetry_repo
+                .get_health_summary(stream_id)
+                .await?
+                .to_mapped_list(|summary| StreamHealthSummary::from(&summary));
+        }
+
+        Ok(StreamStats {
+            stream_id,
+            is_running,
+            viewer_count,
+            peak_viewers: self.telemetry_repo.get_peak_viewers(stream_id).await?,
+            is_owner,
+            health,
+            health_summary,
+        })
+    }
+
+    async fn count_viewers(&self, stream_id: i32) -> Result<u64> {
+        let viewer_count = self
+            .viewer_tracker
+            .count_viewers(stream_id, self.viewer_window())
+            .await?;
+        Ok(viewer_count)
+    }
+
+    async fn record_health(&self) -> Result<usize> {
+        let reports = self.stream_storage.take_health();
+        if reports.is_empty() {
+            return Ok(0);
+        }
+
+        let mut samples = Vec::with_capacity(reports.len());
+        for (stream_id, health) in &reports {
+            let stream_id = stream_id
+                .parse()
+                .app_error("Stream ID has unexpected format")?;
+            samples.push(StreamHealthSample::from_pipeline(stream_id, health));
+        }
+        self.telemetry_repo.add_health_samples(&samples).await?;
+
+        let stream_ids: HashSet<i32> = samples.iter().map(|sample| sample.stream_id).collect();
+        for stream_id in stream_ids {
+            let viewer_count = self
+                .viewer_tracker
+                .count_viewers(stream_id, self.viewer_window())
+                .await?;
+            self.telemetry_repo
+                .update_peak_viewers(stream_id, viewer_count as i32)
+                .await?;
+        }
+
+        Ok(samples.len())
+    }
+}
@@ -1,17 +1,32 @@
+use crate::business::facades::video::{MediaProxy, MediaProxyTrait};
 use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
+use crate::business::models::temp_file::{CreateUploadReq, UploadStatus};
 use crate::business::util::file::{create_dir_if_not_exist, get_file_extension};
+use crate::business::validation::validatable::{EmptyContext, Validatable};
 use crate::business::Result;
-use crate::persistence::entities::temp_file::TempFile;
+use crate::persistence::entities::temp_file::{TempFile, TempUpload};
 use crate::persistence::repositories::temp_file::TempFileRepo;
+use crate::persistence::repositories::unit_of_work::UnitOfWork;
 use actix_files::NamedFile;
 use async_trait::async_trait;
-use log::{debug, warn};
+use chrono::Utc;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
+use log::{debug, error, info, warn};
 use sqlx::{Postgres, Transaction};
+use std::io::SeekFrom;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 use tempfile::NamedTempFile;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::time::MissedTickBehavior;
 use uuid::Uuid;
 
+pub(crate) const THUMBNAIL_WIDTH: u32 = 640;
+pub(crate) const THUMBNAIL_HEIGHT: u32 = 360;
+const UPLOAD_EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 #[async_trait]
 pub trait TempFileFacadeTrait {
     async fn persist_temp_file(
@@ -24,8 +39,7 @@ pub trait TempFileFacadeTrait {
     async fn get_temp_file(&self, file_id: i32, user_id: i32) -> Result<NamedFile>;
     async fn create_temp_directory(temp_file_dir: String) -> anyhow::Result<()>;
     async fn delete_all_temp_files(&self) -> Result<()>;
-    async fn check_mime_type(&self, file: &NamedTempFile, allowed_types: Vec<String>)
-        -> Result<()>;
+    async fn check_mime_type(&self, file: &Path, allowed_types: Vec<String>) -> Result<()>;
 
     async fn persist_permanently(
         &self,
@@ -35,22 +49,102 @@ pub trait TempFileFacadeTrait {
         tx: &mut Transaction<Postgres>,
     ) -> Result<String>;
     async fn delete_temp_file(&self, temp_file_id: i32, user_id: i32) -> Result<()>;
+    async fn create_thumbnail_candidates(
+        &self,
+        video_temp_id: i32,
+        user_id: i32,
+        count: u32,
+    ) -> Result<Vec<i32>>;
+    async fn create_upload(
+        &self,
+        upload_req: CreateUploadReq,
+        max_length: i64,
+        user_id: i32,
+    ) -> Result<i32>;
+    async fn get_upload_status(&self, upload_id: i32, user_id: i32) -> Result<UploadStatus>;
+    async fn append_upload_chunk(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<UploadStatus>;
+    async fn finalize_upload(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        allowed_types: Vec<String>,
+    ) -> Result<i32>;
+    /// Removes the uploads, which didn't receive any chunk for the `expiry`
+    ///
+    /// # Returns
+    ///
+    /// - number of the removed uploads.
+    async fn delete_inactive_uploads(&self, expiry: Duration) -> Result<u64>;
     fn get_temp_directory_path(&self) -> String;
 }
 
 #[derive(Clone)]
 pub struct TempFileFacade {
     temp_file_repo: Arc<dyn TempFileRepo + Sync + Send>,
+    unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
     temp_file_dir: String,
+    media_proxy: Arc<dyn MediaProxyTrait + Send + Sync>,
 }
 
 impl TempFileFacade {
-    pub fn new(temp_file_repo: Arc<dyn TempFileRepo + Sync + Send>, temp_file_dir: String) -> Self {
+    pub fn new(
+        temp_file_repo: Arc<dyn TempFileRepo + Sync + Send>,
+        unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
+        temp_file_dir: String,
+        media_proxy: Option<Arc<dyn MediaProxyTrait + Send + Sync>>,
+    ) -> Self {
         Self {
             temp_file_repo,
+            unit_of_work,
             temp_file_dir,
+            media_proxy: media_proxy.unwrap_or(Arc::new(MediaProxy {})),
         }
     }
+
+    /// Periodically removes the chunked uploads, which were abandoned by the clients
+    ///
+    /// # Arguments
+    ///
+    /// * `expiry` - time without any received chunk, after which the upload is removed
+    pub fn start_expiring_uploads(temp_file_facade: Arc<TempFileFacade>, expiry: Duration) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(UPLOAD_EXPIRY_CHECK_INTERVAL);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match temp_file_facade.delete_inactive_uploads(expiry).await {
+                    Ok(0) => {}
+                    Ok(count) => info!("{} abandoned uploads were removed", count),
+                    Err(err) => error!("Failed to remove abandoned uploads, {:?}", err),
+                }
+            }
+        });
+    }
+
+    async fn create_temp_file_path(&self, file_name: String) -> String {
+        format!(
+            "./{}/{}.{}",
+            self.temp_file_dir,
+            Uuid::new_v4(),
+            get_file_extension(file_name).await
+        )
+    }
+
+    async fn get_upload(&self, upload_id: i32, user_id: i32) -> Result<TempUpload> {
+        self.temp_file_repo
+            .get_upload(upload_id, user_id)
+            .await?
+            .ok_or(AppError::new(
+                "Upload doesn't exist",
+                AppErrorKind::NotFound,
+            ))
+    }
 }
 
 #[async_trait]
@@ -72,14 +166,7 @@ impl TempFileFacadeTrait for TempFileFacade {
         file_name: String,
         user_id: i32,
     ) -> Result<i32> {
-        let uuid = Uuid::new_v4();
-
-        let path_str = format!(
-            "./{}/{}.{}",
-            self.temp_file_dir,
-            uuid,
-            get_file_extension(file_name).await
-        );
+        let path_str = self.create_temp_file_path(file_name).await;
         let entity = TempFile {
             id: -1,
             user_id,
@@ -130,13 +217,9 @@ impl TempFileFacadeTrait for TempFileFacade {
         Ok(())
     }
 
-    async fn check_mime_type(
-        &self,
-        file: &NamedTempFile,
-        allowed_types: Vec<String>,
-    ) -> Result<()> {
+    async fn check_mime_type(&self, file: &Path, allowed_types: Vec<String>) -> Result<()> {
         let temp_file_type =
-            infer::get_from_path(file.path()).app_error("Failed to read temporary file")?;
+            infer::get_from_path(file).app_error("Failed to read temporary file")?;
         match temp_file_type {
             None => Err(AppError::new(
                 "Failed to extract MimeType from file",
@@ -220,6 +303,244 @@ impl TempFileFacadeTrait for TempFileFacade {
         Ok(())
     }
 
+    /// Creates thumbnail candidates from frames of the temporary video
+    ///
+    /// Frames are taken at different timestamps of the video, scaled down to the thumbnail size
+    /// and stored as new temporary PNG files of the user.
+    ///
+    /// # Arguments
+    ///
+    /// * `video_temp_id` - ID of the temporary video file.
+    /// * `user_id` - ID of the user who uploaded the video.
+    /// * `count` - number of candidates to create.
+    ///
+    /// # Returns
+    ///
+    /// - IDs of the temporary files with thumbnails.
+    async fn create_thumbnail_candidates(
+        &self,
+        video_temp_id: i32,
+        user_id: i32,
+        count: u32,
+    ) -> Result<Vec<i32>> {
+        let video = self
+            .temp_file_repo
+            .get_file(video_temp_id, user_id)
+            .await
+            .app_error_kind("Video file doesn't exist", AppErrorKind::NotFound)?;
+
+        let media_proxy = self.media_proxy.clone();
+        let frames = tokio::task::spawn_blocking(move || {
+            media_proxy.capture_frames(video.file_path.as_str(), count)
+        })
+        .await
+        .app_error("Failed to capture frames of the video")?
+        .app_error("Failed to capture frames of the video")?;
+
+        let mut candidate_ids = Vec::with_capacity(frames.len());
+        for frame in frames {
+            let thumbnail = DynamicImage::ImageRgba8(frame).resize(
+                THUMBNAIL_WIDTH,
+                THUMBNAIL_HEIGHT,
+                FilterType::Triangle,
+            );
+            let temp_file = NamedTempFile::new().app_error("Failed to create the thumbnail")?;
+            thumbnail
+                .save_with_format(temp_file.path(), ImageFormat::Png)
+                .app_error("Failed to create the thumbnail")?;
+
+            let temp_file_id = self
+                .persist_temp_file(temp_file, String::from("thumbnail.png"), user_id)
+                .await?;
+            candidate_ids.push(temp_file_id);
+        }
+
+        Ok(candidate_ids)
+    }
+
+    /// Creates new chunked upload
+    ///
+    /// Empty file is created in the temporary directory, so the chunks can be appended to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_req` - name and total size of the uploaded file.
+    /// * `max_length` - maximal allowed size of the file in bytes.
+    /// * `user_id` - ID of the user who uploads the file.
+    ///
+    /// # Returns
+    ///
+    /// - ID of the upload, which is used for appending the chunks.
+    async fn create_upload(
+        &self,
+        upload_req: CreateUploadReq,
+        max_length: i64,
+        user_id: i32,
+    ) -> Result<i32> {
+        upload_req
+            .validate_model(&EmptyContext::new())
+            .await
+            .app_error_kind("Validation failed", AppErrorKind::BadRequestError)?;
+        if upload_req.upload_length > max_length {
+            return Err(AppError::new(
+                "File is too large",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        let entity = TempUpload {
+            id: -1,
+            user_id,
+            file_path: self.create_temp_file_path(upload_req.file_name).await,
+            upload_length: upload_req.upload_length,
+            upload_offset: 0,
+        };
+        let upload_id = self.temp_file_repo.add_upload(entity).await?;
+
+        debug!("Created upload with ID: {}", upload_id);
+        Ok(upload_id)
+    }
+
+    async fn get_upload_status(&self, upload_id: i32, user_id: i32) -> Result<UploadStatus> {
+        let upload = self.get_upload(upload_id, user_id).await?;
+
+        Ok(UploadStatus {
+            upload_offset: upload.upload_offset,
+            upload_length: upload.upload_length,
+        })
+    }
+
+    /// Appends chunk to the upload
+    ///
+    /// The chunk is accepted only if `offset` matches the number of bytes already received, so the
+    /// client has to query the current offset, when the upload was interrupted.
+    ///
+    /// # Arguments
+    ///
+    /// * `upload_id` - ID of the upload.
+    /// * `user_id` - ID of the user who created the upload.
+    /// * `offset` - position of the chunk in the file.
+    /// * `chunk` - received bytes.
+    ///
+    /// # Returns
+    ///
+    /// - `UploadStatus` with the new offset.
+    async fn append_upload_chunk(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        offset: i64,
+        chunk: &[u8],
+    ) -> Result<UploadStatus> {
+        // The upload stays locked until the chunk is written, so the concurrent request
+        // with the same offset waits and then fails the offset check without touching the file
+        let mut tx = self.unit_of_work.begin().await?;
+        let upload = self
+            .temp_file_repo
+            .get_upload_for_update(upload_id, user_id, &mut tx)
+            .await?
+            .ok_or(AppError::new(
+                "Upload doesn't exist",
+                AppErrorKind::NotFound,
+            ))?;
+        if upload.upload_offset != offset {
+            return Err(AppError::new(
+                "Offset doesn't match the uploaded data",
+                AppErrorKind::Conflict,
+            ));
+        }
+        let new_offset = offset + chunk.len() as i64;
+        if new_offset > upload.upload_length {
+            return Err(AppError::new(
+                "Chunk exceeds the length of the upload",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        let mut file = tokio::fs::OpenOptions::new()
+            .write(true)
+            .open(&upload.file_path)
+            .await
+            .app_error("Failed to open the uploaded file")?;
+        // Drops leftovers of the interrupted chunk, which were not acknowledged
+        file.set_len(offset as u64)
+            .await
+            .app_error("Failed to write the chunk")?;
+        file.seek(SeekFrom::Start(offset as u64))
+            .await
+            .app_error("Failed to write the chunk")?;
+        file.write_all(chunk)
+            .await
+            .app_error("Failed to write the chunk")?;
+        file.sync_data()
+            .await
+            .app_error("Failed to write the chunk")?;
+
+        let updated = self
+            .temp_file_repo
+            .update_upload_offset(upload_id, user_id, offset, new_offset, &mut tx)
+            .await?;
+        if !updated {
+            return Err(AppError::new(
+                "Offset doesn't match the uploaded data",
+                AppErrorKind::Conflict,
+            ));
+        }
+        self.unit_of_work.commit(tx).await?;
+
+        Ok(UploadStatus {
+            upload_offset: new_offset,
+            upload_length: upload.upload_length,
+        })
+    }
+
+    /// Converts complete upload to the temporary file
+    ///
+    /// MimeType of the file is checked first. If it's not allowed, the upload is kept,
+    /// so the client can see the error, but it can't be finalized.
+    ///
+    /// # Returns
+    ///
+    /// - ID of the temporary file, which can be used in the same way as the files uploaded at once.
+    async fn finalize_upload(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        allowed_types: Vec<String>,
+    ) -> Result<i32> {
+        let upload = self.get_upload(upload_id, user_id).await?;
+        if upload.upload_offset != upload.upload_length {
+            return Err(AppError::new(
+                "Upload is not complete",
+                AppErrorKind::Conflict,
+            ));
+        }
+
+        self.check_mime_type(Path::new(upload.file_path.as_str()), allowed_types)
+            .await?;
+
+        let temp_file_id = self
+            .temp_file_repo
+            .finalize_upload(upload_id, user_id)
+            .await?;
+
+        debug!(
+            "Upload with ID: {} was stored as temp file with ID: {}",
+            upload_id, temp_file_id
+        );
+        Ok(temp_file_id)
+    }
+
+    async fn delete_inactive_uploads(&self, expiry: Duration) -> Result<u64> {
+        let expiry = chrono::Duration::from_std(expiry).app_error("Invalid upload expiry")?;
+        let deleted = self
+            .temp_file_repo
+            .delete_inactive_uploads(Utc::now() - expiry)
+            .await?;
+
+        Ok(deleted)
+    }
+
     fn get_temp_directory_path(&self) -> String {
         self.temp_file_dir.clone()
     }
@@ -0,0 +1,195 @@
+use crate::business::models::error::AppErrorKind::BadRequestError;
+use crate::business::models::error::{AppError, MapToAppError};
+use crate::business::models::two_factor::{TotpEnrolment, TwoFactorStatus};
+use crate::business::util::totp;
+use crate::business::Result;
+use crate::persistence::entities::two_factor::UserTotp;
+use crate::persistence::repositories::two_factor::TwoFactorRepo;
+use crate::persistence::repositories::user::UserRepositoryTrait;
+use async_trait::async_trait;
+use chrono::Utc;
+use log::info;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+const INVALID_CODE_TEXT: &str = "Invalid authentication code";
+const NOT_ENABLED_TEXT: &str = "Two-factor authentication is not enabled";
+
+#[async_trait]
+pub trait TwoFactorFacadeTrait {
+    async fn is_enabled(&self, user_id: i32) -> Result<bool>;
+    async fn get_status(&self, user_id: i32) -> Result<TwoFactorStatus>;
+    /// Generates a new secret, which is enabled after the user confirms it by a code
+    async fn start_enrolment(&self, user_id: i32) -> Result<TotpEnrolment>;
+    /// Enables two-factor authentication, if the code matches the new secret.
+    /// Returns the recovery codes, which are shown to the user only once.
+    async fn confirm_enrolment(&self, user_id: i32, code: &str) -> Result<Vec<String>>;
+    /// Checks the TOTP code or the recovery code, each code can be used only once
+    async fn verify(&self, user_id: i32, code: &str) -> Result<()>;
+    /// Disables two-factor authentication, the user has to prove it owns the authenticator
+    async fn disable(&self, user_id: i32, code: &str) -> Result<()>;
+    /// Disables two-factor authentication of the user, who lost the authenticator
+    /// and the recovery codes. Meant for admins.
+    async fn reset(&self, user_id: i32) -> Result<()>;
+    async fn list_enabled_user_ids(&self) -> Result<HashSet<i32>>;
+}
+
+pub struct TwoFactorFacade {
+    two_factor_repo: Arc<dyn TwoFactorRepo + Send + Sync>,
+    user_repository: Arc<dyn UserRepositoryTrait + Send + Sync>,
+}
+
+impl TwoFactorFacade {
+    pub fn new(
+        two_factor_repo: Arc<dyn TwoFactorRepo + Send + Sync>,
+        user_repository: Arc<dyn UserRepositoryTrait + Send + Sync>,
+    ) -> Self {
+        Self {
+            two_factor_repo,
+            user_repository,
+        }
+    }
+
+    async fn get_enabled_totp(&self, user_id: i32) -> Result<UserTotp> {
+        self.two_factor_repo
+            .get_totp(user_id)
+            .await?
+            .filter(|totp| totp.enabled_at.is_some())
+            .ok_or(AppError::new(NOT_ENABLED_TEXT, BadRequestError))
+    }
+
+    /// Returns the time step, to which the code belongs
+    async fn check_totp_code(&self, user_totp: &UserTotp, code: &str) -> Result<i64> {
+        let user = self
+            .user_repository
+            .get_user_by_id(user_totp.user_id)
+            .await?
+            .ok_or(AppError::new("User does not exist", BadRequestError))?;
+        let totp = totp::create_totp(&user_totp.secret, &user.username)
+            .app_error("Failed to check the authentication code")?;
+
+        let step = totp::find_step(&totp, code, Utc::now().timestamp() as u64)
+            .ok_or(AppError::new(INVALID_CODE_TEXT, BadRequestError))?;
+
+        Ok(step as i64)
+    }
+}
+
+#[async_trait]
+impl TwoFactorFacadeTrait for TwoFactorFacade {
+    async fn is_enabled(&self, user_id: i32) -> Result<bool> {
+        let totp = self.two_factor_repo.get_totp(user_id).await?;
+
+        Ok(totp.is_some_and(|totp| totp.enabled_at.is_some()))
+    }
+
+    async fn get_status(&self, user_id: i32) -> Result<TwoFactorStatus> {
+        Ok(TwoFactorStatus {
+            enabled: self.is_enabled(user_id).await?,
+            recovery_codes_left: self.two_factor_repo.count_recovery_codes(user_id).await?,
+        })
+    }
+
+    async fn start_enrolment(&self, user_id: i32) -> Result<TotpEnrolment> {
+        let user = self
+            .user_repository
+            .get_user_by_id(user_id)
+            .await?
+            .ok_or(AppError::new("User does not exist", BadRequestError))?;
+
+        let secret = totp::generate_secret();
+        if !self
+            .two_factor_repo
+            .save_pending_totp(user_id, &secret)
+            .await?
+        {
+            return Err(AppError::new(
+                "Two-factor authentication is already enabled",
+                BadRequestError,
+            ));
+        }
+
+        let totp = totp::create_totp(&secret, &user.username)
+            .app_error("Failed to set up two-factor authentication")?;
+        let qr_code_svg =
+            totp::render_qr_code(&totp).app_error("Failed to set up two-factor authentication")?;
+
+        Ok(TotpEnrolment {
+            secret,
+            qr_code_svg,
+        })
+    }
+
+    async fn confirm_enrolment(&self, user_id: i32, code: &str) -> Result<Vec<String>> {
+        let user_totp = self
+            .two_factor_repo
+            .get_totp(user_id)
+            .await?
+            .filter(|totp| totp.enabled_at.is_none())
+            .ok_or(AppError::new(
+                "Start the setup of two-factor authentication first",
+                BadRequestError,
+            ))?;
+        let step = self.check_totp_code(&user_totp, code).await?;
+
+        let recovery_codes = totp::generate_recovery_codes();
+        let recovery_code_hashes: Vec<String> = recovery_codes
+            .iter()
+            .map(|code| totp::hash_recovery_code(code))
+            .collect();
+        if !self
+            .two_factor_repo
+            .enable_totp(user_id, step, &recovery_code_hashes)
+            .await?
+        {
+            return Err(AppError::new(
+                "Two-factor authentication is already enabled",
+                BadRequestError,
+            ));
+        }
+
+        info!("Two-factor authentication of the user {user_id} was enabled");
+        Ok(recovery_codes)
+    }
+
+    async fn verify(&self, user_id: i32, code: &str) -> Result<()> {
+        let user_totp = self.get_enabled_totp(user_id).await?;
+
+        let accepted = if totp::is_totp_code(code) {
+            let step = self.check_totp_code(&user_totp, code).await?;
+            self.two_factor_repo.use_totp_step(user_id, step).await?
+        } else {
+            self.two_factor_repo
+                .use_recovery_code(user_id, &totp::hash_recovery_code(code))
+                .await?
+        };
+        if !accepted {
+            return Err(AppError::new(INVALID_CODE_TEXT, BadRequestError));
+        }
+
+        Ok(())
+    }
+
+    async fn disable(&self, user_id: i32, code: &str) -> Result<()> {
+        self.verify(user_id, code).await?;
+        self.two_factor_repo.delete_totp(user_id).await?;
+
+        info!("Two-factor authentication of the user {user_id} was disabled");
+        Ok(())
+    }
+
+    async fn reset(&self, user_id: i32) -> Result<()> {
+        if !self.two_factor_repo.delete_totp(user_id).await? {
+            return Err(AppError::new(NOT_ENABLED_TEXT, BadRequestError));
+        }
+
+        info!("Two-factor authentication of the user {user_id} was reset");
+        Ok(())
+    }
+
+    async fn list_enabled_user_ids(&self) -> Result<HashSet<i32>> {
+        let user_ids = self.two_factor_repo.list_enabled_user_ids().await?;
+
+        Ok(user_ids.into_iter().collect())
+    }
+}
@@ -1,20 +1,27 @@
 use crate::business::models::error::AppErrorKind::BadRequestError;
 use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
+use crate::business::models::session::ActiveSession;
 use crate::business::models::user::{
-    ProfilePictureUpdate, UserDetail, UserDetailUpdate, UserLogin, UserPasswordUpdate,
-    UserRegister, UserRegisterMultipart, UserRole,
+    PasswordReset, ProfilePictureUpdate, UserDetail, UserDetailUpdate, UserLogin,
+    UserPasswordUpdate, UserRegister, UserRegisterMultipart, UserRole,
 };
 use crate::business::util::file::{create_dir_if_not_exist, get_file_extension};
+use crate::business::util::reset_token;
+use crate::business::util::verification_token::VerificationToken;
 use crate::business::validation::contexts::user::UserValidationContext;
 use crate::business::validation::validatable::Validatable;
 use crate::business::Result;
+use crate::configuration::models::Configuration;
+use crate::mailer::types::{Mail, MailerTrait};
 use crate::persistence::entities::user::{LikedVideo, User};
+use crate::persistence::repositories::session::UserSessionRepo;
 use crate::persistence::repositories::user::UserRepositoryTrait;
 use async_trait::async_trait;
 use bcrypt::{hash, verify, DEFAULT_COST};
-use log::info;
+use chrono::{Duration, Utc};
+use lazy_static::lazy_static;
+use log::{error, info};
 use std::collections::HashSet;
-use std::fmt::Debug;
 use std::io::Read;
 use std::path::Path;
 use std::sync::Arc;
@@ -23,6 +30,9 @@ use validator::{Validate, ValidationError};
 
 const PROFILE_PICTURE_FOLDER_PATH: &str = "resources/images/users/";
 const VALIDATION_ERROR_TEXT: &str = "Validation failed";
+const INVALID_LOGIN_TEXT: &str = "Invalid username or password";
+const INVALID_VERIFICATION_TEXT: &str = "Verification link is invalid";
+const INVALID_RESET_TEXT: &str = "Password reset link is invalid or was already used";
 
 const ALLOWED_IMAGE_MIME_TYPES: &[&str] = &[
     "image/jpeg",
@@ -34,6 +44,12 @@ const ALLOWED_IMAGE_MIME_TYPES: &[&str] = &[
     "image/svg+xml",
 ];
 
+lazy_static! {
+    /// Hash checked against, when the username doesn't exist
+    static ref DUMMY_PASSWORD_HASH: String =
+        hash("dummy password", DEFAULT_COST).expect("Failed to hash the dummy password");
+}
+
 #[async_trait]
 pub trait UserFacadeTrait {
     async fn register(&self, register_model: UserRegisterMultipart) -> Result<UserDetail>;
@@ -76,11 +92,18 @@ pub trait UserFacadeTrait {
         user_id: i32,
         profile_picture_update: ProfilePictureUpdate,
     ) -> Result<Option<UserDetail>>;
+    /// Sets the new password and logs the user out of all other sessions
+    ///
+    /// # Arguments
+    ///
+    /// * `current_session_id` - public ID of the session, which stays logged in
     async fn change_password(
         &self,
         user_id: i32,
+        current_session_id: Option<&str>,
         user_password_update: UserPasswordUpdate,
     ) -> Result<()>;
+    /// Deletes the user and logs it out of all sessions
     async fn delete_user(&self, user_id: i32) -> Result<()>;
     async fn get_users(&self) -> Result<Vec<UserDetail>>;
     async fn change_admin_status(&self, user_id: i32, is_admin: bool) -> Result<()>;
@@ -88,16 +111,87 @@ pub trait UserFacadeTrait {
     async fn liked_videos_by_user(&self, user_id: i32) -> Result<Vec<LikedVideo>>;
     async fn like_video(&self, user_id: i32, video_id: i32) -> Result<()>;
     async fn unlike_video(&self, user_id: i32, video_id: i32) -> Result<()>;
+    /// Verifies the email of the user by the token from the link sent by email.
+    /// Returns the user, whose email is verified.
+    async fn verify_email(&self, token: &str) -> Result<UserDetail>;
+    /// Sends a new verification link, unless the previous one was sent recently
+    async fn resend_verification(&self, user_id: i32) -> Result<()>;
+    /// Sends the password reset link to the email. The result doesn't tell,
+    /// whether the email is registered, the link is sent in the background.
+    async fn request_password_reset(&self, email: &str) -> Result<()>;
+    /// Sets the new password and logs the user out of all devices
+    async fn reset_password(&self, password_reset: PasswordReset) -> Result<()>;
+    /// Returns the sessions, in which the user is logged in, the current one first
+    async fn list_sessions(
+        &self,
+        user_id: i32,
+        current_session_id: Option<&str>,
+    ) -> Result<Vec<ActiveSession>>;
+    /// Logs the user out of the session with the public ID
+    async fn log_out_session(&self, user_id: i32, session_id: &str) -> Result<()>;
+    /// Logs the user out of all sessions, including the current one
+    async fn log_out_everywhere(&self, user_id: i32) -> Result<()>;
 }
 
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct UserFacade {
     user_repository: Arc<dyn UserRepositoryTrait + Send + Sync>,
+    user_session_repository: Arc<dyn UserSessionRepo + Send + Sync>,
+    mailer: Arc<dyn MailerTrait>,
+    config: Arc<Configuration>,
+    /// Key signing the verification links
+    signing_key: Vec<u8>,
 }
 
 impl UserFacade {
-    pub fn new(user_repository: Arc<dyn UserRepositoryTrait + Send + Sync>) -> Self {
-        Self { user_repository }
+    pub fn new(
+        user_repository: Arc<dyn UserRepositoryTrait + Send + Sync>,
+        user_session_repository: Arc<dyn UserSessionRepo + Send + Sync>,
+        mailer: Arc<dyn MailerTrait>,
+        config: Arc<Configuration>,
+        signing_key: Vec<u8>,
+    ) -> Self {
+        Self {
+            user_repository,
+            user_session_repository,
+            mailer,
+            config,
+            signing_key,
+        }
+    }
+
+    /// Sends the verification link, if the previous one was sent at least `cooldown_seconds` ago.
+    /// Returns `false` when the link was not sent because of the cooldown.
+    async fn send_verification(&self, user: &User, cooldown_seconds: i64) -> Result<bool> {
+        if !self
+            .user_repository
+            .claim_verification_mail(user.id, cooldown_seconds)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        let mail_config = &self.config.app.mail;
+        let expires_at = Utc::now() + Duration::hours(mail_config.verification_expiry_hours);
+        let token = VerificationToken::sign(&self.signing_key, user.id, &user.email, expires_at);
+        let mail = Mail {
+            to: user.email.clone(),
+            subject: String::from("Verify your email"),
+            body: format!(
+                "Hi {},\n\nplease confirm your email by opening the link below. \
+                The link expires in {} hours.\n\n{}/user/verify?token={}\n",
+                user.username, mail_config.verification_expiry_hours, mail_config.base_url, token
+            ),
+        };
+        if let Err(err) = self.mailer.send(mail).await {
+            // The cooldown must not block the next attempt, when the link was never delivered
+            self.user_repository
+                .release_verification_mail(user.id)
+                .await?;
+            return Err(err).app_error("Failed to send the verification email");
+        }
+
+        Ok(true)
     }
 
     pub async fn create_profile_picture_folders(
@@ -165,6 +259,14 @@ impl UserFacadeTrait for UserFacade {
             .await
             .app_error("There was an error creating user")?;
 
+        // The account is created anyway, the user can ask for another link later
+        if let Err(err) = self.send_verification(&created_user_entity, 0).await {
+            error!(
+                "Verification email for user {} was not sent: {}",
+                created_user_entity.id, err
+            );
+        }
+
         let created_user_model = UserDetail::from(created_user_entity);
 
         Ok(created_user_model)
@@ -178,17 +280,18 @@ impl UserFacadeTrait for UserFacade {
 
         let user = match user {
             Some(user) => user,
-            None => return Err(AppError::new(VALIDATION_ERROR_TEXT, BadRequestError)),
+            None => {
+                // Unknown usernames take as long as wrong passwords, so they can't be told apart
+                let _ = verify(&login_model.password, &DUMMY_PASSWORD_HASH);
+                return Err(AppError::new(INVALID_LOGIN_TEXT, BadRequestError));
+            }
         };
 
         if !self
             .validate_password(&user.password_hash, &login_model.password)
             .await?
         {
-            return Err(AppError::new(
-                "Invalid username or password",
-                BadRequestError,
-            ));
+            return Err(AppError::new(INVALID_LOGIN_TEXT, BadRequestError));
         }
 
         Ok(UserDetail::from(user))
@@ -323,6 +426,10 @@ impl UserFacadeTrait for UserFacade {
 
         let mut user_permissions = HashSet::from([UserRole::Registered]);
 
+        if user.email_verified_at.is_some() {
+            user_permissions.insert(UserRole::Verified);
+        }
+
         // TODO: check for validity of the membership
         if user.paying_member_id.is_some() {
             user_permissions.insert(UserRole::PayingMember);
@@ -401,17 +508,33 @@ impl UserFacadeTrait for UserFacade {
             return Err(AppError::new("Email already exists", BadRequestError));
         }
 
+        let email_changed = user.email != user_detail_update.email;
         user.username = user_detail_update.username;
         user.email = user_detail_update.email;
+        if email_changed {
+            user.email_verified_at = None;
+        }
 
-        let updated_user = self.user_repository.update_user(user).await?;
+        let updated_user = match self.user_repository.update_user(user).await? {
+            Some(user) => user,
+            None => {
+                return Err(AppError::from(ValidationError::new(
+                    "User update was not successful",
+                )))
+            }
+        };
 
-        match updated_user {
-            Some(user) => Ok(Some(UserDetail::from(user))),
-            None => Err(AppError::from(ValidationError::new(
-                "User update was not successful",
-            ))),
+        // New email is verified regardless of the link sent to the previous one
+        if email_changed {
+            if let Err(err) = self.send_verification(&updated_user, 0).await {
+                error!(
+                    "Verification email for user {} was not sent: {}",
+                    updated_user.id, err
+                );
+            }
         }
+
+        Ok(Some(UserDetail::from(updated_user)))
     }
 
     async fn update_profile_picture(
@@ -480,6 +603,7 @@ impl UserFacadeTrait for UserFacade {
     async fn change_password(
         &self,
         user_id: i32,
+        current_session_id: Option<&str>,
         user_password_update: UserPasswordUpdate,
     ) -> Result<()> {
         user_password_update
@@ -488,7 +612,7 @@ impl UserFacadeTrait for UserFacade {
 
         let user = self.user_repository.get_user_by_id(user_id).await?;
 
-        let mut user = match user {
+        let user = match user {
             Some(user) => user,
             None => {
                 return Err(AppError::new(
@@ -505,11 +629,25 @@ impl UserFacadeTrait for UserFacade {
             return Err(AppError::new("Old password is invalid", BadRequestError));
         }
 
-        user.password_hash = Some(
-            hash(user_password_update.password.as_str(), DEFAULT_COST)
-                .app_error(VALIDATION_ERROR_TEXT)?,
-        );
+        let password_hash = hash(user_password_update.password.as_str(), DEFAULT_COST)
+            .app_error(VALIDATION_ERROR_TEXT)?;
+        self.user_repository
+            .update_password(user_id, &password_hash)
+            .await?;
+        match current_session_id {
+            Some(session_id) => {
+                self.user_session_repository
+                    .delete_other_sessions(user_id, session_id)
+                    .await?
+            }
+            None => {
+                self.user_session_repository
+                    .delete_user_sessions(user_id)
+                    .await?
+            }
+        }
 
+        info!("Password of the user {user_id} was changed");
         Ok(())
     }
 
@@ -524,6 +662,9 @@ impl UserFacadeTrait for UserFacade {
         };
 
         self.user_repository.delete_user(user_id).await?;
+        self.user_session_repository
+            .delete_user_sessions(user_id)
+            .await?;
 
         Ok(())
     }
@@ -567,4 +708,177 @@ impl UserFacadeTrait for UserFacade {
 
         Ok(())
     }
+
+    async fn verify_email(&self, token: &str) -> Result<UserDetail> {
+        let invalid = || AppError::new(INVALID_VERIFICATION_TEXT, BadRequestError);
+        let token = VerificationToken::parse(token).ok_or_else(invalid)?;
+        let user = self
+            .user_repository
+            .get_user_by_id(token.user_id)
+            .await?
+            .ok_or_else(invalid)?;
+        if !token.verify(&self.signing_key, &user.email) {
+            return Err(invalid());
+        }
+
+        if user.email_verified_at.is_some() {
+            return Ok(UserDetail::from(user));
+        }
+        if token.is_expired() {
+            return Err(AppError::new(
+                "Verification link has expired, request a new one on your account page",
+                BadRequestError,
+            ));
+        }
+
+        self.user_repository
+            .verify_email(user.id, &user.email)
+            .await?;
+        let user = self
+            .user_repository
+            .get_user_by_id(user.id)
+            .await?
+            .ok_or_else(invalid)?;
+
+        Ok(UserDetail::from(user))
+    }
+
+    async fn resend_verification(&self, user_id: i32) -> Result<()> {
+        let user = self
+            .user_repository
+            .get_user_by_id(user_id)
+            .await?
+            .ok_or(AppError::new("User does not exist", BadRequestError))?;
+        if user.email_verified_at.is_some() {
+            return Err(AppError::new("Email is already verified", BadRequestError));
+        }
+
+        let cooldown_seconds = self.config.app.mail.resend_cooldown_seconds;
+        if !self.send_verification(&user, cooldown_seconds).await? {
+            return Err(AppError::new(
+                "Verification email was sent recently, try it again later",
+                AppErrorKind::TooManyRequests,
+            ));
+        }
+
+        Ok(())
+    }
+
+    async fn request_password_reset(&self, email: &str) -> Result<()> {
+        let user = match self.user_repository.get_user_by_email(email).await? {
+            Some(user) => user,
+            None => return Ok(()),
+        };
+
+        let mail_config = &self.config.app.mail;
+        let (token, token_hash) = reset_token::generate();
+        let expires_at = Utc::now() + Duration::minutes(mail_config.password_reset_expiry_minutes);
+        if !self
+            .user_repository
+            .create_password_reset(
+                user.id,
+                &token_hash,
+                expires_at,
+                mail_config.resend_cooldown_seconds,
+            )
+            .await?
+        {
+            return Ok(());
+        }
+
+        let mail = Mail {
+            to: user.email,
+            subject: String::from("Reset your password"),
+            body: format!(
+                "Hi {},\n\nyou can set a new password by opening the link below. \
+                The link expires in {} minutes and can be used only once. \
+                If you didn't ask for it, just ignore this email.\n\n{}/user/reset-password?token={}\n",
+                user.username, mail_config.password_reset_expiry_minutes, mail_config.base_url, token
+            ),
+        };
+        // Sending takes a while, the response time would tell, that the email is registered
+        let mailer = self.mailer.clone();
+        tokio::spawn(async move {
+            if let Err(err) = mailer.send(mail).await {
+                error!("Failed to send the password reset email: {err:#}");
+            }
+        });
+
+        Ok(())
+    }
+
+    async fn reset_password(&self, password_reset: PasswordReset) -> Result<()> {
+        password_reset
+            .validate()
+            .app_error_kind(VALIDATION_ERROR_TEXT, BadRequestError)?;
+
+        let token = self
+            .user_repository
+            .consume_password_reset(&reset_token::hash(&password_reset.token))
+            .await?
+            .ok_or(AppError::new(INVALID_RESET_TEXT, BadRequestError))?;
+        if token.expires_at <= Utc::now() {
+            return Err(AppError::new(
+                "Password reset link has expired, request a new one",
+                BadRequestError,
+            ));
+        }
+
+        let password_hash = hash(password_reset.password.as_str(), DEFAULT_COST)
+            .app_error(VALIDATION_ERROR_TEXT)?;
+        self.user_repository
+            .update_password(token.user_id, &password_hash)
+            .await?;
+        self.user_session_repository
+            .delete_user_sessions(token.user_id)
+            .await?;
+
+        info!("Password of the user {} was reset", token.user_id);
+        Ok(())
+    }
+
+    async fn list_sessions(
+        &self,
+        user_id: i32,
+        current_session_id: Option<&str>,
+    ) -> Result<Vec<ActiveSession>> {
+        let mut sessions: Vec<ActiveSession> = self
+            .user_session_repository
+            .list_sessions(user_id)
+            .await?
+            .into_iter()
+            .map(ActiveSession::from)
+            .collect();
+        for session in sessions.iter_mut() {
+            session.current = current_session_id == Some(session.id.as_str());
+        }
+        sessions.sort_by(|a, b| {
+            b.current
+                .cmp(&a.current)
+                .then(b.last_seen_at.cmp(&a.last_seen_at))
+        });
+
+        Ok(sessions)
+    }
+
+    async fn log_out_session(&self, user_id: i32, session_id: &str) -> Result<()> {
+        if !self
+            .user_session_repository
+            .delete_session(user_id, session_id)
+            .await?
+        {
+            return Err(AppError::new("Session does not exist", BadRequestError));
+        }
+
+        Ok(())
+    }
+
+    async fn log_out_everywhere(&self, user_id: i32) -> Result<()> {
+        self.user_session_repository
+            .delete_user_sessions(user_id)
+            .await?;
+
+        info!("User {user_id} was logged out of all sessions");
+        Ok(())
+    }
 }
@@ -1,23 +1,50 @@
 use crate::business::facades::artist::ArtistFacadeTrait;
-use crate::business::facades::temp_file::{TempFileFacade, TempFileFacadeTrait};
+use crate::business::facades::temp_file::{
+    TempFileFacade, TempFileFacadeTrait, THUMBNAIL_HEIGHT, THUMBNAIL_WIDTH,
+};
 use crate::business::facades::user::UserFacadeTrait;
 use crate::business::facades::video_category::VideoCategoryFacadeTrait;
 use crate::business::models;
+use crate::business::models::artist_detail::ARTIST_VIDEOS_PAGE_SIZE;
 use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
 use crate::business::models::user::UserRole;
-use crate::business::models::video::{VideoEditReq, VideoUploadReq};
-use crate::business::util::file::create_dir_if_not_exist;
+use crate::business::models::video::{
+    VideoEditReq, VideoSort, VideoUploadReq, SEARCH_PAGE_SIZE, VIDEO_PAGE_SIZE,
+};
+use crate::business::util::file::{create_dir_if_not_exist, get_file_extension};
 use crate::business::validation::contexts::video::PatchVideoValidationContext;
 use crate::business::validation::validatable::{EmptyContext, Validatable};
 use crate::business::Result;
-use crate::persistence::entities::video::{PatchVideo, Video, VideoVisibility};
+use crate::configuration::models::Configuration;
+use crate::persistence::entities::video::{
+    PatchVideo, Video, VideoAccess, VideoMetadata, VideoSearch, VideoSearchHit, VideoVisibility,
+};
 use crate::persistence::repositories::unit_of_work::UnitOfWork;
 use crate::persistence::repositories::video::VideoRepo;
+use crate::streamer;
+use crate::streamer::gstreamer_controller::HLS_MASTER_PLAYLIST;
+use crate::streamer::types::{MediaInfo, StreamResolution};
 use actix_files::NamedFile;
+use anyhow::anyhow;
 use async_trait::async_trait;
-use std::collections::HashSet;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat, RgbaImage};
+use lazy_static::lazy_static;
+use log::{error, info, warn};
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use uuid::Uuid;
+
+const MAX_SEARCH_QUERY_LENGTH: usize = 200;
+
+lazy_static! {
+    /// Matches master playlist, rendition playlists and transport stream segments
+    /// e.g. master.m3u8, 360/playlist.m3u8, 360/segment00001.ts
+    static ref HLS_FILE_REGEX: Regex = Regex::new(r#"^(\d+/)?[\w-]+\.(m3u8|ts)$"#).unwrap();
+}
 
 #[async_trait]
 pub trait VideoFacadeTrait {
@@ -38,16 +65,59 @@ pub trait VideoFacadeTrait {
     ) -> Result<models::video::Video>;
     async fn get_playable_video(&self, video_id: i32, user_id: Option<i32>) -> Result<NamedFile>;
     async fn get_thumbnail_file(&self, video_id: i32) -> Result<NamedFile>;
+    /// Serves HLS playlist or segment of the transcoded video
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - path relative to the HLS directory of the video, e.g. `360/playlist.m3u8`
+    async fn get_hls_file(
+        &self,
+        video_id: i32,
+        user_id: Option<i32>,
+        file_path: &str,
+    ) -> Result<NamedFile>;
+    /// Checks whether HLS renditions of the video were already created
+    fn has_hls_playlist(&self, video_id: i32) -> bool;
     async fn check_permissions(&self, video: &Video, user_id: Option<i32>) -> Result<()>;
+    /// Returns which videos the user is allowed to watch, so the lists of videos
+    /// can be filtered in the database
+    async fn get_video_access(&self, user_id: Option<i32>) -> Result<VideoAccess>;
     async fn fetch_videos(
         &self,
-        ord: Option<&str>,
+        sort: VideoSort,
         filter: Option<Vec<i32>>,
-        offset: Option<i32>,
+        after: Option<i32>,
     ) -> Result<Vec<Video>>;
     async fn fetch_liked_videos(&self, ids: Vec<i32>) -> Result<Vec<Video>>;
+    async fn search_videos(
+        &self,
+        query: &str,
+        category_ids: Option<Vec<i32>>,
+        offset: i64,
+        user_id: Option<i32>,
+    ) -> Result<Vec<VideoSearchHit>>;
     async fn is_video_owner(&self, video_artist_id: i32, user_id: i32) -> Result<()>;
     fn get_video_thumbnail_dirs(&self) -> (String, String);
+    /// Fetches one page of the videos of the artist, which the user is allowed to watch.
+    /// The artist can see all his videos.
+    async fn fetch_artist_videos(
+        &self,
+        artist_id: i32,
+        offset: i64,
+        user_id: Option<i32>,
+    ) -> Result<Vec<Video>>;
+    /// Counts the videos of the artist, which the user is allowed to watch
+    async fn count_artist_videos(&self, artist_id: i32, user_id: Option<i32>) -> Result<i64>;
+    /// Registers recording of the live stream as a new draft video of the same artist
+    ///
+    /// # Arguments
+    ///
+    /// * `streamed_video` - video, which was streamed
+    /// * `recording_path` - path to the recorded file in the video directory
+    async fn save_recording(&self, streamed_video: &Video, recording_path: String)
+        -> Result<Video>;
+    /// Publishes the draft video of the artist, so other users can watch it
+    async fn publish_video(&self, user_id: i32, video_id: i32) -> Result<()>;
 }
 
 #[derive(Clone)]
@@ -60,6 +130,47 @@ pub struct VideoFacade {
     unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
     video_dir: String,
     thumbnail_dir: String,
+    hls_dir: String,
+    media_proxy: Arc<dyn MediaProxyTrait + Send + Sync>,
+    app_configuration: Arc<Configuration>,
+    /// ID of the latest HLS transcoding of each video, older transcodings are discarded
+    hls_transcodings: Arc<Mutex<HashMap<i32, Uuid>>>,
+}
+
+/// Proxy for calling media processing functions of the streamer.
+/// It allows mocking the transcoding in integration tests
+pub trait MediaProxyTrait {
+    fn transcode_to_hls(
+        &self,
+        video_path: &str,
+        output_dir: &str,
+        resolutions: &[StreamResolution],
+    ) -> anyhow::Result<()>;
+
+    fn capture_frames(&self, video_path: &str, count: u32) -> anyhow::Result<Vec<RgbaImage>>;
+
+    fn probe_media(&self, video_path: &str) -> anyhow::Result<MediaInfo>;
+}
+
+pub struct MediaProxy {}
+
+impl MediaProxyTrait for MediaProxy {
+    fn transcode_to_hls(
+        &self,
+        video_path: &str,
+        output_dir: &str,
+        resolutions: &[StreamResolution],
+    ) -> anyhow::Result<()> {
+        streamer::gstreamer_controller::transcode_to_hls(video_path, output_dir, resolutions)
+    }
+
+    fn capture_frames(&self, video_path: &str, count: u32) -> anyhow::Result<Vec<RgbaImage>> {
+        streamer::gstreamer_controller::capture_frames(video_path, count)
+    }
+
+    fn probe_media(&self, video_path: &str) -> anyhow::Result<MediaInfo> {
+        streamer::gstreamer_controller::probe_media(video_path)
+    }
 }
 
 impl VideoFacade {
@@ -73,6 +184,9 @@ impl VideoFacade {
         unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
         video_dir: String,
         thumbnail_dir: String,
+        hls_dir: String,
+        media_proxy: Option<Arc<dyn MediaProxyTrait + Send + Sync>>,
+        app_configuration: Arc<Configuration>,
     ) -> Self {
         Self {
             temp_file_facade,
@@ -83,15 +197,193 @@ impl VideoFacade {
             unit_of_work,
             video_dir,
             thumbnail_dir,
+            hls_dir,
+            media_proxy: media_proxy.unwrap_or(Arc::new(MediaProxy {})),
+            app_configuration,
+            hls_transcodings: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
-    pub async fn create_dirs(video_path: String, thumbnail_path: String) -> anyhow::Result<()> {
+    pub async fn create_dirs(
+        video_path: String,
+        thumbnail_path: String,
+        hls_path: String,
+    ) -> anyhow::Result<()> {
         create_dir_if_not_exist(video_path).await?;
         create_dir_if_not_exist(thumbnail_path).await?;
+        create_dir_if_not_exist(hls_path).await?;
+
+        Ok(())
+    }
+
+    fn get_video_hls_dir(&self, video_id: i32) -> String {
+        format!("{}/{}", self.hls_dir, video_id)
+    }
+
+    /// Reads technical metadata of the persisted video file.
+    /// The video is saved even if the probe fails, only without the metadata.
+    async fn probe_video(&self, video_path: String) -> VideoMetadata {
+        let media_proxy = self.media_proxy.clone();
+        let path = video_path.clone();
+        let probe = tokio::task::spawn_blocking(move || media_proxy.probe_media(&path))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|info| info);
+
+        match probe {
+            Ok(info) => VideoMetadata::from(&info),
+            Err(err) => {
+                warn!("Failed to probe video {}, {:?}", video_path, err);
+                VideoMetadata::default()
+            }
+        }
+    }
+
+    /// Transcodes the video to HLS renditions in a background thread.
+    /// Renditions created from the previous version of the video file are removed first.
+    ///
+    /// Each transcoding writes into its own directory, which is moved in place of the renditions
+    /// when it's done. If the video is transcoded again meanwhile, only the latest transcoding is kept.
+    fn start_hls_transcoding(&self, video: &Video) -> Result<()> {
+        let video_id = video.id;
+        let video_path = video.file_path.clone();
+        let resolutions = StreamResolution::filter_by_source(
+            StreamResolution::from_profiles(&self.app_configuration.app.stream.profiles),
+            video.height,
+        );
+        let output_dir = self.get_video_hls_dir(video_id);
+        let transcoding_id = Uuid::new_v4();
+        let transcoding_dir = format!("{output_dir}.{transcoding_id}");
+        let media_proxy = self.media_proxy.clone();
+        let transcodings = self.hls_transcodings.clone();
+        transcodings
+            .lock()
+            .unwrap()
+            .insert(video_id, transcoding_id);
+
+        thread::spawn(move || {
+            // The renditions of the newer transcoding may be already in place
+            if transcodings.lock().unwrap().get(&video_id) == Some(&transcoding_id) {
+                std::fs::remove_dir_all(&output_dir).ok();
+            }
+            let result = media_proxy.transcode_to_hls(&video_path, &transcoding_dir, &resolutions);
+
+            let mut transcodings = transcodings.lock().unwrap();
+            if transcodings.get(&video_id) != Some(&transcoding_id) {
+                info!(
+                    "Video with ID: {} was changed during transcoding, the renditions are discarded",
+                    video_id
+                );
+                std::fs::remove_dir_all(&transcoding_dir).ok();
+                return;
+            }
+            transcodings.remove(&video_id);
+
+            let result = result.and_then(|_| {
+                std::fs::remove_dir_all(&output_dir).ok();
+                std::fs::rename(&transcoding_dir, &output_dir)
+                    .map_err(|err| anyhow!("Failed to move the renditions, {:?}", err))
+            });
+            match result {
+                Ok(_) => info!("Video with ID: {} was transcoded to HLS", video_id),
+                Err(err) => {
+                    error!("Failed to transcode video with ID: {}, {:?}", video_id, err);
+                    std::fs::remove_dir_all(&transcoding_dir).ok();
+                }
+            }
+        });
 
         Ok(())
     }
+
+    async fn get_permissions(&self, user_id: Option<i32>) -> Result<HashSet<UserRole>> {
+        match user_id {
+            None => Ok(HashSet::new()),
+            Some(user_id) => self.user_facade.get_permissions(user_id).await,
+        }
+    }
+
+    /// Returns visibilities of videos, which can be watched by the user with given permissions
+    fn visible_for(permissions: &HashSet<UserRole>) -> Vec<VideoVisibility> {
+        let mut visibilities = vec![VideoVisibility::All];
+        if permissions.contains(&UserRole::Registered) {
+            visibilities.push(VideoVisibility::Registered);
+        }
+        if permissions.contains(&UserRole::PayingMember) {
+            visibilities.push(VideoVisibility::Paying);
+        }
+        visibilities
+    }
+
+    /// Returns visibilities of the videos of the artist, which can be watched by the user,
+    /// and whether the user is the artist, who can see also the drafts
+    async fn visible_videos_of_artist(
+        &self,
+        artist_id: i32,
+        user_id: Option<i32>,
+    ) -> Result<(Vec<VideoVisibility>, bool)> {
+        if let Some(user_id) = user_id {
+            if self.is_video_owner(artist_id, user_id).await.is_ok() {
+                return Ok((
+                    vec![
+                        VideoVisibility::All,
+                        VideoVisibility::Registered,
+                        VideoVisibility::Paying,
+                    ],
+                    true,
+                ));
+            }
+        }
+
+        let permissions = self.get_permissions(user_id).await?;
+        Ok((Self::visible_for(&permissions), false))
+    }
+
+    /// Creates thumbnail of the recording from its first frame.
+    /// Thumbnail of the streamed video is copied, when no frame can be captured.
+    async fn create_recording_thumbnail(
+        &self,
+        recording_path: &str,
+        streamed_video: &Video,
+    ) -> Result<String> {
+        let media_proxy = self.media_proxy.clone();
+        let video_path = recording_path.to_string();
+        let frame = tokio::task::spawn_blocking(move || media_proxy.capture_frames(&video_path, 1))
+            .await
+            .map_err(anyhow::Error::from)
+            .and_then(|frames| frames)
+            .and_then(|frames| {
+                frames
+                    .into_iter()
+                    .next()
+                    .ok_or(anyhow!("No frame was captured"))
+            });
+
+        match frame {
+            Ok(frame) => {
+                let thumbnail_path = format!("{}/{}.png", self.thumbnail_dir, Uuid::new_v4());
+                DynamicImage::ImageRgba8(frame)
+                    .resize(THUMBNAIL_WIDTH, THUMBNAIL_HEIGHT, FilterType::Triangle)
+                    .save_with_format(&thumbnail_path, ImageFormat::Png)
+                    .app_error("Failed to create the thumbnail")?;
+                Ok(thumbnail_path)
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to capture frame of recording {}, {:?}",
+                    recording_path, err
+                );
+                // The file can't be shared, it's removed together with the video
+                let extension = get_file_extension(streamed_video.thumbnail_path.clone()).await;
+                let thumbnail_path =
+                    format!("{}/{}.{}", self.thumbnail_dir, Uuid::new_v4(), extension);
+                tokio::fs::copy(&streamed_video.thumbnail_path, &thumbnail_path)
+                    .await
+                    .app_error("Failed to create the thumbnail")?;
+                Ok(thumbnail_path)
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -123,6 +415,21 @@ impl VideoFacadeTrait for VideoFacade {
             .await
             .app_error_kind("Validation failed", AppErrorKind::BadRequestError)?;
 
+        // Frame of the video is used, when the artist didn't choose any thumbnail
+        let temp_thumbnail_id = match video_model.temp_thumbnail_id {
+            Some(temp_thumbnail_id) => temp_thumbnail_id,
+            None => self
+                .temp_file_facade
+                .create_thumbnail_candidates(video_model.temp_video_id, user_id, 1)
+                .await?
+                .first()
+                .copied()
+                .ok_or(AppError::new(
+                    "Failed to create the thumbnail",
+                    AppErrorKind::InternalServerError,
+                ))?,
+        };
+
         let video_path = self
             .temp_file_facade
             .persist_permanently(
@@ -135,13 +442,15 @@ impl VideoFacadeTrait for VideoFacade {
         let thumbnail_path = self
             .temp_file_facade
             .persist_permanently(
-                video_model.temp_thumbnail_id,
+                temp_thumbnail_id,
                 user_id,
                 self.thumbnail_dir.clone(),
                 &mut tx,
             )
             .await?;
 
+        let metadata = self.probe_video(video_path.clone()).await;
+
         let entity = Video {
             id: -1,
             artist_id: artist.id,
@@ -150,6 +459,14 @@ impl VideoFacadeTrait for VideoFacade {
             file_path: video_path,
             thumbnail_path,
             description: video_model.description,
+            duration_ms: metadata.duration_ms,
+            width: metadata.width,
+            height: metadata.height,
+            frame_rate: metadata.frame_rate,
+            container: metadata.container,
+            video_codec: metadata.video_codec,
+            audio_codec: metadata.audio_codec,
+            draft: false,
         };
 
         let video_entity = self.video_repo.save_video(entity, &mut tx).await?;
@@ -161,6 +478,7 @@ impl VideoFacadeTrait for VideoFacade {
             )
             .await?;
         tx.commit().await.app_error("Failed save the video")?;
+        self.start_hls_transcoding(&video_entity)?;
 
         Ok(models::video::Video::from(&video_entity))
     }
@@ -183,6 +501,7 @@ impl VideoFacadeTrait for VideoFacade {
             .await
             .app_error_kind("Validation failed", AppErrorKind::BadRequestError)?;
 
+        let video_changed = edited_video.temp_video_id.is_some();
         let video_path = if let Some(file_id) = edited_video.temp_video_id {
             let path = self
                 .temp_file_facade
@@ -202,6 +521,10 @@ impl VideoFacadeTrait for VideoFacade {
         } else {
             None
         };
+        let metadata = match video_path {
+            Some(ref path) => Some(self.probe_video(path.clone()).await),
+            None => None,
+        };
         let db_entity = PatchVideo {
             id: video_id,
             artist_id: None,
@@ -210,6 +533,7 @@ impl VideoFacadeTrait for VideoFacade {
             file_path: video_path,
             thumbnail_path,
             description: edited_video.description,
+            metadata,
         };
 
         let video = self.video_repo.patch_video(db_entity, &mut tx).await?;
@@ -222,6 +546,9 @@ impl VideoFacadeTrait for VideoFacade {
             .await?;
 
         tx.commit().await.app_error("Failed to patch the video")?;
+        if video_changed {
+            self.start_hls_transcoding(&video)?;
+        }
 
         Ok(models::video::Video::from(&video))
     }
@@ -241,6 +568,11 @@ impl VideoFacadeTrait for VideoFacade {
         deleted.ok_or(AppError::new("Video doesn't exist", AppErrorKind::NotFound))?;
 
         tx.commit().await.app_error("Failed to delete the video")?;
+        // Running transcoding of the video is discarded, when it's done
+        self.hls_transcodings.lock().unwrap().remove(&video_id);
+        tokio::fs::remove_dir_all(self.get_video_hls_dir(video_id))
+            .await
+            .ok();
 
         Ok(())
     }
@@ -318,11 +650,40 @@ impl VideoFacadeTrait for VideoFacade {
         Ok(file)
     }
 
+    async fn get_hls_file(
+        &self,
+        video_id: i32,
+        user_id: Option<i32>,
+        file_path: &str,
+    ) -> Result<NamedFile> {
+        self.get_video_entity(video_id, user_id).await?;
+
+        if !HLS_FILE_REGEX.is_match(file_path) {
+            return Err(AppError::new(
+                "HLS file doesn't exist",
+                AppErrorKind::NotFound,
+            ));
+        }
+
+        let path = format!("{}/{}", self.get_video_hls_dir(video_id), file_path);
+        let file = NamedFile::open_async(path)
+            .await
+            .app_error_kind("HLS file doesn't exist", AppErrorKind::NotFound)?;
+
+        Ok(file)
+    }
+
+    fn has_hls_playlist(&self, video_id: i32) -> bool {
+        Path::new(&format!(
+            "{}/{}",
+            self.get_video_hls_dir(video_id),
+            HLS_MASTER_PLAYLIST
+        ))
+        .exists()
+    }
+
     async fn check_permissions(&self, video: &Video, user_id: Option<i32>) -> Result<()> {
-        let permissions = match user_id {
-            None => HashSet::new(),
-            Some(user_id) => self.user_facade.get_permissions(user_id).await?,
-        };
+        let permissions = self.get_permissions(user_id).await?;
 
         if permissions.contains(&UserRole::Artist) {
             // if permissions hashset contains any UserRole, user_id is always Some
@@ -335,26 +696,50 @@ impl VideoFacadeTrait for VideoFacade {
             }
         }
 
-        let check = |role: &UserRole| -> Result<()> {
-            if permissions.contains(role) {
-                return Ok(());
-            }
-            Err(AppError::new("Video doesn't exist", AppErrorKind::NotFound))
-        };
-        match video.visibility {
-            VideoVisibility::All => Ok(()),
-            VideoVisibility::Registered => check(&UserRole::Registered),
-            VideoVisibility::Paying => check(&UserRole::PayingMember),
+        if video.draft {
+            return Err(AppError::new("Video doesn't exist", AppErrorKind::NotFound));
         }
+        if Self::visible_for(&permissions).contains(&video.visibility) {
+            return Ok(());
+        }
+        Err(AppError::new("Video doesn't exist", AppErrorKind::NotFound))
     }
 
+    async fn get_video_access(&self, user_id: Option<i32>) -> Result<VideoAccess> {
+        let permissions = self.get_permissions(user_id).await?;
+        let owner_artist_id = match user_id {
+            Some(user_id) if permissions.contains(&UserRole::Artist) => self
+                .artist_facade
+                .get_artist_internal(user_id, None)
+                .await
+                .ok()
+                .map(|artist| artist.id),
+            _ => None,
+        };
+
+        Ok(VideoAccess {
+            visibilities: Self::visible_for(&permissions),
+            owner_artist_id,
+        })
+    }
+
+    /// Fetches one page of the videos
+    ///
+    /// # Arguments
+    ///
+    /// * `sort` - order of the videos
+    /// * `filter` - only videos in all these categories are returned
+    /// * `after` - ID of the last video of the previous page, `None` for the first page
     async fn fetch_videos(
         &self,
-        ord: Option<&str>,
+        sort: VideoSort,
         filter: Option<Vec<i32>>,
-        offset: Option<i32>,
+        after: Option<i32>,
     ) -> Result<Vec<Video>> {
-        let videos = self.video_repo.fetch_videos(ord, filter, offset).await;
+        let videos = self
+            .video_repo
+            .fetch_videos(sort, filter, after, VIDEO_PAGE_SIZE)
+            .await;
         let videos = match videos {
             Ok(videos) => videos,
             Err(_e) => {
@@ -381,6 +766,50 @@ impl VideoFacadeTrait for VideoFacade {
         Ok(videos)
     }
 
+    /// Searches videos visible to the user, the most relevant first
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - text of the query, supports quoted phrases, `or` and `-` for excluded words
+    /// * `category_ids` - only videos in all these categories are returned
+    /// * `offset` - number of results to skip
+    /// * `user_id` - ID of the user, who searches the videos
+    async fn search_videos(
+        &self,
+        query: &str,
+        category_ids: Option<Vec<i32>>,
+        offset: i64,
+        user_id: Option<i32>,
+    ) -> Result<Vec<VideoSearchHit>> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(Vec::new());
+        }
+        if query.chars().count() > MAX_SEARCH_QUERY_LENGTH {
+            return Err(AppError::new(
+                "Search query is too long",
+                AppErrorKind::BadRequestError,
+            ));
+        }
+
+        let access = self.get_video_access(user_id).await?;
+        let search = VideoSearch {
+            query: query.to_string(),
+            category_ids,
+            visibilities: access.visibilities,
+            owner_artist_id: access.owner_artist_id,
+            limit: SEARCH_PAGE_SIZE,
+            offset: offset.max(0),
+        };
+
+        let hits = self
+            .video_repo
+            .search_videos(search)
+            .await
+            .app_error("Failed to search videos")?;
+        Ok(hits)
+    }
+
     async fn is_video_owner(&self, video_artist_id: i32, user_id: i32) -> Result<()> {
         if user_id < 0 {
             return Err(AppError::new("Video doesn't exist", AppErrorKind::NotFound));
@@ -401,4 +830,85 @@ impl VideoFacadeTrait for VideoFacade {
     fn get_video_thumbnail_dirs(&self) -> (String, String) {
         (self.video_dir.clone(), self.thumbnail_dir.clone())
     }
+
+    async fn fetch_artist_videos(
+        &self,
+        artist_id: i32,
+        offset: i64,
+        user_id: Option<i32>,
+    ) -> Result<Vec<Video>> {
+        let (visibilities, is_owner) = self.visible_videos_of_artist(artist_id, user_id).await?;
+        let videos = self
+            .video_repo
+            .fetch_artist_videos(
+                artist_id,
+                visibilities,
+                is_owner,
+                offset.max(0),
+                ARTIST_VIDEOS_PAGE_SIZE,
+            )
+            .await?;
+
+        Ok(videos)
+    }
+
+    async fn count_artist_videos(&self, artist_id: i32, user_id: Option<i32>) -> Result<i64> {
+        let (visibilities, is_owner) = self.visible_videos_of_artist(artist_id, user_id).await?;
+        let count = self
+            .video_repo
+            .count_artist_videos(artist_id, visibilities, is_owner)
+            .await?;
+
+        Ok(count)
+    }
+
+    async fn save_recording(
+        &self,
+        streamed_video: &Video,
+        recording_path: String,
+    ) -> Result<Video> {
+        let metadata = self.probe_video(recording_path.clone()).await;
+        let thumbnail_path = self
+            .create_recording_thumbnail(&recording_path, streamed_video)
+            .await?;
+
+        let entity = Video {
+            id: -1,
+            artist_id: streamed_video.artist_id,
+            visibility: streamed_video.visibility,
+            name: format!("{} (stream recording)", streamed_video.name),
+            file_path: recording_path,
+            thumbnail_path,
+            description: streamed_video.description.clone(),
+            duration_ms: metadata.duration_ms,
+            width: metadata.width,
+            height: metadata.height,
+            frame_rate: metadata.frame_rate,
+            container: metadata.container,
+            video_codec: metadata.video_codec,
+            audio_codec: metadata.audio_codec,
+            draft: true,
+        };
+
+        let mut tx = self.unit_of_work.begin().await?;
+        let video = self.video_repo.save_video(entity, &mut tx).await?;
+        tx.commit()
+            .await
+            .app_error("Failed to save the recording")?;
+        self.start_hls_transcoding(&video)?;
+
+        Ok(video)
+    }
+
+    async fn publish_video(&self, user_id: i32, video_id: i32) -> Result<()> {
+        let artist = self
+            .artist_facade
+            .get_artist_internal(user_id, None)
+            .await?;
+        if !self.video_repo.publish_video(video_id, artist.id).await? {
+            return Err(AppError::new("Draft doesn't exist", AppErrorKind::NotFound));
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,167 @@
+use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::models::view::{DailyViews, VideoViewStats, VIEW_STATS_DAYS};
+use crate::business::Result;
+use crate::configuration::models::Configuration;
+use crate::persistence::repositories::view::{ViewBuffer, ViewRepo};
+use async_trait::async_trait;
+use chrono::{Days, Utc};
+use log::{error, info};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+#[async_trait]
+pub trait ViewFacadeTrait {
+    /// Records the view of the video, if the viewer didn't view it recently
+    ///
+    /// # Arguments
+    ///
+    /// * `user_id` - ID of the logged-in viewer
+    /// * `ip_address` - IP address of the viewer, used when the viewer is not logged in
+    ///
+    /// # Returns
+    /// `true` if the view was counted
+    async fn record_view(
+        &self,
+        video_id: i32,
+        user_id: Option<i32>,
+        ip_address: Option<&str>,
+    ) -> Result<bool>;
+    /// Writes the buffered views to the database
+    ///
+    /// # Returns
+    /// Number of the written views
+    async fn flush_views(&self) -> Result<i64>;
+    /// Returns view statistics of the video owned by the user
+    async fn get_view_stats(&self, video_id: i32, user_id: i32) -> Result<VideoViewStats>;
+}
+
+pub struct ViewFacade {
+    view_repo: Arc<dyn ViewRepo + Send + Sync>,
+    view_buffer: Arc<dyn ViewBuffer + Send + Sync>,
+    video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    app_configuration: Arc<Configuration>,
+}
+
+impl ViewFacade {
+    pub fn new(
+        view_repo: Arc<dyn ViewRepo + Send + Sync>,
+        view_buffer: Arc<dyn ViewBuffer + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+        app_configuration: Arc<Configuration>,
+    ) -> Self {
+        Self {
+            view_repo,
+            view_buffer,
+            video_facade,
+            app_configuration,
+        }
+    }
+
+    /// Periodically writes the buffered views to the database in the background task
+    pub fn start_flushing(view_facade: Arc<ViewFacade>) {
+        let period = Duration::from_secs(
+            view_facade
+                .app_configuration
+                .app
+                .views
+                .flush_interval_seconds
+                .max(1),
+        );
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                match view_facade.flush_views().await {
+                    Ok(0) => {}
+                    Ok(count) => info!("{} views were written to the database", count),
+                    Err(err) => error!("Failed to write the buffered views, {:?}", err),
+                }
+            }
+        });
+    }
+}
+
+#[async_trait]
+impl ViewFacadeTrait for ViewFacade {
+    async fn record_view(
+        &self,
+        video_id: i32,
+        user_id: Option<i32>,
+        ip_address: Option<&str>,
+    ) -> Result<bool> {
+        self.video_facade
+            .get_video_entity(video_id, user_id)
+            .await?;
+
+        let viewer = match (user_id, ip_address) {
+            (Some(user_id), _) => format!("user:{user_id}"),
+            (None, Some(ip_address)) => format!("ip:{ip_address}"),
+            (None, None) => return Ok(false),
+        };
+        let window =
+            Duration::from_secs(self.app_configuration.app.views.dedup_window_minutes * 60);
+        if !self
+            .view_buffer
+            .mark_viewer(video_id, &viewer, window)
+            .await?
+        {
+            return Ok(false);
+        }
+
+        self.view_buffer
+            .add_view(video_id, Utc::now().date_naive())
+            .await?;
+        Ok(true)
+    }
+
+    async fn flush_views(&self) -> Result<i64> {
+        let Some(taken) = self.view_buffer.take_pending().await? else {
+            return Ok(0);
+        };
+
+        // If clearing of the buffer fails after the views are written,
+        // they are written once more by the next flush
+        self.view_repo.add_views(&taken.views).await?;
+        self.view_buffer.clear_taken(&taken).await?;
+
+        Ok(taken.views.iter().map(|views| views.view_count).sum())
+    }
+
+    async fn get_view_stats(&self, video_id: i32, user_id: i32) -> Result<VideoViewStats> {
+        let video = self
+            .video_facade
+            .get_video_entity(video_id, Some(user_id))
+            .await?;
+        self.video_facade
+            .is_video_owner(video.artist_id, user_id)
+            .await?;
+
+        let today = Utc::now().date_naive();
+        let since = today - Days::new(VIEW_STATS_DAYS - 1);
+        let views: HashMap<_, _> = self
+            .view_repo
+            .get_daily_views(video_id, since)
+            .await?
+            .into_iter()
+            .map(|views| (views.day, views.view_count))
+            .collect();
+
+        let daily_views = since
+            .iter_days()
+            .take_while(|day| *day <= today)
+            .map(|day| DailyViews {
+                day,
+                view_count: views.get(&day).copied().unwrap_or_default(),
+            })
+            .collect();
+
+        Ok(VideoViewStats {
+            total_views: self.view_repo.get_total_views(video_id).await?,
+            daily_views,
+        })
+    }
+}
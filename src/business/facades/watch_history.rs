@@ -0,0 +1,125 @@
+use crate::business::facades::video::VideoFacadeTrait;
+use crate::business::models::error::{AppError, AppErrorKind, MapToAppError};
+use crate::business::models::watch_history::{resume_position, SavePositionReq, HISTORY_PAGE_SIZE};
+use crate::business::validation::validatable::{EmptyContext, Validatable};
+use crate::business::Result;
+use crate::persistence::entities::watch_history::WatchedVideo;
+use crate::persistence::repositories::watch_history::WatchHistoryRepo;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait WatchHistoryFacadeTrait {
+    /// Saves the position of the playback to the history of the user
+    async fn save_position(&self, user_id: i32, video_id: i32, req: SavePositionReq) -> Result<()>;
+    /// Returns position, from which the user can continue watching the video
+    ///
+    /// # Arguments
+    ///
+    /// * `duration_ms` - duration of the video, almost finished playback is not resumed
+    async fn get_resume_position(
+        &self,
+        user_id: i32,
+        video_id: i32,
+        duration_ms: Option<i64>,
+    ) -> Result<Option<i64>>;
+    /// Returns one page of the history, the most recently watched videos first, without
+    /// the videos the user can't watch anymore, and the offset of the next page
+    async fn list_history(
+        &self,
+        user_id: i32,
+        offset: i64,
+    ) -> Result<(Vec<WatchedVideo>, Option<i64>)>;
+    async fn delete_entry(&self, user_id: i32, video_id: i32) -> Result<()>;
+    async fn clear_history(&self, user_id: i32) -> Result<()>;
+}
+
+pub struct WatchHistoryFacade {
+    watch_history_repo: Arc<dyn WatchHistoryRepo + Send + Sync>,
+    video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+}
+
+impl WatchHistoryFacade {
+    pub fn new(
+        watch_history_repo: Arc<dyn WatchHistoryRepo + Send + Sync>,
+        video_facade: Arc<dyn VideoFacadeTrait + Send + Sync>,
+    ) -> Self {
+        Self {
+            watch_history_repo,
+            video_facade,
+        }
+    }
+}
+
+#[async_trait]
+impl WatchHistoryFacadeTrait for WatchHistoryFacade {
+    async fn save_position(&self, user_id: i32, video_id: i32, req: SavePositionReq) -> Result<()> {
+        req.validate_model(&EmptyContext::new())
+            .await
+            .app_error_kind("Validation failed", AppErrorKind::BadRequestError)?;
+        let video = self
+            .video_facade
+            .get_video_entity(video_id, Some(user_id))
+            .await?;
+
+        let position_ms = match video.duration_ms {
+            Some(duration_ms) => req.position_ms.min(duration_ms),
+            None => req.position_ms,
+        };
+        self.watch_history_repo
+            .save_position(user_id, video_id, position_ms)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_resume_position(
+        &self,
+        user_id: i32,
+        video_id: i32,
+        duration_ms: Option<i64>,
+    ) -> Result<Option<i64>> {
+        let position = self
+            .watch_history_repo
+            .get_position(user_id, video_id)
+            .await?;
+
+        Ok(position.and_then(|position_ms| resume_position(position_ms, duration_ms)))
+    }
+
+    async fn list_history(
+        &self,
+        user_id: i32,
+        offset: i64,
+    ) -> Result<(Vec<WatchedVideo>, Option<i64>)> {
+        let offset = offset.max(0);
+        let access = self.video_facade.get_video_access(Some(user_id)).await?;
+        let history = self
+            .watch_history_repo
+            .list_history(user_id, &access, offset, HISTORY_PAGE_SIZE)
+            .await?;
+        let next_offset =
+            (history.len() as i64 == HISTORY_PAGE_SIZE).then_some(offset + HISTORY_PAGE_SIZE);
+
+        Ok((history, next_offset))
+    }
+
+    async fn delete_entry(&self, user_id: i32, video_id: i32) -> Result<()> {
+        let deleted = self
+            .watch_history_repo
+            .delete_entry(user_id, video_id)
+            .await?;
+
+        if !deleted {
+            return Err(AppError::new(
+                "Video is not in the watch history",
+                AppErrorKind::NotFound,
+            ));
+        }
+        Ok(())
+    }
+
+    async fn clear_history(&self, user_id: i32) -> Result<()> {
+        self.watch_history_repo.clear_history(user_id).await?;
+        Ok(())
+    }
+}
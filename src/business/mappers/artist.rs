@@ -1,5 +1,5 @@
-use crate::business::models::artist_detail::ArtistDetail;
-use crate::persistence::entities::artist::Artist;
+use crate::business::models::artist_detail::{ArtistDetail, ArtistProfile};
+use crate::persistence::entities::artist::{Artist, ArtistProfile as ArtistProfileEntity};
 
 impl From<Artist> for ArtistDetail {
     fn from(artist: Artist) -> Self {
@@ -11,6 +11,18 @@ impl From<Artist> for ArtistDetail {
     }
 }
 
+impl From<ArtistProfileEntity> for ArtistProfile {
+    fn from(profile: ArtistProfileEntity) -> Self {
+        ArtistProfile {
+            id: profile.id,
+            user_id: profile.user_id,
+            name: profile.name,
+            description: profile.description,
+            profile_picture_path: profile.profile_picture_path,
+        }
+    }
+}
+
 // impl From<ArtistDetail> for Artist {
 //     fn from(artist_detail: ArtistDetail) -> Self {
 //         Artist {
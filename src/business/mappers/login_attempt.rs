@@ -0,0 +1,12 @@
+use crate::business::models::login_attempt::LockedAccount;
+use crate::persistence::entities::login_attempt::LockedAccount as LockedAccountEntity;
+
+impl From<LockedAccountEntity> for LockedAccount {
+    fn from(locked_account: LockedAccountEntity) -> Self {
+        LockedAccount {
+            username: locked_account.username,
+            failed_attempts: locked_account.failed_attempts,
+            locked_until: locked_account.locked_until,
+        }
+    }
+}
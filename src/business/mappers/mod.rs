@@ -2,9 +2,14 @@ pub mod artist;
 pub mod comment;
 pub mod deal;
 pub mod generic;
+mod login_attempt;
 pub mod paying_member;
 pub mod payment_method;
+mod playlist;
+mod session;
 mod stream;
+mod stream_chat;
+mod stream_telemetry;
 pub mod user;
 mod video;
 pub mod video_category;
@@ -0,0 +1,48 @@
+use crate::business::models::playlist::Playlist as PlaylistDto;
+use crate::business::models::playlist::PlaylistOverview as PlaylistOverviewDto;
+use crate::business::models::playlist::PlaylistVisibility as PlaylistVisibilityDto;
+use crate::persistence::entities::playlist::Playlist as PlaylistEntity;
+use crate::persistence::entities::playlist::PlaylistOverview as PlaylistOverviewEntity;
+use crate::persistence::entities::playlist::PlaylistVisibility as PlaylistVisibilityEntity;
+
+impl From<&PlaylistVisibilityDto> for PlaylistVisibilityEntity {
+    fn from(value: &PlaylistVisibilityDto) -> Self {
+        match value {
+            PlaylistVisibilityDto::Private => PlaylistVisibilityEntity::Private,
+            PlaylistVisibilityDto::Unlisted => PlaylistVisibilityEntity::Unlisted,
+            PlaylistVisibilityDto::Public => PlaylistVisibilityEntity::Public,
+        }
+    }
+}
+
+impl From<&PlaylistVisibilityEntity> for PlaylistVisibilityDto {
+    fn from(value: &PlaylistVisibilityEntity) -> Self {
+        match value {
+            PlaylistVisibilityEntity::Private => PlaylistVisibilityDto::Private,
+            PlaylistVisibilityEntity::Unlisted => PlaylistVisibilityDto::Unlisted,
+            PlaylistVisibilityEntity::Public => PlaylistVisibilityDto::Public,
+        }
+    }
+}
+
+impl From<&PlaylistEntity> for PlaylistDto {
+    fn from(value: &PlaylistEntity) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            name: value.name.clone(),
+            description: value.description.clone(),
+            visibility: PlaylistVisibilityDto::from(&value.visibility),
+        }
+    }
+}
+
+impl From<&PlaylistOverviewEntity> for PlaylistOverviewDto {
+    fn from(value: &PlaylistOverviewEntity) -> Self {
+        Self {
+            playlist: PlaylistDto::from(&value.playlist),
+            video_count: value.video_count,
+            first_video_id: value.first_video_id,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+use crate::business::models::session::ActiveSession;
+use crate::business::util::user_agent::describe_device;
+use crate::persistence::entities::session::UserSession;
+
+impl From<UserSession> for ActiveSession {
+    fn from(session: UserSession) -> Self {
+        ActiveSession {
+            id: session.info.id,
+            device: describe_device(session.info.user_agent.as_deref()),
+            ip_address: session.info.ip_address,
+            created_at: session.info.created_at,
+            last_seen_at: session.last_seen_at,
+            current: false,
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use crate::business::models::stream::{
-    LiveStream as LiveStreamDto, LiveStreamStart, LiveStreamStatus as StatusDto,
+    LiveStream as LiveStreamDto, LiveStreamSchedule, LiveStreamSource as SourceDto,
+    LiveStreamStart, LiveStreamStatus as StatusDto, RemindedStream as RemindedStreamDto,
 };
 use crate::persistence::entities::stream::{
-    LiveStream as LiveStreamEntity, LiveStreamStatus as StatusEntity,
+    LiveStream as LiveStreamEntity, LiveStreamSource as SourceEntity,
+    LiveStreamStatus as StatusEntity, RemindedStream as RemindedStreamEntity,
 };
 
 impl From<&LiveStreamStart> for LiveStreamEntity {
@@ -12,6 +14,40 @@ impl From<&LiveStreamStart> for LiveStreamEntity {
             video_id: value.video_id,
             start_time: chrono::Local::now(),
             status: StatusEntity::Running,
+            source: SourceEntity::File,
+            recording_video_id: None,
+        }
+    }
+}
+
+impl From<&LiveStreamSchedule> for LiveStreamEntity {
+    fn from(value: &LiveStreamSchedule) -> Self {
+        Self {
+            id: -1,
+            video_id: value.video_id,
+            start_time: value.start_time,
+            status: StatusEntity::Pending,
+            source: SourceEntity::File,
+            recording_video_id: None,
+        }
+    }
+}
+
+impl From<&RemindedStreamEntity> for RemindedStreamDto {
+    fn from(value: &RemindedStreamEntity) -> Self {
+        Self {
+            stream_id: value.stream_id,
+            video_name: value.video_name.clone(),
+            start_time: value.start_time,
+        }
+    }
+}
+
+impl From<SourceEntity> for SourceDto {
+    fn from(value: SourceEntity) -> Self {
+        match value {
+            SourceEntity::File => SourceDto::File,
+            SourceEntity::Rtmp => SourceDto::Rtmp,
         }
     }
 }
@@ -33,6 +69,8 @@ impl LiveStreamDto {
             video_id: value.video_id,
             start_time: value.start_time,
             status: value.status.into(),
+            source: value.source.into(),
+            recording_video_id: value.recording_video_id,
             stream_url,
         }
     }
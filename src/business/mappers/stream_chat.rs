@@ -0,0 +1,33 @@
+use crate::business::models::stream_chat::ChatEvent as ChatEventDto;
+use crate::business::models::stream_chat::ChatMessage as ChatMessageDto;
+use crate::persistence::entities::stream_chat::ChatEvent as ChatEventEntity;
+use crate::persistence::entities::stream_chat::ChatMessage as ChatMessageEntity;
+
+impl From<&ChatMessageEntity> for ChatMessageDto {
+    fn from(value: &ChatMessageEntity) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            username: value.username.clone(),
+            content: value.content.clone(),
+            created_at: value.created_at,
+        }
+    }
+}
+
+impl From<&ChatEventEntity> for ChatEventDto {
+    fn from(value: &ChatEventEntity) -> Self {
+        match value {
+            ChatEventEntity::Message(message) => {
+                ChatEventDto::Message(ChatMessageDto::from(message))
+            }
+            ChatEventEntity::Delete { message_id } => ChatEventDto::Delete {
+                message_id: *message_id,
+            },
+            ChatEventEntity::Timeout { user_id, seconds } => ChatEventDto::Timeout {
+                user_id: *user_id,
+                seconds: *seconds,
+            },
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use crate::business::models::stream_telemetry::StreamHealth as StreamHealthDto;
+use crate::business::models::stream_telemetry::StreamHealthSummary as StreamHealthSummaryDto;
+use crate::persistence::entities::stream_telemetry::StreamHealthSample as StreamHealthSampleEntity;
+use crate::persistence::entities::stream_telemetry::StreamHealthSummary as StreamHealthSummaryEntity;
+use crate::streamer::types::PipelineHealth;
+use chrono::Utc;
+
+impl From<&StreamHealthSampleEntity> for StreamHealthDto {
+    fn from(value: &StreamHealthSampleEntity) -> Self {
+        Self {
+            resolution: value.resolution.clone(),
+            bitrate_kbps: value.bitrate_kbps,
+            dropped_frames: value.dropped_frames,
+            qos_events: value.qos_events,
+            buffering_percent: value.buffering_percent,
+            warnings: value.warnings,
+            recorded_at: value.recorded_at,
+        }
+    }
+}
+
+impl From<&StreamHealthSummaryEntity> for StreamHealthSummaryDto {
+    fn from(value: &StreamHealthSummaryEntity) -> Self {
+        Self {
+            resolution: value.resolution.clone(),
+            avg_bitrate_kbps: value.avg_bitrate_kbps,
+            min_bitrate_kbps: value.min_bitrate_kbps,
+            dropped_frames: value.dropped_frames,
+            qos_events: value.qos_events,
+            warnings: value.warnings,
+            sample_count: value.sample_count,
+        }
+    }
+}
+
+impl StreamHealthSampleEntity {
+    /// Creates sample of the pipeline health measured right now
+    pub fn from_pipeline(stream_id: i32, health: &PipelineHealth) -> Self {
+        Self {
+            id: -1,
+            stream_id,
+            resolution: health.resolution.clone(),
+            bitrate_kbps: health.bitrate_kbps as i32,
+            dropped_frames: health.dropped_frames as i64,
+            qos_events: health.qos_events as i32,
+            buffering_percent: health.buffering_percent,
+            warnings: health.warnings as i32,
+            recorded_at: Utc::now(),
+        }
+    }
+}
@@ -11,6 +11,7 @@ impl From<User> for UserDetail {
             artist_id: user.artist_id,
             paying_member_id: user.paying_member_id,
             is_admin: user.is_admin,
+            email_verified: user.email_verified_at.is_some(),
         }
     }
 }
@@ -26,6 +27,7 @@ impl From<UserDetail> for User {
             artist_id: user_detail.artist_id,
             paying_member_id: user_detail.paying_member_id,
             is_admin: user_detail.is_admin,
+            email_verified_at: None,
         }
     }
 }
@@ -41,6 +43,7 @@ impl From<UserRegister> for User {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         }
     }
 }
@@ -1,7 +1,9 @@
 use crate::business::models::video::VideoVisibility as VideoVisibilityDto;
 use crate::business::models::video::{EditVideoTemplateModel, Video as VideoDto};
 use crate::persistence::entities::video::Video as VideoEntity;
+use crate::persistence::entities::video::VideoMetadata;
 use crate::persistence::entities::video::VideoVisibility as VideoVisibilityEntity;
+use crate::streamer::types::MediaInfo;
 
 impl From<&VideoVisibilityDto> for VideoVisibilityEntity {
     fn from(value: &VideoVisibilityDto) -> Self {
@@ -31,6 +33,28 @@ impl From<&VideoEntity> for VideoDto {
             video_visibility: VideoVisibilityDto::from(&value.visibility),
             name: value.name.clone(),
             description: value.description.clone(),
+            duration_ms: value.duration_ms,
+            width: value.width,
+            height: value.height,
+            frame_rate: value.frame_rate,
+            container: value.container.clone(),
+            video_codec: value.video_codec.clone(),
+            audio_codec: value.audio_codec.clone(),
+            draft: value.draft,
+        }
+    }
+}
+
+impl From<&MediaInfo> for VideoMetadata {
+    fn from(value: &MediaInfo) -> Self {
+        Self {
+            duration_ms: value.duration_ms.map(|duration| duration as i64),
+            width: value.width.map(|width| width as i32),
+            height: value.height.map(|height| height as i32),
+            frame_rate: value.frame_rate,
+            container: value.container.clone(),
+            video_codec: value.video_codec.clone(),
+            audio_codec: value.audio_codec.clone(),
         }
     }
 }
@@ -43,6 +67,14 @@ impl From<EditVideoTemplateModel> for VideoDto {
             video_visibility: value.video_visibility,
             name: value.name,
             description: Option::from(value.description),
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
         }
     }
 }
@@ -1,4 +1,7 @@
 use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+pub const ARTIST_VIDEOS_PAGE_SIZE: i64 = 12;
 
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ArtistDetail {
@@ -13,3 +16,38 @@ pub struct ArtistName {
     pub user_id: i32,
     pub name: String,
 }
+
+/// Artist together with the followers, shown next to the videos of the artist
+pub struct ArtistFollowInfo {
+    pub artist_id: i32,
+    pub name: String,
+    pub follower_count: i64,
+    pub is_following: bool,
+    /// Only logged-in users, who are not the artist, can follow the artist
+    pub can_follow: bool,
+}
+
+#[derive(Deserialize)]
+pub struct GetArtistReq {
+    pub id: i32,
+}
+
+/// Public profile of the artist
+pub struct ArtistProfile {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub profile_picture_path: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct ArtistDescriptionReq {
+    #[validate(length(max = 2000))]
+    pub description: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ArtistVideosReq {
+    pub offset: Option<i64>,
+}
@@ -27,6 +27,8 @@ pub enum AppErrorKind {
     Unauthorized,
     NotFound,
     AccessDenied,
+    Conflict,
+    TooManyRequests,
 }
 
 pub trait MapToAppError<T> {
@@ -77,6 +79,8 @@ impl Display for AppError {
             AppErrorKind::AccessDenied => {
                 write!(f, "Access to the resources denied: {}", self.message)
             }
+            AppErrorKind::Conflict => write!(f, "Conflict: {}", self.message),
+            AppErrorKind::TooManyRequests => write!(f, "Too many requests: {}", self.message),
         }
     }
 }
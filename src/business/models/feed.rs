@@ -0,0 +1,24 @@
+use crate::business::models::video::VideoList;
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+pub const FEED_PAGE_SIZE: i64 = 12;
+
+#[derive(Deserialize)]
+pub struct FeedPageReq {
+    pub offset: Option<i64>,
+}
+
+/// New video or live stream of the followed artist
+pub struct FeedItem {
+    pub video: VideoList,
+    /// ID of the live stream, `None` if the item is an uploaded video
+    pub stream_id: Option<i32>,
+    pub published_at: DateTime<Utc>,
+}
+
+impl FeedItem {
+    pub fn formatted_published_at(&self) -> String {
+        self.published_at.format("%-d. %-m. %Y %H:%M").to_string()
+    }
+}
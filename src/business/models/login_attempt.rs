@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Account locked after too many failed logins
+pub struct LockedAccount {
+    pub username: String,
+    pub failed_attempts: u32,
+    pub locked_until: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AccountUnlock {
+    pub username: String,
+}
@@ -2,11 +2,20 @@ pub mod artist_detail;
 pub mod comment;
 pub mod deal;
 pub mod error;
+pub mod feed;
+pub mod login_attempt;
 pub mod membership_details;
 pub mod paying_member;
 pub mod payment_method;
+pub mod playlist;
+pub mod session;
 pub mod stream;
+pub mod stream_chat;
+pub mod stream_telemetry;
 pub mod temp_file;
+pub mod two_factor;
 pub mod user;
 pub mod video;
 pub mod video_category;
+pub mod view;
+pub mod watch_history;
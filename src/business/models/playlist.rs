@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PlaylistVisibility {
+    Private,
+    Unlisted,
+    Public,
+}
+
+impl PlaylistVisibility {
+    pub const ALL: [PlaylistVisibility; 3] = [
+        PlaylistVisibility::Private,
+        PlaylistVisibility::Unlisted,
+        PlaylistVisibility::Public,
+    ];
+
+    pub fn get_visible_value(&self) -> String {
+        match self {
+            PlaylistVisibility::Private => String::from("Private"),
+            PlaylistVisibility::Unlisted => String::from("Anyone with the link"),
+            PlaylistVisibility::Public => String::from("Public"),
+        }
+    }
+}
+
+impl Display for PlaylistVisibility {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaylistVisibility::Private => write!(f, "PRIVATE"),
+            PlaylistVisibility::Unlisted => write!(f, "UNLISTED"),
+            PlaylistVisibility::Public => write!(f, "PUBLIC"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Playlist {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub visibility: PlaylistVisibility,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct PlaylistReq {
+    #[validate(length(min = 1, max = 128))]
+    pub name: String,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+    pub visibility: PlaylistVisibility,
+}
+
+#[derive(Deserialize)]
+pub struct GetPlaylistReq {
+    pub id: i32,
+}
+
+#[derive(Deserialize)]
+pub struct PlayPlaylistReq {
+    /// ID of the played video, the first video is played when it's not specified
+    pub video_id: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct AddPlaylistItemReq {
+    pub video_id: i32,
+}
+
+#[derive(Deserialize)]
+pub struct PlaylistItemReq {
+    pub id: i32,
+    pub video_id: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MovePlaylistItemReq {
+    /// New position of the video, counted from 0
+    pub position: i32,
+    /// ID of the video played on the page, so it stays highlighted
+    pub current_video_id: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PlaylistItemsReq {
+    /// ID of the video played on the page, so it stays highlighted
+    pub current_video_id: Option<i32>,
+}
+
+#[derive(Deserialize)]
+pub struct SelectPlaylistReq {
+    pub video_id: i32,
+}
+
+/// Playlist in the list of the playlists
+pub struct PlaylistOverview {
+    pub playlist: Playlist,
+    pub video_count: i64,
+    /// ID of the first video, its thumbnail is used as a cover of the playlist
+    pub first_video_id: Option<i32>,
+}
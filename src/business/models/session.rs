@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+
+/// Session, in which the user is logged in on some device
+pub struct ActiveSession {
+    pub id: String,
+    /// Browser and operating system, e.g. "Firefox on Linux"
+    pub device: String,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+    /// Whether it's the session of the request
+    pub current: bool,
+}
@@ -1,10 +1,13 @@
 use crate::business::models::error::{AppError, AppErrorKind};
+use crate::business::models::video::{VideoList, VideoVisibility};
 use crate::business::Result;
 use crate::streamer::types::{
-    CompoundStreamInfoTrait, PipelinesList, Stream, StreamResolution, StreamStorageTrait, Streams,
+    CompoundStreamInfoTrait, PipelineHealth, PipelinesList, Stream, StreamResolution, StreamSource,
+    StreamStorageTrait, Streams,
 };
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex, MutexGuard};
 
 const RTMP_SERVER_ENV: &str = "RTMP_SERVER";
@@ -13,12 +16,15 @@ const STREAM_PATH_PREFIX_KEY: &str = "STREAM_PATH_PREFIX";
 #[derive(Clone)]
 pub struct StreamStorage {
     streams: Streams,
+    /// The latest health of the pipelines by stream ID and resolution
+    health: Arc<Mutex<HashMap<String, HashMap<String, PipelineHealth>>>>,
 }
 
 impl Default for StreamStorage {
     fn default() -> Self {
         StreamStorage {
             streams: Arc::new(Mutex::new(Vec::new())),
+            health: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 }
@@ -49,6 +55,28 @@ impl StreamStorage {
         }
         Err(AppError::new("Stream not found", AppErrorKind::NotFound))
     }
+
+    /// Returns IDs of the streams, which have running pipelines
+    pub fn stream_ids(&self) -> Vec<String> {
+        let streams = self.streams.lock().unwrap();
+        streams
+            .iter()
+            .map(|(stream, _pipelines)| stream.get_stream_id())
+            .collect()
+    }
+
+    /// Returns health of the pipelines reported since the previous call, with IDs of their streams
+    pub fn take_health(&self) -> Vec<(String, PipelineHealth)> {
+        let mut health = self.health.lock().unwrap();
+        health
+            .drain()
+            .flat_map(|(stream_id, pipelines)| {
+                pipelines
+                    .into_values()
+                    .map(move |pipeline| (stream_id.clone(), pipeline))
+            })
+            .collect()
+    }
 }
 
 impl StreamStorageTrait for StreamStorage {
@@ -72,25 +100,37 @@ impl StreamStorageTrait for StreamStorage {
         let streams = self.streams.lock().unwrap();
         streams.len()
     }
+
+    fn report_health(&self, stream_id: &str, health: PipelineHealth) {
+        let mut stored = self.health.lock().unwrap();
+        stored
+            .entry(stream_id.to_string())
+            .or_default()
+            .insert(health.resolution.clone(), health);
+    }
 }
 
 #[derive(Clone)]
 pub struct CompoundStreamInfo {
     pub stream_id: String,
-    pub video_path: String,
+    pub source: StreamSource,
     pub streams: Vec<StreamResolution>,
+    /// Path to the file, where the highest resolution is recorded
+    pub record_path: Option<String>,
 }
 
 impl CompoundStreamInfo {
     pub fn new(
         stream_id: String,
-        video_path: String,
+        source: StreamSource,
         streams: Vec<StreamResolution>,
+        record_path: Option<String>,
     ) -> CompoundStreamInfo {
         CompoundStreamInfo {
             stream_id,
-            video_path,
+            source,
             streams,
+            record_path,
         }
     }
 }
@@ -114,21 +154,62 @@ impl CompoundStreamInfoTrait for CompoundStreamInfo {
         self.stream_id.clone()
     }
 
-    fn get_video_path(&self) -> String {
-        self.video_path.clone()
+    fn get_source(&self) -> StreamSource {
+        self.source.clone()
     }
 
     fn get_resolutions(&self) -> &Vec<StreamResolution> {
         &self.streams
     }
+
+    fn get_record_path(&self) -> Option<String> {
+        self.record_path.clone()
+    }
 }
 
+/// Stream of the video, which starts at the given time
 #[derive(Serialize, Deserialize)]
 pub struct LiveStreamSchedule {
     pub video_id: i32,
     pub start_time: DateTime<Local>,
 }
 
+/// Notifications of the scheduled stream, shown on its countdown page
+#[derive(Serialize, Deserialize)]
+pub struct StreamReminders {
+    pub stream_id: i32,
+    /// Only logged in users can be notified
+    pub can_remind: bool,
+    /// Whether the user asked to be notified, when the stream starts
+    pub is_reminded: bool,
+    /// Number of the users, who will be notified
+    pub reminder_count: i64,
+}
+
+/// Stream, which the user asked to be notified about
+#[derive(Serialize, Deserialize)]
+pub struct RemindedStream {
+    pub stream_id: i32,
+    pub video_name: String,
+    pub start_time: DateTime<Local>,
+}
+
+/// Running or upcoming stream in the directory of the streams
+pub struct StreamListItem {
+    pub stream_id: i32,
+    pub video: VideoList,
+    pub visibility: VideoVisibility,
+    pub start_time: DateTime<Local>,
+    /// Number of the viewers, `None` if the stream didn't start yet
+    pub viewer_count: Option<u64>,
+}
+
+impl StreamListItem {
+    pub fn get_start_time(&self) -> String {
+        self.start_time.format("%d.%m.%Y %H:%M").to_string()
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LiveStreamStart {
     pub video_id: i32,
@@ -141,11 +222,44 @@ pub enum LiveStreamStatus {
     Ended,
 }
 
+#[derive(Serialize, Deserialize, PartialEq)]
+pub enum LiveStreamSource {
+    File,
+    Rtmp,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct LiveStream {
     pub id: i32,
     pub video_id: i32,
     pub start_time: DateTime<Local>,
     pub status: LiveStreamStatus,
+    pub source: LiveStreamSource,
+    pub recording_video_id: Option<i32>,
     pub stream_url: String,
 }
+
+impl LiveStream {
+    /// Start time in RFC 3339 format, so it can be read by JavaScript
+    pub fn get_start_time_rfc3339(&self) -> String {
+        self.start_time.to_rfc3339()
+    }
+
+    pub fn get_start_time(&self) -> String {
+        self.start_time.format("%d.%m.%Y %H:%M").to_string()
+    }
+}
+
+/// Where the artist sends the stream from his encoder, e.g. OBS
+#[derive(Serialize, Deserialize)]
+pub struct StreamIngest {
+    pub server_url: String,
+    pub stream_key: String,
+}
+
+/// Request sent by `on_publish` callback of nginx-rtmp, when the encoder starts publishing.
+/// The name of the published stream is the stream key of the artist.
+#[derive(Serialize, Deserialize)]
+pub struct RtmpPublishReq {
+    pub name: String,
+}
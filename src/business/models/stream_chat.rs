@@ -0,0 +1,60 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub id: i32,
+    pub user_id: i32,
+    pub username: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ChatMessage {
+    /// Time of the message shown in the chat, e.g. 18:05
+    pub fn get_time(&self) -> String {
+        self.created_at
+            .with_timezone(&Local)
+            .format("%H:%M")
+            .to_string()
+    }
+}
+
+/// Change of the chat, which is delivered to all viewers of the stream
+#[derive(Debug, Clone, PartialEq)]
+pub enum ChatEvent {
+    Message(ChatMessage),
+    Delete { message_id: i32 },
+    Timeout { user_id: i32, seconds: u64 },
+}
+
+pub struct ChatRoom {
+    pub stream_id: i32,
+    /// The latest messages, the oldest first
+    pub messages: Vec<ChatMessage>,
+    pub user_id: Option<i32>,
+    /// Owner of the stream can delete messages and time out users
+    pub is_owner: bool,
+    /// New messages can't be sent after the stream ends
+    pub is_open: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct ChatMessageReq {
+    #[validate(length(min = 1, max = 500))]
+    pub content: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct ChatTimeoutReq {
+    pub user_id: i32,
+    #[validate(range(min = 1))]
+    pub minutes: u64,
+}
+
+#[derive(Deserialize)]
+pub struct ChatMessagePathReq {
+    pub stream_id: i32,
+    pub message_id: i32,
+}
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Local, Utc};
+
+/// The latest health of one pipeline of the running stream
+pub struct StreamHealth {
+    pub resolution: String,
+    pub bitrate_kbps: i32,
+    pub dropped_frames: i64,
+    pub qos_events: i32,
+    pub buffering_percent: Option<i32>,
+    pub warnings: i32,
+    pub recorded_at: DateTime<Utc>,
+}
+
+impl StreamHealth {
+    /// Time of the measurement, e.g. 18:05:10
+    pub fn get_time(&self) -> String {
+        self.recorded_at
+            .with_timezone(&Local)
+            .format("%H:%M:%S")
+            .to_string()
+    }
+}
+
+/// Health of one pipeline over the whole stream
+pub struct StreamHealthSummary {
+    pub resolution: String,
+    pub avg_bitrate_kbps: i32,
+    pub min_bitrate_kbps: i32,
+    pub dropped_frames: i64,
+    pub qos_events: i32,
+    pub warnings: i32,
+    pub sample_count: i64,
+}
+
+pub struct StreamStats {
+    pub stream_id: i32,
+    pub is_running: bool,
+    /// Viewers watching the running stream right now
+    pub viewer_count: u64,
+    pub peak_viewers: i32,
+    /// Health is shown only to the owner of the stream
+    pub is_owner: bool,
+    /// The latest health of the pipelines, while the stream runs
+    pub health: Vec<StreamHealth>,
+    /// Recorded health of the pipelines, once the stream ends
+    pub health_summary: Vec<StreamHealthSummary>,
+}
@@ -1,4 +1,5 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 #[derive(Deserialize)]
 pub struct GetFileInputTemplate {
@@ -10,3 +11,23 @@ pub enum TempFileInput {
     Video,
     Thumbnail,
 }
+
+#[derive(Serialize, Deserialize, Debug, Validate)]
+pub struct CreateUploadReq {
+    #[validate(length(min = 1, max = 255))]
+    pub file_name: String,
+    #[validate(range(min = 1))]
+    pub upload_length: i64,
+}
+
+#[derive(Deserialize)]
+pub struct GetUploadReq {
+    pub id: i32,
+}
+
+/// Current state of the chunked upload
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct UploadStatus {
+    pub upload_offset: i64,
+    pub upload_length: i64,
+}
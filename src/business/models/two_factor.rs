@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+/// Secret, which the user adds to the authenticator app
+pub struct TotpEnrolment {
+    /// Base32 encoded secret for the apps, which can't scan the QR code
+    pub secret: String,
+    pub qr_code_svg: String,
+}
+
+pub struct TwoFactorStatus {
+    pub enabled: bool,
+    pub recovery_codes_left: i64,
+}
+
+/// TOTP code from the authenticator app or one of the recovery codes
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TwoFactorCode {
+    pub code: String,
+}
+
+/// Login, which passed the password check and waits for the second factor.
+/// It's kept in the session, until the user is logged in.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingTwoFactorLogin {
+    pub user_id: i32,
+    pub username: String,
+    /// Unix timestamp, after which the user has to enter the password again
+    pub expires_at: i64,
+    pub failed_attempts: u32,
+}
@@ -15,6 +15,7 @@ pub struct UserDetail {
     pub artist_id: Option<i32>,
     pub paying_member_id: Option<i32>,
     pub is_admin: bool,
+    pub email_verified: bool,
 }
 
 #[derive(Clone, Debug, Deserialize, Validate)]
@@ -86,10 +87,32 @@ pub struct EmailQuery {
     pub target_element: String,
 }
 
+/// Token of the link sent by email
+#[derive(Deserialize)]
+pub struct TokenQuery {
+    pub token: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PasswordResetRequest {
+    pub email: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, Validate)]
+pub struct PasswordReset {
+    pub token: String,
+    #[validate(length(min = 8, max = 128))]
+    pub password: String,
+    #[validate(must_match(other = "password"))]
+    pub password2: String,
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum UserRole {
     PayingMember,
     Registered,
+    /// Registered user, who confirmed the email, only they can comment and upload
+    Verified,
     Artist,
     Admin,
 }
@@ -1,3 +1,4 @@
+use crate::persistence::entities::video::{HIGHLIGHT_END, HIGHLIGHT_START};
 use actix_multipart::form::tempfile::TempFile;
 use actix_multipart::form::MultipartForm;
 use serde::{Deserialize, Serialize};
@@ -51,7 +52,7 @@ pub struct VideoUploadReq {
     pub video_visibility: VideoVisibility,
     pub category_ids: Option<Vec<i32>>,
     #[validate(range(min = 1))]
-    pub temp_thumbnail_id: i32,
+    pub temp_thumbnail_id: Option<i32>,
     #[validate(range(min = 1))]
     pub temp_video_id: i32,
     #[validate(length(max = 5000))]
@@ -84,15 +85,109 @@ pub struct Video {
     pub video_visibility: VideoVisibility,
     pub name: String,
     pub description: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub frame_rate: Option<f64>,
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub draft: bool,
+}
+
+impl Video {
+    pub fn formatted_duration(&self) -> Option<String> {
+        self.duration_ms.map(format_duration)
+    }
+
+    /// Returns resolution of the video, e.g. `1920x1080`
+    pub fn resolution(&self) -> Option<String> {
+        match (self.width, self.height) {
+            (Some(width), Some(height)) => Some(format!("{width}x{height}")),
+            _ => None,
+        }
+    }
+
+    /// Returns frame rate rounded to two decimal places, e.g. `29.97`
+    pub fn formatted_frame_rate(&self) -> Option<String> {
+        self.frame_rate.map(|rate| {
+            format!("{:.2}", rate)
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        })
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct VideoList {
     pub id: i32,
     pub artist_id: i32,
     pub artist_name: String,
     pub thumbnail_path: String,
     pub name: String,
+    pub duration_ms: Option<i64>,
+    pub height: Option<i32>,
+    pub draft: bool,
+}
+
+impl VideoList {
+    pub fn formatted_duration(&self) -> Option<String> {
+        self.duration_ms.map(format_duration)
+    }
+}
+
+/// Formats duration in milliseconds as `H:MM:SS`, or `M:SS` for videos shorter than an hour
+pub fn format_duration(duration_ms: i64) -> String {
+    let total_seconds = duration_ms / 1000;
+    let (hours, minutes, seconds) = (
+        total_seconds / 3600,
+        (total_seconds % 3600) / 60,
+        total_seconds % 60,
+    );
+
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
+    }
+}
+
+/// Number of videos returned by one page of the search
+pub const SEARCH_PAGE_SIZE: i64 = 12;
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SearchVideosReq {
+    #[serde(default)]
+    pub q: String,
+    pub category_ids: Option<Vec<i32>>,
+    pub offset: Option<i64>,
+}
+
+pub struct VideoSearchResult {
+    pub video: VideoList,
+    /// Escaped name of the video with matched words wrapped in `<mark>`
+    pub name_html: String,
+    /// Escaped fragments of the description with matched words wrapped in `<mark>`
+    pub snippet_html: Option<String>,
+}
+
+/// Escapes HTML in the search highlight and replaces highlight markers with `<mark>` tags
+pub fn highlight_to_html(highlight: &str) -> String {
+    let mut html = String::with_capacity(highlight.len());
+    for character in highlight.chars() {
+        match character {
+            HIGHLIGHT_START => html.push_str("<mark>"),
+            HIGHLIGHT_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#x27;"),
+            _ => html.push(character),
+        }
+    }
+    html
 }
 
 #[derive(Serialize, Deserialize)]
@@ -108,9 +203,69 @@ pub struct GetVideoByIdReq {
     pub id: i32,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct ThumbnailCandidatesReq {
+    pub temp_video_id: i32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct GetHlsFileReq {
+    pub id: i32,
+    pub file: String,
+}
+
+/// Number of videos returned by one page of the listing
+pub const VIDEO_PAGE_SIZE: i64 = 8;
+
+/// Order of the videos in the listing
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum VideoSort {
+    #[default]
+    Newest,
+    Oldest,
+    MostLiked,
+    MostViewed,
+    Alphabetical,
+}
+
+impl VideoSort {
+    pub const ALL: [VideoSort; 5] = [
+        VideoSort::Newest,
+        VideoSort::Oldest,
+        VideoSort::MostLiked,
+        VideoSort::MostViewed,
+        VideoSort::Alphabetical,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoSort::Newest => "newest",
+            VideoSort::Oldest => "oldest",
+            VideoSort::MostLiked => "most_liked",
+            VideoSort::MostViewed => "most_viewed",
+            VideoSort::Alphabetical => "alphabetical",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            VideoSort::Newest => "Newest",
+            VideoSort::Oldest => "Oldest",
+            VideoSort::MostLiked => "Most liked",
+            VideoSort::MostViewed => "Most viewed",
+            VideoSort::Alphabetical => "A–Z",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct FetchVideoByFilters {
-    pub offset: Option<i32>,
-    pub ord: Option<String>,
+    #[serde(default)]
+    pub sort: VideoSort,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub filter: Option<String>,
+    /// ID of the last video of the previous page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<i32>,
 }
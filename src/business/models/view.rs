@@ -0,0 +1,36 @@
+use chrono::NaiveDate;
+
+/// Number of the days displayed in the view statistics of the video
+pub const VIEW_STATS_DAYS: u64 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyViews {
+    pub day: NaiveDate,
+    pub view_count: i64,
+}
+
+/// View statistics of the video, which are visible only to its artist
+#[derive(Debug, Clone, PartialEq)]
+pub struct VideoViewStats {
+    pub total_views: i64,
+    /// Views of the last days, the oldest first. Days without any view are included too.
+    pub daily_views: Vec<DailyViews>,
+}
+
+impl VideoViewStats {
+    pub fn max_daily_views(&self) -> i64 {
+        self.daily_views
+            .iter()
+            .map(|views| views.view_count)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Height of the bar in the chart in percents of the busiest day
+    pub fn bar_height(&self, views: &DailyViews) -> i64 {
+        match self.max_daily_views() {
+            0 => 0,
+            max => views.view_count * 100 / max,
+        }
+    }
+}
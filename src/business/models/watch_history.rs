@@ -0,0 +1,65 @@
+use crate::business::models::video::{format_duration, VideoList};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use validator::Validate;
+
+pub const HISTORY_PAGE_SIZE: i64 = 12;
+/// Playback closer to the start is not offered for resuming
+const MIN_RESUME_POSITION_MS: i64 = 5_000;
+/// Playback closer to the end is considered finished, so the video starts from the beginning
+const FINISHED_MARGIN_MS: i64 = 10_000;
+
+#[derive(Deserialize, Debug, Validate)]
+pub struct SavePositionReq {
+    #[validate(range(min = 0))]
+    pub position_ms: i64,
+}
+
+#[derive(Deserialize)]
+pub struct HistoryPageReq {
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize)]
+pub struct HistoryEntryReq {
+    pub video_id: i32,
+}
+
+pub struct WatchHistoryItem {
+    pub video: VideoList,
+    pub position_ms: i64,
+    pub watched_at: DateTime<Utc>,
+}
+
+impl WatchHistoryItem {
+    pub fn formatted_position(&self) -> String {
+        format_duration(self.position_ms)
+    }
+
+    pub fn formatted_watched_at(&self) -> String {
+        self.watched_at.format("%-d. %-m. %Y %H:%M").to_string()
+    }
+
+    /// Watched part of the video in percents, 0 if the duration is unknown
+    pub fn progress_percent(&self) -> i64 {
+        match self.video.duration_ms {
+            Some(duration_ms) if duration_ms > 0 => {
+                (self.position_ms * 100 / duration_ms).clamp(0, 100)
+            }
+            _ => 0,
+        }
+    }
+}
+
+/// Returns position, from which the playback can be resumed.
+/// Playback which has just started or is almost finished is not resumed.
+pub fn resume_position(position_ms: i64, duration_ms: Option<i64>) -> Option<i64> {
+    if position_ms < MIN_RESUME_POSITION_MS {
+        return None;
+    }
+
+    match duration_ms {
+        Some(duration_ms) if position_ms >= duration_ms - FINISHED_MARGIN_MS => None,
+        _ => Some(position_ms),
+    }
+}
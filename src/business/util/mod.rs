@@ -1 +1,5 @@
 pub mod file;
+pub mod reset_token;
+pub mod totp;
+pub mod user_agent;
+pub mod verification_token;
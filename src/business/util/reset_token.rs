@@ -0,0 +1,20 @@
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+const TOKEN_BYTES: usize = 32;
+
+/// Generates a random token of the password reset link.
+/// Returns the token, which is sent to the user, and its hash, which is stored.
+pub fn generate() -> (String, String) {
+    let mut token = [0u8; TOKEN_BYTES];
+    rand::thread_rng().fill_bytes(&mut token);
+    let token = hex::encode(token);
+    let token_hash = hash(&token);
+
+    (token, token_hash)
+}
+
+/// Tokens are long and random, so a plain SHA-256 without salt is enough
+pub fn hash(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
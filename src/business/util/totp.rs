@@ -0,0 +1,122 @@
+use qrcode::render::svg;
+use qrcode::QrCode;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
+
+const ISSUER: &str = "EroticHub";
+const DIGITS: usize = 6;
+const STEP_SECONDS: u64 = 30;
+/// Codes of the neighbouring steps are accepted too, since clocks of the devices drift
+const ALLOWED_DRIFT_STEPS: u64 = 1;
+const RECOVERY_CODE_COUNT: usize = 10;
+const RECOVERY_CODE_PART_LENGTH: usize = 5;
+
+/// Generates a new secret encoded in base32, which is shown to the user
+pub fn generate_secret() -> String {
+    Secret::generate_secret().to_encoded().to_string()
+}
+
+/// # Arguments
+///
+/// * `secret` - base32 encoded secret
+/// * `account_name` - name of the account shown in the authenticator app
+pub fn create_totp(secret: &str, account_name: &str) -> anyhow::Result<TOTP> {
+    let secret = Secret::Encoded(secret.to_string()).to_bytes()?;
+
+    // Colon separates the issuer from the account name in the otpauth URL
+    Ok(TOTP::new(
+        Algorithm::SHA1,
+        DIGITS,
+        0,
+        STEP_SECONDS,
+        secret,
+        Some(ISSUER.to_string()),
+        account_name.replace(':', ""),
+    )?)
+}
+
+/// Returns the time step, to which the code belongs, or `None` if the code is not valid
+/// at the given time
+pub fn find_step(totp: &TOTP, code: &str, unix_time: u64) -> Option<u64> {
+    let current_step = unix_time / STEP_SECONDS;
+
+    (current_step.saturating_sub(ALLOWED_DRIFT_STEPS)..=current_step + ALLOWED_DRIFT_STEPS)
+        .find(|step| totp.check(code.trim(), step * STEP_SECONDS))
+}
+
+/// Renders the `otpauth` URL of the TOTP as an SVG QR code, which the authenticator app scans
+pub fn render_qr_code(totp: &TOTP) -> anyhow::Result<String> {
+    let qr_code = QrCode::new(totp.get_url().as_bytes())?;
+
+    Ok(qr_code
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build())
+}
+
+/// Generates recovery codes in the format `abcde-12345`
+pub fn generate_recovery_codes() -> Vec<String> {
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| format!("{}-{}", random_part(), random_part()))
+        .collect()
+}
+
+/// Recovery codes are random enough to be hashed without salt.
+/// Letter case and dashes are ignored, so the user can type the code either way.
+pub fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|char| char.is_ascii_alphanumeric())
+        .map(|char| char.to_ascii_lowercase())
+        .collect();
+
+    hex::encode(Sha256::digest(normalized.as_bytes()))
+}
+
+/// Whether the code looks like a TOTP code and not like a recovery code
+pub fn is_totp_code(code: &str) -> bool {
+    let code = code.trim();
+    code.len() == DIGITS && code.chars().all(|char| char.is_ascii_digit())
+}
+
+fn random_part() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(RECOVERY_CODE_PART_LENGTH)
+        .map(|char| char::from(char).to_ascii_lowercase())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn find_step_accepts_drift() {
+        let totp = create_totp(&generate_secret(), "user").unwrap();
+        let time = 1_700_000_000;
+        let step = time / STEP_SECONDS;
+
+        let previous_code = totp.generate(time - STEP_SECONDS);
+        let current_code = totp.generate(time);
+        let old_code = totp.generate(time - 2 * STEP_SECONDS);
+
+        assert_eq!(find_step(&totp, &previous_code, time), Some(step - 1));
+        assert_eq!(find_step(&totp, &current_code, time), Some(step));
+        assert_eq!(find_step(&totp, &old_code, time), None);
+    }
+
+    #[test]
+    fn recovery_code_hash_ignores_format() {
+        let code = &generate_recovery_codes()[0];
+
+        assert_eq!(
+            hash_recovery_code(code),
+            hash_recovery_code(&code.to_uppercase().replace('-', " "))
+        );
+        assert!(!is_totp_code(code));
+        assert!(is_totp_code("123456"));
+    }
+}
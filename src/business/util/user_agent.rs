@@ -0,0 +1,77 @@
+/// Browsers in the order, in which they are matched.
+/// Most browsers mention the others in their user agents, e.g. Edge mentions Chrome and Safari.
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("OPR/", "Opera"),
+    ("Firefox/", "Firefox"),
+    ("Chrome/", "Chrome"),
+    ("Safari/", "Safari"),
+];
+/// Operating systems in the order, in which they are matched.
+/// Android mentions Linux and iOS mentions Mac OS X.
+const OPERATING_SYSTEMS: &[(&str, &str)] = &[
+    ("Android", "Android"),
+    ("iPhone", "iOS"),
+    ("iPad", "iOS"),
+    ("Windows", "Windows"),
+    ("Mac OS X", "macOS"),
+    ("Linux", "Linux"),
+];
+
+fn find_name(user_agent: &str, names: &[(&'static str, &'static str)]) -> Option<&'static str> {
+    names
+        .iter()
+        .find(|(pattern, _)| user_agent.contains(pattern))
+        .map(|(_, name)| *name)
+}
+
+/// Describes the device by its user agent, e.g. "Firefox on Linux"
+pub fn describe_device(user_agent: Option<&str>) -> String {
+    let user_agent = user_agent.unwrap_or_default();
+    let browser = find_name(user_agent, BROWSERS);
+    let operating_system = find_name(user_agent, OPERATING_SYSTEMS);
+
+    match (browser, operating_system) {
+        (Some(browser), Some(operating_system)) => format!("{browser} on {operating_system}"),
+        (Some(name), None) | (None, Some(name)) => String::from(name),
+        (None, None) => String::from("Unknown device"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn describe_common_devices() {
+        assert_eq!(
+            describe_device(Some(
+                "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0"
+            )),
+            "Firefox on Linux"
+        );
+        assert_eq!(
+            describe_device(Some(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/130.0.0.0 Safari/537.36 Edg/130.0.0.0"
+            )),
+            "Edge on Windows"
+        );
+        assert_eq!(
+            describe_device(Some(
+                "Mozilla/5.0 (Linux; Android 14) AppleWebKit/537.36 (KHTML, like Gecko) \
+                 Chrome/130.0.0.0 Mobile Safari/537.36"
+            )),
+            "Chrome on Android"
+        );
+        assert_eq!(
+            describe_device(Some(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 18_0 like Mac OS X) AppleWebKit/605.1.15 \
+                 (KHTML, like Gecko) Version/18.0 Mobile/15E148 Safari/604.1"
+            )),
+            "Safari on iOS"
+        );
+        assert_eq!(describe_device(Some("curl/8.5.0")), "Unknown device");
+        assert_eq!(describe_device(None), "Unknown device");
+    }
+}
@@ -0,0 +1,60 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Token of the link, which verifies the email, e.g. `12.1735689600.<signature>`.
+/// It carries ID of the user and the expiration. The signature covers the email as well,
+/// so the token is no longer valid, once the user changes the email.
+#[derive(Debug, PartialEq)]
+pub struct VerificationToken {
+    pub user_id: i32,
+    pub expires_at: DateTime<Utc>,
+    signature: Vec<u8>,
+}
+
+impl VerificationToken {
+    pub fn sign(key: &[u8], user_id: i32, email: &str, expires_at: DateTime<Utc>) -> String {
+        let expires_at = expires_at.timestamp();
+        let signature = Self::mac(key, user_id, email, expires_at)
+            .finalize()
+            .into_bytes();
+
+        format!("{user_id}.{expires_at}.{}", hex::encode(signature))
+    }
+
+    /// Reads the token without checking its signature
+    pub fn parse(token: &str) -> Option<Self> {
+        let mut parts = token.split('.');
+        let user_id = parts.next()?.parse().ok()?;
+        let expires_at = DateTime::from_timestamp(parts.next()?.parse().ok()?, 0)?;
+        let signature = hex::decode(parts.next()?).ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+
+        Some(Self {
+            user_id,
+            expires_at,
+            signature,
+        })
+    }
+
+    /// Checks the signature in constant time
+    pub fn verify(&self, key: &[u8], email: &str) -> bool {
+        Self::mac(key, self.user_id, email, self.expires_at.timestamp())
+            .verify_slice(&self.signature)
+            .is_ok()
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    fn mac(key: &[u8], user_id: i32, email: &str, expires_at: i64) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(format!("email-verification|{user_id}|{email}|{expires_at}").as_bytes());
+        mac
+    }
+}
@@ -1,2 +1,7 @@
+mod artist;
+mod playlist;
+mod stream_chat;
+mod temp_file;
 pub mod user_register;
 mod video;
+mod watch_history;
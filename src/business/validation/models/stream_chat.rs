@@ -0,0 +1,32 @@
+use crate::business::models::stream_chat::{ChatMessageReq, ChatTimeoutReq};
+use crate::business::validation::utils::extract_first_error;
+use crate::business::validation::validatable::{EmptyContext, Validatable};
+use validator::{Validate, ValidationError};
+
+impl Validatable<EmptyContext> for ChatMessageReq {
+    async fn validate_model(&self, _context: &EmptyContext) -> Result<(), ValidationError> {
+        if let Err(validation_errors) = self.validate() {
+            if let Some(first_error) = extract_first_error(&validation_errors) {
+                return Err(first_error);
+            }
+        }
+
+        if self.content.trim().is_empty() {
+            return Err(ValidationError::new("Message can't be empty"));
+        }
+
+        Ok(())
+    }
+}
+
+impl Validatable<EmptyContext> for ChatTimeoutReq {
+    async fn validate_model(&self, _context: &EmptyContext) -> Result<(), ValidationError> {
+        if let Err(validation_errors) = self.validate() {
+            if let Some(first_error) = extract_first_error(&validation_errors) {
+                return Err(first_error);
+            }
+        }
+
+        Ok(())
+    }
+}
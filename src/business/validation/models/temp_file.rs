@@ -0,0 +1,16 @@
+use crate::business::models::temp_file::CreateUploadReq;
+use crate::business::validation::utils::extract_first_error;
+use crate::business::validation::validatable::{EmptyContext, Validatable};
+use validator::{Validate, ValidationError};
+
+impl Validatable<EmptyContext> for CreateUploadReq {
+    async fn validate_model(&self, _context: &EmptyContext) -> Result<(), ValidationError> {
+        if let Err(validation_errors) = self.validate() {
+            if let Some(first_error) = extract_first_error(&validation_errors) {
+                return Err(first_error);
+            }
+        }
+
+        Ok(())
+    }
+}
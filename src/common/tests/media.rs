@@ -0,0 +1,40 @@
+use crate::business::facades::video::MediaProxyTrait;
+use crate::streamer::gstreamer_controller::HLS_MASTER_PLAYLIST;
+use crate::streamer::types::{MediaInfo, StreamResolution};
+use image::{Rgba, RgbaImage};
+
+pub(crate) struct MediaProxyMock {}
+
+impl MediaProxyTrait for MediaProxyMock {
+    /// Creates only the master playlist, so the video is considered as transcoded
+    fn transcode_to_hls(
+        &self,
+        _video_path: &str,
+        output_dir: &str,
+        _resolutions: &[StreamResolution],
+    ) -> anyhow::Result<()> {
+        std::fs::create_dir_all(output_dir)?;
+        std::fs::write(format!("{output_dir}/{HLS_MASTER_PLAYLIST}"), "#EXTM3U\n")?;
+        Ok(())
+    }
+
+    /// Returns plain images instead of the real frames
+    fn capture_frames(&self, _video_path: &str, count: u32) -> anyhow::Result<Vec<RgbaImage>> {
+        Ok((0..count)
+            .map(|_| RgbaImage::from_pixel(1280, 720, Rgba([255, 0, 0, 255])))
+            .collect())
+    }
+
+    /// Returns metadata of a Full HD video
+    fn probe_media(&self, _video_path: &str) -> anyhow::Result<MediaInfo> {
+        Ok(MediaInfo {
+            duration_ms: Some(62_000),
+            width: Some(1920),
+            height: Some(1080),
+            frame_rate: Some(30.0),
+            container: Some(String::from("MP4")),
+            video_codec: Some(String::from("H.264")),
+            audio_codec: Some(String::from("AAC")),
+        })
+    }
+}
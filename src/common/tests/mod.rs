@@ -1,2 +1,3 @@
+pub(crate) mod media;
 pub mod setup;
-mod stream;
+pub(crate) mod stream;
@@ -1,27 +1,54 @@
 use crate::api::extractors::permissions_extractor::extract;
+use crate::api::routes::artist::artist_routes;
+use crate::api::routes::feed::feed_routes;
+use crate::api::routes::playlist::playlist_routes;
 use crate::api::routes::stream::stream_routes;
 use crate::api::routes::temp_file::temp_file_routes;
 use crate::api::routes::user::user_routes;
 use crate::api::routes::video::video_routes;
 use crate::business::facades::artist::ArtistFacade;
 use crate::business::facades::comment::CommentFacade;
+use crate::business::facades::feed::FeedFacade;
+use crate::business::facades::login_attempt::LoginAttemptFacade;
+use crate::business::facades::playlist::PlaylistFacade;
 use crate::business::facades::stream::StreamFacade;
+use crate::business::facades::stream_chat::StreamChatFacade;
+use crate::business::facades::stream_telemetry::StreamTelemetryFacade;
 use crate::business::facades::temp_file::{TempFileFacade, TempFileFacadeTrait};
+use crate::business::facades::two_factor::TwoFactorFacade;
 use crate::business::facades::user::UserFacade;
 use crate::business::facades::video::VideoFacade;
 use crate::business::facades::video_category::VideoCategoryFacade;
+use crate::business::facades::view::ViewFacade;
+use crate::business::facades::watch_history::WatchHistoryFacade;
 use crate::business::models::stream::StreamStorage;
+use crate::common::tests::media::MediaProxyMock;
 use crate::common::tests::stream::StreamProxyMock;
+use crate::mailer::file::FileMailer;
 use crate::persistence::repositories::artist::ArtistRepository;
 use crate::persistence::repositories::comment::CommentRepository;
+use crate::persistence::repositories::feed::PgFeedRepo;
+use crate::persistence::repositories::login_attempt::RedisLoginAttemptRepo;
+use crate::persistence::repositories::playlist::PgPlaylistRepo;
+use crate::persistence::repositories::session::{RedisUserSessionRepo, UserSessionStore};
 use crate::persistence::repositories::stream::PgStreamRepo;
+use crate::persistence::repositories::stream_chat::{PgStreamChatRepo, RedisChatBroker};
+use crate::persistence::repositories::stream_telemetry::{
+    PgStreamTelemetryRepo, RedisViewerTracker,
+};
 use crate::persistence::repositories::temp_file::PgTempFileRepo;
+use crate::persistence::repositories::two_factor::PgTwoFactorRepo;
 use crate::persistence::repositories::unit_of_work::PostgresUnitOfWork;
 use crate::persistence::repositories::user::UserRepository;
 use crate::persistence::repositories::video::PgVideoRepo;
 use crate::persistence::repositories::video_category::VideoCategoryRepository;
+use crate::persistence::repositories::view::{PgViewRepo, RedisViewBuffer};
+use crate::persistence::repositories::watch_history::PgWatchHistoryRepo;
 use crate::streamer::gstreamer_controller::init_gstreamer;
-use crate::{init_configuration, setup_auth, setup_redis_pool, CONFIG_FILE_KEY};
+use crate::{
+    get_secret_key, init_configuration, setup_auth, setup_payload_config, setup_redis_client,
+    setup_redis_pool, CONFIG_FILE_KEY,
+};
 use actix_http::body::{BoxBody, EitherBody};
 use actix_http::Request;
 use actix_session::storage::RedisSessionStore;
@@ -67,13 +94,18 @@ impl AsyncContext {
     ) -> impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = actix_web::Error>
     {
         let redis_pool = setup_redis_pool().await.unwrap();
-        let redis_store = RedisSessionStore::new_pooled(redis_pool).await.unwrap();
+        let session_store = UserSessionStore::new(
+            RedisSessionStore::new_pooled(redis_pool.clone())
+                .await
+                .unwrap(),
+            self.create_user_session_repo(redis_pool.clone()),
+        );
 
-        let (identity_middleware, session_middleware) = setup_auth(&redis_store);
+        let (identity_middleware, session_middleware) = setup_auth(&session_store);
 
         test::init_service(
             App::new()
-                .configure(self.configure_app())
+                .configure(self.configure_app(redis_pool))
                 .wrap(GrantsMiddleware::with_extractor(extract))
                 .wrap(identity_middleware)
                 .wrap(session_middleware)
@@ -82,8 +114,22 @@ impl AsyncContext {
         .await
     }
 
-    pub fn configure_app(&self) -> impl Fn(&mut ServiceConfig) {
-        let (video_dir, thumbnail_dir, temp_file_dir) = get_resources_dirs(&self.test_folders_root);
+    /// Keys are prefixed, because all tests share the same Redis
+    pub fn create_user_session_repo(
+        &self,
+        redis_pool: deadpool_redis::Pool,
+    ) -> RedisUserSessionRepo {
+        RedisUserSessionRepo::new(redis_pool, format!("{}:", self.test_db_name))
+    }
+
+    /// Directory, where the emails sent by the app are written
+    pub fn get_mail_dir(&self) -> String {
+        format!("{}/mail", self.test_folders_root)
+    }
+
+    pub fn configure_app(&self, redis_pool: deadpool_redis::Pool) -> impl Fn(&mut ServiceConfig) {
+        let (video_dir, thumbnail_dir, temp_file_dir, hls_dir) =
+            get_resources_dirs(&self.test_folders_root);
 
         init_gstreamer().expect(
             "Failed to initialize GStreamer. Check if you have it installed on your system",
@@ -93,7 +139,29 @@ impl AsyncContext {
         let unit_of_work = Arc::new(PostgresUnitOfWork::new(self.pg_pool.clone()));
         let stream_storage = Arc::new(StreamStorage::default());
         let user_repo = Arc::new(UserRepository::new(self.pg_pool.clone()));
-        let user_facade = Arc::new(UserFacade::new(user_repo));
+        let mailer = Arc::new(
+            FileMailer::new(&app_config.app.mail.from, &self.get_mail_dir())
+                .expect("Failed to create the mailer"),
+        );
+        let user_facade = Arc::new(UserFacade::new(
+            user_repo.clone(),
+            Arc::new(self.create_user_session_repo(redis_pool.clone())),
+            mailer,
+            app_config.clone(),
+            get_secret_key().signing().to_vec(),
+        ));
+
+        let two_factor_repo = Arc::new(PgTwoFactorRepo::new(self.pg_pool.clone()));
+        let two_factor_facade = Arc::new(TwoFactorFacade::new(two_factor_repo, user_repo));
+
+        let login_attempt_repo = Arc::new(RedisLoginAttemptRepo::new(
+            redis_pool.clone(),
+            format!("{}:", self.test_db_name),
+        ));
+        let login_attempt_facade = Arc::new(LoginAttemptFacade::new(
+            login_attempt_repo,
+            app_config.clone(),
+        ));
 
         let artist_repo = Arc::new(ArtistRepository::new(self.pg_pool.clone()));
         let artist_facade = Arc::new(ArtistFacade::new(artist_repo));
@@ -105,7 +173,13 @@ impl AsyncContext {
         let video_category_facade = Arc::new(VideoCategoryFacade::new(video_category_repo));
 
         let temp_file_repo = Arc::new(PgTempFileRepo::new(self.pg_pool.clone()));
-        let temp_file_facade = Arc::new(TempFileFacade::new(temp_file_repo, temp_file_dir));
+        let media_proxy_mock = Arc::new(MediaProxyMock {});
+        let temp_file_facade = Arc::new(TempFileFacade::new(
+            temp_file_repo,
+            unit_of_work.clone(),
+            temp_file_dir,
+            Some(media_proxy_mock.clone()),
+        ));
 
         let video_repo = Arc::new(PgVideoRepo::new(self.pg_pool.clone()));
         let video_facade = Arc::new(VideoFacade::new(
@@ -117,36 +191,102 @@ impl AsyncContext {
             unit_of_work,
             video_dir,
             thumbnail_dir,
+            hls_dir,
+            Some(media_proxy_mock),
+            app_config.clone(),
         ));
 
         let stream_repo = Arc::new(PgStreamRepo::new(self.pg_pool.clone()));
-        let stream_proxy_mock = Arc::new(StreamProxyMock {});
+        let stream_proxy_mock = Arc::new(StreamProxyMock::default());
         let stream_facade = Arc::new(StreamFacade::new(
             video_facade.clone(),
+            artist_facade.clone(),
             stream_storage.clone(),
             stream_repo.clone(),
             Some(stream_proxy_mock),
             app_config.clone(),
         ));
 
+        // Keys are prefixed, because all tests share the same Redis
+        let chat_broker = Arc::new(RedisChatBroker::new(
+            redis_pool.clone(),
+            setup_redis_client().unwrap(),
+            format!("{}:", self.test_db_name),
+        ));
+        let stream_chat_facade = Arc::new(StreamChatFacade::new(
+            stream_facade.clone(),
+            video_facade.clone(),
+            Arc::new(PgStreamChatRepo::new(self.pg_pool.clone())),
+            chat_broker,
+            app_config.clone(),
+        ));
+
+        let viewer_tracker = Arc::new(RedisViewerTracker::new(
+            redis_pool.clone(),
+            format!("{}:", self.test_db_name),
+        ));
+        let stream_telemetry_facade = Arc::new(StreamTelemetryFacade::new(
+            stream_facade.clone(),
+            video_facade.clone(),
+            Arc::new(PgStreamTelemetryRepo::new(self.pg_pool.clone())),
+            viewer_tracker,
+            stream_storage.clone(),
+            app_config.clone(),
+        ));
+
+        let view_buffer = Arc::new(RedisViewBuffer::new(
+            redis_pool,
+            format!("{}:", self.test_db_name),
+        ));
+        let view_facade = Arc::new(ViewFacade::new(
+            Arc::new(PgViewRepo::new(self.pg_pool.clone())),
+            view_buffer,
+            video_facade.clone(),
+            app_config.clone(),
+        ));
+
+        let watch_history_repo = Arc::new(PgWatchHistoryRepo::new(self.pg_pool.clone()));
+        let watch_history_facade = Arc::new(WatchHistoryFacade::new(
+            watch_history_repo,
+            video_facade.clone(),
+        ));
+
+        let playlist_repo = Arc::new(PgPlaylistRepo::new(self.pg_pool.clone()));
+        let playlist_facade = Arc::new(PlaylistFacade::new(playlist_repo, video_facade.clone()));
+
+        let feed_repo = Arc::new(PgFeedRepo::new(self.pg_pool.clone()));
+        let feed_facade = Arc::new(FeedFacade::new(feed_repo, video_facade.clone()));
+
         move |config: &mut ServiceConfig| {
             config
                 .service(actix_files::Files::new("/static", "./static"))
                 .app_data(web::Data::from(app_config.clone()))
                 .app_data(web::Data::from(stream_storage.clone()))
                 .app_data(web::Data::from(stream_facade.clone()))
+                .app_data(web::Data::from(stream_chat_facade.clone()))
+                .app_data(web::Data::from(stream_telemetry_facade.clone()))
                 .app_data(web::Data::from(stream_storage.clone()))
                 .app_data(web::Data::from(user_facade.clone()))
+                .app_data(web::Data::from(two_factor_facade.clone()))
+                .app_data(web::Data::from(login_attempt_facade.clone()))
                 .app_data(web::Data::from(temp_file_facade.clone()))
                 .app_data(web::Data::from(user_facade.clone()))
                 .app_data(web::Data::from(temp_file_facade.clone()))
                 .app_data(web::Data::from(video_facade.clone()))
                 .app_data(web::Data::from(artist_facade.clone()))
                 .app_data(web::Data::from(comment_facade.clone()))
+                .app_data(web::Data::from(view_facade.clone()))
+                .app_data(web::Data::from(watch_history_facade.clone()))
+                .app_data(web::Data::from(playlist_facade.clone()))
+                .app_data(web::Data::from(feed_facade.clone()))
+                .app_data(setup_payload_config(app_config.clone()))
                 .configure(video_routes)
                 .configure(user_routes)
                 .configure(temp_file_routes)
-                .configure(stream_routes);
+                .configure(stream_routes)
+                .configure(playlist_routes)
+                .configure(artist_routes)
+                .configure(feed_routes);
         }
     }
 }
@@ -330,9 +470,10 @@ async fn load_template_data(test_pool: &PgPool) {
 /// creates these directories using facades
 async fn create_test_resources_dir(test_id: Uuid) -> String {
     let current_test_path = format!("./tests_resources/test-{test_id}");
-    let (video_path, thumbnail_path, temp_file_path) = get_resources_dirs(&current_test_path);
+    let (video_path, thumbnail_path, temp_file_path, hls_path) =
+        get_resources_dirs(&current_test_path);
 
-    VideoFacade::create_dirs(video_path, thumbnail_path)
+    VideoFacade::create_dirs(video_path, thumbnail_path, hls_path)
         .await
         .expect("Failed to create test resource folders");
     TempFileFacade::create_temp_directory(temp_file_path)
@@ -342,16 +483,17 @@ async fn create_test_resources_dir(test_id: Uuid) -> String {
     current_test_path
 }
 
-/// Returns triple with paths to resources dirs used in the tests
+/// Returns tuple with paths to resources dirs used in the tests
 ///
 /// # Returns
 ///
-/// `(String, String, String)` - (video dir, thumbnail dir, temp file dir)
-fn get_resources_dirs(test_folders_root: &str) -> (String, String, String) {
+/// `(String, String, String, String)` - (video dir, thumbnail dir, temp file dir, HLS dir)
+fn get_resources_dirs(test_folders_root: &str) -> (String, String, String, String) {
     (
         format!("{}/videos", test_folders_root),
         format!("{}/thumbnails", test_folders_root),
         format!("{}/temp", test_folders_root),
+        format!("{}/hls", test_folders_root),
     )
 }
 
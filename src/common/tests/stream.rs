@@ -1,10 +1,32 @@
 use crate::business::facades::stream::GStreamerProxyTrait;
 use crate::streamer::types::{CompoundStreamInfoTrait, StreamStorageTrait};
 use gstreamer::Pipeline;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::thread;
 use std::thread::JoinHandle;
+use std::time::Duration;
 
-pub(crate) struct StreamProxyMock {}
+#[derive(Default)]
+pub(crate) struct StreamProxyMock {
+    /// Pipelines ignore the end of the stream and run until they are killed
+    pub stalled: bool,
+    killed: Arc<AtomicBool>,
+}
+
+#[cfg(test)]
+impl StreamProxyMock {
+    pub fn stalled() -> Self {
+        Self {
+            stalled: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn was_killed(&self) -> bool {
+        self.killed.load(Ordering::SeqCst)
+    }
+}
 
 impl GStreamerProxyTrait for StreamProxyMock {
     fn create_streams(
@@ -14,10 +36,25 @@ impl GStreamerProxyTrait for StreamProxyMock {
     ) -> anyhow::Result<Vec<JoinHandle<()>>> {
         let pipelines = vec![Arc::new(Pipeline::new())];
         stream_storage.push(compound_stream, pipelines);
-        Ok(Vec::new())
+        if !self.stalled {
+            return Ok(Vec::new());
+        }
+
+        let killed = self.killed.clone();
+        let handle = thread::spawn(move || {
+            while !killed.load(Ordering::SeqCst) {
+                thread::sleep(Duration::from_millis(10));
+            }
+        });
+        Ok(vec![handle])
     }
 
     fn stop_stream(&self, _pipeline: &Pipeline) -> anyhow::Result<()> {
         Ok(())
     }
+
+    fn kill_stream(&self, _pipeline: &Pipeline) -> anyhow::Result<()> {
+        self.killed.store(true, Ordering::SeqCst);
+        Ok(())
+    }
 }
@@ -1,16 +1,49 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::net::IpAddr;
+use validator::{Validate, ValidationError};
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Presets of x264, the faster presets produce bigger output with the same quality
+const X264_PRESETS: [&str; 10] = [
+    "ultrafast",
+    "superfast",
+    "veryfast",
+    "faster",
+    "fast",
+    "medium",
+    "slow",
+    "slower",
+    "veryslow",
+    "placebo",
+];
+const X264_TUNES: [&str; 3] = ["stillimage", "fastdecode", "zerolatency"];
+/// Renditions listed in the master playlist by nginx, see `hls_variant` in
+/// `docker/nginx-rtmp/nginx.conf`. Both lists must be changed together.
+const NGINX_HLS_VARIANTS: [&str; 3] = ["360", "480", "720"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Validate)]
 pub struct Configuration {
+    #[validate(nested)]
     pub app: AppConfiguration,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Validate)]
 pub struct AppConfiguration {
     pub video: VideoConfig,
     pub thumbnail: Thumbnail,
+    #[validate(nested)]
     pub stream: Stream,
     pub file_size_limit_mb: i32,
+    pub upload_chunk_size_mb: i32,
+    /// Chunked uploads, which didn't receive any chunk for this long, are removed
+    pub upload_expiry_hours: u64,
+    pub views: ViewsConfig,
+    pub mail: MailConfig,
+    pub login: LoginConfig,
+    /// Proxies in front of the app, e.g. nginx. The address of the client is read from
+    /// the `X-Real-IP` header only on the requests from these proxies.
+    #[serde(default)]
+    pub trusted_proxies: Vec<TrustedNetwork>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -23,7 +56,318 @@ pub struct Thumbnail {
     pub accepted_mime_type: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Validate)]
 pub struct Stream {
-    pub resolutions: Vec<String>,
+    /// Renditions, into which the streams and the uploaded videos are transcoded.
+    /// The same profiles are used for both, so their names must be the HLS variants
+    /// of nginx, even if only the uploaded videos are transcoded.
+    #[validate(length(min = 1), nested, custom(function = "validate_unique_names"))]
+    pub profiles: Vec<EncoderProfile>,
+    /// Whether the highest resolution of the streams is recorded as a draft video
+    #[serde(default)]
+    pub record: bool,
+    /// Whether the streams, which were running when the app stopped, are started again.
+    /// Otherwise they are marked as ended.
+    #[serde(default)]
+    pub restart_orphaned: bool,
+    /// How long the app waits on shutdown for the streams to finish their recordings
+    pub shutdown_timeout_seconds: u64,
+    /// How often are the scheduled streams checked, whether they should start
+    pub scheduler_interval_seconds: u64,
+    pub chat: ChatConfig,
+    pub telemetry: StreamTelemetryConfig,
+}
+
+/// Settings of the encoders, which create one rendition of the stream
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Validate)]
+pub struct EncoderProfile {
+    /// Name of the rendition, e.g. `720`. It's appended to the URL of the stream,
+    /// so it must be one of the HLS variants in the configuration of nginx.
+    #[validate(custom(function = "validate_nginx_variant"))]
+    pub name: String,
+    #[validate(range(min = 2), custom(function = "validate_even"))]
+    pub width: u32,
+    #[validate(range(min = 2), custom(function = "validate_even"))]
+    pub height: u32,
+    #[validate(range(min = 1, max = 50000))]
+    pub video_bitrate_kbps: u32,
+    /// Preset of x264, e.g. `veryfast`
+    #[validate(custom(function = "validate_preset"))]
+    pub preset: String,
+    /// Tune of x264, e.g. `zerolatency`. The encoder is not tuned, when it's missing.
+    #[serde(default)]
+    #[validate(custom(function = "validate_tune"))]
+    pub tune: Option<String>,
+    /// Maximum number of frames between two keyframes
+    #[validate(range(min = 1))]
+    pub keyframe_interval: u32,
+    #[validate(range(min = 8, max = 512))]
+    pub audio_bitrate_kbps: u32,
+    #[validate(range(min = 1, max = 120))]
+    pub frame_rate: u32,
+}
+
+fn validate_unique_names(profiles: &[EncoderProfile]) -> Result<(), ValidationError> {
+    let mut names = HashSet::new();
+    if profiles.iter().all(|profile| names.insert(&profile.name)) {
+        return Ok(());
+    }
+    Err(ValidationError::new("duplicate_profile_name"))
+}
+
+/// Raw video with 4:2:0 chroma subsampling, which is encoded by x264, needs even dimensions
+fn validate_even(value: u32) -> Result<(), ValidationError> {
+    match value % 2 {
+        0 => Ok(()),
+        _ => Err(ValidationError::new("odd_dimension")),
+    }
+}
+
+fn validate_nginx_variant(name: &str) -> Result<(), ValidationError> {
+    match NGINX_HLS_VARIANTS.contains(&name) {
+        true => Ok(()),
+        false => Err(ValidationError::new("unknown_hls_variant")),
+    }
+}
+
+fn validate_preset(preset: &str) -> Result<(), ValidationError> {
+    match X264_PRESETS.contains(&preset) {
+        true => Ok(()),
+        false => Err(ValidationError::new("unknown_x264_preset")),
+    }
+}
+
+fn validate_tune(tune: &str) -> Result<(), ValidationError> {
+    match X264_TUNES.contains(&tune) {
+        true => Ok(()),
+        false => Err(ValidationError::new("unknown_x264_tune")),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ChatConfig {
+    /// Maximum number of messages, which one user can send within the window
+    pub message_limit: u32,
+    pub rate_window_seconds: u64,
+    /// Number of the latest messages shown to the viewers, who join the chat
+    pub history_size: i64,
+    /// Longest timeout, which the owner of the stream can give to a user
+    pub max_timeout_minutes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StreamTelemetryConfig {
+    /// Viewer is counted as watching, if the player fetched the stream within the window
+    pub viewer_window_seconds: u64,
+    /// How often is the health of the running streams written to the database
+    pub record_interval_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ViewsConfig {
+    /// Seconds of the video, which must be played before the view is recorded
+    pub threshold_seconds: u32,
+    /// Repeated views of the same viewer within the window are counted only once
+    pub dedup_window_minutes: u64,
+    /// How often are the buffered views written to the database
+    pub flush_interval_seconds: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MailConfig {
+    /// Sender of the emails, e.g. `EroticHub <no-reply@erotichub.com>`
+    pub from: String,
+    /// Public URL of the app, which the links in the emails point to
+    pub base_url: String,
+    pub transport: MailTransportConfig,
+    /// How long is the verification link valid
+    pub verification_expiry_hours: i64,
+    /// How long the user has to wait, before another verification or password reset link is sent
+    pub resend_cooldown_seconds: i64,
+    /// How long is the password reset link valid
+    pub password_reset_expiry_minutes: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LoginConfig {
+    /// Failed logins to one account, after which the account is locked
+    pub max_failures_per_user: u32,
+    /// Failed logins from one IP address, after which the address is locked
+    pub max_failures_per_ip: u32,
+    /// Duration of the first lock, every further failure doubles it
+    pub lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+    /// Failed logins are forgotten, when there is no other failure within the window
+    pub failure_window_minutes: u64,
+}
+
+/// Network with the trusted proxies, e.g. `172.16.0.0/12`, or a single address
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(try_from = "String", into = "String")]
+pub struct TrustedNetwork {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl TrustedNetwork {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (self.address, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl TryFrom<String> for TrustedNetwork {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let invalid = || format!("Invalid network of trusted proxies: {value}");
+        let (address, prefix_len) = match value.split_once('/') {
+            Some((address, prefix_len)) => (address, Some(prefix_len)),
+            None => (value.as_str(), None),
+        };
+        let address: IpAddr = address.parse().map_err(|_| invalid())?;
+        let max_prefix_len = if address.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len.parse().map_err(|_| invalid())?,
+            None => max_prefix_len,
+        };
+        if prefix_len > max_prefix_len {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            address,
+            prefix_len,
+        })
+    }
+}
+
+impl From<TrustedNetwork> for String {
+    fn from(network: TrustedNetwork) -> Self {
+        format!("{}/{}", network.address, network.prefix_len)
+    }
+}
+
+/// Where the emails are delivered
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum MailTransportConfig {
+    /// SMTP server, e.g. MailHog on port 1025 during the development.
+    /// Credentials are read from `SMTP_USERNAME` and `SMTP_PASSWORD` env variables, if they are set.
+    Smtp { host: String, port: u16, tls: bool },
+    /// Emails are written to the directory as `.eml` files instead of being sent
+    File { directory: String },
+}
+
+#[cfg(test)]
+mod test {
+    use crate::configuration::models::{Configuration, EncoderProfile, Stream, TrustedNetwork};
+    use config::Config;
+    use validator::Validate;
+
+    fn create_profile(name: &str) -> EncoderProfile {
+        EncoderProfile {
+            name: name.to_string(),
+            width: 1280,
+            height: 720,
+            video_bitrate_kbps: 2048,
+            preset: String::from("veryfast"),
+            tune: Some(String::from("zerolatency")),
+            keyframe_interval: 60,
+            audio_bitrate_kbps: 128,
+            frame_rate: 30,
+        }
+    }
+
+    #[test]
+    fn validate_profile() {
+        assert!(create_profile("720").validate().is_ok());
+        assert!(EncoderProfile {
+            tune: None,
+            ..create_profile("720")
+        }
+        .validate()
+        .is_ok());
+
+        let invalid_profiles = [
+            create_profile("720p/hd"),
+            create_profile("1080"),
+            EncoderProfile {
+                width: 1279,
+                ..create_profile("720")
+            },
+            EncoderProfile {
+                preset: String::from("fastest"),
+                ..create_profile("720")
+            },
+            EncoderProfile {
+                tune: Some(String::from("film-grain")),
+                ..create_profile("720")
+            },
+            EncoderProfile {
+                frame_rate: 0,
+                ..create_profile("720")
+            },
+            EncoderProfile {
+                video_bitrate_kbps: 2048000,
+                ..create_profile("720")
+            },
+        ];
+        for profile in invalid_profiles {
+            assert!(
+                profile.validate().is_err(),
+                "{:?} should be invalid",
+                profile
+            );
+        }
+    }
+
+    #[test]
+    fn validate_unique_profile_names() {
+        let config = Config::builder()
+            .add_source(config::File::with_name("tests/test_data/test-config.yaml"))
+            .build()
+            .and_then(|config| config.try_deserialize::<Configuration>())
+            .expect("Test configuration should be loaded");
+        assert!(config.validate().is_ok());
+
+        let mut stream: Stream = config.app.stream;
+        stream.profiles.push(create_profile("720"));
+        assert!(stream.validate().is_err());
+
+        stream.profiles.clear();
+        assert!(stream.validate().is_err());
+    }
+
+    #[test]
+    fn trusted_network_contains() {
+        let network = TrustedNetwork::try_from(String::from("172.16.0.0/12")).unwrap();
+        assert!(network.contains("172.18.0.5".parse().unwrap()));
+        assert!(network.contains("::ffff:172.18.0.5".parse().unwrap()));
+        assert!(!network.contains("172.32.0.1".parse().unwrap()));
+        assert!(!network.contains("::1".parse().unwrap()));
+
+        let address = TrustedNetwork::try_from(String::from("127.0.0.1")).unwrap();
+        assert!(address.contains("127.0.0.1".parse().unwrap()));
+        assert!(!address.contains("127.0.0.2".parse().unwrap()));
+
+        let everything = TrustedNetwork::try_from(String::from("::/0")).unwrap();
+        assert!(everything.contains("2001:db8::1".parse().unwrap()));
+
+        assert!(TrustedNetwork::try_from(String::from("10.0.0.0/33")).is_err());
+        assert!(TrustedNetwork::try_from(String::from("nginx")).is_err());
+    }
 }
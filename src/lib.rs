@@ -1,10 +1,11 @@
 use crate::configuration::models::Configuration;
+use crate::persistence::repositories::session::UserSessionStore;
 use actix_identity::IdentityMiddleware;
 use actix_multipart::form::MultipartFormConfig;
 use actix_session::config::PersistentSession;
-use actix_session::storage::RedisSessionStore;
 use actix_session::SessionMiddleware;
 use actix_web::cookie::{Key, SameSite};
+use actix_web::web::PayloadConfig;
 use actix_web::HttpResponse;
 use config::Config;
 use deadpool_redis::Runtime;
@@ -13,11 +14,13 @@ use serde_qs::actix::QsQueryConfig;
 use std::env;
 use std::sync::Arc;
 use std::time::Duration;
+use validator::Validate;
 
 pub mod api;
 pub mod business;
 pub mod common;
 pub mod configuration;
+pub mod mailer;
 pub mod persistence;
 pub mod seed;
 pub mod streamer;
@@ -27,9 +30,11 @@ pub const CONFIG_FILE_KEY: &str = "CONFIG_FILE_PATH";
 const DEFAULT_VIDEO_DIRECTORY: &str = "./resources/videos";
 const DEFAULT_THUMBNAILS_PATH: &str = "./resources/thumbnails";
 const DEFAULT_PROFILE_PICTURE_DIRECTORY: &str = "./resources/images/users";
+const DEFAULT_HLS_DIRECTORY: &str = "./resources/hls";
 const VIDEOS_DIRECTORY_KEY: &str = "VIDEO_DIRECTORY_PATH";
 const THUMBNAIL_DIRECTORY_KEY: &str = "THUMBNAIL_DIRECTORY_PATH";
 const PROFILE_PICTURE_DIRECTORY_KEY: &str = "PROFILE_PICTURE_DIRECTORY_PATH";
+const HLS_DIRECTORY_KEY: &str = "HLS_DIRECTORY_PATH";
 
 const DEFAULT_TEMP_DIRECTORY: &str = "temp";
 const TEMP_DIRECTORY_KEY: &str = "TEMP_DIRECTORY_PATH";
@@ -41,6 +46,8 @@ pub fn init_configuration() -> anyhow::Result<Configuration> {
         .add_source(config::File::with_name(config_file.as_str()))
         .build()?;
     let config = config.try_deserialize::<Configuration>()?;
+    // Invalid encoder profiles would break the streams only once they start
+    config.validate()?;
 
     info!("Config {} was loaded!", config_file);
     Ok(config)
@@ -60,6 +67,11 @@ pub fn get_video_thumbnail_dirs() -> (String, String) {
     (video, thumbnail)
 }
 
+/// Returns path to the folder, where HLS renditions of the videos are stored.
+pub fn get_hls_directory_path() -> String {
+    dotenvy::var(HLS_DIRECTORY_KEY).unwrap_or(DEFAULT_HLS_DIRECTORY.to_string())
+}
+
 pub fn get_temp_directory_path() -> String {
     dotenvy::var(TEMP_DIRECTORY_KEY).unwrap_or(DEFAULT_TEMP_DIRECTORY.to_string())
 }
@@ -83,15 +95,15 @@ pub fn get_secret_key() -> Key {
 }
 
 pub fn setup_auth(
-    redis_store: &RedisSessionStore,
-) -> (IdentityMiddleware, SessionMiddleware<RedisSessionStore>) {
+    session_store: &UserSessionStore,
+) -> (IdentityMiddleware, SessionMiddleware<UserSessionStore>) {
     let cookie_expiration = Duration::from_secs(7 * 24 * 60 * 60); // 7 days
 
     let identity_middleware = IdentityMiddleware::builder()
         .visit_deadline(Some(cookie_expiration))
         .build();
 
-    let session_middleware = SessionMiddleware::builder(redis_store.clone(), get_secret_key())
+    let session_middleware = SessionMiddleware::builder(session_store.clone(), get_secret_key())
         .cookie_name("erotic-hub".to_string())
         .cookie_secure(false) // Use secure cookies (only HTTPS)
         .cookie_http_only(true) // Prevent JavaScript access
@@ -113,6 +125,14 @@ pub async fn setup_redis_pool() -> anyhow::Result<deadpool_redis::Pool> {
     Ok(pool)
 }
 
+/// Client for Redis connections, which can't be pooled, like pub/sub subscriptions
+pub fn setup_redis_client() -> anyhow::Result<deadpool_redis::redis::Client> {
+    let redis_url = env::var("REDIS_DATABASE_URL").expect("REDIS_DATABASE_URL must be set");
+    let client = deadpool_redis::redis::Client::open(redis_url)?;
+
+    Ok(client)
+}
+
 pub fn setup_multipart_config(config: Arc<Configuration>) -> MultipartFormConfig {
     let total_limit_mb = config.app.file_size_limit_mb * 1024 * 1024;
     MultipartFormConfig::default()
@@ -124,6 +144,12 @@ pub fn setup_multipart_config(config: Arc<Configuration>) -> MultipartFormConfig
         })
 }
 
+/// Limits size of the raw request body, which is used for chunks of the resumable uploads
+pub fn setup_payload_config(config: Arc<Configuration>) -> PayloadConfig {
+    let chunk_limit = config.app.upload_chunk_size_mb * 1024 * 1024;
+    PayloadConfig::new(chunk_limit as usize)
+}
+
 /// Setups non-strict mode of serde_qs, so the brackets can be encoded in name of parameters
 pub fn setup_qs_config() -> QsQueryConfig {
     QsQueryConfig::default().qs_config(serde_qs::Config::new(10, false))
@@ -0,0 +1,39 @@
+use crate::mailer::types::{build_message, Mail, MailerTrait};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use log::info;
+use std::path::PathBuf;
+
+/// Writes the emails to the directory instead of sending them,
+/// so they can be read during the development and in the tests
+pub struct FileMailer {
+    from: Mailbox,
+    directory: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(from: &str, directory: &str) -> anyhow::Result<Self> {
+        Ok(Self {
+            from: from.parse()?,
+            directory: PathBuf::from(directory),
+        })
+    }
+}
+
+#[async_trait]
+impl MailerTrait for FileMailer {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+        let message = build_message(&self.from, mail)?;
+        tokio::fs::create_dir_all(&self.directory).await?;
+
+        let path = self.directory.join(format!(
+            "{}-{}.eml",
+            chrono::Utc::now().timestamp_millis(),
+            uuid::Uuid::new_v4()
+        ));
+        tokio::fs::write(&path, message.formatted()).await?;
+        info!("Email was written to {}", path.display());
+
+        Ok(())
+    }
+}
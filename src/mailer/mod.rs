@@ -0,0 +1,35 @@
+use crate::configuration::models::{MailConfig, MailTransportConfig};
+use crate::mailer::file::FileMailer;
+use crate::mailer::smtp::SmtpMailer;
+use crate::mailer::types::MailerTrait;
+use std::sync::Arc;
+
+pub mod file;
+pub mod smtp;
+pub mod types;
+
+const SMTP_USERNAME_KEY: &str = "SMTP_USERNAME";
+const SMTP_PASSWORD_KEY: &str = "SMTP_PASSWORD";
+
+/// Creates the mailer with the transport selected in the configuration
+pub fn create_mailer(config: &MailConfig) -> anyhow::Result<Arc<dyn MailerTrait>> {
+    let mailer: Arc<dyn MailerTrait> = match &config.transport {
+        MailTransportConfig::Smtp { host, port, tls } => {
+            let credentials = dotenvy::var(SMTP_USERNAME_KEY)
+                .ok()
+                .zip(dotenvy::var(SMTP_PASSWORD_KEY).ok());
+            Arc::new(SmtpMailer::new(
+                &config.from,
+                host,
+                *port,
+                *tls,
+                credentials,
+            )?)
+        }
+        MailTransportConfig::File { directory } => {
+            Arc::new(FileMailer::new(&config.from, directory)?)
+        }
+    };
+
+    Ok(mailer)
+}
@@ -0,0 +1,47 @@
+use crate::mailer::types::{build_message, Mail, MailerTrait};
+use async_trait::async_trait;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+
+/// Sends the emails through the SMTP server
+pub struct SmtpMailer {
+    from: Mailbox,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl SmtpMailer {
+    /// Without `tls` the connection is not encrypted at all, which is meant
+    /// only for local servers like MailHog
+    pub fn new(
+        from: &str,
+        host: &str,
+        port: u16,
+        tls: bool,
+        credentials: Option<(String, String)>,
+    ) -> anyhow::Result<Self> {
+        let mut builder = match tls {
+            true => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            false => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        }
+        .port(port);
+        if let Some((username, password)) = credentials {
+            builder = builder.credentials(Credentials::new(username, password));
+        }
+
+        Ok(Self {
+            from: from.parse()?,
+            transport: builder.build(),
+        })
+    }
+}
+
+#[async_trait]
+impl MailerTrait for SmtpMailer {
+    async fn send(&self, mail: Mail) -> anyhow::Result<()> {
+        let message = build_message(&self.from, mail)?;
+        self.transport.send(message).await?;
+
+        Ok(())
+    }
+}
@@ -5,43 +5,69 @@ use actix_web_grants::GrantsMiddleware;
 use env_logger::Env;
 use erotic_hub::api::extractors::permissions_extractor::extract;
 use erotic_hub::api::routes::admin::admin_routes;
+use erotic_hub::api::routes::artist::artist_routes;
 use erotic_hub::api::routes::comment::comment_routes;
+use erotic_hub::api::routes::feed::feed_routes;
 use erotic_hub::api::routes::membership::membership_routes;
+use erotic_hub::api::routes::playlist::playlist_routes;
 use erotic_hub::api::routes::stream::stream_routes;
 use erotic_hub::api::routes::temp_file::temp_file_routes;
 use erotic_hub::api::routes::user::user_routes;
 use erotic_hub::api::routes::video::video_routes;
 use erotic_hub::business::facades::artist::ArtistFacade;
 use erotic_hub::business::facades::comment::CommentFacade;
+use erotic_hub::business::facades::feed::FeedFacade;
+use erotic_hub::business::facades::login_attempt::LoginAttemptFacade;
 use erotic_hub::business::facades::membership::MembershipFacade;
-use erotic_hub::business::facades::stream::StreamFacade;
+use erotic_hub::business::facades::playlist::PlaylistFacade;
+use erotic_hub::business::facades::stream::{StreamFacade, StreamFacadeTrait};
+use erotic_hub::business::facades::stream_chat::StreamChatFacade;
+use erotic_hub::business::facades::stream_telemetry::StreamTelemetryFacade;
 use erotic_hub::business::facades::temp_file::{TempFileFacade, TempFileFacadeTrait};
+use erotic_hub::business::facades::two_factor::TwoFactorFacade;
 use erotic_hub::business::facades::user::UserFacade;
 use erotic_hub::business::facades::video::VideoFacade;
 use erotic_hub::business::facades::video_category::VideoCategoryFacade;
+use erotic_hub::business::facades::view::{ViewFacade, ViewFacadeTrait};
+use erotic_hub::business::facades::watch_history::WatchHistoryFacade;
 use erotic_hub::business::models::stream::StreamStorage;
+use erotic_hub::mailer::create_mailer;
 use erotic_hub::persistence::repositories::artist::ArtistRepository;
 use erotic_hub::persistence::repositories::comment::CommentRepository;
 use erotic_hub::persistence::repositories::deal::PostgresDealRepo;
+use erotic_hub::persistence::repositories::feed::PgFeedRepo;
+use erotic_hub::persistence::repositories::login_attempt::RedisLoginAttemptRepo;
 use erotic_hub::persistence::repositories::paying_member::PostgresPayingMemberRepo;
 use erotic_hub::persistence::repositories::payment_method::PostgresPaymentMethodRepo;
+use erotic_hub::persistence::repositories::playlist::PgPlaylistRepo;
+use erotic_hub::persistence::repositories::session::{RedisUserSessionRepo, UserSessionStore};
 use erotic_hub::persistence::repositories::stream::PgStreamRepo;
+use erotic_hub::persistence::repositories::stream_chat::{PgStreamChatRepo, RedisChatBroker};
+use erotic_hub::persistence::repositories::stream_telemetry::{
+    PgStreamTelemetryRepo, RedisViewerTracker,
+};
 use erotic_hub::persistence::repositories::temp_file::PgTempFileRepo;
+use erotic_hub::persistence::repositories::two_factor::PgTwoFactorRepo;
 use erotic_hub::persistence::repositories::unit_of_work::PostgresUnitOfWork;
 use erotic_hub::persistence::repositories::user::UserRepository;
 use erotic_hub::persistence::repositories::video::PgVideoRepo;
 use erotic_hub::persistence::repositories::video_category::VideoCategoryRepository;
+use erotic_hub::persistence::repositories::view::{PgViewRepo, RedisViewBuffer};
+use erotic_hub::persistence::repositories::watch_history::PgWatchHistoryRepo;
 use erotic_hub::seed::{create_admin, seed_database};
 use erotic_hub::streamer::gstreamer_controller::init_gstreamer;
 use erotic_hub::{
-    get_profile_picture_folder_path, get_temp_directory_path, get_video_thumbnail_dirs,
-    init_configuration, setup_auth, setup_multipart_config, setup_qs_config, setup_redis_pool,
+    get_hls_directory_path, get_profile_picture_folder_path, get_secret_key,
+    get_temp_directory_path, get_video_thumbnail_dirs, init_configuration, setup_auth,
+    setup_multipart_config, setup_payload_config, setup_qs_config, setup_redis_client,
+    setup_redis_pool,
 };
-use log::warn;
+use log::{error, warn};
 use sqlx::postgres::PgPoolOptions;
 use sqlx::{Pool, Postgres};
 use std::env;
 use std::sync::Arc;
+use std::time::Duration;
 
 static EH_HOST_KEY: &str = "EH_HOST";
 static EH_PORT_KEY: &str = "EH_PORT";
@@ -70,18 +96,36 @@ async fn main() -> anyhow::Result<()> {
 
     let redis_pool = setup_redis_pool().await?;
 
-    let redis_store = RedisSessionStore::new_pooled(redis_pool).await?;
+    let user_session_repo = RedisUserSessionRepo::new(redis_pool.clone(), "");
+    let session_store = UserSessionStore::new(
+        RedisSessionStore::new_pooled(redis_pool.clone()).await?,
+        user_session_repo.clone(),
+    );
 
     let unit_of_work = Arc::new(PostgresUnitOfWork::new(pool.clone()));
     let stream_storage = Arc::new(StreamStorage::default());
     let user_repo = Arc::new(UserRepository::new(pool.clone()));
-    let user_facade = Arc::new(UserFacade::new(user_repo));
+    let mailer = create_mailer(&config.app.mail).expect("Failed to create the mailer");
+    let user_facade = Arc::new(UserFacade::new(
+        user_repo.clone(),
+        Arc::new(user_session_repo),
+        mailer,
+        config.clone(),
+        get_secret_key().signing().to_vec(),
+    ));
 
     let profile_picture_folders_path = get_profile_picture_folder_path();
     UserFacade::create_profile_picture_folders(profile_picture_folders_path)
         .await
         .expect("Failed to create profile picture folders");
 
+    let two_factor_repo = Arc::new(PgTwoFactorRepo::new(pool.clone()));
+    let two_factor_facade = Arc::new(TwoFactorFacade::new(two_factor_repo, user_repo));
+
+    let login_attempt_repo = Arc::new(RedisLoginAttemptRepo::new(redis_pool.clone(), ""));
+    let login_attempt_facade =
+        Arc::new(LoginAttemptFacade::new(login_attempt_repo, config.clone()));
+
     let artist_repo = Arc::new(ArtistRepository::new(pool.clone()));
     let artist_facade = Arc::new(ArtistFacade::new(artist_repo));
 
@@ -94,7 +138,12 @@ async fn main() -> anyhow::Result<()> {
     let temp_file_repo = Arc::new(PgTempFileRepo::new(pool.clone()));
 
     let temp_dir_path = get_temp_directory_path();
-    let temp_file_facade = Arc::new(TempFileFacade::new(temp_file_repo, temp_dir_path.clone()));
+    let temp_file_facade = Arc::new(TempFileFacade::new(
+        temp_file_repo,
+        unit_of_work.clone(),
+        temp_dir_path.clone(),
+        None,
+    ));
 
     temp_file_facade
         .delete_all_temp_files()
@@ -103,9 +152,14 @@ async fn main() -> anyhow::Result<()> {
     TempFileFacade::create_temp_directory(temp_dir_path)
         .await
         .expect("Failed to create temp directory");
+    TempFileFacade::start_expiring_uploads(
+        temp_file_facade.clone(),
+        Duration::from_secs(config.app.upload_expiry_hours * 60 * 60),
+    );
 
     let video_repo = Arc::new(PgVideoRepo::new(pool.clone()));
     let (video_dir, thumbnail_dir) = get_video_thumbnail_dirs();
+    let hls_dir = get_hls_directory_path();
     let video_facade = Arc::new(VideoFacade::new(
         temp_file_facade.clone(),
         video_repo,
@@ -115,21 +169,80 @@ async fn main() -> anyhow::Result<()> {
         unit_of_work.clone(),
         video_dir.clone(),
         thumbnail_dir.clone(),
+        hls_dir.clone(),
+        None,
+        config.clone(),
     ));
 
-    VideoFacade::create_dirs(video_dir, thumbnail_dir)
+    VideoFacade::create_dirs(video_dir, thumbnail_dir, hls_dir)
         .await
         .expect("Failed to create video folder");
 
     let stream_repo = Arc::new(PgStreamRepo::new(pool.clone()));
     let stream_facade = Arc::new(StreamFacade::new(
         video_facade.clone(),
+        artist_facade.clone(),
         stream_storage.clone(),
         stream_repo.clone(),
         None,
         config.clone(),
     ));
 
+    if let Err(err) = stream_facade.reconcile_streams().await {
+        error!("Failed to reconcile the orphaned streams, {:?}", err);
+    }
+    StreamFacade::start_scheduler(stream_facade.clone());
+    let shutdown_stream_facade = stream_facade.clone();
+
+    let chat_repo = Arc::new(PgStreamChatRepo::new(pool.clone()));
+    let chat_broker = Arc::new(RedisChatBroker::new(
+        redis_pool.clone(),
+        setup_redis_client()?,
+        "",
+    ));
+    let stream_chat_facade = Arc::new(StreamChatFacade::new(
+        stream_facade.clone(),
+        video_facade.clone(),
+        chat_repo,
+        chat_broker,
+        config.clone(),
+    ));
+
+    let telemetry_repo = Arc::new(PgStreamTelemetryRepo::new(pool.clone()));
+    let viewer_tracker = Arc::new(RedisViewerTracker::new(redis_pool.clone(), ""));
+    let stream_telemetry_facade = Arc::new(StreamTelemetryFacade::new(
+        stream_facade.clone(),
+        video_facade.clone(),
+        telemetry_repo,
+        viewer_tracker,
+        stream_storage.clone(),
+        config.clone(),
+    ));
+    StreamTelemetryFacade::start_recording(stream_telemetry_facade.clone());
+
+    let view_repo = Arc::new(PgViewRepo::new(pool.clone()));
+    let view_buffer = Arc::new(RedisViewBuffer::new(redis_pool, ""));
+    let view_facade = Arc::new(ViewFacade::new(
+        view_repo,
+        view_buffer,
+        video_facade.clone(),
+        config.clone(),
+    ));
+    ViewFacade::start_flushing(view_facade.clone());
+    let shutdown_view_facade = view_facade.clone();
+
+    let watch_history_repo = Arc::new(PgWatchHistoryRepo::new(pool.clone()));
+    let watch_history_facade = Arc::new(WatchHistoryFacade::new(
+        watch_history_repo,
+        video_facade.clone(),
+    ));
+
+    let playlist_repo = Arc::new(PgPlaylistRepo::new(pool.clone()));
+    let playlist_facade = Arc::new(PlaylistFacade::new(playlist_repo, video_facade.clone()));
+
+    let feed_repo = Arc::new(PgFeedRepo::new(pool.clone()));
+    let feed_facade = Arc::new(FeedFacade::new(feed_repo, video_facade.clone()));
+
     let paying_member_repo = Arc::new(PostgresPayingMemberRepo::new(pool.clone()));
     let payment_method_repo = Arc::new(PostgresPaymentMethodRepo::new(pool.clone()));
     let deal_repo = Arc::new(PostgresDealRepo::new(pool.clone()));
@@ -164,7 +277,7 @@ async fn main() -> anyhow::Result<()> {
         .expect("The port must be a number!");
 
     HttpServer::new(move || {
-        let (identity_middleware, session_middleware) = setup_auth(&redis_store);
+        let (identity_middleware, session_middleware) = setup_auth(&session_store);
 
         App::new()
             .service(actix_files::Files::new("/static", "./static"))
@@ -180,8 +293,12 @@ async fn main() -> anyhow::Result<()> {
             .app_data(web::Data::from(config.clone()))
             .app_data(web::Data::from(stream_storage.clone()))
             .app_data(web::Data::from(stream_facade.clone()))
+            .app_data(web::Data::from(stream_chat_facade.clone()))
+            .app_data(web::Data::from(stream_telemetry_facade.clone()))
             .app_data(web::Data::from(stream_storage.clone()))
             .app_data(web::Data::from(user_facade.clone()))
+            .app_data(web::Data::from(two_factor_facade.clone()))
+            .app_data(web::Data::from(login_attempt_facade.clone()))
             .app_data(web::Data::from(temp_file_facade.clone()))
             .app_data(web::Data::from(user_facade.clone()))
             .app_data(web::Data::from(temp_file_facade.clone()))
@@ -190,7 +307,12 @@ async fn main() -> anyhow::Result<()> {
             .app_data(web::Data::from(comment_facade.clone()))
             .app_data(web::Data::from(video_category_facade.clone()))
             .app_data(web::Data::from(membership_facade.clone()))
+            .app_data(web::Data::from(view_facade.clone()))
+            .app_data(web::Data::from(watch_history_facade.clone()))
+            .app_data(web::Data::from(playlist_facade.clone()))
+            .app_data(web::Data::from(feed_facade.clone()))
             .app_data(setup_multipart_config(config.clone()))
+            .app_data(setup_payload_config(config.clone()))
             .app_data(setup_qs_config())
             .configure(video_routes)
             .configure(comment_routes)
@@ -199,10 +321,22 @@ async fn main() -> anyhow::Result<()> {
             .configure(stream_routes)
             .configure(membership_routes)
             .configure(admin_routes)
+            .configure(playlist_routes)
+            .configure(artist_routes)
+            .configure(feed_routes)
     })
     .bind((host, port))?
     .run()
     .await?;
 
+    // Recordings of the running streams would be unfinished otherwise
+    if let Err(err) = shutdown_stream_facade.shutdown_streams().await {
+        error!("Failed to end the running streams, {:?}", err);
+    }
+    // Views buffered since the last flush would be lost otherwise
+    if let Err(err) = shutdown_view_facade.flush_views().await {
+        error!("Failed to write the buffered views, {:?}", err);
+    }
+
     Ok(())
 }
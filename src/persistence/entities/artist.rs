@@ -6,3 +6,13 @@ pub struct Artist {
     pub user_id: i32,
     pub description: Option<String>,
 }
+
+/// Artist together with the public data of the user
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct ArtistProfile {
+    pub id: i32,
+    pub user_id: i32,
+    pub description: Option<String>,
+    pub name: String,
+    pub profile_picture_path: Option<String>,
+}
@@ -0,0 +1,12 @@
+use crate::persistence::entities::video::Video;
+use chrono::{DateTime, Utc};
+
+/// New video or live stream of the followed artist
+#[derive(sqlx::FromRow)]
+pub struct FeedEntry {
+    #[sqlx(flatten)]
+    pub video: Video,
+    /// ID of the live stream, `None` if the entry is an uploaded video
+    pub stream_id: Option<i32>,
+    pub published_at: DateTime<Utc>,
+}
@@ -0,0 +1,13 @@
+use chrono::{DateTime, Utc};
+
+/// Who tried to log in, failed logins are counted separately for both
+pub enum LoginSubject<'a> {
+    Username(&'a str),
+    IpAddress(&'a str),
+}
+
+pub struct LockedAccount {
+    pub username: String,
+    pub failed_attempts: u32,
+    pub locked_until: DateTime<Utc>,
+}
@@ -0,0 +1,39 @@
+use crate::persistence::entities::video::Video;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(type_name = "playlist_visibility", rename_all = "UPPERCASE")]
+pub enum PlaylistVisibility {
+    /// Only the owner can see the playlist
+    Private,
+    /// Anyone with the link can see the playlist
+    Unlisted,
+    /// The playlist is listed on the profile of the owner
+    Public,
+}
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct Playlist {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub description: Option<String>,
+    pub visibility: PlaylistVisibility,
+}
+
+/// Playlist together with information displayed in the list of the playlists
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct PlaylistOverview {
+    #[sqlx(flatten)]
+    pub playlist: Playlist,
+    pub video_count: i64,
+    /// ID of the first video, its thumbnail is used as a cover of the playlist
+    pub first_video_id: Option<i32>,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct PlaylistVideo {
+    #[sqlx(flatten)]
+    pub video: Video,
+    pub position: i32,
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Login details kept in the session state
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SessionInfo {
+    /// Public ID of the session, the session key itself must stay secret
+    pub id: String,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl SessionInfo {
+    pub fn new(user_agent: Option<String>, ip_address: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4().to_string(),
+            user_agent,
+            ip_address,
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Session in the index of the sessions of the user
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserSession {
+    #[serde(flatten)]
+    pub info: SessionInfo,
+    pub last_seen_at: DateTime<Utc>,
+}
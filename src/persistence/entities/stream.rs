@@ -1,3 +1,4 @@
+use crate::persistence::entities::video::Video;
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
@@ -11,6 +12,9 @@ pub struct LiveStream {
     pub video_id: i32,
     pub start_time: DateTime<Local>,
     pub status: LiveStreamStatus,
+    pub source: LiveStreamSource,
+    /// Draft video with the recording of the stream, once the stream ends
+    pub recording_video_id: Option<i32>,
 }
 
 #[derive(Clone, Debug, PartialEq, PartialOrd, Deserialize, Serialize, sqlx::Type)]
@@ -21,3 +25,31 @@ pub enum LiveStreamStatus {
     Running,
     Ended,
 }
+
+/// Where the streamed content comes from
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, sqlx::Type)]
+#[sqlx(type_name = "live_stream_source", rename_all = "UPPERCASE")]
+pub enum LiveStreamSource {
+    /// Uploaded video is re-broadcasted
+    File,
+    /// Artist sends the stream from his encoder, e.g. OBS
+    Rtmp,
+}
+
+/// Stream, which the user asked to be notified about
+#[derive(sqlx::FromRow, Clone, Debug, PartialEq)]
+pub struct RemindedStream {
+    pub stream_id: i32,
+    pub video_name: String,
+    pub start_time: DateTime<Local>,
+}
+
+/// Stream together with its video, shown in the directory of the streams
+#[derive(sqlx::FromRow)]
+pub struct ListedStream {
+    #[sqlx(flatten)]
+    pub video: Video,
+    pub stream_id: i32,
+    pub start_time: DateTime<Local>,
+    pub source: LiveStreamSource,
+}
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(sqlx::FromRow, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ChatMessage {
+    pub id: i32,
+    pub stream_id: i32,
+    pub user_id: i32,
+    /// Username of the author
+    pub username: String,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Change of the chat, which is delivered to all viewers of the stream
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ChatEvent {
+    Message(ChatMessage),
+    /// The message was removed by the owner of the stream
+    Delete {
+        message_id: i32,
+    },
+    /// The user can't send messages until the timeout expires
+    Timeout {
+        user_id: i32,
+        seconds: u64,
+    },
+}
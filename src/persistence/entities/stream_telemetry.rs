@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+
+/// Health of one pipeline of the stream at the given time
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct StreamHealthSample {
+    pub id: i32,
+    pub stream_id: i32,
+    pub resolution: String,
+    pub bitrate_kbps: i32,
+    pub dropped_frames: i64,
+    pub qos_events: i32,
+    pub buffering_percent: Option<i32>,
+    pub warnings: i32,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Health of one pipeline over the whole stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamHealthSummary {
+    pub resolution: String,
+    pub avg_bitrate_kbps: i32,
+    pub min_bitrate_kbps: i32,
+    pub dropped_frames: i64,
+    pub qos_events: i32,
+    pub warnings: i32,
+    pub sample_count: i64,
+}
@@ -5,3 +5,14 @@ pub struct TempFile {
     pub user_id: i32,
     pub file_path: String,
 }
+
+/// File which is being uploaded in chunks. Once the whole file is received,
+/// it's converted into the `TempFile`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TempUpload {
+    pub id: i32,
+    pub user_id: i32,
+    pub file_path: String,
+    pub upload_length: i64,
+    pub upload_offset: i64,
+}
@@ -0,0 +1,11 @@
+use chrono::{DateTime, Utc};
+
+#[derive(sqlx::FromRow, Debug, Clone, PartialEq)]
+pub struct UserTotp {
+    pub user_id: i32,
+    /// Base32 encoded secret shared with the authenticator app
+    pub secret: String,
+    /// `None` until the user confirms the secret by a valid code
+    pub enabled_at: Option<DateTime<Utc>>,
+    pub last_used_step: i64,
+}
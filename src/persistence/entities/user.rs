@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -10,6 +11,8 @@ pub struct User {
     pub artist_id: Option<i32>,
     pub paying_member_id: Option<i32>,
     pub is_admin: bool,
+    /// `None` until the user opens the verification link sent to the email
+    pub email_verified_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
@@ -23,3 +26,9 @@ pub struct LikedVideo {
     pub user_id: i32,
     pub video_id: i32,
 }
+
+#[derive(Clone, Debug, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PasswordResetToken {
+    pub user_id: i32,
+    pub expires_at: DateTime<Utc>,
+}
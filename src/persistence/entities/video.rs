@@ -1,5 +1,10 @@
 use serde::{Deserialize, Serialize};
 
+/// Marks beginning of the matched word in highlights returned by the search
+pub const HIGHLIGHT_START: char = '\u{2}';
+/// Marks end of the matched word in highlights returned by the search
+pub const HIGHLIGHT_END: char = '\u{3}';
+
 #[derive(sqlx::FromRow)]
 #[cfg_attr(test, derive(Debug, PartialEq, Clone))]
 pub struct Video {
@@ -10,6 +15,15 @@ pub struct Video {
     pub file_path: String,
     pub thumbnail_path: String,
     pub description: Option<String>,
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub frame_rate: Option<f64>,
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    /// Drafts are visible only to their artist until they are published
+    pub draft: bool,
 }
 
 pub struct PatchVideo {
@@ -20,6 +34,19 @@ pub struct PatchVideo {
     pub file_path: Option<String>,
     pub thumbnail_path: Option<String>,
     pub description: Option<String>,
+    pub metadata: Option<VideoMetadata>,
+}
+
+/// Technical metadata of the video file
+#[derive(Default, Clone)]
+pub struct VideoMetadata {
+    pub duration_ms: Option<i64>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub frame_rate: Option<f64>,
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Deserialize, Serialize, sqlx::Type)]
@@ -29,3 +56,34 @@ pub enum VideoVisibility {
     Registered,
     Paying,
 }
+
+/// Videos, which the user is allowed to watch
+pub struct VideoAccess {
+    /// Published videos with these visibilities can be watched
+    pub visibilities: Vec<VideoVisibility>,
+    /// All videos of this artist, including the drafts, can be watched
+    pub owner_artist_id: Option<i32>,
+}
+
+/// Parameters of the full-text search
+pub struct VideoSearch {
+    pub query: String,
+    pub category_ids: Option<Vec<i32>>,
+    /// Videos with these visibilities are returned
+    pub visibilities: Vec<VideoVisibility>,
+    /// Videos of this artist are returned regardless of their visibility
+    pub owner_artist_id: Option<i32>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(sqlx::FromRow)]
+pub struct VideoSearchHit {
+    #[sqlx(flatten)]
+    pub video: Video,
+    pub rank: f32,
+    /// Name of the video with matched words wrapped in `HIGHLIGHT_START` and `HIGHLIGHT_END`
+    pub name_highlight: String,
+    /// Fragments of the description with matched words wrapped in the highlight markers
+    pub description_snippet: Option<String>,
+}
@@ -0,0 +1,22 @@
+use chrono::NaiveDate;
+
+/// Views of the video on the given day, which were not written to the database yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingViews {
+    pub video_id: i32,
+    pub day: NaiveDate,
+    pub view_count: i64,
+}
+
+/// Views taken from the buffer by the flush, which holds the lock of the buffer
+#[derive(Debug, Clone, PartialEq)]
+pub struct TakenViews {
+    pub lock_token: String,
+    pub views: Vec<PendingViews>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyViews {
+    pub day: NaiveDate,
+    pub view_count: i64,
+}
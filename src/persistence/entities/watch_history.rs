@@ -0,0 +1,11 @@
+use crate::persistence::entities::video::Video;
+use chrono::{DateTime, Utc};
+
+/// Video watched by the user together with the last position of the playback
+#[derive(sqlx::FromRow)]
+pub struct WatchedVideo {
+    #[sqlx(flatten)]
+    pub video: Video,
+    pub position_ms: i64,
+    pub watched_at: DateTime<Utc>,
+}
@@ -1,5 +1,6 @@
 use crate::persistence::entities::error::DatabaseError;
 
+pub mod redis_store;
 pub mod repositories;
 
 pub mod entities;
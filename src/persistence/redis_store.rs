@@ -0,0 +1,53 @@
+use crate::persistence::entities::error::MapToDatabaseError;
+use crate::persistence::Result;
+use deadpool_redis::redis::AsyncCommands;
+use deadpool_redis::{Connection, Pool};
+use std::time::Duration;
+
+/// Pool of Redis connections together with the prefix of the keys,
+/// shared by the repositories, which keep their data in Redis
+#[derive(Clone)]
+pub struct RedisStore {
+    redis_pool: Pool,
+    key_prefix: String,
+}
+
+impl RedisStore {
+    /// # Arguments
+    ///
+    /// * `key_prefix` - prefix of all keys, so more instances can share the same Redis
+    pub fn new(redis_pool: Pool, key_prefix: impl Into<String>) -> Self {
+        Self {
+            redis_pool,
+            key_prefix: key_prefix.into(),
+        }
+    }
+
+    /// Prepends the prefix to the key
+    pub fn key(&self, key: &str) -> String {
+        format!("{}{}", self.key_prefix, key)
+    }
+
+    pub async fn connection(&self) -> Result<Connection> {
+        self.redis_pool
+            .get()
+            .await
+            .db_error("Failed to connect to Redis")
+    }
+
+    /// Returns how long the key lives, `None` if it doesn't exist or doesn't expire
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - key with the prefix
+    pub async fn ttl(&self, key: &str) -> Result<Option<Duration>> {
+        let mut conn = self.connection().await?;
+        let ttl: i64 = conn
+            .ttl(key)
+            .await
+            .db_error("Failed to read the expiration of the key")?;
+
+        // Negative TTL means the key doesn't exist or has no expiration
+        Ok((ttl > 0).then(|| Duration::from_secs(ttl as u64)))
+    }
+}
@@ -1,5 +1,5 @@
 use crate::business::models::artist_detail::ArtistName;
-use crate::persistence::entities::artist::Artist;
+use crate::persistence::entities::artist::{Artist, ArtistProfile};
 use crate::persistence::entities::error::MapToDatabaseError;
 use crate::persistence::Result;
 use async_trait::async_trait;
@@ -17,6 +17,16 @@ pub trait ArtistRepoTrait: Debug {
         tx: Option<&mut Transaction<'_, Postgres>>,
     ) -> Result<Artist>;
     async fn make_user_artist(&self, user_id: i32) -> Result<()>;
+    /// # Returns
+    /// `false` if the user already follows the artist
+    async fn follow_artist(&self, user_id: i32, artist_id: i32) -> Result<bool>;
+    /// # Returns
+    /// `false` if the user didn't follow the artist
+    async fn unfollow_artist(&self, user_id: i32, artist_id: i32) -> Result<bool>;
+    async fn is_following(&self, user_id: i32, artist_id: i32) -> Result<bool>;
+    async fn get_follower_count(&self, artist_id: i32) -> Result<i64>;
+    async fn get_artist_profile(&self, artist_id: i32) -> Result<Option<ArtistProfile>>;
+    async fn update_description(&self, artist_id: i32, description: Option<String>) -> Result<()>;
 }
 
 #[derive(Debug, Clone)]
@@ -114,4 +124,133 @@ impl ArtistRepoTrait for ArtistRepository {
 
         Ok(())
     }
+
+    async fn follow_artist(&self, user_id: i32, artist_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"INSERT INTO artist_follow (user_id, artist_id) VALUES ($1, $2)
+            ON CONFLICT (user_id, artist_id) DO NOTHING"#,
+            user_id,
+            artist_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn unfollow_artist(&self, user_id: i32, artist_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM artist_follow WHERE user_id = $1 AND artist_id = $2",
+            user_id,
+            artist_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn is_following(&self, user_id: i32, artist_id: i32) -> Result<bool> {
+        let record = sqlx::query!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM artist_follow WHERE user_id = $1 AND artist_id = $2
+            ) AS "following!""#,
+            user_id,
+            artist_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(record.following)
+    }
+
+    async fn get_follower_count(&self, artist_id: i32) -> Result<i64> {
+        let record = sqlx::query!(
+            r#"SELECT COUNT(*) AS "count!" FROM artist_follow WHERE artist_id = $1"#,
+            artist_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(record.count)
+    }
+
+    async fn get_artist_profile(&self, artist_id: i32) -> Result<Option<ArtistProfile>> {
+        let profile = sqlx::query_as!(
+            ArtistProfile,
+            r#"SELECT artist.id, artist.user_id, artist.description,
+                user_table.username AS name, user_table.profile_picture_path
+            FROM artist JOIN user_table ON user_table.id = artist.user_id
+            WHERE artist.id = $1"#,
+            artist_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(profile)
+    }
+
+    async fn update_description(&self, artist_id: i32, description: Option<String>) -> Result<()> {
+        sqlx::query!(
+            "UPDATE artist SET description = $1 WHERE id = $2",
+            description,
+            artist_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn follow_artist(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = ArtistRepository::new(ctx.pg_pool.clone());
+
+        assert!(repo.follow_artist(2, 1).await?);
+        assert!(
+            !repo.follow_artist(2, 1).await?,
+            "Artist was followed twice"
+        );
+        assert!(repo.follow_artist(4, 1).await?);
+        assert!(repo.is_following(2, 1).await?);
+        assert!(!repo.is_following(2, 2).await?);
+        assert_eq!(repo.get_follower_count(1).await?, 2);
+
+        assert!(repo.unfollow_artist(2, 1).await?);
+        assert!(!repo.unfollow_artist(2, 1).await?);
+        assert!(!repo.is_following(2, 1).await?);
+        assert_eq!(repo.get_follower_count(1).await?, 1);
+        assert_eq!(repo.get_follower_count(2).await?, 0);
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn artist_profile(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = ArtistRepository::new(ctx.pg_pool.clone());
+
+        repo.update_description(1, Some(String::from("About me")))
+            .await?;
+        let profile = repo.get_artist_profile(1).await?.unwrap();
+        assert_eq!(profile.user_id, 1);
+        assert_eq!(profile.name, "JohnArtist");
+        assert_eq!(profile.description.as_deref(), Some("About me"));
+
+        repo.update_description(1, None).await?;
+        let profile = repo.get_artist_profile(1).await?.unwrap();
+        assert_eq!(profile.description, None);
+
+        assert!(repo.get_artist_profile(999).await?.is_none());
+
+        Ok(())
+    }
 }
@@ -0,0 +1,194 @@
+use crate::persistence::entities::feed::FeedEntry;
+use crate::persistence::entities::video::VideoAccess;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[async_trait]
+pub trait FeedRepo {
+    /// Returns videos and live streams of the artists followed by the user,
+    /// the most recently published first. Only live streams, which didn't end yet, are returned.
+    /// Videos, which the user can't watch, are skipped.
+    async fn list_feed(
+        &self,
+        user_id: i32,
+        access: &VideoAccess,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>>;
+}
+
+pub struct PgFeedRepo {
+    pg_pool: PgPool,
+}
+
+impl PgFeedRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl FeedRepo for PgFeedRepo {
+    async fn list_feed(
+        &self,
+        user_id: i32,
+        access: &VideoAccess,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<FeedEntry>> {
+        let feed = sqlx::query_as::<_, FeedEntry>(
+            r#"WITH followed_video AS (
+                SELECT video.*
+                FROM video
+                JOIN artist_follow ON artist_follow.artist_id = video.artist_id
+                WHERE artist_follow.user_id = $1 AND NOT video.draft
+                    AND (video.artist_id = $2 OR video.visibility = ANY($3))
+            )
+            SELECT
+                id,
+                artist_id,
+                visibility,
+                name,
+                file_path,
+                thumbnail_path,
+                description,
+                duration_ms,
+                width,
+                height,
+                frame_rate,
+                container,
+                video_codec,
+                audio_codec,
+                draft,
+                stream_id,
+                published_at
+            FROM (
+                SELECT followed_video.*, NULL::INTEGER AS stream_id, created_at AS published_at
+                FROM followed_video
+                UNION ALL
+                SELECT followed_video.*, live_stream.id AS stream_id, live_stream.start_time AS published_at
+                FROM followed_video
+                JOIN live_stream ON live_stream.video_id = followed_video.id
+                WHERE live_stream.status <> 'ENDED'
+            ) feed
+            ORDER BY published_at DESC, id DESC, stream_id DESC NULLS LAST
+            OFFSET $4 LIMIT $5"#,
+        )
+        .bind(user_id)
+        .bind(access.owner_artist_id)
+        .bind(&access.visibilities)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(feed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::persistence::entities::stream::{LiveStream, LiveStreamSource, LiveStreamStatus};
+    use crate::persistence::entities::video::{Video, VideoVisibility};
+    use crate::persistence::repositories::artist::{ArtistRepoTrait, ArtistRepository};
+    use crate::persistence::repositories::stream::{PgStreamRepo, StreamRepoTrait};
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use chrono::Local;
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn list_feed(ctx: &mut AsyncContext) -> Result<()> {
+        let followed_video = create_dummy_video(ctx, 1, VideoVisibility::All).await?;
+        let other_video = create_dummy_video(ctx, 2, VideoVisibility::All).await?;
+        let paying_video = create_dummy_video(ctx, 1, VideoVisibility::Paying).await?;
+        let newer_video = create_dummy_video(ctx, 1, VideoVisibility::All).await?;
+
+        let artist_repo = ArtistRepository::new(ctx.pg_pool.clone());
+        assert!(artist_repo.follow_artist(2, 1).await?);
+
+        let stream_repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let stream_id = add_stream(&stream_repo, followed_video.id).await?;
+        let ended_stream_id = add_stream(&stream_repo, newer_video.id).await?;
+        stream_repo
+            .change_status(ended_stream_id, LiveStreamStatus::Ended)
+            .await?;
+
+        let repo = PgFeedRepo::new(ctx.pg_pool.clone());
+        let access = VideoAccess {
+            visibilities: vec![VideoVisibility::All],
+            owner_artist_id: None,
+        };
+        let feed = repo.list_feed(2, &access, 0, 10).await?;
+        let entries: Vec<(i32, Option<i32>)> = feed
+            .iter()
+            .map(|entry| (entry.video.id, entry.stream_id))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (followed_video.id, Some(stream_id)),
+                (newer_video.id, None),
+                (followed_video.id, None),
+            ]
+        );
+        assert!(!entries.contains(&(other_video.id, None)));
+        assert!(
+            !entries.contains(&(paying_video.id, None)),
+            "Video, which the user can't watch, is in the feed"
+        );
+
+        assert_eq!(repo.list_feed(2, &access, 1, 1).await?.len(), 1);
+        assert!(repo.list_feed(3, &access, 0, 10).await?.is_empty());
+
+        Ok(())
+    }
+
+    async fn add_stream(stream_repo: &PgStreamRepo, video_id: i32) -> Result<i32> {
+        stream_repo
+            .add_stream(LiveStream {
+                id: -1,
+                video_id,
+                start_time: Local::now(),
+                status: LiveStreamStatus::Pending,
+                source: LiveStreamSource::File,
+                recording_video_id: None,
+            })
+            .await
+    }
+
+    async fn create_dummy_video(
+        ctx: &AsyncContext,
+        artist_id: i32,
+        visibility: VideoVisibility,
+    ) -> Result<Video> {
+        let video = Video {
+            id: 1,
+            artist_id,
+            visibility,
+            name: String::from("Test video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+
+        let repo = PgVideoRepo::new(ctx.pg_pool.clone());
+
+        let mut tx = ctx.pg_pool.begin().await?;
+        let video = repo.save_video(video, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(video)
+    }
+}
@@ -0,0 +1,229 @@
+use crate::persistence::entities::error::MapToDatabaseError;
+use crate::persistence::entities::login_attempt::{LockedAccount, LoginSubject};
+use crate::persistence::redis_store::RedisStore;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_redis::redis::AsyncCommands;
+use deadpool_redis::{redis, Pool};
+use std::time::Duration;
+
+const FAILURES_KEY: &str = "login:failures";
+const LOCK_KEY: &str = "login:lock";
+/// Sorted set of the locked usernames, scored by the end of the lock
+const LOCKED_ACCOUNTS_KEY: &str = "login:locked";
+
+#[async_trait]
+pub trait LoginAttemptRepo {
+    /// Counts the failed login
+    ///
+    /// # Returns
+    /// Number of the failed logins, none of which was further apart than the window
+    async fn add_failure(&self, subject: &LoginSubject, window: Duration) -> Result<u32>;
+    async fn reset_failures(&self, subject: &LoginSubject) -> Result<()>;
+    async fn lock(&self, subject: &LoginSubject, duration: Duration) -> Result<()>;
+    /// Returns the remaining time of the lock, `None` if the subject is not locked
+    async fn get_lock(&self, subject: &LoginSubject) -> Result<Option<Duration>>;
+    async fn list_locked_accounts(&self) -> Result<Vec<LockedAccount>>;
+    /// Removes the lock and forgets the failed logins of the account
+    ///
+    /// # Returns
+    /// `true` if the account was locked
+    async fn unlock_account(&self, username: &str) -> Result<bool>;
+}
+
+pub struct RedisLoginAttemptRepo {
+    redis: RedisStore,
+}
+
+impl RedisLoginAttemptRepo {
+    pub fn new(redis_pool: Pool, key_prefix: impl Into<String>) -> Self {
+        Self {
+            redis: RedisStore::new(redis_pool, key_prefix),
+        }
+    }
+
+    fn key(&self, key: &str, subject: &LoginSubject) -> String {
+        match subject {
+            LoginSubject::Username(username) => self.redis.key(&format!("{key}:user:{username}")),
+            LoginSubject::IpAddress(ip_address) => {
+                self.redis.key(&format!("{key}:ip:{ip_address}"))
+            }
+        }
+    }
+
+    fn locked_accounts_key(&self) -> String {
+        self.redis.key(LOCKED_ACCOUNTS_KEY)
+    }
+}
+
+#[async_trait]
+impl LoginAttemptRepo for RedisLoginAttemptRepo {
+    async fn add_failure(&self, subject: &LoginSubject, window: Duration) -> Result<u32> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.key(FAILURES_KEY, subject);
+
+        let (count,): (u32,) = redis::pipe()
+            .atomic()
+            .incr(&key, 1)
+            .expire(&key, window.as_secs().max(1) as i64)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to count the failed login")?;
+
+        Ok(count)
+    }
+
+    async fn reset_failures(&self, subject: &LoginSubject) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let _: () = conn
+            .del(self.key(FAILURES_KEY, subject))
+            .await
+            .db_error("Failed to reset the failed logins")?;
+
+        Ok(())
+    }
+
+    async fn lock(&self, subject: &LoginSubject, duration: Duration) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let duration = duration.as_secs().max(1);
+
+        let mut pipe = redis::pipe();
+        pipe.atomic()
+            .set_ex(self.key(LOCK_KEY, subject), 1, duration)
+            .ignore();
+        if let LoginSubject::Username(username) = subject {
+            let locked_until = Utc::now().timestamp() + duration as i64;
+            pipe.zadd(self.locked_accounts_key(), *username, locked_until)
+                .ignore();
+        }
+        let _: () = pipe
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to lock the login")?;
+
+        Ok(())
+    }
+
+    async fn get_lock(&self, subject: &LoginSubject) -> Result<Option<Duration>> {
+        self.redis.ttl(&self.key(LOCK_KEY, subject)).await
+    }
+
+    async fn list_locked_accounts(&self) -> Result<Vec<LockedAccount>> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.locked_accounts_key();
+
+        let (locked,): (Vec<(String, i64)>,) = redis::pipe()
+            .atomic()
+            .zrembyscore(&key, "-inf", Utc::now().timestamp())
+            .ignore()
+            .zrange_withscores(&key, 0, -1)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to list the locked accounts")?;
+        if locked.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for (username, _) in &locked {
+            pipe.get(self.key(FAILURES_KEY, &LoginSubject::Username(username)));
+        }
+        let failures: Vec<Option<u32>> = pipe
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to list the locked accounts")?;
+
+        Ok(locked
+            .into_iter()
+            .zip(failures)
+            .map(
+                |((username, locked_until), failed_attempts)| LockedAccount {
+                    username,
+                    failed_attempts: failed_attempts.unwrap_or_default(),
+                    locked_until: DateTime::from_timestamp(locked_until, 0).unwrap_or_default(),
+                },
+            )
+            .collect())
+    }
+
+    async fn unlock_account(&self, username: &str) -> Result<bool> {
+        let mut conn = self.redis.connection().await?;
+        let subject = LoginSubject::Username(username);
+
+        let (locked,): (bool,) = redis::pipe()
+            .atomic()
+            .del(self.key(LOCK_KEY, &subject))
+            .del(self.key(FAILURES_KEY, &subject))
+            .ignore()
+            .zrem(self.locked_accounts_key(), username)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to unlock the account")?;
+
+        Ok(locked)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::setup_redis_pool;
+
+    async fn create_repo() -> RedisLoginAttemptRepo {
+        let redis_pool = setup_redis_pool().await.unwrap();
+
+        RedisLoginAttemptRepo::new(redis_pool, format!("{}:", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn count_failures() -> Result<()> {
+        let repo = create_repo().await;
+        let window = Duration::from_secs(60);
+        let user = LoginSubject::Username("john");
+        let ip_address = LoginSubject::IpAddress("127.0.0.1");
+
+        assert_eq!(repo.add_failure(&user, window).await?, 1);
+        assert_eq!(repo.add_failure(&user, window).await?, 2);
+        assert_eq!(repo.add_failure(&ip_address, window).await?, 1);
+
+        repo.reset_failures(&user).await?;
+        assert_eq!(repo.add_failure(&user, window).await?, 1);
+        assert_eq!(repo.add_failure(&ip_address, window).await?, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn lock_and_unlock_account() -> Result<()> {
+        let repo = create_repo().await;
+        let user = LoginSubject::Username("john");
+        let ip_address = LoginSubject::IpAddress("127.0.0.1");
+
+        repo.add_failure(&user, Duration::from_secs(60)).await?;
+        repo.lock(&user, Duration::from_secs(60)).await?;
+        repo.lock(&ip_address, Duration::from_secs(60)).await?;
+
+        assert!(repo.get_lock(&user).await?.is_some());
+        assert!(repo
+            .get_lock(&LoginSubject::Username("jane"))
+            .await?
+            .is_none());
+
+        // IP addresses are not accounts
+        let locked_accounts = repo.list_locked_accounts().await?;
+        assert_eq!(locked_accounts.len(), 1);
+        assert_eq!(locked_accounts[0].username, "john");
+        assert_eq!(locked_accounts[0].failed_attempts, 1);
+
+        assert!(repo.unlock_account("john").await?);
+        assert!(!repo.unlock_account("john").await?);
+        assert!(repo.get_lock(&user).await?.is_none());
+        assert!(repo.get_lock(&ip_address).await?.is_some());
+        assert!(repo.list_locked_accounts().await?.is_empty());
+
+        Ok(())
+    }
+}
@@ -1,11 +1,20 @@
 pub mod artist;
 pub mod comment;
 pub mod deal;
+pub mod feed;
+pub mod login_attempt;
 pub mod paying_member;
 pub mod payment_method;
+pub mod playlist;
+pub mod session;
 pub mod stream;
+pub mod stream_chat;
+pub mod stream_telemetry;
 pub mod temp_file;
+pub mod two_factor;
 pub mod unit_of_work;
 pub mod user;
 pub mod video;
 pub mod video_category;
+pub mod view;
+pub mod watch_history;
@@ -0,0 +1,449 @@
+use crate::persistence::entities::playlist::{
+    Playlist, PlaylistOverview, PlaylistVideo, PlaylistVisibility,
+};
+use crate::persistence::entities::video::VideoAccess;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[async_trait]
+pub trait PlaylistRepo {
+    async fn create_playlist(&self, playlist: Playlist) -> Result<Playlist>;
+    /// Updates name, description and visibility of the playlist owned by the user
+    ///
+    /// # Returns
+    /// `None` if the user doesn't own such playlist
+    async fn update_playlist(&self, playlist: Playlist) -> Result<Option<Playlist>>;
+    async fn delete_playlist(&self, playlist_id: i32, user_id: i32) -> Result<bool>;
+    async fn get_playlist(&self, playlist_id: i32) -> Result<Option<Playlist>>;
+    /// Returns playlists of the user with one of the given visibilities, the newest first.
+    /// Only the videos, which the viewer can watch, are counted.
+    async fn list_playlists(
+        &self,
+        user_id: i32,
+        visibilities: &[PlaylistVisibility],
+        access: &VideoAccess,
+    ) -> Result<Vec<PlaylistOverview>>;
+    /// Returns videos of the playlist in their order, which the viewer can watch
+    async fn get_playlist_videos(
+        &self,
+        playlist_id: i32,
+        access: &VideoAccess,
+    ) -> Result<Vec<PlaylistVideo>>;
+    /// Appends the video to the end of the playlist
+    ///
+    /// # Returns
+    /// `false` if the video is already in the playlist
+    async fn add_video(&self, playlist_id: i32, video_id: i32) -> Result<bool>;
+    async fn remove_video(&self, playlist_id: i32, video_id: i32) -> Result<bool>;
+    /// Moves the video to the given position, other videos are shifted.
+    /// Position out of the playlist moves the video to the start or the end.
+    ///
+    /// # Returns
+    /// `false` if the video is not in the playlist
+    async fn move_video(&self, playlist_id: i32, video_id: i32, position: i32) -> Result<bool>;
+}
+
+pub struct PgPlaylistRepo {
+    pg_pool: PgPool,
+}
+
+impl PgPlaylistRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl PlaylistRepo for PgPlaylistRepo {
+    async fn create_playlist(&self, playlist: Playlist) -> Result<Playlist> {
+        let playlist = sqlx::query_as!(
+            Playlist,
+            r#"INSERT INTO playlist (user_id, name, description, visibility)
+            VALUES ($1, $2, $3, $4)
+            RETURNING id, user_id, name, description, visibility AS "visibility: PlaylistVisibility""#,
+            playlist.user_id,
+            playlist.name,
+            playlist.description,
+            playlist.visibility as PlaylistVisibility
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(playlist)
+    }
+
+    async fn update_playlist(&self, playlist: Playlist) -> Result<Option<Playlist>> {
+        let playlist = sqlx::query_as!(
+            Playlist,
+            r#"UPDATE playlist SET name = $3, description = $4, visibility = $5
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, user_id, name, description, visibility AS "visibility: PlaylistVisibility""#,
+            playlist.id,
+            playlist.user_id,
+            playlist.name,
+            playlist.description,
+            playlist.visibility as PlaylistVisibility
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(playlist)
+    }
+
+    async fn delete_playlist(&self, playlist_id: i32, user_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM playlist WHERE id = $1 AND user_id = $2",
+            playlist_id,
+            user_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_playlist(&self, playlist_id: i32) -> Result<Option<Playlist>> {
+        let playlist = sqlx::query_as!(
+            Playlist,
+            r#"SELECT id, user_id, name, description, visibility AS "visibility: PlaylistVisibility"
+            FROM playlist WHERE id = $1"#,
+            playlist_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(playlist)
+    }
+
+    async fn list_playlists(
+        &self,
+        user_id: i32,
+        visibilities: &[PlaylistVisibility],
+        access: &VideoAccess,
+    ) -> Result<Vec<PlaylistOverview>> {
+        let playlists = sqlx::query_as::<_, PlaylistOverview>(
+            r#"SELECT
+                playlist.id,
+                playlist.user_id,
+                playlist.name,
+                playlist.description,
+                playlist.visibility,
+                COUNT(video.id) AS video_count,
+                (SELECT first_item.video_id FROM playlist_item first_item
+                 JOIN video ON video.id = first_item.video_id
+                 WHERE first_item.playlist_id = playlist.id
+                    AND (video.artist_id = $4 OR (NOT video.draft AND video.visibility = ANY($3)))
+                 ORDER BY first_item.position LIMIT 1) AS first_video_id
+            FROM playlist
+            LEFT JOIN playlist_item ON playlist_item.playlist_id = playlist.id
+            LEFT JOIN video ON video.id = playlist_item.video_id
+                AND (video.artist_id = $4 OR (NOT video.draft AND video.visibility = ANY($3)))
+            WHERE playlist.user_id = $1 AND playlist.visibility = ANY($2)
+            GROUP BY playlist.id
+            ORDER BY playlist.created_at DESC, playlist.id DESC"#,
+        )
+        .bind(user_id)
+        .bind(visibilities)
+        .bind(&access.visibilities)
+        .bind(access.owner_artist_id)
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(playlists)
+    }
+
+    async fn get_playlist_videos(
+        &self,
+        playlist_id: i32,
+        access: &VideoAccess,
+    ) -> Result<Vec<PlaylistVideo>> {
+        let videos = sqlx::query_as::<_, PlaylistVideo>(
+            r#"SELECT
+                video.id,
+                video.artist_id,
+                video.visibility,
+                video.name,
+                video.file_path,
+                video.thumbnail_path,
+                video.description,
+                video.duration_ms,
+                video.width,
+                video.height,
+                video.frame_rate,
+                video.container,
+                video.video_codec,
+                video.audio_codec,
+                video.draft,
+                playlist_item.position
+            FROM playlist_item
+            JOIN video ON video.id = playlist_item.video_id
+            WHERE playlist_item.playlist_id = $1
+                AND (video.artist_id = $2 OR (NOT video.draft AND video.visibility = ANY($3)))
+            ORDER BY playlist_item.position"#,
+        )
+        .bind(playlist_id)
+        .bind(access.owner_artist_id)
+        .bind(&access.visibilities)
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(videos)
+    }
+
+    async fn add_video(&self, playlist_id: i32, video_id: i32) -> Result<bool> {
+        let mut tx = self.pg_pool.begin().await?;
+        // Concurrent additions would get the same position otherwise
+        sqlx::query!(
+            "SELECT id FROM playlist WHERE id = $1 FOR UPDATE",
+            playlist_id
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        let result = sqlx::query!(
+            r#"INSERT INTO playlist_item (playlist_id, video_id, position)
+            SELECT $1, $2, COUNT(*) FROM playlist_item WHERE playlist_id = $1
+            ON CONFLICT (playlist_id, video_id) DO NOTHING"#,
+            playlist_id,
+            video_id
+        )
+        .execute(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn remove_video(&self, playlist_id: i32, video_id: i32) -> Result<bool> {
+        // Positions of the following videos are shifted by the trigger
+        let result = sqlx::query!(
+            "DELETE FROM playlist_item WHERE playlist_id = $1 AND video_id = $2",
+            playlist_id,
+            video_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn move_video(&self, playlist_id: i32, video_id: i32, position: i32) -> Result<bool> {
+        let mut tx = self.pg_pool.begin().await?;
+        sqlx::query!(
+            "SELECT id FROM playlist WHERE id = $1 FOR UPDATE",
+            playlist_id
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        let result = sqlx::query!(
+            r#"WITH moved AS (
+                SELECT position AS old_position FROM playlist_item
+                WHERE playlist_id = $1 AND video_id = $2
+            ), target AS (
+                SELECT LEAST(GREATEST($3, 0), COUNT(*) - 1)::INTEGER AS new_position
+                FROM playlist_item WHERE playlist_id = $1
+            )
+            UPDATE playlist_item SET position = CASE
+                WHEN video_id = $2 THEN new_position
+                WHEN new_position < old_position
+                    AND position >= new_position AND position < old_position THEN position + 1
+                WHEN new_position > old_position
+                    AND position <= new_position AND position > old_position THEN position - 1
+                ELSE position
+            END
+            FROM moved, target
+            WHERE playlist_id = $1"#,
+            playlist_id,
+            video_id,
+            position
+        )
+        .execute(tx.as_mut())
+        .await?;
+        tx.commit().await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::persistence::entities::video::{Video, VideoVisibility};
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn playlist_crud(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = PgPlaylistRepo::new(ctx.pg_pool.clone());
+
+        let playlist = repo.create_playlist(create_playlist_entity(2)).await?;
+        assert_eq!(
+            repo.get_playlist(playlist.id).await?,
+            Some(playlist.clone())
+        );
+
+        let updated = Playlist {
+            name: String::from("Updated"),
+            visibility: PlaylistVisibility::Public,
+            ..playlist.clone()
+        };
+        assert_eq!(
+            repo.update_playlist(updated.clone()).await?,
+            Some(updated.clone())
+        );
+        let foreign = Playlist {
+            user_id: 4,
+            ..updated.clone()
+        };
+        assert_eq!(
+            repo.update_playlist(foreign).await?,
+            None,
+            "Playlist of another user was updated"
+        );
+
+        let public = repo
+            .list_playlists(2, &[PlaylistVisibility::Public], &access_all())
+            .await?;
+        assert_eq!(public.len(), 1);
+        let private = repo
+            .list_playlists(2, &[PlaylistVisibility::Private], &access_all())
+            .await?;
+        assert!(private.is_empty());
+
+        assert!(!repo.delete_playlist(playlist.id, 4).await?);
+        assert!(repo.delete_playlist(playlist.id, 2).await?);
+        assert_eq!(repo.get_playlist(playlist.id).await?, None);
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn playlist_items(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = PgPlaylistRepo::new(ctx.pg_pool.clone());
+        let playlist = repo.create_playlist(create_playlist_entity(2)).await?;
+        let mut video_ids = Vec::new();
+        for _ in 0..4 {
+            let video_id = create_dummy_video(ctx, VideoVisibility::All).await?.id;
+            assert!(repo.add_video(playlist.id, video_id).await?);
+            video_ids.push(video_id);
+        }
+        assert!(
+            !repo.add_video(playlist.id, video_ids[0]).await?,
+            "Video was added to the playlist twice"
+        );
+        assert_eq!(get_order(&repo, playlist.id).await?, video_ids);
+
+        assert!(repo.move_video(playlist.id, video_ids[3], 1).await?);
+        assert_eq!(
+            get_order(&repo, playlist.id).await?,
+            vec![video_ids[0], video_ids[3], video_ids[1], video_ids[2]]
+        );
+
+        assert!(repo.move_video(playlist.id, video_ids[0], 100).await?);
+        assert_eq!(
+            get_order(&repo, playlist.id).await?,
+            vec![video_ids[3], video_ids[1], video_ids[2], video_ids[0]]
+        );
+
+        assert!(repo.remove_video(playlist.id, video_ids[1]).await?);
+        assert!(!repo.move_video(playlist.id, video_ids[1], 0).await?);
+        let videos = repo.get_playlist_videos(playlist.id, &access_all()).await?;
+        let positions: Vec<i32> = videos.iter().map(|video| video.position).collect();
+        assert_eq!(positions, vec![0, 1, 2], "Positions are not continuous");
+
+        let overview = repo
+            .list_playlists(2, &[PlaylistVisibility::Private], &access_all())
+            .await?;
+        assert_eq!(overview[0].video_count, 3);
+        assert_eq!(overview[0].first_video_id, Some(video_ids[3]));
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn playlist_video_access(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = PgPlaylistRepo::new(ctx.pg_pool.clone());
+        let playlist = repo.create_playlist(create_playlist_entity(2)).await?;
+        let paying_video = create_dummy_video(ctx, VideoVisibility::Paying).await?;
+        let video = create_dummy_video(ctx, VideoVisibility::All).await?;
+        repo.add_video(playlist.id, paying_video.id).await?;
+        repo.add_video(playlist.id, video.id).await?;
+        let access = VideoAccess {
+            visibilities: vec![VideoVisibility::All],
+            owner_artist_id: None,
+        };
+
+        let videos = repo.get_playlist_videos(playlist.id, &access).await?;
+        let ids: Vec<i32> = videos.iter().map(|video| video.video.id).collect();
+        assert_eq!(
+            ids,
+            vec![video.id],
+            "Video, which the user can't watch, is listed"
+        );
+
+        let overview = repo
+            .list_playlists(2, &[PlaylistVisibility::Private], &access)
+            .await?;
+        assert_eq!(overview[0].video_count, 1);
+        assert_eq!(overview[0].first_video_id, Some(video.id));
+
+        Ok(())
+    }
+
+    async fn get_order(repo: &PgPlaylistRepo, playlist_id: i32) -> Result<Vec<i32>> {
+        let videos = repo.get_playlist_videos(playlist_id, &access_all()).await?;
+        Ok(videos.iter().map(|video| video.video.id).collect())
+    }
+
+    /// Access of the artist, who owns all the dummy videos
+    fn access_all() -> VideoAccess {
+        VideoAccess {
+            visibilities: vec![VideoVisibility::All],
+            owner_artist_id: Some(1),
+        }
+    }
+
+    fn create_playlist_entity(user_id: i32) -> Playlist {
+        Playlist {
+            id: -1,
+            user_id,
+            name: String::from("Playlist"),
+            description: None,
+            visibility: PlaylistVisibility::Private,
+        }
+    }
+
+    async fn create_dummy_video(ctx: &AsyncContext, visibility: VideoVisibility) -> Result<Video> {
+        let video = Video {
+            id: -1,
+            artist_id: 1,
+            visibility,
+            name: String::from("Test video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+
+        let repo = PgVideoRepo::new(ctx.pg_pool.clone());
+
+        let mut tx = ctx.pg_pool.begin().await?;
+        let video = repo.save_video(video, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(video)
+    }
+}
@@ -0,0 +1,488 @@
+use crate::persistence::entities::error::MapToDatabaseError;
+use crate::persistence::entities::session::{SessionInfo, UserSession};
+use crate::persistence::redis_store::RedisStore;
+use crate::persistence::Result;
+use actix_session::storage::{
+    LoadError, RedisSessionStore, SaveError, SessionKey, SessionStore, UpdateError,
+};
+use actix_web::cookie::time::Duration;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use deadpool_redis::{redis, Connection, Pool};
+use std::collections::HashMap;
+
+/// Hash of the sessions of the user, the fields are the session keys
+const USER_SESSIONS_KEY: &str = "user:session-index";
+/// Key of the session state, under which `actix_identity` stores ID of the logged-in user
+const IDENTITY_KEY: &str = "actix_identity.user_id";
+/// Key of the session state, under which `actix_identity` stores the time of the last request
+const LAST_VISIT_KEY: &str = "actix_identity.last_visited_at";
+/// Key of the session state, under which the login details are stored
+pub const SESSION_INFO_KEY: &str = "session_info";
+
+type SessionState = HashMap<String, String>;
+
+/// Index of the sessions, in which the users are logged in.
+/// Sessions are identified by their keys in Redis, which are never shown to the users,
+/// and by their public IDs.
+#[async_trait]
+pub trait UserSessionRepo {
+    /// Adds the session to the index, or updates it
+    async fn save_session(
+        &self,
+        user_id: i32,
+        session_key: &str,
+        session: &UserSession,
+    ) -> Result<()>;
+    async fn remove_session(&self, user_id: i32, session_key: &str) -> Result<()>;
+    /// Returns the sessions of the user, which didn't expire yet
+    async fn list_sessions(&self, user_id: i32) -> Result<Vec<UserSession>>;
+    /// Deletes the session by its public ID
+    ///
+    /// # Returns
+    /// `false` if the user has no such session
+    async fn delete_session(&self, user_id: i32, session_id: &str) -> Result<bool>;
+    /// Deletes all sessions of the user except the one with the given public ID
+    async fn delete_other_sessions(&self, user_id: i32, keep_session_id: &str) -> Result<()>;
+    /// Deletes all sessions of the user, so the user is logged out on all devices
+    async fn delete_user_sessions(&self, user_id: i32) -> Result<()>;
+}
+
+#[derive(Clone)]
+pub struct RedisUserSessionRepo {
+    redis: RedisStore,
+}
+
+impl RedisUserSessionRepo {
+    pub fn new(redis_pool: Pool, key_prefix: impl Into<String>) -> Self {
+        Self {
+            redis: RedisStore::new(redis_pool, key_prefix),
+        }
+    }
+
+    fn key(&self, user_id: i32) -> String {
+        self.redis.key(&format!("{USER_SESSIONS_KEY}:{user_id}"))
+    }
+
+    /// Returns the sessions of the user, which still exist, with their keys.
+    /// Sessions, which expired meanwhile, are dropped from the index.
+    async fn list_live_sessions(
+        &self,
+        conn: &mut Connection,
+        user_id: i32,
+    ) -> Result<Vec<(String, UserSession)>> {
+        let key = self.key(user_id);
+        let sessions: HashMap<String, String> = redis::cmd("HGETALL")
+            .arg(&key)
+            .query_async(conn)
+            .await
+            .db_error("Failed to list sessions of the user")?;
+        if sessions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut pipe = redis::pipe();
+        for session_key in sessions.keys() {
+            pipe.exists(session_key);
+        }
+        let exist: Vec<bool> = pipe
+            .query_async(conn)
+            .await
+            .db_error("Failed to list sessions of the user")?;
+
+        let (live, expired): (Vec<_>, Vec<_>) = sessions
+            .into_iter()
+            .zip(exist)
+            .partition(|(_, exists)| *exists);
+        if !expired.is_empty() {
+            let expired: Vec<String> = expired.into_iter().map(|((key, _), _)| key).collect();
+            let _: () = redis::cmd("HDEL")
+                .arg(&key)
+                .arg(expired)
+                .query_async(conn)
+                .await
+                .db_error("Failed to list sessions of the user")?;
+        }
+
+        Ok(live
+            .into_iter()
+            .filter_map(|((session_key, session), _)| {
+                let session = serde_json::from_str(&session).ok()?;
+                Some((session_key, session))
+            })
+            .collect())
+    }
+
+    /// Deletes the sessions and drops them from the index
+    async fn delete_sessions(
+        &self,
+        conn: &mut Connection,
+        user_id: i32,
+        session_keys: &[String],
+    ) -> Result<()> {
+        if session_keys.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for session_key in session_keys {
+            pipe.del(session_key).ignore();
+        }
+        let _: () = pipe
+            .cmd("HDEL")
+            .arg(self.key(user_id))
+            .arg(session_keys)
+            .ignore()
+            .query_async(conn)
+            .await
+            .db_error("Failed to delete sessions of the user")?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl UserSessionRepo for RedisUserSessionRepo {
+    async fn save_session(
+        &self,
+        user_id: i32,
+        session_key: &str,
+        session: &UserSession,
+    ) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let session = serde_json::to_string(session).db_error("Failed to serialize session")?;
+
+        let added: u32 = redis::cmd("HSET")
+            .arg(self.key(user_id))
+            .arg(session_key)
+            .arg(session)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to save session of the user")?;
+        // Login is a good time to forget the sessions, which expired
+        if added > 0 {
+            self.list_live_sessions(&mut conn, user_id).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn remove_session(&self, user_id: i32, session_key: &str) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let _: () = redis::cmd("HDEL")
+            .arg(self.key(user_id))
+            .arg(session_key)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to remove session of the user")?;
+
+        Ok(())
+    }
+
+    async fn list_sessions(&self, user_id: i32) -> Result<Vec<UserSession>> {
+        let mut conn = self.redis.connection().await?;
+        let sessions = self.list_live_sessions(&mut conn, user_id).await?;
+
+        Ok(sessions.into_iter().map(|(_, session)| session).collect())
+    }
+
+    async fn delete_session(&self, user_id: i32, session_id: &str) -> Result<bool> {
+        let mut conn = self.redis.connection().await?;
+        let session_keys: Vec<String> = self
+            .list_live_sessions(&mut conn, user_id)
+            .await?
+            .into_iter()
+            .filter(|(_, session)| session.info.id == session_id)
+            .map(|(session_key, _)| session_key)
+            .collect();
+
+        self.delete_sessions(&mut conn, user_id, &session_keys)
+            .await?;
+        Ok(!session_keys.is_empty())
+    }
+
+    async fn delete_other_sessions(&self, user_id: i32, keep_session_id: &str) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let session_keys: Vec<String> = self
+            .list_live_sessions(&mut conn, user_id)
+            .await?
+            .into_iter()
+            .filter(|(_, session)| session.info.id != keep_session_id)
+            .map(|(session_key, _)| session_key)
+            .collect();
+
+        self.delete_sessions(&mut conn, user_id, &session_keys)
+            .await
+    }
+
+    async fn delete_user_sessions(&self, user_id: i32) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.key(user_id);
+        let session_keys: Vec<String> = redis::cmd("HKEYS")
+            .arg(&key)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to delete sessions of the user")?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        for session_key in &session_keys {
+            pipe.del(session_key).ignore();
+        }
+        let _: () = pipe
+            .del(&key)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to delete sessions of the user")?;
+
+        Ok(())
+    }
+}
+
+/// Redis session store, which keeps the index of the sessions of the logged-in users,
+/// so the users can see their sessions and the sessions can be deleted
+/// e.g. after the password is reset.
+/// The inner store has to use session keys as Redis keys, which is its default.
+#[derive(Clone)]
+pub struct UserSessionStore {
+    inner: RedisSessionStore,
+    sessions: RedisUserSessionRepo,
+}
+
+impl UserSessionStore {
+    pub fn new(inner: RedisSessionStore, sessions: RedisUserSessionRepo) -> Self {
+        Self { inner, sessions }
+    }
+
+    fn user_id(session_state: &SessionState) -> Option<i32> {
+        let user_id = session_state.get(IDENTITY_KEY)?;
+        serde_json::from_str::<String>(user_id).ok()?.parse().ok()
+    }
+
+    /// Returns the index entry of the logged-in session.
+    /// Sessions without the login details get new ones, so they can be told apart.
+    fn index_entry(session_state: &mut SessionState) -> UserSession {
+        let info = session_state
+            .get(SESSION_INFO_KEY)
+            .and_then(|info| serde_json::from_str::<SessionInfo>(info).ok());
+        let info = match info {
+            Some(info) => info,
+            None => {
+                let info = SessionInfo::new(None, None);
+                if let Ok(serialized) = serde_json::to_string(&info) {
+                    session_state.insert(String::from(SESSION_INFO_KEY), serialized);
+                }
+                info
+            }
+        };
+        let last_seen_at = session_state
+            .get(LAST_VISIT_KEY)
+            .and_then(|timestamp| timestamp.parse::<i64>().ok())
+            .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+            .unwrap_or_else(Utc::now);
+
+        UserSession { info, last_seen_at }
+    }
+
+    async fn index_session(
+        &self,
+        user_id: i32,
+        session_key: &SessionKey,
+        session: &UserSession,
+    ) -> anyhow::Result<()> {
+        self.sessions
+            .save_session(user_id, session_key.as_ref(), session)
+            .await
+            .map_err(|err| anyhow::Error::msg(err.error))
+    }
+}
+
+impl SessionStore for UserSessionStore {
+    async fn load(
+        &self,
+        session_key: &SessionKey,
+    ) -> std::result::Result<Option<SessionState>, LoadError> {
+        self.inner.load(session_key).await
+    }
+
+    async fn save(
+        &self,
+        mut session_state: SessionState,
+        ttl: &Duration,
+    ) -> std::result::Result<SessionKey, SaveError> {
+        let user_id = Self::user_id(&session_state);
+        let session = user_id.map(|_| Self::index_entry(&mut session_state));
+        let session_key = self.inner.save(session_state, ttl).await?;
+
+        if let (Some(user_id), Some(session)) = (user_id, session) {
+            self.index_session(user_id, &session_key, &session)
+                .await
+                .map_err(SaveError::Other)?;
+        }
+
+        Ok(session_key)
+    }
+
+    async fn update(
+        &self,
+        session_key: SessionKey,
+        mut session_state: SessionState,
+        ttl: &Duration,
+    ) -> std::result::Result<SessionKey, UpdateError> {
+        let previous_key = String::from(session_key.as_ref());
+        let user_id = Self::user_id(&session_state);
+        let session = user_id.map(|_| Self::index_entry(&mut session_state));
+        let session_key = self.inner.update(session_key, session_state, ttl).await?;
+
+        let (Some(user_id), Some(session)) = (user_id, session) else {
+            return Ok(session_key);
+        };
+
+        // The inner store saves the state under a new key, when the session was deleted
+        // during the request. Logged-in sessions must not come back, once they are deleted.
+        if session_key.as_ref() != previous_key {
+            self.inner
+                .delete(&session_key)
+                .await
+                .map_err(UpdateError::Other)?;
+            return self
+                .inner
+                .save(SessionState::new(), ttl)
+                .await
+                .map_err(|err| UpdateError::Other(err.into()));
+        }
+
+        // Every request of the logged-in user updates the state, which keeps the last visit
+        self.index_session(user_id, &session_key, &session)
+            .await
+            .map_err(UpdateError::Other)?;
+
+        Ok(session_key)
+    }
+
+    async fn update_ttl(&self, session_key: &SessionKey, ttl: &Duration) -> anyhow::Result<()> {
+        self.inner.update_ttl(session_key, ttl).await
+    }
+
+    async fn delete(&self, session_key: &SessionKey) -> anyhow::Result<()> {
+        let session_state = self.inner.load(session_key).await?;
+        self.inner.delete(session_key).await?;
+
+        if let Some(user_id) = session_state.as_ref().and_then(Self::user_id) {
+            self.sessions
+                .remove_session(user_id, session_key.as_ref())
+                .await
+                .map_err(|err| anyhow::Error::msg(err.error))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::setup_redis_pool;
+
+    fn logged_in_state(user_id: i32) -> SessionState {
+        HashMap::from([(String::from(IDENTITY_KEY), format!("\"{user_id}\""))])
+    }
+
+    async fn create_store(key_prefix: &str) -> UserSessionStore {
+        let redis_pool = setup_redis_pool().await.unwrap();
+        let inner = RedisSessionStore::new_pooled(redis_pool.clone())
+            .await
+            .unwrap();
+
+        UserSessionStore::new(inner, RedisUserSessionRepo::new(redis_pool, key_prefix))
+    }
+
+    #[tokio::test]
+    async fn delete_user_sessions() -> anyhow::Result<()> {
+        let store = create_store(&format!("{}:", uuid::Uuid::new_v4())).await;
+        let ttl = Duration::minutes(5);
+
+        let first = store.save(logged_in_state(1), &ttl).await?;
+        let second = store.save(logged_in_state(1), &ttl).await?;
+        let other_user = store.save(logged_in_state(2), &ttl).await?;
+
+        store.sessions.delete_user_sessions(1).await.unwrap();
+
+        assert!(store.load(&first).await?.is_none());
+        assert!(store.load(&second).await?.is_none());
+        assert!(store.load(&other_user).await?.is_some());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn deleted_session_stays_logged_out() -> anyhow::Result<()> {
+        let store = create_store(&format!("{}:", uuid::Uuid::new_v4())).await;
+        let ttl = Duration::minutes(5);
+
+        let session_key = store.save(logged_in_state(1), &ttl).await?;
+        let session_key = SessionKey::try_from(String::from(session_key.as_ref()))?;
+        store.sessions.delete_user_sessions(1).await.unwrap();
+
+        // Request, which loaded the session before it was deleted, saves it afterward
+        let new_key = store.update(session_key, logged_in_state(1), &ttl).await?;
+
+        let session_state = store.load(&new_key).await?.unwrap();
+        assert_eq!(UserSessionStore::user_id(&session_state), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn list_and_delete_sessions() -> anyhow::Result<()> {
+        let store = create_store(&format!("{}:", uuid::Uuid::new_v4())).await;
+        let ttl = Duration::minutes(5);
+
+        let mut state = logged_in_state(1);
+        let info = SessionInfo::new(Some(String::from("Firefox")), None);
+        state.insert(
+            String::from(SESSION_INFO_KEY),
+            serde_json::to_string(&info)?,
+        );
+        state.insert(String::from(LAST_VISIT_KEY), String::from("1700000000"));
+        let first = store.save(state, &ttl).await?;
+        let second = store.save(logged_in_state(1), &ttl).await?;
+        let third = store.save(logged_in_state(1), &ttl).await?;
+        store.save(logged_in_state(2), &ttl).await?;
+
+        let sessions = store.sessions.list_sessions(1).await.unwrap();
+        assert_eq!(sessions.len(), 3);
+        let first_session = sessions
+            .iter()
+            .find(|session| session.info.id == info.id)
+            .unwrap();
+        assert_eq!(first_session.info.user_agent.as_deref(), Some("Firefox"));
+        assert_eq!(first_session.last_seen_at.timestamp(), 1700000000);
+
+        // Sessions without the login details get their own IDs
+        let second_state = store.load(&second).await?.unwrap();
+        let second_info: SessionInfo = serde_json::from_str(&second_state[SESSION_INFO_KEY])?;
+
+        assert!(store
+            .sessions
+            .delete_session(1, &second_info.id)
+            .await
+            .unwrap());
+        assert!(!store.sessions.delete_session(2, &info.id).await.unwrap());
+        assert!(store.load(&second).await?.is_none());
+        assert_eq!(store.sessions.list_sessions(1).await.unwrap().len(), 2);
+
+        store
+            .sessions
+            .delete_other_sessions(1, &info.id)
+            .await
+            .unwrap();
+        assert!(store.load(&first).await?.is_some());
+        assert!(store.load(&third).await?.is_none());
+        assert_eq!(store.sessions.list_sessions(2).await.unwrap().len(), 1);
+
+        Ok(())
+    }
+}
@@ -1,16 +1,67 @@
-use crate::persistence::entities::stream::{LiveStream, LiveStreamStatus};
+use crate::persistence::entities::stream::{
+    ListedStream, LiveStream, LiveStreamSource, LiveStreamStatus, RemindedStream,
+};
 use crate::persistence::entities::video::{Video, VideoVisibility};
 use crate::persistence::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Local};
 use sqlx::PgPool;
 
 #[async_trait]
 pub trait StreamRepoTrait {
     async fn add_stream(&self, stream: LiveStream) -> Result<i32>;
     async fn change_status(&self, stream_id: i32, status: LiveStreamStatus) -> Result<()>;
+    /// Changes status of the stream only if it has the expected status, so the change
+    /// is done only once, even when more instances of the app try it at the same time
+    ///
+    /// # Returns
+    /// `true` if the status was changed
+    async fn change_status_if(
+        &self,
+        stream_id: i32,
+        expected: LiveStreamStatus,
+        status: LiveStreamStatus,
+    ) -> Result<bool>;
     async fn get_stream(&self, stream_id: i32) -> Result<Option<LiveStream>>;
+    /// Returns all streams with the given status, the oldest first
+    async fn list_streams_by_status(&self, status: LiveStreamStatus) -> Result<Vec<LiveStream>>;
+    /// Returns streams with the given status, whose pipelines were started by the given
+    /// instance of the app, the oldest first
+    async fn list_instance_streams(
+        &self,
+        instance_id: &str,
+        status: LiveStreamStatus,
+    ) -> Result<Vec<LiveStream>>;
+    /// Records the instance of the app, which runs the pipelines of the stream
+    async fn set_instance(&self, stream_id: i32, instance_id: &str) -> Result<()>;
+    /// Returns all streams with the given status together with their videos, the earliest start first
+    async fn list_streams_with_video(&self, status: LiveStreamStatus) -> Result<Vec<ListedStream>>;
     async fn get_streamed_video(&self, stream_id: i32) -> Result<Video>;
     async fn get_visibility(&self, stream_id: i32) -> Result<VideoVisibility>;
+    /// Returns the most recently started stream of the artist, which didn't end yet
+    async fn get_active_stream_of_artist(&self, artist_id: i32) -> Result<Option<LiveStream>>;
+    /// Returns the most recent stream of the artist, which waits for his encoder to connect
+    async fn get_waiting_rtmp_stream(&self, artist_id: i32) -> Result<Option<LiveStream>>;
+    async fn get_stream_key(&self, artist_id: i32) -> Result<Option<String>>;
+    /// Sets the stream key of the artist, the previous key stops working
+    async fn set_stream_key(&self, artist_id: i32, stream_key: &str) -> Result<()>;
+    /// Returns ID of the artist, who owns the stream key
+    async fn get_artist_by_stream_key(&self, stream_key: &str) -> Result<Option<i32>>;
+    /// Links the draft video with the recording to the ended stream
+    async fn set_recording(&self, stream_id: i32, video_id: i32) -> Result<()>;
+    /// Returns scheduled streams of the files, which should have started until the given time
+    async fn list_due_streams(&self, until: DateTime<Local>) -> Result<Vec<LiveStream>>;
+    /// The user is notified, when the scheduled stream starts
+    async fn add_reminder(&self, stream_id: i32, user_id: i32) -> Result<()>;
+    async fn delete_reminder(&self, stream_id: i32, user_id: i32) -> Result<()>;
+    async fn has_reminder(&self, stream_id: i32, user_id: i32) -> Result<bool>;
+    async fn count_reminders(&self, stream_id: i32) -> Result<i64>;
+    /// Returns streams with the given status, which the user asked to be notified about
+    async fn list_reminded_streams(
+        &self,
+        user_id: i32,
+        status: LiveStreamStatus,
+    ) -> Result<Vec<RemindedStream>>;
 }
 
 pub struct PgStreamRepo {
@@ -28,11 +79,12 @@ impl StreamRepoTrait for PgStreamRepo {
     async fn add_stream(&self, stream: LiveStream) -> Result<i32> {
         // SQLx doesn't support optional for enum type
         let result = sqlx::query!(
-            r#"INSERT INTO live_stream(video_id, start_time, status)
-            VALUES ($1, $2, $3) RETURNING live_stream.id"#,
+            r#"INSERT INTO live_stream(video_id, start_time, status, source)
+            VALUES ($1, $2, $3, $4) RETURNING live_stream.id"#,
             stream.video_id,
             stream.start_time,
-            stream.status as LiveStreamStatus
+            stream.status as LiveStreamStatus,
+            stream.source as LiveStreamSource
         )
         .fetch_one(&self.pg_pool)
         .await?;
@@ -51,10 +103,28 @@ impl StreamRepoTrait for PgStreamRepo {
         Ok(())
     }
 
+    async fn change_status_if(
+        &self,
+        stream_id: i32,
+        expected: LiveStreamStatus,
+        status: LiveStreamStatus,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE live_stream SET status = $1 WHERE id = $2 AND status = $3",
+            status as LiveStreamStatus,
+            stream_id,
+            expected as LiveStreamStatus
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
     async fn get_stream(&self, stream_id: i32) -> Result<Option<LiveStream>> {
         let stream = sqlx::query_as!(
             LiveStream,
-            r#"SELECT id, video_id, start_time, status as "status: LiveStreamStatus"
+            r#"SELECT id, video_id, start_time, status as "status: LiveStreamStatus",
+                source as "source: LiveStreamSource", recording_video_id
             FROM live_stream WHERE id = $1"#,
             stream_id
         )
@@ -63,6 +133,82 @@ impl StreamRepoTrait for PgStreamRepo {
         Ok(stream)
     }
 
+    async fn list_streams_by_status(&self, status: LiveStreamStatus) -> Result<Vec<LiveStream>> {
+        let streams = sqlx::query_as!(
+            LiveStream,
+            r#"SELECT id, video_id, start_time, status as "status: LiveStreamStatus",
+                source as "source: LiveStreamSource", recording_video_id
+            FROM live_stream WHERE status = $1
+            ORDER BY start_time, id"#,
+            status as LiveStreamStatus
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(streams)
+    }
+
+    async fn list_instance_streams(
+        &self,
+        instance_id: &str,
+        status: LiveStreamStatus,
+    ) -> Result<Vec<LiveStream>> {
+        let streams = sqlx::query_as!(
+            LiveStream,
+            r#"SELECT id, video_id, start_time, status as "status: LiveStreamStatus",
+                source as "source: LiveStreamSource", recording_video_id
+            FROM live_stream WHERE instance_id = $1 AND status = $2
+            ORDER BY start_time, id"#,
+            instance_id,
+            status as LiveStreamStatus
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(streams)
+    }
+
+    async fn set_instance(&self, stream_id: i32, instance_id: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE live_stream SET instance_id = $2 WHERE id = $1",
+            stream_id,
+            instance_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_streams_with_video(&self, status: LiveStreamStatus) -> Result<Vec<ListedStream>> {
+        let streams = sqlx::query_as::<_, ListedStream>(
+            r#"SELECT
+                video.id,
+                video.artist_id,
+                video.visibility,
+                video.name,
+                video.file_path,
+                video.thumbnail_path,
+                video.description,
+                video.duration_ms,
+                video.width,
+                video.height,
+                video.frame_rate,
+                video.container,
+                video.video_codec,
+                video.audio_codec,
+                video.draft,
+                live_stream.id AS stream_id,
+                live_stream.start_time,
+                live_stream.source
+            FROM live_stream
+            JOIN video ON video.id = live_stream.video_id
+            WHERE live_stream.status = $1
+            ORDER BY live_stream.start_time, live_stream.id"#,
+        )
+        .bind(status)
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(streams)
+    }
+
     async fn get_streamed_video(&self, stream_id: i32) -> Result<Video> {
         let video = sqlx::query_as!(
             Video,
@@ -73,7 +219,15 @@ impl StreamRepoTrait for PgStreamRepo {
                 name,
                 file_path,
                 thumbnail_path,
-                description 
+                description,
+                duration_ms,
+                width,
+                height,
+                frame_rate,
+                container,
+                video_codec,
+                audio_codec,
+                draft
             FROM video
             JOIN live_stream ON live_stream.video_id = video.id
             WHERE live_stream.id = $1"#,
@@ -99,23 +253,182 @@ impl StreamRepoTrait for PgStreamRepo {
 
         Ok(record.visibility)
     }
+
+    async fn get_active_stream_of_artist(&self, artist_id: i32) -> Result<Option<LiveStream>> {
+        let stream = sqlx::query_as!(
+            LiveStream,
+            r#"SELECT live_stream.id, video_id, start_time, status as "status: LiveStreamStatus",
+                source as "source: LiveStreamSource", recording_video_id
+            FROM live_stream JOIN video ON video.id = live_stream.video_id
+            WHERE video.artist_id = $1 AND live_stream.status <> 'ENDED'
+            ORDER BY start_time DESC, live_stream.id DESC
+            LIMIT 1"#,
+            artist_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(stream)
+    }
+
+    async fn get_waiting_rtmp_stream(&self, artist_id: i32) -> Result<Option<LiveStream>> {
+        let stream = sqlx::query_as!(
+            LiveStream,
+            r#"SELECT live_stream.id, video_id, start_time, status as "status: LiveStreamStatus",
+                source as "source: LiveStreamSource", recording_video_id
+            FROM live_stream JOIN video ON video.id = live_stream.video_id
+            WHERE video.artist_id = $1 AND live_stream.status = 'PENDING'
+                AND live_stream.source = 'RTMP'
+            ORDER BY start_time DESC, live_stream.id DESC
+            LIMIT 1"#,
+            artist_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(stream)
+    }
+
+    async fn get_stream_key(&self, artist_id: i32) -> Result<Option<String>> {
+        let stream_key = sqlx::query_scalar!(
+            "SELECT stream_key FROM stream_key WHERE artist_id = $1",
+            artist_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(stream_key)
+    }
+
+    async fn set_stream_key(&self, artist_id: i32, stream_key: &str) -> Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO stream_key (artist_id, stream_key) VALUES ($1, $2)
+            ON CONFLICT (artist_id)
+            DO UPDATE SET stream_key = EXCLUDED.stream_key, created_at = NOW()"#,
+            artist_id,
+            stream_key
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn get_artist_by_stream_key(&self, stream_key: &str) -> Result<Option<i32>> {
+        let artist_id = sqlx::query_scalar!(
+            "SELECT artist_id FROM stream_key WHERE stream_key = $1",
+            stream_key
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+        Ok(artist_id)
+    }
+
+    async fn set_recording(&self, stream_id: i32, video_id: i32) -> Result<()> {
+        sqlx::query!(
+            "UPDATE live_stream SET recording_video_id = $2 WHERE id = $1",
+            stream_id,
+            video_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn list_due_streams(&self, until: DateTime<Local>) -> Result<Vec<LiveStream>> {
+        let streams = sqlx::query_as!(
+            LiveStream,
+            r#"SELECT id, video_id, start_time, status as "status: LiveStreamStatus",
+                source as "source: LiveStreamSource", recording_video_id
+            FROM live_stream
+            WHERE status = 'PENDING' AND source = 'FILE' AND start_time <= $1
+            ORDER BY start_time, id"#,
+            until
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(streams)
+    }
+
+    async fn add_reminder(&self, stream_id: i32, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO stream_reminder (stream_id, user_id) VALUES ($1, $2)
+            ON CONFLICT DO NOTHING"#,
+            stream_id,
+            user_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn delete_reminder(&self, stream_id: i32, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM stream_reminder WHERE stream_id = $1 AND user_id = $2",
+            stream_id,
+            user_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn has_reminder(&self, stream_id: i32, user_id: i32) -> Result<bool> {
+        let exists = sqlx::query_scalar!(
+            r#"SELECT EXISTS (
+                SELECT 1 FROM stream_reminder WHERE stream_id = $1 AND user_id = $2
+            ) AS "exists!""#,
+            stream_id,
+            user_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+        Ok(exists)
+    }
+
+    async fn count_reminders(&self, stream_id: i32) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM stream_reminder WHERE stream_id = $1"#,
+            stream_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+        Ok(count)
+    }
+
+    async fn list_reminded_streams(
+        &self,
+        user_id: i32,
+        status: LiveStreamStatus,
+    ) -> Result<Vec<RemindedStream>> {
+        let streams = sqlx::query_as!(
+            RemindedStream,
+            r#"SELECT live_stream.id AS stream_id, video.name AS video_name, start_time
+            FROM stream_reminder
+            JOIN live_stream ON live_stream.id = stream_reminder.stream_id
+            JOIN video ON video.id = live_stream.video_id
+            WHERE stream_reminder.user_id = $1 AND live_stream.status = $2
+            ORDER BY start_time, live_stream.id"#,
+            user_id,
+            status as LiveStreamStatus
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+        Ok(streams)
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::common::tests::setup::{AsyncContext, EmptyAsyncContext};
-    use crate::persistence::entities::stream::{LiveStream, LiveStreamStatus};
+    use crate::persistence::entities::stream::{LiveStream, LiveStreamSource, LiveStreamStatus};
     use crate::persistence::entities::video::{Video, VideoVisibility};
     use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
-    use chrono::Local;
+    use chrono::{Duration, Local};
     use strum::IntoEnumIterator;
     use test_context::test_context;
 
     #[test_context(AsyncContext)]
     #[tokio::test]
     async fn add_get_stream(ctx: &mut AsyncContext) -> Result<()> {
-        let video = create_dummy_video(&ctx).await?;
+        let video = create_dummy_video(ctx).await?;
         let stream = create_stream_entity(&video);
         let repo = PgStreamRepo::new(ctx.pg_pool.clone());
 
@@ -150,7 +463,7 @@ mod test {
     #[test_context(AsyncContext)]
     #[tokio::test]
     async fn change_status(ctx: &mut AsyncContext) -> Result<()> {
-        let video = create_dummy_video(&ctx).await?;
+        let video = create_dummy_video(ctx).await?;
         let stream = create_stream_entity(&video);
         let repo = PgStreamRepo::new(ctx.pg_pool.clone());
 
@@ -168,10 +481,93 @@ mod test {
         Ok(())
     }
 
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn list_streams_by_status(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let first_id = repo.add_stream(create_stream_entity(&video)).await?;
+        let second_id = repo.add_stream(create_stream_entity(&video)).await?;
+        repo.change_status(second_id, LiveStreamStatus::Ended)
+            .await?;
+        let third_id = repo.add_stream(create_stream_entity(&video)).await?;
+
+        let streams = repo
+            .list_streams_by_status(LiveStreamStatus::Pending)
+            .await?;
+        let stream_ids: Vec<i32> = streams.iter().map(|stream| stream.id).collect();
+        assert_eq!(stream_ids, vec![first_id, third_id]);
+
+        let streams = repo.list_streams_by_status(LiveStreamStatus::Ended).await?;
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].id, second_id);
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn list_instance_streams(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let own_id = repo.add_stream(create_stream_entity(&video)).await?;
+        repo.set_instance(own_id, "first").await?;
+        let other_id = repo.add_stream(create_stream_entity(&video)).await?;
+        repo.set_instance(other_id, "second").await?;
+        repo.add_stream(create_stream_entity(&video)).await?;
+
+        let streams = repo
+            .list_instance_streams("first", LiveStreamStatus::Pending)
+            .await?;
+        let stream_ids: Vec<i32> = streams.iter().map(|stream| stream.id).collect();
+        assert_eq!(stream_ids, vec![own_id]);
+
+        let streams = repo
+            .list_instance_streams("first", LiveStreamStatus::Running)
+            .await?;
+        assert!(streams.is_empty());
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn list_streams_with_video(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let later_id = repo
+            .add_stream(LiveStream {
+                start_time: Local::now() + Duration::hours(1),
+                ..create_stream_entity(&video)
+            })
+            .await?;
+        let sooner_id = repo.add_stream(create_stream_entity(&video)).await?;
+        let running_id = repo.add_stream(create_stream_entity(&video)).await?;
+        repo.change_status(running_id, LiveStreamStatus::Running)
+            .await?;
+
+        let streams = repo
+            .list_streams_with_video(LiveStreamStatus::Pending)
+            .await?;
+        let stream_ids: Vec<i32> = streams.iter().map(|stream| stream.stream_id).collect();
+        assert_eq!(stream_ids, vec![sooner_id, later_id]);
+        assert_eq!(streams[0].video, video);
+        assert_eq!(streams[0].source, LiveStreamSource::File);
+
+        let streams = repo
+            .list_streams_with_video(LiveStreamStatus::Running)
+            .await?;
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].stream_id, running_id);
+        assert!(repo
+            .list_streams_with_video(LiveStreamStatus::Ended)
+            .await?
+            .is_empty());
+        Ok(())
+    }
+
     #[test_context(AsyncContext)]
     #[tokio::test]
     async fn get_streamed_video(ctx: &mut AsyncContext) -> Result<()> {
-        let video = create_dummy_video(&ctx).await?;
+        let video = create_dummy_video(ctx).await?;
         let stream = create_stream_entity(&video);
         let repo = PgStreamRepo::new(ctx.pg_pool.clone());
 
@@ -189,7 +585,7 @@ mod test {
     #[test_context(AsyncContext)]
     #[tokio::test]
     async fn get_visibility(ctx: &mut AsyncContext) -> Result<()> {
-        let video = create_dummy_video(&ctx).await?;
+        let video = create_dummy_video(ctx).await?;
         let stream = create_stream_entity(&video);
         let repo = PgStreamRepo::new(ctx.pg_pool.clone());
 
@@ -204,6 +600,186 @@ mod test {
         Ok(())
     }
 
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn get_active_stream_of_artist(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        assert!(repo.get_active_stream_of_artist(1).await?.is_none());
+
+        let stream_id = repo.add_stream(create_stream_entity(&video)).await?;
+        let active_stream = repo.get_active_stream_of_artist(1).await?;
+        assert_eq!(active_stream.map(|stream| stream.id), Some(stream_id));
+        assert!(repo.get_active_stream_of_artist(2).await?.is_none());
+
+        repo.change_status(stream_id, LiveStreamStatus::Ended)
+            .await?;
+        assert!(
+            repo.get_active_stream_of_artist(1).await?.is_none(),
+            "Ended stream should not be returned"
+        );
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn get_waiting_rtmp_stream(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        repo.add_stream(create_stream_entity(&video)).await?;
+        assert!(
+            repo.get_waiting_rtmp_stream(1).await?.is_none(),
+            "Stream of the file should not wait for the encoder"
+        );
+
+        let stream_id = repo
+            .add_stream(LiveStream {
+                source: LiveStreamSource::Rtmp,
+                ..create_stream_entity(&video)
+            })
+            .await?;
+        let waiting_stream = repo.get_waiting_rtmp_stream(1).await?;
+        assert_eq!(waiting_stream.map(|stream| stream.id), Some(stream_id));
+        assert!(repo.get_waiting_rtmp_stream(2).await?.is_none());
+
+        repo.change_status(stream_id, LiveStreamStatus::Running)
+            .await?;
+        assert!(repo.get_waiting_rtmp_stream(1).await?.is_none());
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn stream_key(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        assert!(repo.get_stream_key(1).await?.is_none());
+
+        repo.set_stream_key(1, "first-key").await?;
+        repo.set_stream_key(2, "other-key").await?;
+        assert_eq!(repo.get_stream_key(1).await?.as_deref(), Some("first-key"));
+        assert_eq!(repo.get_artist_by_stream_key("first-key").await?, Some(1));
+
+        repo.set_stream_key(1, "second-key").await?;
+        assert_eq!(repo.get_stream_key(1).await?.as_deref(), Some("second-key"));
+        assert!(
+            repo.get_artist_by_stream_key("first-key").await?.is_none(),
+            "Replaced stream key should not work anymore"
+        );
+        assert_eq!(repo.get_artist_by_stream_key("other-key").await?, Some(2));
+        assert!(repo.set_stream_key(1, "other-key").await.is_err());
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn set_recording(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let recording = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let stream_id = repo.add_stream(create_stream_entity(&video)).await?;
+        let stream = repo.get_stream(stream_id).await?.unwrap();
+        assert!(stream.recording_video_id.is_none());
+
+        repo.set_recording(stream_id, recording.id).await?;
+        let stream = repo.get_stream(stream_id).await?.unwrap();
+        assert_eq!(stream.recording_video_id, Some(recording.id));
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn change_status_if(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let stream_id = repo.add_stream(create_stream_entity(&video)).await?;
+
+        let changed = repo
+            .change_status_if(
+                stream_id,
+                LiveStreamStatus::Pending,
+                LiveStreamStatus::Running,
+            )
+            .await?;
+        assert!(changed);
+        let changed = repo
+            .change_status_if(
+                stream_id,
+                LiveStreamStatus::Pending,
+                LiveStreamStatus::Running,
+            )
+            .await?;
+        assert!(!changed, "Status should be changed only once");
+        let stream = repo.get_stream(stream_id).await?.unwrap();
+        assert_eq!(stream.status, LiveStreamStatus::Running);
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn list_due_streams(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let due_id = repo
+            .add_stream(LiveStream {
+                start_time: Local::now() - Duration::minutes(1),
+                ..create_stream_entity(&video)
+            })
+            .await?;
+        repo.add_stream(LiveStream {
+            start_time: Local::now() + Duration::hours(1),
+            ..create_stream_entity(&video)
+        })
+        .await?;
+        repo.add_stream(LiveStream {
+            start_time: Local::now() - Duration::minutes(1),
+            source: LiveStreamSource::Rtmp,
+            ..create_stream_entity(&video)
+        })
+        .await?;
+
+        let streams = repo.list_due_streams(Local::now()).await?;
+        let stream_ids: Vec<i32> = streams.iter().map(|stream| stream.id).collect();
+        assert_eq!(stream_ids, vec![due_id]);
+
+        repo.change_status(due_id, LiveStreamStatus::Running)
+            .await?;
+        assert!(repo.list_due_streams(Local::now()).await?.is_empty());
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn reminders(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgStreamRepo::new(ctx.pg_pool.clone());
+        let stream_id = repo.add_stream(create_stream_entity(&video)).await?;
+
+        repo.add_reminder(stream_id, 2).await?;
+        repo.add_reminder(stream_id, 2).await?;
+        repo.add_reminder(stream_id, 3).await?;
+        assert!(repo.has_reminder(stream_id, 2).await?);
+        assert!(!repo.has_reminder(stream_id, 1).await?);
+        assert_eq!(repo.count_reminders(stream_id).await?, 2);
+
+        assert!(repo
+            .list_reminded_streams(2, LiveStreamStatus::Running)
+            .await?
+            .is_empty());
+        repo.change_status(stream_id, LiveStreamStatus::Running)
+            .await?;
+        let streams = repo
+            .list_reminded_streams(2, LiveStreamStatus::Running)
+            .await?;
+        assert_eq!(streams.len(), 1);
+        assert_eq!(streams[0].stream_id, stream_id);
+        assert_eq!(streams[0].video_name, video.name);
+
+        repo.delete_reminder(stream_id, 2).await?;
+        assert!(!repo.has_reminder(stream_id, 2).await?);
+        assert_eq!(repo.count_reminders(stream_id).await?, 1);
+        Ok(())
+    }
+
     async fn create_dummy_video(ctx: &AsyncContext) -> Result<Video> {
         let video = Video {
             id: 1,
@@ -213,6 +789,14 @@ mod test {
             file_path: String::from("dummy path"),
             thumbnail_path: String::from("dummy path"),
             description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
         };
 
         let repo = PgVideoRepo::new(ctx.pg_pool.clone());
@@ -230,6 +814,8 @@ mod test {
             video_id: video.id,
             start_time: Local::now(),
             status: LiveStreamStatus::Pending,
+            source: LiveStreamSource::File,
+            recording_video_id: None,
         }
     }
 }
@@ -0,0 +1,380 @@
+use crate::persistence::entities::error::MapToDatabaseError;
+use crate::persistence::entities::stream_chat::{ChatEvent, ChatMessage};
+use crate::persistence::redis_store::RedisStore;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use deadpool_redis::redis::AsyncCommands;
+use deadpool_redis::{redis, Pool};
+use futures::stream::{BoxStream, StreamExt};
+use sqlx::PgPool;
+use std::time::Duration;
+
+const CHANNEL_KEY: &str = "chat:channel";
+const RATE_KEY: &str = "chat:rate";
+const TIMEOUT_KEY: &str = "chat:timeout";
+
+#[async_trait]
+pub trait StreamChatRepo {
+    async fn add_message(&self, stream_id: i32, user_id: i32, content: &str)
+        -> Result<ChatMessage>;
+    /// Returns the latest messages of the stream, the oldest first
+    async fn list_recent_messages(&self, stream_id: i32, limit: i64) -> Result<Vec<ChatMessage>>;
+    /// Returns all messages of the stream, the oldest first
+    async fn list_messages(&self, stream_id: i32) -> Result<Vec<ChatMessage>>;
+    /// # Returns
+    /// `false` if the stream has no such message
+    async fn delete_message(&self, stream_id: i32, message_id: i32) -> Result<bool>;
+}
+
+/// Delivers the chat events between all instances of the application and keeps
+/// the short-lived state of the chat, which doesn't need to be persisted
+#[async_trait]
+pub trait ChatBroker {
+    async fn publish(&self, stream_id: i32, event: &ChatEvent) -> Result<()>;
+    /// Returns events of the stream, which are published after the subscription
+    async fn subscribe(&self, stream_id: i32) -> Result<BoxStream<'static, ChatEvent>>;
+    /// Counts the message of the user within a fixed time window
+    ///
+    /// # Returns
+    /// `true` if the user didn't send more than `limit` messages within the window
+    async fn check_rate_limit(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        limit: u32,
+        window: Duration,
+    ) -> Result<bool>;
+    async fn set_timeout(&self, stream_id: i32, user_id: i32, duration: Duration) -> Result<()>;
+    /// Returns the remaining time of the user's timeout, `None` if the user isn't timed out
+    async fn get_timeout(&self, stream_id: i32, user_id: i32) -> Result<Option<Duration>>;
+}
+
+pub struct PgStreamChatRepo {
+    pg_pool: PgPool,
+}
+
+impl PgStreamChatRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl StreamChatRepo for PgStreamChatRepo {
+    async fn add_message(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        content: &str,
+    ) -> Result<ChatMessage> {
+        let message = sqlx::query_as!(
+            ChatMessage,
+            r#"WITH inserted AS (
+                INSERT INTO stream_chat_message (stream_id, user_id, content)
+                VALUES ($1, $2, $3)
+                RETURNING *
+            )
+            SELECT inserted.id AS "id!", inserted.stream_id AS "stream_id!",
+                inserted.user_id AS "user_id!", user_table.username AS "username!",
+                inserted.content AS "content!", inserted.created_at AS "created_at!"
+            FROM inserted
+            JOIN user_table ON user_table.id = inserted.user_id"#,
+            stream_id,
+            user_id,
+            content
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(message)
+    }
+
+    async fn list_recent_messages(&self, stream_id: i32, limit: i64) -> Result<Vec<ChatMessage>> {
+        let messages = sqlx::query_as!(
+            ChatMessage,
+            r#"SELECT recent.id AS "id!", recent.stream_id AS "stream_id!",
+                recent.user_id AS "user_id!", recent.username AS "username!",
+                recent.content AS "content!", recent.created_at AS "created_at!"
+            FROM (
+                SELECT message.id, message.stream_id, message.user_id, user_table.username,
+                    message.content, message.created_at
+                FROM stream_chat_message message
+                JOIN user_table ON user_table.id = message.user_id
+                WHERE message.stream_id = $1
+                ORDER BY message.id DESC
+                LIMIT $2
+            ) recent
+            ORDER BY recent.id"#,
+            stream_id,
+            limit
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    async fn list_messages(&self, stream_id: i32) -> Result<Vec<ChatMessage>> {
+        let messages = sqlx::query_as!(
+            ChatMessage,
+            r#"SELECT message.id, message.stream_id, message.user_id, user_table.username,
+                message.content, message.created_at
+            FROM stream_chat_message message
+            JOIN user_table ON user_table.id = message.user_id
+            WHERE message.stream_id = $1
+            ORDER BY message.id"#,
+            stream_id
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(messages)
+    }
+
+    async fn delete_message(&self, stream_id: i32, message_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM stream_chat_message WHERE id = $1 AND stream_id = $2",
+            message_id,
+            stream_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+pub struct RedisChatBroker {
+    redis: RedisStore,
+    redis_client: redis::Client,
+}
+
+impl RedisChatBroker {
+    /// # Arguments
+    ///
+    /// * `redis_client` - client for the dedicated pub/sub connections of the subscribers
+    /// * `key_prefix` - prefix of all keys and channels
+    pub fn new(
+        redis_pool: Pool,
+        redis_client: redis::Client,
+        key_prefix: impl Into<String>,
+    ) -> Self {
+        Self {
+            redis: RedisStore::new(redis_pool, key_prefix),
+            redis_client,
+        }
+    }
+}
+
+#[async_trait]
+impl ChatBroker for RedisChatBroker {
+    async fn publish(&self, stream_id: i32, event: &ChatEvent) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let payload = serde_json::to_string(event).db_error("Failed to serialize the event")?;
+
+        let _: i64 = conn
+            .publish(
+                self.redis.key(&format!("{CHANNEL_KEY}:{stream_id}")),
+                payload,
+            )
+            .await
+            .db_error("Failed to publish the chat event")?;
+
+        Ok(())
+    }
+
+    async fn subscribe(&self, stream_id: i32) -> Result<BoxStream<'static, ChatEvent>> {
+        let mut pubsub = self
+            .redis_client
+            .get_async_pubsub()
+            .await
+            .db_error("Failed to connect to Redis")?;
+        pubsub
+            .subscribe(self.redis.key(&format!("{CHANNEL_KEY}:{stream_id}")))
+            .await
+            .db_error("Failed to subscribe to the chat")?;
+
+        let events = pubsub.into_on_message().filter_map(|message| async move {
+            let payload: String = message.get_payload().ok()?;
+            serde_json::from_str(&payload).ok()
+        });
+
+        Ok(events.boxed())
+    }
+
+    async fn check_rate_limit(
+        &self,
+        stream_id: i32,
+        user_id: i32,
+        limit: u32,
+        window: Duration,
+    ) -> Result<bool> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.redis.key(&format!("{RATE_KEY}:{stream_id}:{user_id}"));
+
+        // The counter expires together with the window started by the first message
+        let (count,): (u32,) = redis::pipe()
+            .atomic()
+            .cmd("SET")
+            .arg(&key)
+            .arg(0)
+            .arg("NX")
+            .arg("EX")
+            .arg(window.as_secs().max(1))
+            .ignore()
+            .incr(&key, 1)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to count the chat message")?;
+
+        Ok(count <= limit)
+    }
+
+    async fn set_timeout(&self, stream_id: i32, user_id: i32, duration: Duration) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let _: () = conn
+            .set_ex(
+                self.redis
+                    .key(&format!("{TIMEOUT_KEY}:{stream_id}:{user_id}")),
+                1,
+                duration.as_secs().max(1),
+            )
+            .await
+            .db_error("Failed to time out the user")?;
+
+        Ok(())
+    }
+
+    async fn get_timeout(&self, stream_id: i32, user_id: i32) -> Result<Option<Duration>> {
+        self.redis
+            .ttl(
+                &self
+                    .redis
+                    .key(&format!("{TIMEOUT_KEY}:{stream_id}:{user_id}")),
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::persistence::entities::stream::{LiveStream, LiveStreamSource, LiveStreamStatus};
+    use crate::persistence::entities::video::{Video, VideoVisibility};
+    use crate::persistence::repositories::stream::{PgStreamRepo, StreamRepoTrait};
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use crate::{setup_redis_client, setup_redis_pool};
+    use chrono::Local;
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn add_list_delete_messages(ctx: &mut AsyncContext) -> Result<()> {
+        let stream_id = create_dummy_stream(ctx).await?;
+        let repo = PgStreamChatRepo::new(ctx.pg_pool.clone());
+
+        let first = repo.add_message(stream_id, 1, "Hello").await?;
+        assert_eq!(first.username, "JohnArtist");
+        assert_eq!(first.content, "Hello");
+        let second = repo.add_message(stream_id, 2, "Hi").await?;
+        let third = repo.add_message(stream_id, 1, "How are you?").await?;
+
+        let ids = |messages: Vec<ChatMessage>| -> Vec<i32> {
+            messages.iter().map(|message| message.id).collect()
+        };
+        assert_eq!(
+            ids(repo.list_messages(stream_id).await?),
+            vec![first.id, second.id, third.id]
+        );
+        assert_eq!(
+            ids(repo.list_recent_messages(stream_id, 2).await?),
+            vec![second.id, third.id]
+        );
+
+        assert!(
+            !repo.delete_message(stream_id + 1, second.id).await?,
+            "Message of other stream should not be deleted"
+        );
+        assert!(repo.delete_message(stream_id, second.id).await?);
+        assert_eq!(
+            ids(repo.list_messages(stream_id).await?),
+            vec![first.id, third.id]
+        );
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn chat_broker(ctx: &mut AsyncContext) -> Result<()> {
+        let broker = RedisChatBroker::new(
+            setup_redis_pool().await.unwrap(),
+            setup_redis_client().unwrap(),
+            format!("{}:", ctx.test_db_name),
+        );
+        let window = Duration::from_secs(60);
+
+        assert!(broker.check_rate_limit(1, 1, 2, window).await?);
+        assert!(broker.check_rate_limit(1, 1, 2, window).await?);
+        assert!(!broker.check_rate_limit(1, 1, 2, window).await?);
+        assert!(broker.check_rate_limit(1, 2, 2, window).await?);
+
+        assert_eq!(broker.get_timeout(1, 1).await?, None);
+        broker.set_timeout(1, 1, window).await?;
+        let timeout = broker.get_timeout(1, 1).await?;
+        assert!(timeout.is_some_and(|timeout| timeout <= window));
+        assert_eq!(broker.get_timeout(2, 1).await?, None);
+
+        let mut events = broker.subscribe(1).await?;
+        let event = ChatEvent::Delete { message_id: 5 };
+        broker
+            .publish(2, &ChatEvent::Delete { message_id: 4 })
+            .await?;
+        broker.publish(1, &event).await?;
+
+        let received = tokio::time::timeout(Duration::from_secs(5), events.next())
+            .await
+            .expect("The event was not delivered");
+        assert_eq!(received, Some(event));
+
+        Ok(())
+    }
+
+    async fn create_dummy_stream(ctx: &AsyncContext) -> Result<i32> {
+        let video = Video {
+            id: 1,
+            artist_id: 1,
+            visibility: VideoVisibility::All,
+            name: String::from("Test video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+
+        let mut tx = ctx.pg_pool.begin().await?;
+        let video = PgVideoRepo::new(ctx.pg_pool.clone())
+            .save_video(video, &mut tx)
+            .await?;
+        tx.commit().await?;
+
+        PgStreamRepo::new(ctx.pg_pool.clone())
+            .add_stream(LiveStream {
+                id: -1,
+                video_id: video.id,
+                start_time: Local::now(),
+                status: LiveStreamStatus::Pending,
+                source: LiveStreamSource::File,
+                recording_video_id: None,
+            })
+            .await
+    }
+}
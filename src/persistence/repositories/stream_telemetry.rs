@@ -0,0 +1,331 @@
+use crate::persistence::entities::error::MapToDatabaseError;
+use crate::persistence::entities::stream_telemetry::{StreamHealthSample, StreamHealthSummary};
+use crate::persistence::redis_store::RedisStore;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use deadpool_redis::{redis, Pool};
+use sqlx::PgPool;
+use std::time::Duration;
+
+const VIEWERS_KEY: &str = "stream:viewers";
+
+#[async_trait]
+pub trait StreamTelemetryRepo {
+    /// Saves the health of the pipelines, time of the samples is set to now.
+    /// Samples of the deleted streams are skipped.
+    async fn add_health_samples(&self, samples: &[StreamHealthSample]) -> Result<()>;
+    /// Returns the newest sample of each pipeline of the stream
+    async fn list_latest_health(&self, stream_id: i32) -> Result<Vec<StreamHealthSample>>;
+    /// Summarizes all samples of each pipeline of the stream
+    async fn get_health_summary(&self, stream_id: i32) -> Result<Vec<StreamHealthSummary>>;
+    /// Raises the peak viewer count of the stream, if the given count is higher
+    async fn update_peak_viewers(&self, stream_id: i32, viewer_count: i32) -> Result<()>;
+    async fn get_peak_viewers(&self, stream_id: i32) -> Result<i32>;
+}
+
+/// Tracks viewers of the streams, who are shared by all instances of the application
+#[async_trait]
+pub trait ViewerTracker {
+    /// Marks the viewer as watching the stream right now
+    async fn mark_viewer(&self, stream_id: i32, viewer: &str, window: Duration) -> Result<()>;
+    /// Returns number of the viewers, who were marked within the window
+    async fn count_viewers(&self, stream_id: i32, window: Duration) -> Result<u64>;
+}
+
+pub struct PgStreamTelemetryRepo {
+    pg_pool: PgPool,
+}
+
+impl PgStreamTelemetryRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl StreamTelemetryRepo for PgStreamTelemetryRepo {
+    async fn add_health_samples(&self, samples: &[StreamHealthSample]) -> Result<()> {
+        let mut tx = self.pg_pool.begin().await?;
+        for sample in samples {
+            sqlx::query!(
+                r#"INSERT INTO stream_health_sample
+                    (stream_id, resolution, bitrate_kbps, dropped_frames, qos_events,
+                    buffering_percent, warnings)
+                SELECT $1, $2, $3, $4, $5, $6, $7
+                WHERE EXISTS (SELECT 1 FROM live_stream WHERE id = $1)"#,
+                sample.stream_id,
+                sample.resolution,
+                sample.bitrate_kbps,
+                sample.dropped_frames,
+                sample.qos_events,
+                sample.buffering_percent,
+                sample.warnings
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn list_latest_health(&self, stream_id: i32) -> Result<Vec<StreamHealthSample>> {
+        let samples = sqlx::query_as!(
+            StreamHealthSample,
+            r#"SELECT DISTINCT ON (resolution) id, stream_id, resolution, bitrate_kbps,
+                dropped_frames, qos_events, buffering_percent, warnings, recorded_at
+            FROM stream_health_sample
+            WHERE stream_id = $1
+            ORDER BY resolution, recorded_at DESC, id DESC"#,
+            stream_id
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(samples)
+    }
+
+    async fn get_health_summary(&self, stream_id: i32) -> Result<Vec<StreamHealthSummary>> {
+        let summary = sqlx::query_as!(
+            StreamHealthSummary,
+            r#"SELECT resolution,
+                AVG(bitrate_kbps)::INTEGER AS "avg_bitrate_kbps!",
+                MIN(bitrate_kbps) AS "min_bitrate_kbps!",
+                MAX(dropped_frames) AS "dropped_frames!",
+                MAX(qos_events) AS "qos_events!",
+                MAX(warnings) AS "warnings!",
+                COUNT(*) AS "sample_count!"
+            FROM stream_health_sample
+            WHERE stream_id = $1
+            GROUP BY resolution
+            ORDER BY resolution"#,
+            stream_id
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(summary)
+    }
+
+    async fn update_peak_viewers(&self, stream_id: i32, viewer_count: i32) -> Result<()> {
+        sqlx::query!(
+            "UPDATE live_stream SET peak_viewers = GREATEST(peak_viewers, $2) WHERE id = $1",
+            stream_id,
+            viewer_count
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_peak_viewers(&self, stream_id: i32) -> Result<i32> {
+        let record = sqlx::query!(
+            "SELECT peak_viewers FROM live_stream WHERE id = $1",
+            stream_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(record.map(|record| record.peak_viewers).unwrap_or_default())
+    }
+}
+
+pub struct RedisViewerTracker {
+    redis: RedisStore,
+}
+
+impl RedisViewerTracker {
+    pub fn new(redis_pool: Pool, key_prefix: impl Into<String>) -> Self {
+        Self {
+            redis: RedisStore::new(redis_pool, key_prefix),
+        }
+    }
+
+    fn key(&self, stream_id: i32) -> String {
+        self.redis.key(&format!("{VIEWERS_KEY}:{stream_id}"))
+    }
+}
+
+#[async_trait]
+impl ViewerTracker for RedisViewerTracker {
+    async fn mark_viewer(&self, stream_id: i32, viewer: &str, window: Duration) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.key(stream_id);
+
+        // Viewers are scored by the time they were seen, the set expires after the stream ends
+        let _: () = redis::pipe()
+            .atomic()
+            .zadd(&key, viewer, Utc::now().timestamp())
+            .ignore()
+            .expire(&key, window.as_secs().max(1) as i64 * 2)
+            .ignore()
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to mark the viewer of the stream")?;
+
+        Ok(())
+    }
+
+    async fn count_viewers(&self, stream_id: i32, window: Duration) -> Result<u64> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.key(stream_id);
+        let seen_since = Utc::now().timestamp() - window.as_secs() as i64;
+
+        let (count,): (u64,) = redis::pipe()
+            .atomic()
+            .zrembyscore(&key, "-inf", format!("({seen_since}"))
+            .ignore()
+            .zcard(&key)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to count the viewers of the stream")?;
+
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::persistence::entities::stream::{LiveStream, LiveStreamSource, LiveStreamStatus};
+    use crate::persistence::entities::video::{Video, VideoVisibility};
+    use crate::persistence::repositories::stream::{PgStreamRepo, StreamRepoTrait};
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use crate::setup_redis_pool;
+    use chrono::Local;
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn health_samples(ctx: &mut AsyncContext) -> Result<()> {
+        let stream_id = create_dummy_stream(ctx).await?;
+        let repo = PgStreamTelemetryRepo::new(ctx.pg_pool.clone());
+
+        repo.add_health_samples(&[
+            health_sample(stream_id, "360", 300, 0),
+            health_sample(stream_id, "720", 2000, 4),
+            // samples of the deleted streams are skipped
+            health_sample(stream_id + 1, "360", 300, 0),
+        ])
+        .await?;
+        repo.add_health_samples(&[health_sample(stream_id, "720", 1000, 10)])
+            .await?;
+
+        let latest = repo.list_latest_health(stream_id).await?;
+        let latest: Vec<(&str, i32)> = latest
+            .iter()
+            .map(|sample| (sample.resolution.as_str(), sample.bitrate_kbps))
+            .collect();
+        assert_eq!(latest, vec![("360", 300), ("720", 1000)]);
+        assert!(repo.list_latest_health(stream_id + 1).await?.is_empty());
+
+        let summary = repo.get_health_summary(stream_id).await?;
+        assert_eq!(summary.len(), 2);
+        assert_eq!(
+            summary[1],
+            StreamHealthSummary {
+                resolution: String::from("720"),
+                avg_bitrate_kbps: 1500,
+                min_bitrate_kbps: 1000,
+                dropped_frames: 10,
+                qos_events: 0,
+                warnings: 0,
+                sample_count: 2,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn peak_viewers(ctx: &mut AsyncContext) -> Result<()> {
+        let stream_id = create_dummy_stream(ctx).await?;
+        let repo = PgStreamTelemetryRepo::new(ctx.pg_pool.clone());
+
+        assert_eq!(repo.get_peak_viewers(stream_id).await?, 0);
+        repo.update_peak_viewers(stream_id, 5).await?;
+        repo.update_peak_viewers(stream_id, 3).await?;
+        assert_eq!(repo.get_peak_viewers(stream_id).await?, 5);
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn track_viewers(ctx: &mut AsyncContext) -> Result<()> {
+        let redis_pool = setup_redis_pool().await.unwrap();
+        let tracker = RedisViewerTracker::new(redis_pool, format!("{}:", ctx.test_db_name));
+        let window = Duration::from_secs(30);
+
+        assert_eq!(tracker.count_viewers(1, window).await?, 0);
+        tracker.mark_viewer(1, "user:1", window).await?;
+        tracker.mark_viewer(1, "user:1", window).await?;
+        tracker.mark_viewer(1, "ip:127.0.0.1", window).await?;
+        tracker.mark_viewer(2, "user:1", window).await?;
+
+        assert_eq!(tracker.count_viewers(1, window).await?, 2);
+        assert_eq!(tracker.count_viewers(2, window).await?, 1);
+
+        Ok(())
+    }
+
+    fn health_sample(
+        stream_id: i32,
+        resolution: &str,
+        bitrate_kbps: i32,
+        dropped_frames: i64,
+    ) -> StreamHealthSample {
+        StreamHealthSample {
+            id: -1,
+            stream_id,
+            resolution: resolution.to_string(),
+            bitrate_kbps,
+            dropped_frames,
+            qos_events: 0,
+            buffering_percent: None,
+            warnings: 0,
+            recorded_at: Utc::now(),
+        }
+    }
+
+    async fn create_dummy_stream(ctx: &AsyncContext) -> Result<i32> {
+        let video = Video {
+            id: 1,
+            artist_id: 1,
+            visibility: VideoVisibility::All,
+            name: String::from("Test video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+
+        let mut tx = ctx.pg_pool.begin().await?;
+        let video = PgVideoRepo::new(ctx.pg_pool.clone())
+            .save_video(video, &mut tx)
+            .await?;
+        tx.commit().await?;
+
+        PgStreamRepo::new(ctx.pg_pool.clone())
+            .add_stream(LiveStream {
+                id: -1,
+                video_id: video.id,
+                start_time: Local::now(),
+                status: LiveStreamStatus::Running,
+                source: LiveStreamSource::File,
+                recording_video_id: None,
+            })
+            .await
+    }
+}
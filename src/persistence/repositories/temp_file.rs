@@ -1,7 +1,9 @@
 use crate::persistence::entities::error::{DatabaseError, MapToDatabaseError};
-use crate::persistence::entities::temp_file::TempFile;
+use crate::persistence::entities::temp_file::{TempFile, TempUpload};
 use crate::persistence::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use log::warn;
 use sqlx::{PgPool, Postgres, Transaction};
 use std::path::Path;
 use tempfile::NamedTempFile;
@@ -26,6 +28,43 @@ pub trait TempFileRepo {
     /// # Returns
     /// `bool` if deletion was successful
     async fn delete_file(&self, file_id: i32, user_id: i32) -> Result<Option<TempFile>>;
+    /// Creates an empty file on the file system and stores the new upload
+    ///
+    /// # Returns
+    /// ID of the new upload
+    async fn add_upload(&self, upload: TempUpload) -> Result<i32>;
+    async fn get_upload(&self, upload_id: i32, user_id: i32) -> Result<Option<TempUpload>>;
+    /// Fetches the upload and locks it until the end of the transaction,
+    /// so the chunks of the same upload are written one after another
+    async fn get_upload_for_update(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        tx: &mut Transaction<Postgres>,
+    ) -> Result<Option<TempUpload>>;
+    /// Moves offset of the upload, only if the stored offset matches `current_offset`
+    ///
+    /// # Returns
+    /// `true` if the offset was updated
+    async fn update_upload_offset(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        current_offset: i64,
+        new_offset: i64,
+        tx: &mut Transaction<Postgres>,
+    ) -> Result<bool>;
+    /// Removes the uploads, which didn't receive any chunk since `inactive_since`,
+    /// together with their files
+    ///
+    /// # Returns
+    /// Number of the removed uploads
+    async fn delete_inactive_uploads(&self, inactive_since: DateTime<Utc>) -> Result<u64>;
+    /// Removes the upload and creates temporary file pointing to the uploaded file
+    ///
+    /// # Returns
+    /// ID of the new temporary file
+    async fn finalize_upload(&self, upload_id: i32, user_id: i32) -> Result<i32>;
 }
 
 pub struct PgTempFileRepo {
@@ -115,6 +154,9 @@ impl TempFileRepo for PgTempFileRepo {
         sqlx::query!("DELETE FROM temp_file")
             .execute(&mut *transaction)
             .await?;
+        sqlx::query!("DELETE FROM temp_upload")
+            .execute(&mut *transaction)
+            .await?;
         tokio::fs::remove_dir_all(temp_directory_path)
             .await
             .db_error("Failed to delete temporary files!")?;
@@ -139,6 +181,145 @@ impl TempFileRepo for PgTempFileRepo {
 
         Ok(deleted)
     }
+
+    async fn add_upload(&self, upload: TempUpload) -> Result<i32> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let result = sqlx::query!(
+            r#"INSERT INTO
+            temp_upload (user_id, file_path, upload_length)
+            VALUES ($1, $2, $3)
+            RETURNING id"#,
+            upload.user_id,
+            upload.file_path,
+            upload.upload_length
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        tokio::fs::File::create(&upload.file_path)
+            .await
+            .db_error("Failed to create the upload file")?;
+
+        transaction.commit().await?;
+
+        Ok(result.id)
+    }
+
+    async fn get_upload(&self, upload_id: i32, user_id: i32) -> Result<Option<TempUpload>> {
+        let result = sqlx::query_as!(
+            TempUpload,
+            r#"
+            SELECT u.id, u.user_id, u.file_path, u.upload_length, u.upload_offset
+            FROM temp_upload u
+            WHERE u.id=$1 AND u.user_id=$2;
+        "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn get_upload_for_update(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        tx: &mut Transaction<Postgres>,
+    ) -> Result<Option<TempUpload>> {
+        let result = sqlx::query_as!(
+            TempUpload,
+            r#"
+            SELECT u.id, u.user_id, u.file_path, u.upload_length, u.upload_offset
+            FROM temp_upload u
+            WHERE u.id=$1 AND u.user_id=$2
+            FOR UPDATE;
+        "#,
+            upload_id,
+            user_id
+        )
+        .fetch_optional(tx.as_mut())
+        .await?;
+
+        Ok(result)
+    }
+
+    async fn update_upload_offset(
+        &self,
+        upload_id: i32,
+        user_id: i32,
+        current_offset: i64,
+        new_offset: i64,
+        tx: &mut Transaction<Postgres>,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE temp_upload SET upload_offset=$4, updated_at=NOW()
+            WHERE id=$1 AND user_id=$2 AND upload_offset=$3
+        "#,
+            upload_id,
+            user_id,
+            current_offset,
+            new_offset
+        )
+        .execute(tx.as_mut())
+        .await
+        .db_error("Failed to update the upload offset")?;
+
+        Ok(result.rows_affected() == 1)
+    }
+
+    async fn delete_inactive_uploads(&self, inactive_since: DateTime<Utc>) -> Result<u64> {
+        let file_paths = sqlx::query_scalar!(
+            "DELETE FROM temp_upload WHERE updated_at < $1 RETURNING file_path",
+            inactive_since
+        )
+        .fetch_all(&self.pg_pool)
+        .await
+        .db_error("Failed to delete inactive uploads")?;
+
+        for file_path in &file_paths {
+            if let Err(err) = tokio::fs::remove_file(file_path).await {
+                warn!("Failed to delete the file of the inactive upload {file_path}: {err}");
+            }
+        }
+
+        Ok(file_paths.len() as u64)
+    }
+
+    async fn finalize_upload(&self, upload_id: i32, user_id: i32) -> Result<i32> {
+        let mut transaction = self.pg_pool.begin().await?;
+
+        let upload = sqlx::query!(
+            r#"
+            DELETE FROM temp_upload
+            WHERE id=$1 AND user_id=$2 AND upload_offset=upload_length
+            RETURNING user_id, file_path
+        "#,
+            upload_id,
+            user_id
+        )
+        .fetch_one(&mut *transaction)
+        .await
+        .db_error("Upload doesn't exist or is not complete")?;
+
+        let result = sqlx::query!(
+            r#"INSERT INTO
+            temp_file (user_id, file_path)
+            VALUES ($1, $2)
+            RETURNING id"#,
+            upload.user_id,
+            upload.file_path
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+
+        transaction.commit().await?;
+
+        Ok(result.id)
+    }
 }
 
 #[cfg(test)]
@@ -229,6 +410,126 @@ mod test {
         Ok(())
     }
 
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn upload_in_chunks(ctx: &mut EmptyAsyncContext) {
+        create_dummy_user(&ctx.pg_pool)
+            .await
+            .expect("Failed to create dummy user");
+
+        let repo = create_repo(ctx.pg_pool.clone());
+        let path = get_temp_file_path(ctx);
+        let upload = TempUpload {
+            id: -1,
+            user_id: 1,
+            file_path: path.clone(),
+            upload_length: 10,
+            upload_offset: 0,
+        };
+
+        let upload_id = repo
+            .add_upload(upload)
+            .await
+            .expect("Failed to create the upload");
+        tokio::fs::read(&path)
+            .await
+            .expect("Upload file should exist");
+
+        let finalize_result = repo.finalize_upload(upload_id, 1).await;
+        assert!(
+            finalize_result.is_err(),
+            "It's possible to finalize incomplete upload"
+        );
+
+        let mut tx = ctx.pg_pool.begin().await.unwrap();
+        let locked = repo
+            .get_upload_for_update(upload_id, 1, &mut tx)
+            .await
+            .expect("Failed to lock the upload")
+            .expect("Upload doesn't exist");
+        assert_eq!(locked.upload_offset, 0);
+
+        let updated = repo
+            .update_upload_offset(upload_id, 1, 5, 10, &mut tx)
+            .await
+            .expect("Failed to update the offset");
+        assert!(!updated, "Offset was updated even if it doesn't match");
+
+        let updated = repo
+            .update_upload_offset(upload_id, 1, 0, 10, &mut tx)
+            .await
+            .expect("Failed to update the offset");
+        assert!(updated, "Offset was not updated");
+        tx.commit().await.unwrap();
+
+        let upload = repo
+            .get_upload(upload_id, 1)
+            .await
+            .expect("Failed to fetch the upload")
+            .expect("Upload doesn't exist");
+        assert_eq!(upload.upload_offset, 10);
+
+        let temp_file_id = repo
+            .finalize_upload(upload_id, 1)
+            .await
+            .expect("Failed to finalize the upload");
+        let temp_file = repo
+            .get_file(temp_file_id, 1)
+            .await
+            .expect("Upload was not converted to temporary file");
+        assert_eq!(temp_file.file_path, path);
+        assert!(
+            repo.get_upload(upload_id, 1)
+                .await
+                .expect("Failed to fetch the upload")
+                .is_none(),
+            "Upload should be removed after finalization"
+        );
+    }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn delete_inactive_uploads(ctx: &mut EmptyAsyncContext) {
+        create_dummy_user(&ctx.pg_pool)
+            .await
+            .expect("Failed to create dummy user");
+
+        let repo = create_repo(ctx.pg_pool.clone());
+        let path = get_temp_file_path(ctx);
+        let upload = TempUpload {
+            id: -1,
+            user_id: 1,
+            file_path: path.clone(),
+            upload_length: 10,
+            upload_offset: 0,
+        };
+        let upload_id = repo
+            .add_upload(upload)
+            .await
+            .expect("Failed to create the upload");
+
+        let deleted = repo
+            .delete_inactive_uploads(Utc::now() - chrono::Duration::hours(1))
+            .await
+            .expect("Failed to delete inactive uploads");
+        assert_eq!(deleted, 0, "Active upload was deleted");
+
+        let deleted = repo
+            .delete_inactive_uploads(Utc::now() + chrono::Duration::seconds(1))
+            .await
+            .expect("Failed to delete inactive uploads");
+        assert_eq!(deleted, 1);
+        assert!(repo
+            .get_upload(upload_id, 1)
+            .await
+            .expect("Failed to fetch the upload")
+            .is_none());
+        assert!(
+            tokio::fs::metadata(&path).await.is_err(),
+            "File of the inactive upload was not deleted"
+        );
+    }
+
     fn create_entity(id: Option<i32>, temp_file_path: &str) -> (NamedTempFile, TempFile) {
         let temp_file = NamedTempFile::new().expect("Failed to created temporary file");
 
@@ -0,0 +1,231 @@
+use crate::persistence::entities::two_factor::UserTotp;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[async_trait]
+pub trait TwoFactorRepo {
+    async fn get_totp(&self, user_id: i32) -> Result<Option<UserTotp>>;
+    /// Saves the secret, which is not enabled until it's confirmed.
+    /// Secret of the enabled two-factor authentication is not replaced.
+    async fn save_pending_totp(&self, user_id: i32, secret: &str) -> Result<bool>;
+    /// Enables the pending secret and replaces the recovery codes of the user
+    async fn enable_totp(
+        &self,
+        user_id: i32,
+        used_step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool>;
+    /// Marks the step of the accepted code as used.
+    /// Fails, when the code of this or any later step was used before.
+    async fn use_totp_step(&self, user_id: i32, step: i64) -> Result<bool>;
+    /// Marks the recovery code as used, fails if there is no such unused code
+    async fn use_recovery_code(&self, user_id: i32, code_hash: &str) -> Result<bool>;
+    async fn count_recovery_codes(&self, user_id: i32) -> Result<i64>;
+    /// Removes the secret and the recovery codes of the user
+    async fn delete_totp(&self, user_id: i32) -> Result<bool>;
+    async fn list_enabled_user_ids(&self) -> Result<Vec<i32>>;
+}
+
+pub struct PgTwoFactorRepo {
+    pg_pool: PgPool,
+}
+
+impl PgTwoFactorRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl TwoFactorRepo for PgTwoFactorRepo {
+    async fn get_totp(&self, user_id: i32) -> Result<Option<UserTotp>> {
+        let totp = sqlx::query_as!(
+            UserTotp,
+            "SELECT user_id, secret, enabled_at, last_used_step FROM user_totp WHERE user_id = $1",
+            user_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(totp)
+    }
+
+    async fn save_pending_totp(&self, user_id: i32, secret: &str) -> Result<bool> {
+        let rows_affected = sqlx::query!(
+            r#"INSERT INTO user_totp (user_id, secret)
+            VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = 0
+            WHERE user_totp.enabled_at IS NULL"#,
+            user_id,
+            secret
+        )
+        .execute(&self.pg_pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+
+    async fn enable_totp(
+        &self,
+        user_id: i32,
+        used_step: i64,
+        recovery_code_hashes: &[String],
+    ) -> Result<bool> {
+        let mut tx = self.pg_pool.begin().await?;
+
+        let rows_affected = sqlx::query!(
+            r#"UPDATE user_totp SET enabled_at = now(), last_used_step = $2
+            WHERE user_id = $1 AND enabled_at IS NULL"#,
+            user_id,
+            used_step
+        )
+        .execute(tx.as_mut())
+        .await?
+        .rows_affected();
+        if rows_affected == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query!("DELETE FROM user_recovery_code WHERE user_id = $1", user_id)
+            .execute(tx.as_mut())
+            .await?;
+        sqlx::query!(
+            r#"INSERT INTO user_recovery_code (user_id, code_hash)
+            SELECT $1, code_hash FROM UNNEST($2::TEXT[]) AS code_hash"#,
+            user_id,
+            recovery_code_hashes
+        )
+        .execute(tx.as_mut())
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn use_totp_step(&self, user_id: i32, step: i64) -> Result<bool> {
+        let rows_affected = sqlx::query!(
+            r#"UPDATE user_totp SET last_used_step = $2
+            WHERE user_id = $1 AND enabled_at IS NOT NULL AND last_used_step < $2"#,
+            user_id,
+            step
+        )
+        .execute(&self.pg_pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+
+    async fn use_recovery_code(&self, user_id: i32, code_hash: &str) -> Result<bool> {
+        let rows_affected = sqlx::query!(
+            r#"UPDATE user_recovery_code SET used_at = now()
+            WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL"#,
+            user_id,
+            code_hash
+        )
+        .execute(&self.pg_pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+
+    async fn count_recovery_codes(&self, user_id: i32) -> Result<i64> {
+        let count = sqlx::query_scalar!(
+            r#"SELECT COUNT(*) AS "count!" FROM user_recovery_code
+            WHERE user_id = $1 AND used_at IS NULL"#,
+            user_id
+        )
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn delete_totp(&self, user_id: i32) -> Result<bool> {
+        let mut tx = self.pg_pool.begin().await?;
+
+        sqlx::query!("DELETE FROM user_recovery_code WHERE user_id = $1", user_id)
+            .execute(tx.as_mut())
+            .await?;
+        let rows_affected = sqlx::query!("DELETE FROM user_totp WHERE user_id = $1", user_id)
+            .execute(tx.as_mut())
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok(rows_affected > 0)
+    }
+
+    async fn list_enabled_user_ids(&self) -> Result<Vec<i32>> {
+        let user_ids = sqlx::query_scalar!(
+            "SELECT user_id FROM user_totp WHERE enabled_at IS NOT NULL ORDER BY user_id"
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(user_ids)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use test_context::test_context;
+
+    const USER_ID: i32 = 1;
+
+    fn code_hashes() -> Vec<String> {
+        vec![String::from("first_hash"), String::from("second_hash")]
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn enable_totp(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = PgTwoFactorRepo::new(ctx.pg_pool.clone());
+
+        assert!(repo.save_pending_totp(USER_ID, "FIRSTSECRET").await?);
+        assert!(repo.save_pending_totp(USER_ID, "SECONDSECRET").await?);
+        assert!(repo.list_enabled_user_ids().await?.is_empty());
+
+        assert!(repo.enable_totp(USER_ID, 10, &code_hashes()).await?);
+        assert!(!repo.enable_totp(USER_ID, 11, &code_hashes()).await?);
+        // Secret of the enabled two-factor authentication can't be replaced
+        assert!(!repo.save_pending_totp(USER_ID, "THIRDSECRET").await?);
+
+        let totp = repo.get_totp(USER_ID).await?.unwrap();
+        assert_eq!(totp.secret, "SECONDSECRET");
+        assert!(totp.enabled_at.is_some());
+        assert_eq!(repo.list_enabled_user_ids().await?, vec![USER_ID]);
+        assert_eq!(repo.count_recovery_codes(USER_ID).await?, 2);
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn codes_are_used_once(ctx: &mut AsyncContext) -> Result<()> {
+        let repo = PgTwoFactorRepo::new(ctx.pg_pool.clone());
+        repo.save_pending_totp(USER_ID, "SECRET").await?;
+        repo.enable_totp(USER_ID, 10, &code_hashes()).await?;
+
+        assert!(!repo.use_totp_step(USER_ID, 10).await?);
+        assert!(repo.use_totp_step(USER_ID, 11).await?);
+        assert!(!repo.use_totp_step(USER_ID, 11).await?);
+
+        assert!(repo.use_recovery_code(USER_ID, "first_hash").await?);
+        assert!(!repo.use_recovery_code(USER_ID, "first_hash").await?);
+        assert!(!repo.use_recovery_code(USER_ID, "unknown_hash").await?);
+        assert_eq!(repo.count_recovery_codes(USER_ID).await?, 1);
+
+        assert!(repo.delete_totp(USER_ID).await?);
+        assert!(repo.get_totp(USER_ID).await?.is_none());
+        assert_eq!(repo.count_recovery_codes(USER_ID).await?, 0);
+
+        Ok(())
+    }
+}
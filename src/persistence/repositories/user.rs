@@ -1,6 +1,7 @@
-use crate::persistence::entities::user::{LikedVideo, User, UserName};
+use crate::persistence::entities::user::{LikedVideo, PasswordResetToken, User, UserName};
 use crate::persistence::Result;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use std::fmt::Debug;
 
@@ -19,6 +20,27 @@ pub trait UserRepositoryTrait: Debug {
     async fn liked_videos_by_user(&self, user_id: i32) -> Result<Vec<LikedVideo>>;
     async fn like_video(&self, user_id: i32, video_id: i32) -> Result<()>;
     async fn unlike_video(&self, user_id: i32, video_id: i32) -> Result<()>;
+    /// Marks the email as verified, if it's still the email of the user.
+    /// Returns `false` if the email was changed or was verified before.
+    async fn verify_email(&self, user_id: i32, email: &str) -> Result<bool>;
+    /// Claims sending of the verification link, the claim fails when the email
+    /// is already verified or the previous link was sent less than `cooldown_seconds` ago
+    async fn claim_verification_mail(&self, user_id: i32, cooldown_seconds: i64) -> Result<bool>;
+    /// Releases the claim of the verification link, which failed to be sent,
+    /// so the link can be sent again without waiting for the cooldown
+    async fn release_verification_mail(&self, user_id: i32) -> Result<()>;
+    async fn update_password(&self, user_id: i32, password_hash: &str) -> Result<()>;
+    /// Saves the reset token of the user, replacing the previous one. Fails, when the previous
+    /// token was created less than `cooldown_seconds` ago.
+    async fn create_password_reset(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        cooldown_seconds: i64,
+    ) -> Result<bool>;
+    /// Removes the reset token, so it can be used only once
+    async fn consume_password_reset(&self, token_hash: &str) -> Result<Option<PasswordResetToken>>;
 }
 
 #[derive(Debug, Clone)]
@@ -40,7 +62,7 @@ impl UserRepositoryTrait for UserRepository {
             r#"
             INSERT INTO user_table (username, password_hash, email, profile_picture_path, artist_id, paying_member_id)
             VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin
+            RETURNING id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin, email_verified_at
             "#,
             user.username,
             user.password_hash,
@@ -59,7 +81,7 @@ impl UserRepositoryTrait for UserRepository {
         let user = sqlx::query_as!(
             User,
             r#"
-            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin
+            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin, email_verified_at
             FROM user_table
             WHERE id = $1
             "#,
@@ -75,7 +97,7 @@ impl UserRepositoryTrait for UserRepository {
         let user = sqlx::query_as!(
             User,
             r#"
-            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin
+            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin, email_verified_at
             FROM user_table
             WHERE username = $1
             "#,
@@ -91,7 +113,7 @@ impl UserRepositoryTrait for UserRepository {
         let user = sqlx::query_as!(
             User,
             r#"
-            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin
+            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin, email_verified_at
             FROM user_table
             WHERE email = $1
             "#,
@@ -131,9 +153,10 @@ impl UserRepositoryTrait for UserRepository {
                 profile_picture_path = $4,
                 artist_id = $5,
                 paying_member_id = $6,
-                is_admin = $7
-            WHERE id = $8
-            RETURNING id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin
+                is_admin = $7,
+                email_verified_at = $8
+            WHERE id = $9
+            RETURNING id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin, email_verified_at
             "#,
             user.username,
             user.password_hash,
@@ -142,6 +165,7 @@ impl UserRepositoryTrait for UserRepository {
             user.artist_id,
             user.paying_member_id,
             user.is_admin,
+            user.email_verified_at,
             user.id
         )
             .fetch_optional(&self.pool)
@@ -169,7 +193,7 @@ impl UserRepositoryTrait for UserRepository {
         let users = sqlx::query_as!(
             User,
             r#"
-            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin
+            SELECT id, username, password_hash, email, profile_picture_path, artist_id, paying_member_id, is_admin, email_verified_at
             FROM user_table
             ORDER BY username ASC
             "#
@@ -256,6 +280,107 @@ impl UserRepositoryTrait for UserRepository {
 
         Ok(())
     }
+
+    async fn verify_email(&self, user_id: i32, email: &str) -> Result<bool> {
+        let rows_affected = sqlx::query!(
+            r#"
+            UPDATE user_table
+            SET email_verified_at = now()
+            WHERE id = $1 AND email = $2 AND email_verified_at IS NULL
+            "#,
+            user_id,
+            email
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+
+    async fn claim_verification_mail(&self, user_id: i32, cooldown_seconds: i64) -> Result<bool> {
+        let rows_affected = sqlx::query!(
+            r#"
+            INSERT INTO email_verification_mail (user_id)
+            SELECT id FROM user_table WHERE id = $1 AND email_verified_at IS NULL
+            ON CONFLICT (user_id) DO UPDATE
+            SET sent_at = now()
+            WHERE email_verification_mail.sent_at <= now() - make_interval(secs => $2)
+            "#,
+            user_id,
+            cooldown_seconds as f64
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+
+    async fn release_verification_mail(&self, user_id: i32) -> Result<()> {
+        sqlx::query!(
+            "DELETE FROM email_verification_mail WHERE user_id = $1",
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    async fn update_password(&self, user_id: i32, password_hash: &str) -> Result<()> {
+        sqlx::query!(
+            "UPDATE user_table SET password_hash = $1 WHERE id = $2",
+            password_hash,
+            user_id
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_password_reset(
+        &self,
+        user_id: i32,
+        token_hash: &str,
+        expires_at: DateTime<Utc>,
+        cooldown_seconds: i64,
+    ) -> Result<bool> {
+        let rows_affected = sqlx::query!(
+            r#"
+            INSERT INTO password_reset_token (user_id, token_hash, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id) DO UPDATE
+            SET token_hash = EXCLUDED.token_hash, expires_at = EXCLUDED.expires_at, created_at = now()
+            WHERE password_reset_token.created_at <= now() - make_interval(secs => $4)
+            "#,
+            user_id,
+            token_hash,
+            expires_at,
+            cooldown_seconds as f64
+        )
+        .execute(&self.pool)
+        .await?
+        .rows_affected();
+
+        Ok(rows_affected > 0)
+    }
+
+    async fn consume_password_reset(&self, token_hash: &str) -> Result<Option<PasswordResetToken>> {
+        let token = sqlx::query_as!(
+            PasswordResetToken,
+            r#"
+            DELETE FROM password_reset_token
+            WHERE token_hash = $1
+            RETURNING user_id, expires_at
+            "#,
+            token_hash
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(token)
+    }
 }
 
 #[cfg(test)]
@@ -264,6 +389,7 @@ mod tests {
     use crate::persistence::entities::user::User;
     use crate::persistence::repositories::user::{UserRepository, UserRepositoryTrait};
     use crate::persistence::Result;
+    use chrono::Utc;
     use test_context::test_context;
 
     #[test_context(EmptyAsyncContext)]
@@ -280,6 +406,7 @@ mod tests {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         };
 
         let created_user = user_repo.create_user(new_user.clone()).await?;
@@ -305,6 +432,7 @@ mod tests {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         };
 
         let created_user = user_repo.create_user(new_user).await?;
@@ -330,6 +458,7 @@ mod tests {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         };
 
         let created_user = user_repo.create_user(new_user).await?;
@@ -357,6 +486,7 @@ mod tests {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         };
 
         let created_user = user_repo.create_user(new_user).await?;
@@ -382,6 +512,7 @@ mod tests {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         };
 
         let created_user = user_repo.create_user(new_user.clone()).await?;
@@ -410,6 +541,7 @@ mod tests {
             artist_id: None,
             paying_member_id: None,
             is_admin: false,
+            email_verified_at: None,
         };
         let created_user = user_repo.create_user(new_user).await?;
 
@@ -421,4 +553,155 @@ mod tests {
 
         Ok(())
     }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn test_verify_email(context: &EmptyAsyncContext) -> Result<()> {
+        let user_repo = UserRepository::new(context.pg_pool.clone());
+
+        let new_user = User {
+            id: 0, // id will be auto-generated
+            username: "verify_test".to_string(),
+            password_hash: Some("hashed_password".to_string()),
+            email: "verify_test@example.com".to_string(),
+            profile_picture_path: None,
+            artist_id: None,
+            paying_member_id: None,
+            is_admin: false,
+            email_verified_at: None,
+        };
+        let created_user = user_repo.create_user(new_user).await?;
+        assert!(created_user.email_verified_at.is_none());
+
+        // Link sent to the previous email must not verify the current one
+        assert!(
+            !user_repo
+                .verify_email(created_user.id, "old@example.com")
+                .await?
+        );
+        assert!(
+            user_repo
+                .verify_email(created_user.id, &created_user.email)
+                .await?
+        );
+        assert!(
+            !user_repo
+                .verify_email(created_user.id, &created_user.email)
+                .await?
+        );
+
+        let fetched_user = user_repo.get_user_by_id(created_user.id).await?.unwrap();
+        assert!(fetched_user.email_verified_at.is_some());
+
+        Ok(())
+    }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn test_claim_verification_mail(context: &EmptyAsyncContext) -> Result<()> {
+        let user_repo = UserRepository::new(context.pg_pool.clone());
+
+        let new_user = User {
+            id: 0, // id will be auto-generated
+            username: "claim_test".to_string(),
+            password_hash: Some("hashed_password".to_string()),
+            email: "claim_test@example.com".to_string(),
+            profile_picture_path: None,
+            artist_id: None,
+            paying_member_id: None,
+            is_admin: false,
+            email_verified_at: None,
+        };
+        let created_user = user_repo.create_user(new_user).await?;
+
+        assert!(
+            user_repo
+                .claim_verification_mail(created_user.id, 60)
+                .await?
+        );
+        assert!(
+            !user_repo
+                .claim_verification_mail(created_user.id, 60)
+                .await?
+        );
+        assert!(
+            user_repo
+                .claim_verification_mail(created_user.id, 0)
+                .await?
+        );
+        user_repo.release_verification_mail(created_user.id).await?;
+        assert!(
+            user_repo
+                .claim_verification_mail(created_user.id, 60)
+                .await?,
+            "Released claim should not wait for the cooldown"
+        );
+
+        user_repo
+            .verify_email(created_user.id, &created_user.email)
+            .await?;
+        assert!(
+            !user_repo
+                .claim_verification_mail(created_user.id, 0)
+                .await?
+        );
+
+        Ok(())
+    }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn test_password_reset(context: &EmptyAsyncContext) -> Result<()> {
+        let user_repo = UserRepository::new(context.pg_pool.clone());
+
+        let new_user = User {
+            id: 0, // id will be auto-generated
+            username: "reset_test".to_string(),
+            password_hash: Some("hashed_password".to_string()),
+            email: "reset_test@example.com".to_string(),
+            profile_picture_path: None,
+            artist_id: None,
+            paying_member_id: None,
+            is_admin: false,
+            email_verified_at: None,
+        };
+        let created_user = user_repo.create_user(new_user).await?;
+        let expires_at = Utc::now() + chrono::Duration::hours(1);
+
+        assert!(
+            user_repo
+                .create_password_reset(created_user.id, "first_hash", expires_at, 60)
+                .await?
+        );
+        assert!(
+            !user_repo
+                .create_password_reset(created_user.id, "second_hash", expires_at, 60)
+                .await?
+        );
+        assert!(
+            user_repo
+                .create_password_reset(created_user.id, "third_hash", expires_at, 0)
+                .await?
+        );
+
+        // Only the newest token is valid and only once
+        assert!(user_repo
+            .consume_password_reset("first_hash")
+            .await?
+            .is_none());
+        let token = user_repo.consume_password_reset("third_hash").await?;
+        assert_eq!(token.map(|token| token.user_id), Some(created_user.id));
+        assert!(user_repo
+            .consume_password_reset("third_hash")
+            .await?
+            .is_none());
+
+        user_repo
+            .update_password(created_user.id, "new_hash")
+            .await?;
+        let user = user_repo.get_user_by_id(created_user.id).await?.unwrap();
+        assert_eq!(user.password_hash, Some("new_hash".to_string()));
+
+        Ok(())
+    }
 }
@@ -1,5 +1,8 @@
+use crate::business::models::video::VideoSort;
 use crate::persistence::entities::error::MapToDatabaseError;
-use crate::persistence::entities::video::{PatchVideo, Video, VideoVisibility};
+use crate::persistence::entities::video::{
+    PatchVideo, Video, VideoSearch, VideoSearchHit, VideoVisibility, HIGHLIGHT_END, HIGHLIGHT_START,
+};
 use crate::persistence::Result;
 use async_trait::async_trait;
 use sqlx::{PgPool, Postgres, QueryBuilder, Transaction};
@@ -27,13 +30,47 @@ pub trait VideoRepo {
         video_id: i32,
         tx: Option<&mut Transaction<Postgres>>,
     ) -> Result<i32>;
+    /// Fetches one page of the videos
+    ///
+    /// # Arguments
+    ///
+    /// * `filter` - only videos in all these categories are returned
+    /// * `after` - ID of the last video of the previous page
     async fn fetch_videos(
         &self,
-        ord: Option<&str>,
+        sort: VideoSort,
         filter: Option<Vec<i32>>,
-        offset: Option<i32>,
+        after: Option<i32>,
+        limit: i64,
     ) -> Result<Vec<Video>>;
     async fn fetch_liked_videos(&self, ids: Vec<i32>) -> Result<Vec<Video>>;
+    /// Searches videos by name, description, categories and artist, the most relevant first
+    async fn search_videos(&self, search: VideoSearch) -> Result<Vec<VideoSearchHit>>;
+    /// Fetches one page of the videos of the artist, the newest first
+    ///
+    /// # Arguments
+    ///
+    /// * `visibilities` - only videos with these visibilities are returned
+    /// * `include_drafts` - whether the unpublished drafts are returned too
+    async fn fetch_artist_videos(
+        &self,
+        artist_id: i32,
+        visibilities: Vec<VideoVisibility>,
+        include_drafts: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Video>>;
+    async fn count_artist_videos(
+        &self,
+        artist_id: i32,
+        visibilities: Vec<VideoVisibility>,
+        include_drafts: bool,
+    ) -> Result<i64>;
+    /// Publishes the draft video of the artist
+    ///
+    /// # Returns
+    /// `false` if the artist has no such draft
+    async fn publish_video(&self, video_id: i32, artist_id: i32) -> Result<bool>;
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +83,18 @@ impl PgVideoRepo {
         Self { pg_pool }
     }
 
+    /// Returns SQL expression, by which the videos are sorted and whether the order is descending.
+    /// `id` is always used as a tiebreaker.
+    fn sort_key(sort: VideoSort) -> (&'static str, bool) {
+        match sort {
+            VideoSort::Newest => ("id", true),
+            VideoSort::Oldest => ("id", false),
+            VideoSort::MostLiked => ("like_count", true),
+            VideoSort::MostViewed => ("view_count", true),
+            VideoSort::Alphabetical => ("lower(name)", false),
+        }
+    }
+
     async fn remove_old_file<'a>(file_path: &str) -> Result<()> {
         tokio::fs::remove_file(file_path)
             .await
@@ -66,7 +115,15 @@ impl VideoRepo for PgVideoRepo {
             name,
             file_path,
             thumbnail_path,
-            description FROM video ORDER BY id"#
+            description,
+            duration_ms,
+            width,
+            height,
+            frame_rate,
+            container,
+            video_codec,
+            audio_codec,
+            draft FROM video ORDER BY id"#
         )
         .fetch_all(&self.pg_pool)
         .await?;
@@ -84,17 +141,34 @@ impl VideoRepo for PgVideoRepo {
                 file_path,
                 thumbnail_path,
                 description,
-                visibility
-            ) VALUES ($1, $2, $3, $4, $5, $6) 
+                visibility,
+                duration_ms,
+                width,
+                height,
+                frame_rate,
+                container,
+                video_codec,
+                audio_codec,
+                draft
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
             RETURNING id, artist_id, visibility AS "visibility: VideoVisibility",
-            name, file_path, thumbnail_path, description 
+            name, file_path, thumbnail_path, description, duration_ms, width, height,
+            frame_rate, container, video_codec, audio_codec, draft
         "#,
             video.artist_id,
             video.name,
             video.file_path,
             video.thumbnail_path,
             video.description,
-            video.visibility as VideoVisibility
+            video.visibility as VideoVisibility,
+            video.duration_ms,
+            video.width,
+            video.height,
+            video.frame_rate,
+            video.container,
+            video.video_codec,
+            video.audio_codec,
+            video.draft
         )
         .fetch_one(tx.as_mut())
         .await?;
@@ -171,6 +245,29 @@ impl VideoRepo for PgVideoRepo {
 
             query.push(" description = ");
             query.push_bind(description);
+
+            first = false;
+        }
+
+        if let Some(metadata) = new_video.metadata {
+            if !first {
+                query.push(",");
+            };
+
+            query.push(" duration_ms = ");
+            query.push_bind(metadata.duration_ms);
+            query.push(", width = ");
+            query.push_bind(metadata.width);
+            query.push(", height = ");
+            query.push_bind(metadata.height);
+            query.push(", frame_rate = ");
+            query.push_bind(metadata.frame_rate);
+            query.push(", container = ");
+            query.push_bind(metadata.container);
+            query.push(", video_codec = ");
+            query.push_bind(metadata.video_codec);
+            query.push(", audio_codec = ");
+            query.push_bind(metadata.audio_codec);
         }
 
         query.push(", visibility = ");
@@ -205,7 +302,8 @@ impl VideoRepo for PgVideoRepo {
             Video,
             r#"DELETE FROM video WHERE id = $1 AND artist_id = $2
             RETURNING id, artist_id, visibility AS "visibility: VideoVisibility",
-            name, file_path, thumbnail_path, description "#,
+            name, file_path, thumbnail_path, description, duration_ms, width, height,
+            frame_rate, container, video_codec, audio_codec, draft "#,
             video_id,
             user_id
         )
@@ -238,7 +336,15 @@ impl VideoRepo for PgVideoRepo {
             name, 
             file_path, 
             thumbnail_path, 
-            description
+            description,
+            duration_ms,
+            width,
+            height,
+            frame_rate,
+            container,
+            video_codec,
+            audio_codec,
+            draft
             FROM video WHERE id = $1
             "#,
             video_id
@@ -270,62 +376,69 @@ impl VideoRepo for PgVideoRepo {
 
     async fn fetch_videos(
         &self,
-        ord: Option<&str>,
+        sort: VideoSort,
         filter: Option<Vec<i32>>,
-        offset: Option<i32>,
+        after: Option<i32>,
+        limit: i64,
     ) -> Result<Vec<Video>> {
+        let (sort_key, descending) = Self::sort_key(sort);
+        let (comparison, direction) = if descending {
+            ("<", "DESC")
+        } else {
+            (">", "ASC")
+        };
+
         let mut query = QueryBuilder::new(
-            r#"SELECT
+            r#"WITH listed AS (
+                SELECT
+                    video.*,
+                    (SELECT COUNT(*) FROM favorite WHERE favorite.video_id = video.id) AS like_count
+                FROM video
+            )
+            SELECT
             id,
             artist_id,
             name,
             visibility,
             file_path,
             thumbnail_path,
-            description FROM video"#,
+            description,
+            duration_ms,
+            width,
+            height,
+            frame_rate,
+            container,
+            video_codec,
+            audio_codec,
+            draft FROM listed WHERE NOT draft"#,
         );
 
-        // did not really find a better solution(it works), may be you will
         if let Some(filter) = filter {
-            query.push(" JOIN video_category_video ON id = video_id ");
-            if filter.len() > 1 {
-                let mut filter_array = String::new();
-                filter_array.push_str(
-                    &filter
-                        .iter()
-                        .map(|&x| x.to_string()) // Convert each i32 to String
-                        .collect::<Vec<String>>()
-                        .join(","),
-                );
-                query.push(format!(
-                    " WHERE video.id IN (SELECT video_id FROM video_category_video \
-                    WHERE category_id IN ({})
-                    GROUP BY
-                        video_id
-                    HAVING
-                    COUNT(DISTINCT category_id) = {})",
-                    filter_array,
-                    filter.len()
-                ));
-            } else {
-                query.push(" WHERE category_id = ");
-                query.push_bind(filter[0]);
-            }
+            let category_count = filter.len() as i64;
+            query.push(
+                " AND id IN (SELECT video_id FROM video_category_video \
+                WHERE category_id = ANY(",
+            );
+            query.push_bind(filter);
+            query.push(") GROUP BY video_id HAVING COUNT(DISTINCT category_id) = ");
+            query.push_bind(category_count);
+            query.push(")");
         }
 
-        query.push(" GROUP BY video.id ");
-
-        if let Some(ord) = ord {
-            query.push(" ORDER BY ");
-            query.push_bind(ord);
-            query.push(" ");
+        // Keyset pagination, the page continues right after the last video of the previous one
+        if let Some(after) = after {
+            query.push(format!(
+                " AND ({sort_key}, id) {comparison} \
+                (SELECT {sort_key}, id FROM listed WHERE id = "
+            ));
+            query.push_bind(after);
+            query.push(")");
         }
 
-        query.push(" LIMIT 8");
-        if let Some(offset) = offset {
-            query.push(" OFFSET ");
-            query.push_bind(offset);
-        }
+        query.push(format!(
+            " ORDER BY {sort_key} {direction}, id {direction} LIMIT "
+        ));
+        query.push_bind(limit);
 
         let result = query.build_query_as().fetch_all(&self.pg_pool).await;
 
@@ -341,7 +454,15 @@ impl VideoRepo for PgVideoRepo {
             visibility,
             file_path,
             thumbnail_path,
-            description FROM video"#,
+            description,
+            duration_ms,
+            width,
+            height,
+            frame_rate,
+            container,
+            video_codec,
+            audio_codec,
+            draft FROM video"#,
         );
         query.push(format!(
             r#"
@@ -356,6 +477,156 @@ impl VideoRepo for PgVideoRepo {
 
         Ok(result?)
     }
+
+    async fn search_videos(&self, search: VideoSearch) -> Result<Vec<VideoSearchHit>> {
+        let name_options =
+            format!("StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_END}, HighlightAll=true");
+        let snippet_options = format!(
+            "StartSel={HIGHLIGHT_START}, StopSel={HIGHLIGHT_END}, \
+            MaxFragments=2, MaxWords=25, MinWords=10, FragmentDelimiter=\" ... \""
+        );
+
+        let mut query = QueryBuilder::new(
+            r#"SELECT
+            video.id,
+            artist_id,
+            name,
+            visibility,
+            file_path,
+            thumbnail_path,
+            description,
+            duration_ms,
+            width,
+            height,
+            frame_rate,
+            container,
+            video_codec,
+            audio_codec,
+            draft,
+            ts_rank_cd(search_vector, search_query) AS rank,
+            ts_headline('english', name, search_query, "#,
+        );
+        query.push_bind(name_options);
+        query.push(
+            r#") AS name_highlight,
+            CASE WHEN description IS NULL THEN NULL
+                ELSE ts_headline('english', description, search_query, "#,
+        );
+        query.push_bind(snippet_options);
+        query.push(
+            r#") END AS description_snippet
+            FROM video, websearch_to_tsquery('english', "#,
+        );
+        query.push_bind(search.query);
+        query.push(
+            ") AS search_query WHERE search_vector @@ search_query AND NOT draft \
+            AND (visibility = ANY(",
+        );
+        query.push_bind(search.visibilities);
+        query.push(")");
+        if let Some(owner_artist_id) = search.owner_artist_id {
+            query.push(" OR artist_id = ");
+            query.push_bind(owner_artist_id);
+        }
+        query.push(")");
+
+        if let Some(category_ids) = search.category_ids {
+            let category_count = category_ids.len() as i64;
+            query.push(
+                " AND video.id IN (SELECT video_id FROM video_category_video \
+                WHERE category_id = ANY(",
+            );
+            query.push_bind(category_ids);
+            query.push(") GROUP BY video_id HAVING COUNT(DISTINCT category_id) = ");
+            query.push_bind(category_count);
+            query.push(")");
+        }
+
+        query.push(" ORDER BY rank DESC, video.id DESC LIMIT ");
+        query.push_bind(search.limit);
+        query.push(" OFFSET ");
+        query.push_bind(search.offset);
+
+        let result = query
+            .build_query_as()
+            .fetch_all(&self.pg_pool)
+            .await
+            .db_error("Failed to search videos")?;
+
+        Ok(result)
+    }
+
+    async fn fetch_artist_videos(
+        &self,
+        artist_id: i32,
+        visibilities: Vec<VideoVisibility>,
+        include_drafts: bool,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<Video>> {
+        let videos = sqlx::query_as::<_, Video>(
+            r#"SELECT
+            id,
+            artist_id,
+            name,
+            visibility,
+            file_path,
+            thumbnail_path,
+            description,
+            duration_ms,
+            width,
+            height,
+            frame_rate,
+            container,
+            video_codec,
+            audio_codec,
+            draft
+            FROM video
+            WHERE artist_id = $1 AND visibility = ANY($2) AND (NOT draft OR $3)
+            ORDER BY created_at DESC, id DESC
+            OFFSET $4 LIMIT $5"#,
+        )
+        .bind(artist_id)
+        .bind(visibilities)
+        .bind(include_drafts)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(videos)
+    }
+
+    async fn count_artist_videos(
+        &self,
+        artist_id: i32,
+        visibilities: Vec<VideoVisibility>,
+        include_drafts: bool,
+    ) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM video \
+            WHERE artist_id = $1 AND visibility = ANY($2) AND (NOT draft OR $3)",
+        )
+        .bind(artist_id)
+        .bind(visibilities)
+        .bind(include_drafts)
+        .fetch_one(&self.pg_pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    async fn publish_video(&self, video_id: i32, artist_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            "UPDATE video SET draft = FALSE WHERE id = $1 AND artist_id = $2 AND draft",
+            video_id,
+            artist_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 #[cfg(test)]
@@ -494,6 +765,7 @@ mod test {
             file_path: None,
             thumbnail_path: None,
             description: Some(String::from("Description2")),
+            metadata: None,
         };
         let updated_video = repo.patch_video(edited_video, &mut tx).await?;
         tx.commit().await?;
@@ -537,6 +809,216 @@ mod test {
         Ok(())
     }
 
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn search_videos(ctx: &mut EmptyAsyncContext) -> Result<()> {
+        create_dummy_artist(&ctx.pg_pool)
+            .await
+            .expect("Failed to create dummy artist");
+        let repo = create_repository(ctx.pg_pool.clone());
+        let mut tx = ctx.pg_pool.begin().await?;
+        let mut public_video = create_test_video(None, None, &ctx.test_folders_root).await;
+        public_video.name = String::from("Sunset on the beach");
+        let public_video = repo.save_video(public_video, &mut tx).await?;
+        let mut paying_video =
+            create_test_video(None, Some(VideoVisibility::Paying), &ctx.test_folders_root).await;
+        paying_video.description = Some(String::from("Long walk on sandy beaches"));
+        let paying_video = repo.save_video(paying_video, &mut tx).await?;
+        tx.commit().await?;
+
+        let search =
+            |visibilities: Vec<VideoVisibility>, owner_artist_id: Option<i32>| VideoSearch {
+                query: String::from("beach"),
+                category_ids: None,
+                visibilities,
+                owner_artist_id,
+                limit: 10,
+                offset: 0,
+            };
+
+        let hits = repo
+            .search_videos(search(vec![VideoVisibility::All], None))
+            .await?;
+        assert_eq!(hits.len(), 1, "Paying video must not be found");
+        assert_eq!(hits[0].video.id, public_video.id);
+        assert_eq!(
+            hits[0].name_highlight,
+            format!("Sunset on the {HIGHLIGHT_START}beach{HIGHLIGHT_END}")
+        );
+
+        let hits = repo
+            .search_videos(search(vec![VideoVisibility::All], Some(1)))
+            .await?;
+        let ids: Vec<i32> = hits.iter().map(|hit| hit.video.id).collect();
+        assert_eq!(
+            ids,
+            vec![public_video.id, paying_video.id],
+            "Match in the name must be ranked higher"
+        );
+        assert!(hits[1]
+            .description_snippet
+            .as_ref()
+            .is_some_and(|snippet| snippet.contains(HIGHLIGHT_START)));
+
+        Ok(())
+    }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn fetch_artist_videos(ctx: &mut EmptyAsyncContext) -> Result<()> {
+        create_dummy_artist(&ctx.pg_pool)
+            .await
+            .expect("Failed to create dummy artist");
+        let repo = create_repository(ctx.pg_pool.clone());
+        let mut tx = ctx.pg_pool.begin().await?;
+        let public_video = repo
+            .save_video(
+                create_test_video(None, None, &ctx.test_folders_root).await,
+                &mut tx,
+            )
+            .await?;
+        let paying_video = repo
+            .save_video(
+                create_test_video(None, Some(VideoVisibility::Paying), &ctx.test_folders_root)
+                    .await,
+                &mut tx,
+            )
+            .await?;
+        tx.commit().await?;
+
+        let videos = repo
+            .fetch_artist_videos(1, vec![VideoVisibility::All], false, 0, 10)
+            .await?;
+        let ids: Vec<i32> = videos.iter().map(|video| video.id).collect();
+        assert_eq!(ids, vec![public_video.id], "Paying video must be skipped");
+        assert_eq!(
+            repo.count_artist_videos(1, vec![VideoVisibility::All], false)
+                .await?,
+            1
+        );
+
+        let all = vec![VideoVisibility::All, VideoVisibility::Paying];
+        let videos = repo
+            .fetch_artist_videos(1, all.clone(), false, 0, 10)
+            .await?;
+        let ids: Vec<i32> = videos.iter().map(|video| video.id).collect();
+        assert_eq!(ids, vec![paying_video.id, public_video.id]);
+        assert_eq!(
+            repo.fetch_artist_videos(1, all.clone(), false, 1, 10)
+                .await?
+                .len(),
+            1
+        );
+        assert_eq!(repo.count_artist_videos(1, all, false).await?, 2);
+        assert!(repo
+            .fetch_artist_videos(2, vec![VideoVisibility::All], false, 0, 10)
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn publish_draft_video(ctx: &mut EmptyAsyncContext) -> Result<()> {
+        create_dummy_artist(&ctx.pg_pool)
+            .await
+            .expect("Failed to create dummy artist");
+        let repo = create_repository(ctx.pg_pool.clone());
+        let mut draft = create_test_video(None, None, &ctx.test_folders_root).await;
+        draft.draft = true;
+        let mut tx = ctx.pg_pool.begin().await?;
+        let draft = repo.save_video(draft, &mut tx).await?;
+        tx.commit().await?;
+        assert!(draft.draft);
+
+        let all = vec![VideoVisibility::All];
+        assert!(
+            repo.fetch_videos(VideoSort::Newest, None, None, 10)
+                .await?
+                .is_empty(),
+            "Drafts must not be listed"
+        );
+        assert_eq!(repo.count_artist_videos(1, all.clone(), false).await?, 0);
+        let videos = repo
+            .fetch_artist_videos(1, all.clone(), true, 0, 10)
+            .await?;
+        assert_eq!(videos.len(), 1, "Artist should see own drafts");
+
+        assert!(!repo.publish_video(draft.id, 2).await?);
+        assert!(repo.publish_video(draft.id, 1).await?);
+        assert!(
+            !repo.publish_video(draft.id, 1).await?,
+            "Published video is not a draft anymore"
+        );
+
+        let video = repo.get_video_by_id(draft.id, None).await?.unwrap();
+        assert!(!video.draft);
+        assert_eq!(repo.count_artist_videos(1, all, false).await?, 1);
+
+        Ok(())
+    }
+
+    #[test_context(EmptyAsyncContext)]
+    #[tokio::test]
+    async fn fetch_videos_sorted(ctx: &mut EmptyAsyncContext) -> Result<()> {
+        create_dummy_artist(&ctx.pg_pool)
+            .await
+            .expect("Failed to create dummy artist");
+        let repo = create_repository(ctx.pg_pool.clone());
+        let mut tx = ctx.pg_pool.begin().await?;
+        let mut ids = Vec::new();
+        for name in ["beta", "Alpha", "gamma", "delta"] {
+            let mut video = create_test_video(None, None, &ctx.test_folders_root).await;
+            video.name = name.to_string();
+            ids.push(repo.save_video(video, &mut tx).await?.id);
+        }
+        tx.commit().await?;
+        sqlx::query!(
+            "INSERT INTO favorite (user_id, video_id) VALUES (1, $1)",
+            ids[2]
+        )
+        .execute(&ctx.pg_pool)
+        .await?;
+
+        assert_eq!(
+            fetch_names(&repo, VideoSort::Alphabetical, None).await?,
+            vec!["Alpha", "beta"]
+        );
+        assert_eq!(
+            fetch_names(&repo, VideoSort::Alphabetical, Some(ids[0])).await?,
+            vec!["delta", "gamma"],
+            "Second page must continue after the last video"
+        );
+        assert_eq!(
+            fetch_names(&repo, VideoSort::Newest, None).await?,
+            vec!["delta", "gamma"]
+        );
+        assert_eq!(
+            fetch_names(&repo, VideoSort::Oldest, Some(ids[1])).await?,
+            vec!["gamma", "delta"]
+        );
+        assert_eq!(
+            fetch_names(&repo, VideoSort::MostLiked, None).await?,
+            vec!["gamma", "delta"]
+        );
+        assert_eq!(
+            fetch_names(&repo, VideoSort::MostLiked, Some(ids[3])).await?,
+            vec!["Alpha", "beta"]
+        );
+
+        Ok(())
+    }
+
+    async fn fetch_names(
+        repo: &impl VideoRepo,
+        sort: VideoSort,
+        after: Option<i32>,
+    ) -> Result<Vec<String>> {
+        let videos = repo.fetch_videos(sort, None, after, 2).await?;
+        Ok(videos.into_iter().map(|video| video.name).collect())
+    }
+
     fn create_repository(pg_pool: PgPool) -> impl VideoRepo {
         PgVideoRepo { pg_pool }
     }
@@ -563,6 +1045,14 @@ mod test {
             file_path: video_path,
             thumbnail_path,
             description: Some(String::from("Description")),
+            duration_ms: Some(90_000),
+            width: Some(1920),
+            height: Some(1080),
+            frame_rate: Some(30.0),
+            container: Some(String::from("MP4")),
+            video_codec: Some(String::from("H.264")),
+            audio_codec: None,
+            draft: false,
         }
     }
 
@@ -0,0 +1,390 @@
+use crate::persistence::entities::error::{DatabaseError, MapToDatabaseError};
+use crate::persistence::entities::view::{DailyViews, PendingViews, TakenViews};
+use crate::persistence::redis_store::RedisStore;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use deadpool_redis::redis::AsyncCommands;
+use deadpool_redis::{redis, Pool};
+use sqlx::PgPool;
+use std::collections::HashMap;
+use std::time::Duration;
+use uuid::Uuid;
+
+const PENDING_KEY: &str = "view:pending";
+const FLUSHING_KEY: &str = "view:flushing";
+const FLUSH_LOCK_KEY: &str = "view:flush-lock";
+const DEDUP_KEY: &str = "view:dedup";
+/// The lock expires, so the views of the instance, which crashed during the flush,
+/// are flushed by another instance later
+const FLUSH_LOCK_TTL_MS: u64 = 5 * 60 * 1000;
+/// Clears the taken views and releases the lock, only if the lock still has the given token
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    redis.call('DEL', KEYS[2])
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+#[async_trait]
+pub trait ViewRepo {
+    /// Adds the views to the total and daily counters of the videos.
+    /// Views of the deleted videos are skipped.
+    async fn add_views(&self, views: &[PendingViews]) -> Result<()>;
+    async fn get_total_views(&self, video_id: i32) -> Result<i64>;
+    /// Returns daily counters of the video since the given day, the oldest first.
+    /// Days without any view are not included.
+    async fn get_daily_views(&self, video_id: i32, since: NaiveDate) -> Result<Vec<DailyViews>>;
+}
+
+/// Buffer of the views, which are not written to the database yet
+#[async_trait]
+pub trait ViewBuffer {
+    /// Marks the video as viewed by the viewer for the given time window
+    ///
+    /// # Returns
+    /// `true` if the viewer didn't view the video within the window yet
+    async fn mark_viewer(&self, video_id: i32, viewer: &str, window: Duration) -> Result<bool>;
+    async fn add_view(&self, video_id: i32, day: NaiveDate) -> Result<()>;
+    /// Locks the buffer for the flush, moves the buffered views aside and returns them.
+    /// Views left by the previous unfinished flush are returned first, before any new views
+    /// are taken.
+    ///
+    /// # Returns
+    /// `None` if there is nothing to flush, or another flush holds the lock,
+    /// so the same views are never taken by two flushes at once
+    async fn take_pending(&self) -> Result<Option<TakenViews>>;
+    /// Removes the views returned by `take_pending`, once they are persisted,
+    /// and releases the lock
+    async fn clear_taken(&self, taken: &TakenViews) -> Result<()>;
+}
+
+pub struct PgViewRepo {
+    pg_pool: PgPool,
+}
+
+impl PgViewRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl ViewRepo for PgViewRepo {
+    async fn add_views(&self, views: &[PendingViews]) -> Result<()> {
+        let mut tx = self.pg_pool.begin().await?;
+        for views in views {
+            sqlx::query!(
+                "UPDATE video SET view_count = view_count + $2 WHERE id = $1",
+                views.video_id,
+                views.view_count
+            )
+            .execute(tx.as_mut())
+            .await?;
+
+            sqlx::query!(
+                r#"INSERT INTO video_daily_views (video_id, day, view_count)
+                SELECT $1, $2, $3 WHERE EXISTS (SELECT 1 FROM video WHERE id = $1)
+                ON CONFLICT (video_id, day)
+                DO UPDATE SET view_count = video_daily_views.view_count + EXCLUDED.view_count"#,
+                views.video_id,
+                views.day,
+                views.view_count
+            )
+            .execute(tx.as_mut())
+            .await?;
+        }
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    async fn get_total_views(&self, video_id: i32) -> Result<i64> {
+        let record = sqlx::query!("SELECT view_count FROM video WHERE id = $1", video_id)
+            .fetch_optional(&self.pg_pool)
+            .await?;
+
+        Ok(record.map(|record| record.view_count).unwrap_or_default())
+    }
+
+    async fn get_daily_views(&self, video_id: i32, since: NaiveDate) -> Result<Vec<DailyViews>> {
+        let views = sqlx::query_as!(
+            DailyViews,
+            r#"SELECT day, view_count FROM video_daily_views
+            WHERE video_id = $1 AND day >= $2
+            ORDER BY day"#,
+            video_id,
+            since
+        )
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(views)
+    }
+}
+
+pub struct RedisViewBuffer {
+    redis: RedisStore,
+}
+
+impl RedisViewBuffer {
+    pub fn new(redis_pool: Pool, key_prefix: impl Into<String>) -> Self {
+        Self {
+            redis: RedisStore::new(redis_pool, key_prefix),
+        }
+    }
+
+    /// Parses field of the buffer in format `{video_id}:{day}`
+    fn parse_field(field: &str, view_count: i64) -> Result<PendingViews> {
+        let (video_id, day) = field
+            .split_once(':')
+            .ok_or(DatabaseError::new("Invalid field of the view buffer"))?;
+
+        Ok(PendingViews {
+            video_id: video_id
+                .parse()
+                .db_error("Invalid ID of the viewed video")?,
+            day: day.parse().db_error("Invalid day of the view")?,
+            view_count,
+        })
+    }
+}
+
+#[async_trait]
+impl ViewBuffer for RedisViewBuffer {
+    async fn mark_viewer(&self, video_id: i32, viewer: &str, window: Duration) -> Result<bool> {
+        let mut conn = self.redis.connection().await?;
+        let key = self.redis.key(&format!("{DEDUP_KEY}:{video_id}:{viewer}"));
+
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(window.as_secs().max(1))
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to mark the viewer")?;
+
+        Ok(result.is_some())
+    }
+
+    async fn add_view(&self, video_id: i32, day: NaiveDate) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let _: i64 = conn
+            .hincr(self.redis.key(PENDING_KEY), format!("{video_id}:{day}"), 1)
+            .await
+            .db_error("Failed to buffer the view")?;
+
+        Ok(())
+    }
+
+    async fn take_pending(&self) -> Result<Option<TakenViews>> {
+        let mut conn = self.redis.connection().await?;
+        let pending_key = self.redis.key(PENDING_KEY);
+        let flushing_key = self.redis.key(FLUSHING_KEY);
+        let lock_key = self.redis.key(FLUSH_LOCK_KEY);
+
+        let lock_token = Uuid::new_v4().to_string();
+        let locked: Option<String> = redis::cmd("SET")
+            .arg(&lock_key)
+            .arg(&lock_token)
+            .arg("NX")
+            .arg("PX")
+            .arg(FLUSH_LOCK_TTL_MS)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to lock the view buffer")?;
+        if locked.is_none() {
+            return Ok(None);
+        }
+
+        let unfinished: bool = conn
+            .exists(&flushing_key)
+            .await
+            .db_error("Failed to read the view buffer")?;
+        if !unfinished {
+            let has_pending: bool = conn
+                .exists(&pending_key)
+                .await
+                .db_error("Failed to read the view buffer")?;
+            if !has_pending {
+                let _: () = conn
+                    .del(&lock_key)
+                    .await
+                    .db_error("Failed to unlock the view buffer")?;
+                return Ok(None);
+            }
+
+            let _: () = conn
+                .rename(&pending_key, &flushing_key)
+                .await
+                .db_error("Failed to take the buffered views")?;
+        }
+
+        let fields: HashMap<String, i64> = conn
+            .hgetall(&flushing_key)
+            .await
+            .db_error("Failed to read the view buffer")?;
+        let views = fields
+            .iter()
+            .map(|(field, view_count)| Self::parse_field(field, *view_count))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Some(TakenViews { lock_token, views }))
+    }
+
+    async fn clear_taken(&self, taken: &TakenViews) -> Result<()> {
+        let mut conn = self.redis.connection().await?;
+        let _: i64 = redis::cmd("EVAL")
+            .arg(RELEASE_SCRIPT)
+            .arg(2)
+            .arg(self.redis.key(FLUSH_LOCK_KEY))
+            .arg(self.redis.key(FLUSHING_KEY))
+            .arg(&taken.lock_token)
+            .query_async(&mut conn)
+            .await
+            .db_error("Failed to clear the view buffer")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::persistence::entities::video::{Video, VideoVisibility};
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use crate::setup_redis_pool;
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn add_views(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx).await?;
+        let repo = PgViewRepo::new(ctx.pg_pool.clone());
+        let first_day = NaiveDate::from_ymd_opt(2025, 2, 10).unwrap();
+        let second_day = NaiveDate::from_ymd_opt(2025, 2, 11).unwrap();
+
+        repo.add_views(&[
+            pending_views(video.id, first_day, 3),
+            pending_views(video.id, second_day, 2),
+            // views of the deleted videos are skipped
+            pending_views(video.id + 1, second_day, 5),
+        ])
+        .await?;
+        repo.add_views(&[pending_views(video.id, second_day, 4)])
+            .await?;
+
+        assert_eq!(repo.get_total_views(video.id).await?, 9);
+        assert_eq!(repo.get_total_views(video.id + 1).await?, 0);
+
+        let daily_views = repo.get_daily_views(video.id, first_day).await?;
+        assert_eq!(
+            daily_views,
+            vec![
+                DailyViews {
+                    day: first_day,
+                    view_count: 3
+                },
+                DailyViews {
+                    day: second_day,
+                    view_count: 6
+                },
+            ]
+        );
+
+        let daily_views = repo.get_daily_views(video.id, second_day).await?;
+        assert_eq!(daily_views.len(), 1, "Older days should be skipped");
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn buffer_views(ctx: &mut AsyncContext) -> Result<()> {
+        let redis_pool = setup_redis_pool().await.unwrap();
+        let buffer = RedisViewBuffer::new(redis_pool, format!("{}:", ctx.test_db_name));
+        let day = NaiveDate::from_ymd_opt(2025, 2, 10).unwrap();
+        let window = Duration::from_secs(60);
+
+        assert!(buffer.mark_viewer(1, "user:1", window).await?);
+        assert!(
+            !buffer.mark_viewer(1, "user:1", window).await?,
+            "Repeated view within the window should not be counted"
+        );
+        assert!(buffer.mark_viewer(2, "user:1", window).await?);
+        assert!(buffer.mark_viewer(1, "ip:127.0.0.1", window).await?);
+
+        buffer.add_view(1, day).await?;
+        buffer.add_view(1, day).await?;
+        buffer.add_view(2, day).await?;
+
+        let mut taken = buffer.take_pending().await?.unwrap();
+        taken.views.sort_by_key(|views| views.video_id);
+        assert_eq!(
+            taken.views,
+            vec![pending_views(1, day, 2), pending_views(2, day, 1)]
+        );
+
+        // Concurrent flush doesn't get the same views
+        buffer.add_view(3, day).await?;
+        assert!(buffer.take_pending().await?.is_none());
+
+        // Views of the unfinished flush are returned again, once its lock expires
+        let mut conn = buffer.redis.connection().await?;
+        let _: () = conn.del(buffer.redis.key(FLUSH_LOCK_KEY)).await.unwrap();
+        let unfinished = buffer.take_pending().await?.unwrap();
+        assert_eq!(unfinished.views.len(), 2);
+
+        // The expired lock doesn't release the lock of the new flush
+        buffer.clear_taken(&taken).await?;
+        assert!(buffer.take_pending().await?.is_none());
+
+        buffer.clear_taken(&unfinished).await?;
+        let taken = buffer.take_pending().await?.unwrap();
+        assert_eq!(taken.views, vec![pending_views(3, day, 1)]);
+        buffer.clear_taken(&taken).await?;
+        assert!(buffer.take_pending().await?.is_none());
+
+        Ok(())
+    }
+
+    fn pending_views(video_id: i32, day: NaiveDate, view_count: i64) -> PendingViews {
+        PendingViews {
+            video_id,
+            day,
+            view_count,
+        }
+    }
+
+    async fn create_dummy_video(ctx: &AsyncContext) -> Result<Video> {
+        let video = Video {
+            id: 1,
+            artist_id: 1,
+            visibility: VideoVisibility::All,
+            name: String::from("Test video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+
+        let repo = PgVideoRepo::new(ctx.pg_pool.clone());
+
+        let mut tx = ctx.pg_pool.begin().await?;
+        let video = repo.save_video(video, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(video)
+    }
+}
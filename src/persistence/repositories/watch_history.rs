@@ -0,0 +1,241 @@
+use crate::persistence::entities::video::VideoAccess;
+use crate::persistence::entities::watch_history::WatchedVideo;
+use crate::persistence::Result;
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+#[async_trait]
+pub trait WatchHistoryRepo {
+    /// Saves the last position of the playback and moves the video to the top of the history
+    async fn save_position(&self, user_id: i32, video_id: i32, position_ms: i64) -> Result<()>;
+    async fn get_position(&self, user_id: i32, video_id: i32) -> Result<Option<i64>>;
+    /// Returns videos watched by the user, the most recently watched first.
+    /// Videos, which the user can't watch anymore, are skipped.
+    async fn list_history(
+        &self,
+        user_id: i32,
+        access: &VideoAccess,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WatchedVideo>>;
+    /// # Returns
+    /// `true` if the video was in the history
+    async fn delete_entry(&self, user_id: i32, video_id: i32) -> Result<bool>;
+    async fn clear_history(&self, user_id: i32) -> Result<()>;
+}
+
+pub struct PgWatchHistoryRepo {
+    pg_pool: PgPool,
+}
+
+impl PgWatchHistoryRepo {
+    pub fn new(pg_pool: PgPool) -> Self {
+        Self { pg_pool }
+    }
+}
+
+#[async_trait]
+impl WatchHistoryRepo for PgWatchHistoryRepo {
+    async fn save_position(&self, user_id: i32, video_id: i32, position_ms: i64) -> Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO watch_history (user_id, video_id, position_ms)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (user_id, video_id)
+            DO UPDATE SET position_ms = EXCLUDED.position_ms, watched_at = NOW()"#,
+            user_id,
+            video_id,
+            position_ms
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn get_position(&self, user_id: i32, video_id: i32) -> Result<Option<i64>> {
+        let record = sqlx::query!(
+            "SELECT position_ms FROM watch_history WHERE user_id = $1 AND video_id = $2",
+            user_id,
+            video_id
+        )
+        .fetch_optional(&self.pg_pool)
+        .await?;
+
+        Ok(record.map(|record| record.position_ms))
+    }
+
+    async fn list_history(
+        &self,
+        user_id: i32,
+        access: &VideoAccess,
+        offset: i64,
+        limit: i64,
+    ) -> Result<Vec<WatchedVideo>> {
+        let history = sqlx::query_as::<_, WatchedVideo>(
+            r#"SELECT
+                video.id,
+                video.artist_id,
+                video.visibility,
+                video.name,
+                video.file_path,
+                video.thumbnail_path,
+                video.description,
+                video.duration_ms,
+                video.width,
+                video.height,
+                video.frame_rate,
+                video.container,
+                video.video_codec,
+                video.audio_codec,
+                video.draft,
+                watch_history.position_ms,
+                watch_history.watched_at
+            FROM watch_history
+            JOIN video ON video.id = watch_history.video_id
+            WHERE watch_history.user_id = $1
+                AND (video.artist_id = $2 OR (NOT video.draft AND video.visibility = ANY($3)))
+            ORDER BY watch_history.watched_at DESC, video.id DESC
+            OFFSET $4 LIMIT $5"#,
+        )
+        .bind(user_id)
+        .bind(access.owner_artist_id)
+        .bind(&access.visibilities)
+        .bind(offset)
+        .bind(limit)
+        .fetch_all(&self.pg_pool)
+        .await?;
+
+        Ok(history)
+    }
+
+    async fn delete_entry(&self, user_id: i32, video_id: i32) -> Result<bool> {
+        let result = sqlx::query!(
+            "DELETE FROM watch_history WHERE user_id = $1 AND video_id = $2",
+            user_id,
+            video_id
+        )
+        .execute(&self.pg_pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn clear_history(&self, user_id: i32) -> Result<()> {
+        sqlx::query!("DELETE FROM watch_history WHERE user_id = $1", user_id)
+            .execute(&self.pg_pool)
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::common::tests::setup::AsyncContext;
+    use crate::persistence::entities::video::{Video, VideoVisibility};
+    use crate::persistence::repositories::video::{PgVideoRepo, VideoRepo};
+    use test_context::test_context;
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn watch_history(ctx: &mut AsyncContext) -> Result<()> {
+        let first_video = create_dummy_video(ctx, VideoVisibility::All).await?;
+        let second_video = create_dummy_video(ctx, VideoVisibility::All).await?;
+        let repo = PgWatchHistoryRepo::new(ctx.pg_pool.clone());
+        let access = VideoAccess {
+            visibilities: vec![VideoVisibility::All],
+            owner_artist_id: None,
+        };
+
+        repo.save_position(2, first_video.id, 1000).await?;
+        repo.save_position(2, second_video.id, 2000).await?;
+        repo.save_position(2, first_video.id, 5000).await?;
+        repo.save_position(4, second_video.id, 3000).await?;
+
+        assert_eq!(repo.get_position(2, first_video.id).await?, Some(5000));
+        assert_eq!(repo.get_position(4, first_video.id).await?, None);
+
+        let history = repo.list_history(2, &access, 0, 10).await?;
+        let ids: Vec<i32> = history.iter().map(|watched| watched.video.id).collect();
+        assert_eq!(
+            ids,
+            vec![first_video.id, second_video.id],
+            "The most recently watched video should be first"
+        );
+        assert_eq!(history[0].position_ms, 5000);
+        assert_eq!(repo.list_history(2, &access, 1, 10).await?.len(), 1);
+
+        assert!(repo.delete_entry(2, second_video.id).await?);
+        assert!(!repo.delete_entry(2, second_video.id).await?);
+        assert_eq!(repo.list_history(2, &access, 0, 10).await?.len(), 1);
+
+        repo.clear_history(2).await?;
+        assert!(repo.list_history(2, &access, 0, 10).await?.is_empty());
+        assert_eq!(
+            repo.list_history(4, &access, 0, 10).await?.len(),
+            1,
+            "History of another user should not be cleared"
+        );
+
+        Ok(())
+    }
+
+    #[test_context(AsyncContext)]
+    #[tokio::test]
+    async fn list_history_access(ctx: &mut AsyncContext) -> Result<()> {
+        let video = create_dummy_video(ctx, VideoVisibility::All).await?;
+        let paying_video = create_dummy_video(ctx, VideoVisibility::Paying).await?;
+        let repo = PgWatchHistoryRepo::new(ctx.pg_pool.clone());
+        repo.save_position(2, video.id, 1000).await?;
+        repo.save_position(2, paying_video.id, 1000).await?;
+
+        let mut access = VideoAccess {
+            visibilities: vec![VideoVisibility::All],
+            owner_artist_id: None,
+        };
+        let history = repo.list_history(2, &access, 0, 1).await?;
+        assert_eq!(history.len(), 1);
+        assert_eq!(
+            history[0].video.id, video.id,
+            "Video, which the user can't watch, should be skipped"
+        );
+
+        access.owner_artist_id = Some(1);
+        assert_eq!(
+            repo.list_history(2, &access, 0, 10).await?.len(),
+            2,
+            "Artist should see all his videos"
+        );
+
+        Ok(())
+    }
+
+    async fn create_dummy_video(ctx: &AsyncContext, visibility: VideoVisibility) -> Result<Video> {
+        let video = Video {
+            id: -1,
+            artist_id: 1,
+            visibility,
+            name: String::from("Test video"),
+            file_path: String::from("dummy path"),
+            thumbnail_path: String::from("dummy path"),
+            description: None,
+            duration_ms: None,
+            width: None,
+            height: None,
+            frame_rate: None,
+            container: None,
+            video_codec: None,
+            audio_codec: None,
+            draft: false,
+        };
+
+        let repo = PgVideoRepo::new(ctx.pg_pool.clone());
+
+        let mut tx = ctx.pg_pool.begin().await?;
+        let video = repo.save_video(video, &mut tx).await?;
+        tx.commit().await?;
+
+        Ok(video)
+    }
+}
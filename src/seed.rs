@@ -13,8 +13,8 @@ pub async fn create_admin(
     let password_hash = hash(password, DEFAULT_COST)?;
     sqlx::query!(
         r#"
-        INSERT INTO user_table (username, password_hash, email, is_admin)
-        VALUES ($1, $2, $3, $4)
+        INSERT INTO user_table (username, password_hash, email, is_admin, email_verified_at)
+        VALUES ($1, $2, $3, $4, now())
         "#,
         username,
         password_hash,
@@ -31,8 +31,8 @@ async fn seed_users(tx: &mut Transaction<'_, Postgres>) -> anyhow::Result<()> {
     // registered user
     sqlx::query!(
         r#"
-        INSERT INTO user_table (username, password_hash, email)
-        VALUES ($1, $2, $3)
+        INSERT INTO user_table (username, password_hash, email, email_verified_at)
+        VALUES ($1, $2, $3, now())
         "#,
         "registered_user",
         hash("password123", DEFAULT_COST)?,
@@ -44,8 +44,8 @@ async fn seed_users(tx: &mut Transaction<'_, Postgres>) -> anyhow::Result<()> {
     // paying user
     let paying_user_id = sqlx::query!(
         r#"
-        INSERT INTO user_table (username, password_hash, email)
-        VALUES ($1, $2, $3)
+        INSERT INTO user_table (username, password_hash, email, email_verified_at)
+        VALUES ($1, $2, $3, now())
         RETURNING id
         "#,
         "paying_user",
@@ -116,8 +116,8 @@ async fn seed_users(tx: &mut Transaction<'_, Postgres>) -> anyhow::Result<()> {
     // artist user
     let artist_user_id = sqlx::query!(
         r#"
-        INSERT INTO user_table (username, password_hash, email)
-        VALUES ($1, $2, $3)
+        INSERT INTO user_table (username, password_hash, email, email_verified_at)
+        VALUES ($1, $2, $3, now())
         RETURNING id
         "#,
         "artist_user",
@@ -184,8 +184,8 @@ async fn seed_artists(tx: &mut Transaction<'_, Postgres>) -> anyhow::Result<()>
     for (name, description) in templates.iter() {
         let artist_user_id = sqlx::query!(
             r#"
-            INSERT INTO user_table (username, password_hash, email)
-            VALUES ($1, $2, $3)
+            INSERT INTO user_table (username, password_hash, email, email_verified_at)
+            VALUES ($1, $2, $3, now())
             RETURNING id
             "#,
             name.to_lowercase().replace(" ", "_"),
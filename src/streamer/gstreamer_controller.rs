@@ -1,13 +1,38 @@
-use crate::streamer::types::{CompoundStreamInfoTrait, StreamResolution, StreamStorageTrait};
+use crate::streamer::types::{
+    CompoundStreamInfoTrait, MediaInfo, PipelineHealth, StreamResolution, StreamSource,
+    StreamStorageTrait,
+};
 use anyhow::Result;
 use gstreamer::prelude::{
-    ElementExt, ElementExtManual, GObjectExtManualGst, GstBinExtManual, PadExt,
+    Cast, ElementExt, ElementExtManual, GObjectExtManualGst, GstBinExt, GstBinExtManual,
+    GstObjectExt, ObjectExt, PadExt, PadExtManual,
+};
+use gstreamer::{
+    ClockTime, Element, ElementFactory, EventType, Message, MessageType, MessageView, Pad,
+    PadProbeReturn, PadProbeType, Pipeline, Sample, SeekFlags, State, StructureRef,
 };
-use gstreamer::{ClockTime, Element, ElementFactory, MessageView, Pad, Pipeline, State};
-use log::{debug, error, info};
+use gstreamer_pbutils::prelude::DiscovererStreamInfoExt;
+use gstreamer_pbutils::Discoverer;
+use gstreamer_video::VideoInfo;
+use image::RgbaImage;
+use log::{debug, error, info, warn};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread;
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Name of the HLS playlist, which references the renditions of all resolutions
+pub const HLS_MASTER_PLAYLIST: &str = "master.m3u8";
+const HLS_RENDITION_PLAYLIST: &str = "playlist.m3u8";
+const HLS_SEGMENT_DURATION: &str = "6";
+const FRAME_CAPTURE_TIMEOUT_SECS: u64 = 10;
+const MEDIA_PROBE_TIMEOUT_SECS: u64 = 10;
+/// Recordings are fragmented, so the recorded part stays playable when the stream crashes
+const RECORDING_FRAGMENT_DURATION_MS: &str = "1000";
+const RTMP_SINK_NAME: &str = "rtmp";
+const HEALTH_REPORT_INTERVAL: Duration = Duration::from_secs(5);
 
 pub fn init_gstreamer() -> std::result::Result<(), gstreamer::glib::Error> {
     gstreamer::init()
@@ -19,10 +44,20 @@ pub fn create_streams(
 ) -> Result<Vec<JoinHandle<()>>> {
     let mut pipelines = Vec::new();
     let mut handles = Vec::new();
+    let highest = compound_stream
+        .get_resolutions()
+        .iter()
+        .max_by_key(|resolution| resolution.height)
+        .map(|resolution| resolution.name.clone());
     for resolution in compound_stream.get_resolutions().clone() {
+        // Only the highest resolution is recorded
+        let record_path = compound_stream
+            .get_record_path()
+            .filter(|_| Some(&resolution.name) == highest.as_ref());
         let pipeline = Arc::new(create_stream_pipeline(
             compound_stream.clone(),
             &resolution,
+            record_path.as_deref(),
         )?);
         let stream_storage = stream_storage.clone();
         pipelines.push(pipeline.clone());
@@ -36,7 +71,7 @@ pub fn create_streams(
             resolution.as_str()
         );
         handles.push(thread::spawn(move || {
-            match pipeline_listen(pipeline, stream_id.as_str(), stream_storage) {
+            match pipeline_listen(pipeline, stream_id.as_str(), &resolution, stream_storage) {
                 Ok(_) => {}
                 Err(err) => error!("Error occurred during live stream, {:?}", err),
             }
@@ -56,18 +91,29 @@ fn start_stream(pipeline: Arc<Pipeline>) -> Result<()> {
 fn pipeline_listen(
     pipeline: Arc<Pipeline>,
     stream_id: &str,
+    resolution: &StreamResolution,
     stream_storage: Arc<dyn StreamStorageTrait>,
 ) -> Result<()> {
-    if pipeline.bus().is_none() {
+    let Some(bus) = pipeline.bus() else {
         return Err(anyhow::Error::msg(
             "Error while initializing bus for stream: {}",
         ));
-    }
+    };
+    let mut health_monitor = HealthMonitor::new(&pipeline, resolution);
+
+    loop {
+        if health_monitor.is_report_due() {
+            stream_storage.report_health(stream_id, health_monitor.report());
+        }
+        // Waiting is limited, so the health is reported even if the bus is silent
+        let Some(msg) = bus.timed_pop(ClockTime::from_seconds(1)) else {
+            continue;
+        };
 
-    for msg in pipeline.bus().unwrap().iter_timed(ClockTime::NONE) {
         match msg.view() {
             MessageView::Eos(_) => {
                 info!("Stream with ID: {} ended", stream_id);
+                stream_storage.report_health(stream_id, health_monitor.report());
                 stop_stream(&pipeline)?;
                 stream_storage.remove(stream_id);
                 debug!("Stream storage size: {}", stream_storage.size());
@@ -78,25 +124,112 @@ fn pipeline_listen(
                     "Error occurred during stream with ID: {}, {}",
                     stream_id, err
                 );
+                stream_storage.report_health(stream_id, health_monitor.report());
                 stop_stream(&pipeline)?;
                 stream_storage.remove(stream_id);
                 debug!("Stream storage size: {}", stream_storage.size());
                 break;
             }
-            _ => (),
+            MessageView::Warning(warning) => {
+                warn!("Warning during stream with ID: {}, {}", stream_id, warning);
+                health_monitor.handle_message(&msg);
+            }
+            _ => health_monitor.handle_message(&msg),
         }
     }
 
     Ok(())
 }
 
+/// Collects health of the pipeline from the messages of its bus and from the data sent to the server
+struct HealthMonitor {
+    resolution: String,
+    sent_bytes: Arc<AtomicU64>,
+    /// Frames dropped by each element, which reports QoS
+    dropped_frames: HashMap<String, u64>,
+    qos_events: u32,
+    buffering_percent: Option<i32>,
+    warnings: u32,
+    last_report: Instant,
+}
+
+impl HealthMonitor {
+    fn new(pipeline: &Pipeline, resolution: &StreamResolution) -> Self {
+        let sent_bytes = Arc::new(AtomicU64::new(0));
+        let sink_pad = pipeline
+            .by_name(RTMP_SINK_NAME)
+            .and_then(|sink| sink.static_pad("sink"));
+        if let Some(sink_pad) = sink_pad {
+            let sent_bytes = sent_bytes.clone();
+            sink_pad.add_probe(PadProbeType::BUFFER, move |_pad, info| {
+                if let Some(buffer) = info.buffer() {
+                    sent_bytes.fetch_add(buffer.size() as u64, Ordering::Relaxed);
+                }
+                PadProbeReturn::Ok
+            });
+        }
+
+        Self {
+            resolution: resolution.as_str().to_string(),
+            sent_bytes,
+            dropped_frames: HashMap::new(),
+            qos_events: 0,
+            buffering_percent: None,
+            warnings: 0,
+            last_report: Instant::now(),
+        }
+    }
+
+    fn handle_message(&mut self, msg: &Message) {
+        match msg.view() {
+            MessageView::Qos(qos) => {
+                self.qos_events += 1;
+                let (_processed, dropped) = qos.stats();
+                // Dropped frames are counted by each element since the start
+                if dropped.value() >= 0 {
+                    let element = msg
+                        .src()
+                        .map(|src| src.name().to_string())
+                        .unwrap_or_default();
+                    self.dropped_frames.insert(element, dropped.value() as u64);
+                }
+            }
+            MessageView::Buffering(buffering) => {
+                self.buffering_percent = Some(buffering.percent());
+            }
+            MessageView::Warning(_) => self.warnings += 1,
+            _ => (),
+        }
+    }
+
+    fn is_report_due(&self) -> bool {
+        self.last_report.elapsed() >= HEALTH_REPORT_INTERVAL
+    }
+
+    fn report(&mut self) -> PipelineHealth {
+        let elapsed = self.last_report.elapsed().as_secs_f64().max(0.001);
+        let sent_bytes = self.sent_bytes.swap(0, Ordering::Relaxed);
+        self.last_report = Instant::now();
+
+        PipelineHealth {
+            resolution: self.resolution.clone(),
+            bitrate_kbps: (sent_bytes as f64 * 8.0 / 1000.0 / elapsed) as u32,
+            dropped_frames: self.dropped_frames.values().sum(),
+            qos_events: self.qos_events,
+            buffering_percent: self.buffering_percent,
+            warnings: self.warnings,
+        }
+    }
+}
+
 fn create_stream_pipeline(
     parent_stream: Arc<dyn CompoundStreamInfoTrait>,
     resolution: &StreamResolution,
+    record_path: Option<&str>,
 ) -> Result<Pipeline> {
     let pipeline = Pipeline::new();
 
-    add_elements_to_pipeline(&pipeline, parent_stream, resolution)?;
+    add_elements_to_pipeline(&pipeline, parent_stream, resolution, record_path)?;
     Ok(pipeline)
 }
 
@@ -106,6 +239,18 @@ pub fn stop_stream(pipeline: &Pipeline) -> Result<()> {
     Ok(())
 }
 
+/// Asks the pipeline to finish the stream. The muxers write the remaining data first,
+/// then the pipeline is stopped by its listener, once the end of the stream reaches the bus.
+pub fn end_stream(pipeline: &Pipeline) -> Result<()> {
+    if !pipeline.send_event(gstreamer::event::Eos::new()) {
+        return Err(anyhow::Error::msg(
+            "Pipeline didn't accept end of the stream",
+        ));
+    }
+
+    Ok(())
+}
+
 fn build_element(name: &str, props: Option<&[(&str, &str)]>) -> Result<Element> {
     let element = ElementFactory::make(name).build()?;
     if let Some(props) = props {
@@ -121,130 +266,696 @@ fn add_elements_to_pipeline(
     pipeline: &Pipeline,
     stream: Arc<dyn CompoundStreamInfoTrait>,
     resolution: &StreamResolution,
+    record_path: Option<&str>,
 ) -> Result<()> {
-    let (width, height, bitrate) = resolution.get_resolution();
     let rtmp_url = stream.compose_rtmp_url(resolution.clone());
 
-    // Video elements
-    let file_src = build_element(
-        "filesrc",
-        Some(&[("location", stream.get_video_path().as_str())]),
-    )?;
+    let source = build_source(&stream.get_source())?;
     let decode_bin = build_element("decodebin", Some(&[("name", "d")]))?;
-    let queue = build_element("queue", None)?;
-    let video_convert = build_element("videoconvert", None)?;
-    let video_scale = build_element("videoscale", None)?;
-    let video_h264 = build_element(
-        "capsfilter",
-        Some(&[(
-            "caps",
-            format!("video/x-raw, width={width}, height={height}").as_str(),
-        )]),
-    )?;
-
-    let x264_enc = build_element("x264enc", Some(&[("bitrate", &bitrate.to_string())]))?;
+    let video_branch = build_video_branch(resolution)?;
+    let audio_branch = build_audio_branch(resolution)?;
     let flv_mux = build_element("flvmux", Some(&[("name", "mux"), ("streamable", "true")]))?;
     let queue2 = build_element("queue", None)?;
-    let rtmp_sink = build_element("rtmpsink", Some(&[("location", rtmp_url.as_str())]))?;
-
-    // Audio elements
-    let queue3 = build_element("queue", None)?;
-    let audio_convert = build_element("audioconvert", None)?;
-    let audio_resample = build_element("audioresample", None)?;
-    let audio_xraw = build_element("capsfilter", Some(&[("caps", "audio/x-raw")]))?;
-    let avenc_aac = build_element("avenc_aac", Some(&[("bitrate", "128000")]))?;
-    let audio_mpeg = build_element("capsfilter", Some(&[("caps", "audio/mpeg")]))?;
-    let aac_parse = build_element("aacparse", None)?;
-    let audio_mpeg4 = build_element("capsfilter", Some(&[("caps", "audio/mpeg, mpegversion=4")]))?;
-
-    let pipeline_elements = [
-        &file_src,
-        &decode_bin,
-        &queue.clone(),
-        &video_convert,
-        &video_scale,
-        &video_h264,
-        &x264_enc,
-        &flv_mux,
-        &queue2,
-        &rtmp_sink,
-        &queue3.clone(),
-        &audio_convert,
-        &audio_resample,
-        &audio_xraw,
-        &avenc_aac,
-        &audio_mpeg,
-        &aac_parse,
-        &audio_mpeg4,
-    ];
-    pipeline.add_many(pipeline_elements)?;
+    let rtmp_sink = build_element(
+        "rtmpsink",
+        Some(&[("name", RTMP_SINK_NAME), ("location", rtmp_url.as_str())]),
+    )?;
 
-    file_src.link(&decode_bin)?;
-    Element::link_many([
-        &queue,
-        &video_convert,
-        &video_scale,
-        &video_h264,
-        &x264_enc,
-        &flv_mux,
-    ])?;
-    Element::link_many([
-        &queue3.clone(),
-        &audio_convert,
-        &audio_resample,
-        &audio_xraw,
-        &avenc_aac,
-        &audio_mpeg,
-        &aac_parse,
-        &audio_mpeg4,
-        &flv_mux,
-    ])?;
+    pipeline.add_many([&source, &decode_bin, &flv_mux, &queue2, &rtmp_sink])?;
+    pipeline.add_many(&video_branch)?;
+    pipeline.add_many(&audio_branch)?;
 
+    source.link(&decode_bin)?;
+    Element::link_many(&video_branch)?;
+    Element::link_many(&audio_branch)?;
+    match record_path {
+        Some(record_path) => add_recording_branch(
+            pipeline,
+            branch_end(&video_branch)?,
+            branch_end(&audio_branch)?,
+            &flv_mux,
+            record_path,
+        )?,
+        None => {
+            branch_end(&video_branch)?.link(&flv_mux)?;
+            branch_end(&audio_branch)?.link(&flv_mux)?;
+        }
+    }
     flv_mux.link(&rtmp_sink)?;
+
+    let is_live = matches!(stream.get_source(), StreamSource::Rtmp(_));
+    link_decoded_pads(&decode_bin, &video_branch, &audio_branch, is_live)?;
+
+    Ok(())
+}
+
+/// Splits the encoded video and audio, so they are both streamed and recorded to the MP4 file
+fn add_recording_branch(
+    pipeline: &Pipeline,
+    video_end: &Element,
+    audio_end: &Element,
+    stream_mux: &Element,
+    record_path: &str,
+) -> Result<()> {
+    let video_tee = build_element("tee", None)?;
+    let audio_tee = build_element("tee", None)?;
+    let video_stream_queue = build_element("queue", None)?;
+    let audio_stream_queue = build_element("queue", None)?;
+    let video_record_queue = build_element("queue", None)?;
+    let audio_record_queue = build_element("queue", None)?;
+    let video_parse = build_element("h264parse", None)?;
+    let record_mux = build_element(
+        "mp4mux",
+        Some(&[("fragment-duration", RECORDING_FRAGMENT_DURATION_MS)]),
+    )?;
+    let file_sink = build_element("filesink", Some(&[("location", record_path)]))?;
+
+    pipeline.add_many([
+        &video_tee,
+        &audio_tee,
+        &video_stream_queue,
+        &audio_stream_queue,
+        &video_record_queue,
+        &audio_record_queue,
+        &video_parse,
+        &record_mux,
+        &file_sink,
+    ])?;
+
+    video_end.link(&video_tee)?;
+    audio_end.link(&audio_tee)?;
+    Element::link_many([&video_tee, &video_stream_queue, stream_mux])?;
+    Element::link_many([&audio_tee, &audio_stream_queue, stream_mux])?;
+    Element::link_many([&video_tee, &video_record_queue, &video_parse, &record_mux])?;
+    Element::link_many([&audio_tee, &audio_record_queue, &record_mux])?;
+    record_mux.link(&file_sink)?;
+
+    Ok(())
+}
+
+/// Builds element, which reads the stream from the given source
+fn build_source(source: &StreamSource) -> Result<Element> {
+    match source {
+        StreamSource::File(path) => build_element("filesrc", Some(&[("location", path.as_str())])),
+        StreamSource::Rtmp(url) => {
+            // librtmp options are appended to the URL, live mode disables seeking in the feed
+            let location = format!("{url} live=1");
+            build_element("rtmpsrc", Some(&[("location", location.as_str())]))
+        }
+    }
+}
+
+/// Transcodes the video file into HLS renditions, one for each of the given resolutions.
+/// Each rendition is stored in its own subdirectory of `output_dir` and the master playlist
+/// referencing them is written as the last step, so its presence means the renditions are complete.
+///
+/// The function blocks until the whole video is transcoded, so it should run in a separate thread.
+pub fn transcode_to_hls(
+    video_path: &str,
+    output_dir: &str,
+    resolutions: &[StreamResolution],
+) -> Result<()> {
+    for resolution in resolutions {
+        let rendition_dir = format!("{output_dir}/{}", resolution.as_str());
+        std::fs::create_dir_all(&rendition_dir)?;
+
+        let pipeline = create_hls_pipeline(video_path, &rendition_dir, resolution)?;
+        run_to_end(&pipeline)?;
+        info!(
+            "HLS rendition {} of {} was created",
+            resolution.as_str(),
+            video_path
+        );
+    }
+
+    std::fs::write(
+        format!("{output_dir}/{HLS_MASTER_PLAYLIST}"),
+        compose_master_playlist(resolutions),
+    )?;
+
+    Ok(())
+}
+
+/// Composes HLS master playlist, which points to the rendition playlists of all resolutions
+fn compose_master_playlist(resolutions: &[StreamResolution]) -> String {
+    let mut playlist = String::from("#EXTM3U\n#EXT-X-VERSION:3\n");
+    for resolution in resolutions {
+        let (width, height, bitrate) = resolution.get_resolution();
+        let bandwidth = (bitrate + resolution.audio_bitrate_kbps) * 1000;
+        playlist.push_str(&format!(
+            "#EXT-X-STREAM-INF:BANDWIDTH={bandwidth},RESOLUTION={width}x{height}\n{}/{HLS_RENDITION_PLAYLIST}\n",
+            resolution.as_str()
+        ));
+    }
+
+    playlist
+}
+
+fn create_hls_pipeline(
+    video_path: &str,
+    rendition_dir: &str,
+    resolution: &StreamResolution,
+) -> Result<Pipeline> {
+    let pipeline = Pipeline::new();
+
+    let file_src = build_element("filesrc", Some(&[("location", video_path)]))?;
+    let decode_bin = build_element("decodebin", None)?;
+    let mut video_branch = build_video_branch(resolution)?;
+    video_branch.push(build_element("h264parse", None)?);
+    let audio_branch = build_audio_branch(resolution)?;
+    let hls_sink = build_element(
+        "hlssink2",
+        Some(&[
+            (
+                "location",
+                format!("{rendition_dir}/segment%05d.ts").as_str(),
+            ),
+            (
+                "playlist-location",
+                format!("{rendition_dir}/{HLS_RENDITION_PLAYLIST}").as_str(),
+            ),
+            ("target-duration", HLS_SEGMENT_DURATION),
+            ("playlist-length", "0"),
+            ("max-files", "0"),
+        ]),
+    )?;
+
+    pipeline.add_many([&file_src, &decode_bin, &hls_sink])?;
+    pipeline.add_many(&video_branch)?;
+    pipeline.add_many(&audio_branch)?;
+
+    file_src.link(&decode_bin)?;
+    Element::link_many(&video_branch)?;
+    Element::link_many(&audio_branch)?;
+    branch_end(&video_branch)?.link_pads(Some("src"), &hls_sink, Some("video"))?;
+    branch_end(&audio_branch)?.link_pads(Some("src"), &hls_sink, Some("audio"))?;
+
+    link_decoded_pads(&decode_bin, &video_branch, &audio_branch, false)?;
+
+    Ok(pipeline)
+}
+
+/// Plays the pipeline and blocks until the end of the stream is reached
+fn run_to_end(pipeline: &Pipeline) -> Result<()> {
+    let bus = pipeline.bus().ok_or(anyhow::Error::msg(
+        "Error while initializing bus of pipeline",
+    ))?;
+    pipeline.set_state(State::Playing)?;
+
+    for msg in bus.iter_timed(ClockTime::NONE) {
+        match msg.view() {
+            MessageView::Eos(_) => break,
+            MessageView::Error(err) => {
+                stop_stream(pipeline)?;
+                return Err(anyhow::Error::msg(err.error().to_string()));
+            }
+            _ => (),
+        }
+    }
+
+    stop_stream(pipeline)
+}
+
+/// Captures frames of the video evenly distributed over its duration,
+/// e.g. for `count` 3 the frames are taken at 25 %, 50 % and 75 % of the video.
+///
+/// The frames keep the original resolution of the video. Function blocks until all frames
+/// are captured.
+pub fn capture_frames(video_path: &str, count: u32) -> Result<Vec<RgbaImage>> {
+    let pipeline = Pipeline::new();
+
+    let file_src = build_element("filesrc", Some(&[("location", video_path)]))?;
+    let decode_bin = build_element("decodebin", None)?;
+    let video_convert = build_element("videoconvert", None)?;
+    let rgba_caps = build_element("capsfilter", Some(&[("caps", "video/x-raw, format=RGBA")]))?;
+    let sink = build_element("fakesink", Some(&[("sync", "false")]))?;
+
+    pipeline.add_many([&file_src, &decode_bin, &video_convert, &rgba_caps, &sink])?;
+    file_src.link(&decode_bin)?;
+    Element::link_many([&video_convert, &rgba_caps, &sink])?;
+
     decode_bin.connect_pad_added(move |_, src_pad: &Pad| {
-        let video_sink_pad = &queue
+        let sink_pad = video_convert
+            .static_pad("sink")
+            .expect("Failed to get static sink pad from videoconvert");
+        if is_video_pad(src_pad) && !sink_pad.is_linked() {
+            src_pad.link(&sink_pad).err();
+        }
+    });
+
+    pipeline.set_state(State::Paused)?;
+    let frames = wait_for_preroll(&pipeline).and_then(|_| {
+        let duration = pipeline
+            .query_duration::<ClockTime>()
+            .ok_or(anyhow::Error::msg("Failed to read duration of the video"))?;
+
+        (1..=count as u64)
+            .map(|index| {
+                let position =
+                    ClockTime::from_nseconds(duration.nseconds() * index / (count as u64 + 1));
+                pipeline.seek_simple(SeekFlags::FLUSH | SeekFlags::ACCURATE, position)?;
+                wait_for_preroll(&pipeline)?;
+
+                let sample = sink
+                    .property::<Option<Sample>>("last-sample")
+                    .ok_or(anyhow::Error::msg("No frame was captured"))?;
+                sample_to_image(&sample)
+            })
+            .collect()
+    });
+    stop_stream(&pipeline)?;
+
+    frames
+}
+
+/// Reads technical information about the media file, i.e. duration, resolution, frame rate,
+/// container and codecs. The discoverer only prerolls the file, so the probe is cheap.
+pub fn probe_media(video_path: &str) -> Result<MediaInfo> {
+    let path = std::fs::canonicalize(video_path)?;
+    let uri = gstreamer::glib::filename_to_uri(&path, None)?;
+    let discoverer = Discoverer::new(ClockTime::from_seconds(MEDIA_PROBE_TIMEOUT_SECS))?;
+    let discovered = discoverer.discover_uri(&uri)?;
+
+    let mut info = MediaInfo {
+        duration_ms: discovered.duration().map(|duration| duration.mseconds()),
+        container: discovered
+            .stream_info()
+            .and_then(|stream| stream.caps())
+            .and_then(|caps| caps.structure(0).map(describe_format)),
+        ..MediaInfo::default()
+    };
+
+    if let Some(video) = discovered.video_streams().first() {
+        let frame_rate = video.framerate();
+        info.video_codec = video
+            .caps()
+            .and_then(|caps| caps.structure(0).map(describe_format));
+        info.width = Some(video.width()).filter(|width| *width > 0);
+        info.height = Some(video.height()).filter(|height| *height > 0);
+        info.frame_rate = (frame_rate.denom() != 0 && frame_rate.numer() != 0)
+            .then(|| frame_rate.numer() as f64 / frame_rate.denom() as f64);
+    }
+    if let Some(audio) = discovered.audio_streams().first() {
+        info.audio_codec = audio
+            .caps()
+            .and_then(|caps| caps.structure(0).map(describe_format));
+    }
+
+    Ok(info)
+}
+
+/// Returns human readable name of the container or codec described by the caps
+fn describe_format(structure: &StructureRef) -> String {
+    let mpeg_version = structure.get::<i32>("mpegversion").ok();
+    let name = match (structure.name().as_str(), mpeg_version) {
+        ("video/quicktime", _) => "MP4",
+        ("video/x-matroska", _) => "Matroska",
+        ("video/webm", _) => "WebM",
+        ("video/x-msvideo", _) => "AVI",
+        ("video/mpegts", _) => "MPEG-TS",
+        ("video/x-flv", _) => "FLV",
+        ("video/x-h264", _) => "H.264",
+        ("video/x-h265", _) => "H.265",
+        ("video/x-vp8", _) => "VP8",
+        ("video/x-vp9", _) => "VP9",
+        ("video/x-av1", _) => "AV1",
+        ("video/mpeg", Some(4)) => "MPEG-4 Part 2",
+        ("video/mpeg", _) => "MPEG-2",
+        ("audio/mpeg", Some(1)) => "MP3",
+        ("audio/mpeg", _) => "AAC",
+        ("audio/x-opus", _) => "Opus",
+        ("audio/x-vorbis", _) => "Vorbis",
+        ("audio/x-flac", _) => "FLAC",
+        ("audio/x-ac3", _) => "AC-3",
+        (other, _) => other,
+    };
+
+    name.to_string()
+}
+
+/// Waits until the paused pipeline is prerolled, i.e. the sink received the first buffer
+fn wait_for_preroll(pipeline: &Pipeline) -> Result<()> {
+    let bus = pipeline.bus().ok_or(anyhow::Error::msg(
+        "Error while initializing bus of pipeline",
+    ))?;
+    let msg = bus
+        .timed_pop_filtered(
+            ClockTime::from_seconds(FRAME_CAPTURE_TIMEOUT_SECS),
+            &[MessageType::AsyncDone, MessageType::Error],
+        )
+        .ok_or(anyhow::Error::msg("Pipeline didn't preroll in time"))?;
+
+    match msg.view() {
+        MessageView::Error(err) => Err(anyhow::Error::msg(err.error().to_string())),
+        _ => Ok(()),
+    }
+}
+
+/// Copies RGBA frame from the sample to an image, so the row padding is removed
+fn sample_to_image(sample: &Sample) -> Result<RgbaImage> {
+    let caps = sample
+        .caps()
+        .ok_or(anyhow::Error::msg("Captured frame has no caps"))?;
+    let info = VideoInfo::from_caps(caps)?;
+    let buffer = sample
+        .buffer()
+        .ok_or(anyhow::Error::msg("Captured frame has no buffer"))?;
+    let map = buffer.map_readable()?;
+
+    let row_length = info.width() as usize * 4;
+    let stride = info.stride()[0] as usize;
+    let mut pixels = Vec::with_capacity(row_length * info.height() as usize);
+    for row in map.as_slice().chunks(stride).take(info.height() as usize) {
+        pixels.extend_from_slice(&row[..row_length]);
+    }
+
+    RgbaImage::from_raw(info.width(), info.height(), pixels)
+        .ok_or(anyhow::Error::msg("Captured frame has unexpected size"))
+}
+
+fn is_video_pad(pad: &Pad) -> bool {
+    has_media_type(pad, "video/")
+}
+
+fn is_audio_pad(pad: &Pad) -> bool {
+    has_media_type(pad, "audio/")
+}
+
+fn has_media_type(pad: &Pad, prefix: &str) -> bool {
+    pad.current_caps()
+        .and_then(|caps| {
+            caps.structure(0)
+                .map(|structure| structure.name().starts_with(prefix))
+        })
+        .unwrap_or(false)
+}
+
+/// Builds elements which scale and encode raw video to H.264 with the encoder profile
+/// of the given resolution. The elements are expected to be linked in the returned order.
+fn build_video_branch(resolution: &StreamResolution) -> Result<Vec<Element>> {
+    let (width, height, bitrate) = resolution.get_resolution();
+    let frame_rate = resolution.frame_rate;
+    let bitrate = bitrate.to_string();
+    let keyframe_interval = resolution.keyframe_interval.to_string();
+    let mut encoder_props = vec![
+        ("bitrate", bitrate.as_str()),
+        ("speed-preset", resolution.preset.as_str()),
+        ("key-int-max", keyframe_interval.as_str()),
+    ];
+    if let Some(tune) = &resolution.tune {
+        encoder_props.push(("tune", tune.as_str()));
+    }
+
+    Ok(vec![
+        build_element("queue", None)?,
+        build_element("videoconvert", None)?,
+        build_element("videoscale", None)?,
+        build_element("videorate", None)?,
+        build_element(
+            "capsfilter",
+            Some(&[(
+                "caps",
+                format!("video/x-raw, width={width}, height={height}, framerate={frame_rate}/1")
+                    .as_str(),
+            )]),
+        )?,
+        build_element("x264enc", Some(&encoder_props))?,
+    ])
+}
+
+/// Builds elements which encode raw audio to AAC with the bitrate of the given resolution.
+/// The elements are expected to be linked in the returned order.
+fn build_audio_branch(resolution: &StreamResolution) -> Result<Vec<Element>> {
+    let bitrate = (resolution.audio_bitrate_kbps * 1000).to_string();
+
+    Ok(vec![
+        build_element("queue", None)?,
+        build_element("audioconvert", None)?,
+        build_element("audioresample", None)?,
+        build_element("capsfilter", Some(&[("caps", "audio/x-raw")]))?,
+        build_element("avenc_aac", Some(&[("bitrate", bitrate.as_str())]))?,
+        build_element("capsfilter", Some(&[("caps", "audio/mpeg")]))?,
+        build_element("aacparse", None)?,
+        build_element("capsfilter", Some(&[("caps", "audio/mpeg, mpegversion=4")]))?,
+    ])
+}
+
+fn branch_start(branch: &[Element]) -> Result<&Element> {
+    branch
+        .first()
+        .ok_or(anyhow::Error::msg("Pipeline branch is empty"))
+}
+
+fn branch_end(branch: &[Element]) -> Result<&Element> {
+    branch
+        .last()
+        .ok_or(anyhow::Error::msg("Pipeline branch is empty"))
+}
+
+/// Links pads created by decodebin to the video and audio branches by their caps.
+/// Once all pads are created, the branch without its track is fed by a test source,
+/// e.g. by silence, so the muxer and the players still get both tracks.
+fn link_decoded_pads(
+    decode_bin: &Element,
+    video_branch: &[Element],
+    audio_branch: &[Element],
+    is_live: bool,
+) -> Result<()> {
+    let video_queue = branch_start(video_branch)?.clone();
+    let audio_queue = branch_start(audio_branch)?.clone();
+
+    let (queue, queue3) = (video_queue.clone(), audio_queue.clone());
+    decode_bin.connect_pad_added(move |_, src_pad: &Pad| {
+        let queue = match (is_video_pad(src_pad), is_audio_pad(src_pad)) {
+            (true, _) => &queue,
+            (_, true) => &queue3,
+            _ => {
+                debug!(
+                    "Decoded pad {} is not linked, it's neither video nor audio",
+                    src_pad.name()
+                );
+                return;
+            }
+        };
+
+        let sink_pad = queue
             .static_pad("sink")
             .expect("Failed to get static sink pad from queue");
+        if !sink_pad.is_linked() {
+            if let Err(err) = src_pad.link(&sink_pad) {
+                warn!("Failed to link decoded pad {}: {:?}", src_pad.name(), err);
+            }
+        }
+    });
 
-        if !src_pad.is_linked() {
-            src_pad.link(video_sink_pad).err();
+    let is_live = if is_live { "true" } else { "false" };
+    decode_bin.connect_no_more_pads(move |decode_bin| {
+        let fillers = [
+            (
+                &video_queue,
+                &audio_queue,
+                "videotestsrc",
+                ("pattern", "black"),
+            ),
+            (
+                &audio_queue,
+                &video_queue,
+                "audiotestsrc",
+                ("wave", "silence"),
+            ),
+        ];
+        for (queue, other_queue, factory, pattern) in fillers {
+            let props = [pattern, ("is-live", is_live)];
+            if let Err(err) = fill_missing_track(decode_bin, queue, other_queue, factory, &props) {
+                error!("Failed to fill the missing track by {}: {:?}", factory, err);
+            }
         }
+    });
 
-        let audio_sink_pad = &queue3
-            .static_pad("sink")
-            .expect("failed to get static sink pad from audio queue");
-        if !audio_sink_pad.is_linked() {
-            src_pad.link(audio_sink_pad).err();
+    Ok(())
+}
+
+/// Links the test source to the queue, when no decoded pad was linked to it.
+/// The test source ends together with the track of `other_queue`, otherwise the muxer
+/// would wait for the end of the endless test source.
+fn fill_missing_track(
+    decode_bin: &Element,
+    queue: &Element,
+    other_queue: &Element,
+    factory: &str,
+    props: &[(&str, &str)],
+) -> Result<()> {
+    let sink_pad = queue.static_pad("sink").ok_or(anyhow::Error::msg(
+        "Failed to get static sink pad from queue",
+    ))?;
+    let other_sink_pad = other_queue.static_pad("sink").ok_or(anyhow::Error::msg(
+        "Failed to get static sink pad from queue",
+    ))?;
+    if sink_pad.is_linked() || !other_sink_pad.is_linked() {
+        return Ok(());
+    }
+
+    let pipeline = decode_bin
+        .parent()
+        .and_then(|parent| parent.downcast::<Pipeline>().ok())
+        .ok_or(anyhow::Error::msg("Decodebin is not part of the pipeline"))?;
+    let filler = build_element(factory, Some(props))?;
+    pipeline.add(&filler)?;
+    filler.link(queue)?;
+    filler.sync_state_with_parent()?;
+
+    let filler = filler.downgrade();
+    other_sink_pad.add_probe(PadProbeType::EVENT_DOWNSTREAM, move |_pad, info| {
+        let is_eos = info
+            .event()
+            .is_some_and(|event| event.type_() == EventType::Eos);
+        if let Some(filler) = filler.upgrade().filter(|_| is_eos) {
+            filler.send_event(gstreamer::event::Eos::new());
         }
+        PadProbeReturn::Ok
     });
+    info!("Missing track of the source is filled by {}", factory);
 
     Ok(())
 }
 
 // Use this test only for offline tests of streaming controller
 // Remove it before the project is done
+#[cfg(test)]
 #[allow(unused_imports)]
 mod test {
     use crate::business::models::stream::CompoundStreamInfo;
-    use crate::streamer::gstreamer_controller::{create_stream_pipeline, init_gstreamer};
-    use crate::streamer::types::StreamResolution;
-    use gstreamer::prelude::ElementExt;
-    use gstreamer::{ClockTime, MessageView, State};
+    use crate::configuration::models::EncoderProfile;
+    use crate::streamer::gstreamer_controller::{
+        create_stream_pipeline, init_gstreamer, probe_media, run_to_end, transcode_to_hls,
+        HLS_MASTER_PLAYLIST, HLS_RENDITION_PLAYLIST,
+    };
+    use crate::streamer::types::{StreamResolution, StreamSource};
+    use gstreamer::prelude::{Cast, ElementExt};
+    use gstreamer::{ClockTime, MessageView, Pipeline, State};
     use std::env;
+    use std::fs;
+    use std::path::Path;
     use std::sync::Arc;
+    use tempfile::TempDir;
+
+    const VIDEO_FIXTURE: &str =
+        "videotestsrc num-buffers=90 ! video/x-raw, width=320, height=240, \
+        framerate=30/1 ! x264enc ! mux.";
+    const AUDIO_FIXTURE: &str = "audiotestsrc num-buffers=130 ! audioconvert ! avenc_aac ! mux.";
+
+    fn create_resolution() -> StreamResolution {
+        StreamResolution::from(&EncoderProfile {
+            name: String::from("240"),
+            width: 320,
+            height: 240,
+            video_bitrate_kbps: 256,
+            preset: String::from("ultrafast"),
+            tune: None,
+            keyframe_interval: 30,
+            audio_bitrate_kbps: 64,
+            frame_rate: 30,
+        })
+    }
+
+    /// Generates MP4 file with the tracks described by the given pipeline branches
+    fn create_fixture(dir: &TempDir, name: &str, tracks: &[&str]) -> anyhow::Result<String> {
+        let path = dir.path().join(name).to_string_lossy().to_string();
+        let description = format!(
+            "mp4mux name=mux ! filesink location={path} {}",
+            tracks.join(" ")
+        );
+        let pipeline = gstreamer::parse::launch(&description)?
+            .downcast::<Pipeline>()
+            .map_err(|_| anyhow::Error::msg("Fixture pipeline is not a pipeline"))?;
+        run_to_end(&pipeline)?;
+        pipeline.set_state(State::Null)?;
+
+        Ok(path)
+    }
+
+    /// Transcodes the fixture and checks that the rendition has some segments
+    fn assert_transcoded(dir: &TempDir, video_path: &str) -> anyhow::Result<()> {
+        let output_dir = dir.path().join("hls").to_string_lossy().to_string();
+        transcode_to_hls(video_path, &output_dir, &[create_resolution()])?;
+
+        assert!(Path::new(&format!("{output_dir}/{HLS_MASTER_PLAYLIST}")).exists());
+        let playlist = fs::read_to_string(format!("{output_dir}/240/{HLS_RENDITION_PLAYLIST}"))?;
+        assert!(playlist.contains("#EXTINF"));
+        assert!(playlist.contains("#EXT-X-ENDLIST"));
+        Ok(())
+    }
+
+    #[test]
+    fn transcode_video_with_audio() -> anyhow::Result<()> {
+        init_gstreamer()?;
+        let dir = tempfile::tempdir()?;
+        // Audio track goes first, so the pads are not created in the order of the branches
+        let path = create_fixture(&dir, "both.mp4", &[AUDIO_FIXTURE, VIDEO_FIXTURE])?;
+
+        assert_transcoded(&dir, &path)
+    }
+
+    #[test]
+    fn transcode_video_without_audio() -> anyhow::Result<()> {
+        init_gstreamer()?;
+        let dir = tempfile::tempdir()?;
+        let path = create_fixture(&dir, "video.mp4", &[VIDEO_FIXTURE])?;
+
+        assert_transcoded(&dir, &path)
+    }
+
+    #[test]
+    fn transcode_audio_only() -> anyhow::Result<()> {
+        init_gstreamer()?;
+        let dir = tempfile::tempdir()?;
+        let path = create_fixture(&dir, "audio.mp4", &[AUDIO_FIXTURE])?;
+
+        assert_transcoded(&dir, &path)
+    }
+
+    #[test]
+    fn probe_video_with_audio() -> anyhow::Result<()> {
+        init_gstreamer()?;
+        let dir = tempfile::tempdir()?;
+        let path = create_fixture(&dir, "both.mp4", &[VIDEO_FIXTURE, AUDIO_FIXTURE])?;
+
+        let info = probe_media(&path)?;
+
+        assert_eq!(info.container.as_deref(), Some("MP4"));
+        assert_eq!(info.video_codec.as_deref(), Some("H.264"));
+        assert_eq!(info.audio_codec.as_deref(), Some("AAC"));
+        assert_eq!((info.width, info.height), (Some(320), Some(240)));
+        assert_eq!(info.frame_rate, Some(30.0));
+        // 90 frames at 30 fps
+        assert!(info.duration_ms.is_some_and(|duration| duration >= 2900));
+        Ok(())
+    }
 
     // #[test]
     #[allow(dead_code)]
     fn test01() -> anyhow::Result<()> {
         println!("{:?}", env::current_dir());
         init_gstreamer()?;
+        let resolution = StreamResolution::from(&EncoderProfile {
+            name: String::from("360"),
+            width: 640,
+            height: 360,
+            video_bitrate_kbps: 288,
+            preset: String::from("veryfast"),
+            tune: Some(String::from("zerolatency")),
+            keyframe_interval: 60,
+            audio_bitrate_kbps: 128,
+            frame_rate: 30,
+        });
         let main_stream = CompoundStreamInfo::new(
             String::from("2"),
-            String::from("video_resources/video3.mp4"),
-            vec![StreamResolution::P360],
+            StreamSource::File(String::from("video_resources/video3.mp4")),
+            vec![resolution.clone()],
+            None,
         );
 
-        let pipeline = create_stream_pipeline(Arc::new(main_stream), &StreamResolution::P360)?;
+        let pipeline = create_stream_pipeline(Arc::new(main_stream), &resolution, None)?;
         match pipeline.set_state(State::Playing) {
             Ok(_) => {
                 println!("Stream started!");
@@ -1,6 +1,5 @@
-use crate::business::models::error::{AppError, AppErrorKind};
+use crate::configuration::models::EncoderProfile;
 use gstreamer::Pipeline;
-use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 
 pub type Stream = (Arc<dyn CompoundStreamInfoTrait>, PipelinesList);
@@ -11,57 +10,120 @@ pub trait StreamStorageTrait: Send + Sync {
     fn push(&self, stream: Arc<dyn CompoundStreamInfoTrait>, pipeline: PipelinesList);
     fn remove(&self, stream_id: &str);
     fn size(&self) -> usize;
+    /// Stores the latest health of one pipeline of the stream
+    fn report_health(&self, stream_id: &str, health: PipelineHealth);
 }
 
 pub trait CompoundStreamInfoTrait: Send + Sync {
     fn compose_rtmp_url(&self, resolution: StreamResolution) -> String;
     fn get_stream_id(&self) -> String;
-    fn get_video_path(&self) -> String;
+    fn get_source(&self) -> StreamSource;
     fn get_resolutions(&self) -> &Vec<StreamResolution>;
+    /// Path to the file, where the highest resolution is recorded, `None` if it's not recorded
+    fn get_record_path(&self) -> Option<String>;
 }
 
-#[derive(Clone)]
-pub enum StreamResolution {
-    P360,
-    P480,
-    P720,
+/// Input of the stream pipeline
+#[derive(Clone, Debug)]
+pub enum StreamSource {
+    /// Path to the video file
+    File(String),
+    /// URL of the RTMP feed sent by the encoder of the artist
+    Rtmp(String),
+}
+
+/// Health of one pipeline of the stream, which is reported periodically while the stream runs
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PipelineHealth {
+    /// Height of the streamed resolution, e.g. 720
+    pub resolution: String,
+    /// Bitrate sent to the streaming server since the previous report
+    pub bitrate_kbps: u32,
+    /// Frames dropped by the elements of the pipeline since the stream started
+    pub dropped_frames: u64,
+    /// QoS messages sent since the stream started, because some element can't keep up
+    pub qos_events: u32,
+    /// Fill level of the buffers of the source, `None` if the source doesn't buffer
+    pub buffering_percent: Option<i32>,
+    pub warnings: u32,
+}
+
+/// Technical information about the media file
+#[derive(Default, Clone, Debug)]
+pub struct MediaInfo {
+    pub duration_ms: Option<u64>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub container: Option<String>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+}
+
+/// Rendition of the stream, which is created by the encoders with the configured profile
+#[derive(Clone, Debug, PartialEq)]
+pub struct StreamResolution {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub video_bitrate_kbps: u32,
+    pub preset: String,
+    pub tune: Option<String>,
+    pub keyframe_interval: u32,
+    pub audio_bitrate_kbps: u32,
+    pub frame_rate: u32,
 }
 
 impl StreamResolution {
-    pub fn as_str(&self) -> &'static str {
-        match &self {
-            StreamResolution::P360 => "360",
-            StreamResolution::P480 => "480",
-            StreamResolution::P720 => "720",
-        }
+    pub fn as_str(&self) -> &str {
+        self.name.as_str()
     }
 
     /// Returns (width, height, bitrate)
     pub fn get_resolution(&self) -> (u32, u32, u32) {
-        match &self {
-            StreamResolution::P360 => (640, 360, 288),
-            StreamResolution::P480 => (854, 480, 448),
-            StreamResolution::P720 => (1280, 720, 2048),
-        }
+        (self.width, self.height, self.video_bitrate_kbps)
     }
-}
 
-impl FromStr for StreamResolution {
-    type Err = AppError;
-
-    fn from_str(resolution: &str) -> Result<Self, Self::Err> {
-        let value = match resolution {
-            "360" => StreamResolution::P360,
-            "480" => StreamResolution::P480,
-            "720" => StreamResolution::P720,
-            &_ => {
-                return Err(AppError::new(
-                    "Unknown resolution defined",
-                    AppErrorKind::InternalServerError,
-                ))
-            }
+    /// Removes resolutions higher than the height of the source video, because upscaling
+    /// only wastes the bandwidth. The lowest resolution is always kept.
+    pub fn filter_by_source(resolutions: Vec<Self>, source_height: Option<i32>) -> Vec<Self> {
+        let Some(source_height) = source_height else {
+            return resolutions;
         };
 
-        Ok(value)
+        let lowest = resolutions
+            .iter()
+            .min_by_key(|resolution| resolution.height)
+            .cloned();
+        let filtered: Vec<Self> = resolutions
+            .into_iter()
+            .filter(|resolution| resolution.height as i32 <= source_height)
+            .collect();
+
+        match (filtered.is_empty(), lowest) {
+            (true, Some(lowest)) => vec![lowest],
+            _ => filtered,
+        }
+    }
+
+    /// Creates resolutions from the encoder profiles defined in the configuration
+    pub fn from_profiles(profiles: &[EncoderProfile]) -> Vec<Self> {
+        profiles.iter().map(Self::from).collect()
+    }
+}
+
+impl From<&EncoderProfile> for StreamResolution {
+    fn from(profile: &EncoderProfile) -> Self {
+        Self {
+            name: profile.name.clone(),
+            width: profile.width,
+            height: profile.height,
+            video_bitrate_kbps: profile.video_bitrate_kbps,
+            preset: profile.preset.clone(),
+            tune: profile.tune.clone(),
+            keyframe_interval: profile.keyframe_interval,
+            audio_bitrate_kbps: profile.audio_bitrate_kbps,
+            frame_rate: profile.frame_rate,
+        }
     }
 }
@@ -0,0 +1,62 @@
+/**
+ * Connects the chat to the events of the stream, so new messages appear without reloading
+ * @param streamId ID of the stream
+ */
+function setupChat(streamId) {
+    const chat = document.getElementById("stream-chat");
+    const messages = document.getElementById("chat-messages");
+    const notice = document.getElementById("chat-notice");
+    if (!chat) {
+        return;
+    }
+    messages.scrollTop = messages.scrollHeight;
+
+    // Only one chat can be shown at a time, the previous one was replaced by navigation
+    if (window.chatEvents) {
+        window.chatEvents.close();
+    }
+    const events = new EventSource(`/stream/${streamId}/chat/events`);
+    window.chatEvents = events;
+
+    const isDetached = () => {
+        if (document.body.contains(chat)) {
+            return false;
+        }
+        events.close();
+        return true;
+    };
+
+    events.addEventListener("message", (event) => {
+        if (isDetached()) {
+            return;
+        }
+        messages.insertAdjacentHTML("beforeend", event.data);
+        htmx.process(messages.lastElementChild);
+        messages.scrollTop = messages.scrollHeight;
+    });
+    events.addEventListener("delete", (event) => {
+        if (!isDetached()) {
+            document.getElementById(`chat-message-${event.data}`)?.remove();
+        }
+    });
+    events.addEventListener("timeout", (event) => {
+        const timeout = JSON.parse(event.data);
+        if (!isDetached() && chat.dataset.userId === String(timeout.user_id)) {
+            notice.textContent = `You were timed out for ${Math.ceil(timeout.seconds / 60)} minutes`;
+        }
+    });
+
+    chat.addEventListener("htmx:responseError", (event) => {
+        const xhr = event.detail.xhr;
+        // Users with unverified email are rejected before the message reaches the chat
+        notice.textContent = xhr.status === 403 && !xhr.responseText
+            ? "Verify your email to join the chat"
+            : xhr.responseText;
+    });
+    chat.addEventListener("htmx:afterRequest", (event) => {
+        if (event.detail.successful && event.target.id === "chat-form") {
+            event.target.reset();
+            notice.textContent = "";
+        }
+    });
+}
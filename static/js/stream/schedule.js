@@ -0,0 +1,52 @@
+/**
+ * Counts down to the start of the scheduled stream, the page is reloaded once the stream starts
+ * @param streamId ID of the stream
+ */
+function setupCountdown(streamId) {
+    const countdown = document.getElementById("stream-countdown");
+    if (!countdown) {
+        return;
+    }
+    const startTime = new Date(countdown.dataset.startTime);
+
+    // Only one countdown can be shown at a time, the previous one was replaced by navigation
+    if (window.streamCountdown) {
+        clearInterval(window.streamCountdown);
+    }
+
+    const update = () => {
+        if (!document.body.contains(countdown)) {
+            clearInterval(window.streamCountdown);
+            return;
+        }
+
+        const remaining = Math.floor((startTime - new Date()) / 1000);
+        if (remaining > 0) {
+            const days = Math.floor(remaining / 86400);
+            const hours = Math.floor((remaining % 86400) / 3600);
+            const minutes = Math.floor((remaining % 3600) / 60);
+            const seconds = remaining % 60;
+            const time = [hours, minutes, seconds].map((part) => String(part).padStart(2, "0")).join(":");
+            countdown.textContent = days > 0 ? `Starts in ${days}d ${time}` : `Starts in ${time}`;
+            return;
+        }
+
+        countdown.textContent = "Stream is starting";
+        // The scheduler starts the stream within a few seconds, so the page is checked until it runs
+        if (remaining % 5 === 0) {
+            htmx.ajax("GET", `/stream/${streamId}/watch`, "#content-container");
+        }
+    };
+
+    update();
+    window.streamCountdown = setInterval(update, 1000);
+}
+
+/**
+ * Converts the start time picked by the artist to RFC 3339, which includes his time zone
+ * @param inputId ID of the datetime-local input
+ */
+function getScheduledStartTime(inputId) {
+    const value = document.getElementById(inputId).value;
+    return value ? new Date(value).toISOString() : "";
+}
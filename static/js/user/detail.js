@@ -1,6 +1,8 @@
 document.addEventListener("htmx:configRequest", checkUserDetailReadonly);
 document.addEventListener("htmx:beforeRequest", handleBeforeRequest);
 document.addEventListener("htmx:responseError", handleUpdateServerError);
+document.addEventListener("htmx:responseError", handleResendVerificationError);
+document.addEventListener("htmx:responseError", handleSessionLogoutError);
 
 function handleUpdateServerError(event) {
     const updateFormId = "user-edit-form";
@@ -28,6 +30,19 @@ function handleUpdateServerError(event) {
     displayErrorMessage(errorContainer, errorMessage);
 }
 
+function handleResendVerificationError(event) {
+    if (event && event.target.id !== "resend-verification") {
+        return;
+    }
+
+    const errorContainer = document.getElementById("verification-error");
+    const errorMessage = event.detail.xhr.status === 429
+        ? event.detail.xhr.responseText
+        : "The link was not sent. Please try again.";
+
+    displayErrorMessage(errorContainer, errorMessage);
+}
+
 function enableEditing(event) {
     const usernameField = document.getElementById("user-username");
     const emailField = document.getElementById("user-email");
@@ -179,3 +194,17 @@ function validateEmail() {
     emailError.classList.add("hidden");
     return true;
 }
+
+function handleSessionLogoutError(event) {
+    const sessions = document.getElementById("user-sessions");
+    if (!sessions || !event || !sessions.contains(event.target)) {
+        return;
+    }
+
+    const errorContainer = document.getElementById("user-sessions-error");
+    const errorMessage = event.detail.xhr.status === 400
+        ? event.detail.xhr.responseText
+        : "An unexpected error occurred. Please try again.";
+
+    displayErrorMessage(errorContainer, errorMessage);
+}
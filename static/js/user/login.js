@@ -27,6 +27,8 @@ function handleLoginServerError(event) {
         const errorCode = event.detail.xhr.status;
 
         switch (true) {
+            case errorCode === 429:
+                return event.detail.xhr.responseText;
             case errorCode >= 400 && errorCode < 500:
                 return "Invalid username or password. Please try again.";
             case errorCode >= 500:
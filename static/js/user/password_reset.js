@@ -0,0 +1,59 @@
+document.addEventListener("htmx:beforeRequest", handleBeforeResetPasswordRequest);
+document.addEventListener("htmx:responseError", handleResetPasswordServerError);
+
+const passwordResetForms = {
+    "password-forgot-form": "password-forgot-error",
+    "password-reset-form": "password-reset-error",
+};
+
+function handleResetPasswordServerError(event) {
+    const errorContainerId = passwordResetForms[event?.target.id];
+    if (!errorContainerId) {
+        return;
+    }
+
+    const errorMessage = (() => {
+        switch (event.detail.xhr.status) {
+            case 400:
+                return event.detail.xhr.responseText;
+            default:
+                return "An unexpected error occurred. Please try again.";
+        }
+    })();
+
+    const errorContainer = document.getElementById(errorContainerId);
+
+    displayErrorMessage(errorContainer, errorMessage);
+}
+
+function handleBeforeResetPasswordRequest(event) {
+    if (event && event.target && event.target.id !== "password-reset-form") {
+        return;
+    }
+
+    const password = document.getElementById("reset-password").value;
+    const password2 = document.getElementById("reset-password2").value;
+    const passwordError = document.getElementById("password-reset-error");
+
+    const errorMessage = (() => {
+        if (password.length < 8) {
+            return "Password must be at least 8 characters.";
+        }
+        if (password.length > 128) {
+            return "Password must be maximum of 128 characters long.";
+        }
+        if (password !== password2) {
+            return "Passwords do not match.";
+        }
+        return null;
+    })();
+
+    if (errorMessage) {
+        passwordError.textContent = errorMessage;
+        passwordError.classList.remove("hidden");
+        event.preventDefault();
+        return;
+    }
+
+    passwordError.classList.add("hidden");
+}
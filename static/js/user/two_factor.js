@@ -0,0 +1,29 @@
+document.addEventListener("htmx:responseError", handleTwoFactorServerError);
+
+const twoFactorForms = {
+    "two-factor-enable-form": "two-factor-enable-error",
+    "two-factor-confirm-form": "two-factor-confirm-error",
+    "two-factor-disable-form": "two-factor-disable-error",
+    "two-factor-challenge-form": "two-factor-challenge-error",
+};
+
+function handleTwoFactorServerError(event) {
+    const errorContainerId = twoFactorForms[event?.target.id];
+    if (!errorContainerId) {
+        return;
+    }
+
+    const errorMessage = (() => {
+        switch (event.detail.xhr.status) {
+            case 400:
+            case 429:
+                return event.detail.xhr.responseText;
+            default:
+                return "An unexpected error occurred. Please try again.";
+        }
+    })();
+
+    const errorContainer = document.getElementById(errorContainerId);
+
+    displayErrorMessage(errorContainer, errorMessage);
+}
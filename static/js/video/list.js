@@ -117,9 +117,6 @@ function updateFilters(checkbox) {
         return;
     }
 
-    const selectedTags = Array.from(document.querySelectorAll('input[data-value]:checked'))
-        .map(checkbox => checkbox.getAttribute('data-value'));
-
     const tag = checkbox.closest('.tag');
     if (!tag) {
         console.error("Tag container not found for checkbox:", checkbox);
@@ -131,29 +128,26 @@ function updateFilters(checkbox) {
         tag.classList.remove('selected');
     }
 
-    const tagsContainer = document.querySelector('.tags-container');
-    const offset = document.getElementById('offset_liked');
-    const more_videos = document.getElementById('more-videos');
+    reloadVideos();
+}
 
-    if (selectedTags.length === 0) {
-        if (tagsContainer) {
-            tagsContainer.querySelectorAll('input[data-value]').forEach(input => {
-                input.removeAttribute('hx-vals');
-            });
-        }
-        more_videos.setAttribute('hx-vals', JSON.stringify({offset: 8, filter: ""}))
-        htmx.ajax('GET', '/video', {
-            target: '#video-grid',
-            swap: 'innerHTML'
-        });
-    } else {
-        const filterString = selectedTags.join(',');
-        more_videos.setAttribute('hx-vals', JSON.stringify({offset: 8, filter: filterString}))
-        if (tagsContainer) {
-            tagsContainer.querySelectorAll('input[data-value]').forEach(input => {
-                input.setAttribute('hx-vals', JSON.stringify({filter: filterString}));
-            });
-        }
+/**
+ * Loads the first page of the videos with the selected sort and categories.
+ * Following pages are loaded by the "Load More" button, which is updated by the server.
+ */
+function reloadVideos() {
+    const selectedTags = Array.from(document.querySelectorAll('input[data-value]:checked'))
+        .map(checkbox => checkbox.getAttribute('data-value'));
+    const sort = document.getElementById('video-sort');
+
+    const values = {filter: selectedTags.join(',')};
+    if (sort) {
+        values.sort = sort.value;
     }
-    offset.value = 8;
-}
\ No newline at end of file
+
+    htmx.ajax('GET', '/video', {
+        target: '#video-grid',
+        swap: 'innerHTML',
+        values: values,
+    });
+}
@@ -3,14 +3,126 @@ function setupVideo() {
     if (!videoElement) {
         return;
     }
-    videojs(videoElement, {
+    const player = videojs(videoElement, {
         controls: true,
-        autoplay: false,
+        autoplay: videoElement.dataset.autoplay === "true",
         preload: 'auto',
         responsive: true,
         fluid: true,
         aspectRatio: "16:9",
     });
+
+    player.ready(function () {
+        if (this.currentType() === 'application/x-mpegURL') {
+            this.qualityLevels();
+            this.hlsQualitySelector({displayCurrentQuality: true});
+        }
+    });
+
+    trackView(player, videoElement.dataset.viewUrl, parseInt(videoElement.dataset.viewThreshold));
+    trackPosition(player, videoElement.dataset.positionUrl);
+    offerResume(player, parseInt(videoElement.dataset.resumePosition));
+    playNext(player, videoElement.dataset.nextUrl);
+}
+
+/**
+ * Loads the page of the next video, once the playback ends
+ * @param player VideoJS player
+ * @param nextUrl URL of the next page, nothing is loaded if undefined
+ */
+function playNext(player, nextUrl) {
+    if (!nextUrl) {
+        return;
+    }
+    player.one("ended", function () {
+        htmx.ajax("GET", nextUrl, {target: "#content-container", swap: "innerHTML"})
+            .then(() => history.pushState({}, "", nextUrl));
+    });
+}
+
+/**
+ * Periodically saves the position of the playback to the watch history
+ * @param player VideoJS player
+ * @param positionUrl URL to save the position, tracking is disabled if undefined
+ */
+function trackPosition(player, positionUrl) {
+    if (!positionUrl) {
+        return;
+    }
+    const SAVE_INTERVAL_MS = 10000;
+    let lastSaveTime = Date.now();
+
+    const savePosition = function () {
+        lastSaveTime = Date.now();
+        const body = new URLSearchParams({position_ms: Math.floor(player.currentTime() * 1000)});
+        navigator.sendBeacon(positionUrl, body);
+    };
+
+    player.on("timeupdate", function () {
+        if (!player.paused() && Date.now() - lastSaveTime >= SAVE_INTERVAL_MS) {
+            savePosition();
+        }
+    });
+    player.on("pause", savePosition);
+    player.on("ended", savePosition);
+}
+
+/**
+ * Wires buttons of the prompt, which offers to continue from the saved position
+ * @param player VideoJS player
+ * @param resumePositionMs saved position in milliseconds
+ */
+function offerResume(player, resumePositionMs) {
+    const prompt = document.getElementById("resume-prompt");
+    if (!prompt || isNaN(resumePositionMs)) {
+        return;
+    }
+
+    const startPlayback = function (positionSeconds) {
+        prompt.hidden = true;
+        player.currentTime(positionSeconds);
+        player.play();
+    };
+    document.getElementById("resume-button")
+        .addEventListener("click", () => startPlayback(resumePositionMs / 1000));
+    document.getElementById("restart-button")
+        .addEventListener("click", () => startPlayback(0));
+    player.on("play", () => prompt.hidden = true);
+}
+
+/**
+ * Records the view, once the threshold of played seconds is reached.
+ * Shorter videos have to be played at least to the half. Skipped parts are not counted.
+ * @param player VideoJS player
+ * @param viewUrl URL to record the view, tracking is disabled if undefined
+ * @param thresholdSeconds number of seconds, which must be played
+ */
+function trackView(player, viewUrl, thresholdSeconds) {
+    if (!viewUrl || isNaN(thresholdSeconds)) {
+        return;
+    }
+    const MAX_TIME_UPDATE_GAP = 1.5;
+    let playedSeconds = 0;
+    let lastTime = player.currentTime();
+
+    const onTimeUpdate = function () {
+        const currentTime = player.currentTime();
+        const delta = currentTime - lastTime;
+        lastTime = currentTime;
+        if (delta <= 0 || delta > MAX_TIME_UPDATE_GAP) {
+            return;
+        }
+
+        playedSeconds += delta;
+        const duration = player.duration();
+        const threshold = duration > 0 ? Math.min(thresholdSeconds, duration / 2) : thresholdSeconds;
+        if (playedSeconds >= threshold) {
+            player.off("timeupdate", onTimeUpdate);
+            fetch(viewUrl, {method: "POST", credentials: "include"}).catch(console.error);
+        }
+    };
+
+    player.on("timeupdate", onTimeUpdate);
 }
 
 
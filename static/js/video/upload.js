@@ -0,0 +1,95 @@
+const UPLOAD_RETRY_COUNT = 5;
+const UPLOAD_RETRY_DELAY_MS = 2000;
+
+/**
+ * Uploads the selected video in chunks, so the upload can be resumed,
+ * when the connection drops. Preview returned by the server replaces the input.
+ * @param input file input with `chunk-size` attribute
+ */
+function uploadVideoInChunks(input) {
+    // Validators registered on the same event are evaluated first
+    setTimeout(async () => {
+        const file = input.files.item(0);
+        if (!file || !input.checkValidity()) {
+            return;
+        }
+        const target = document.getElementById(input.getAttribute("upload-target"));
+        const progress = document.getElementById("video-upload-progress");
+        input.disabled = true;
+        progress.hidden = false;
+
+        try {
+            const uploadUrl = await createUpload(file);
+            const chunkSize = Number(input.getAttribute("chunk-size"));
+            let offset = 0;
+            while (offset < file.size) {
+                offset = await sendChunkWithRetry(uploadUrl, file, offset, chunkSize);
+                progress.value = Math.floor(offset / file.size * 100);
+            }
+
+            const response = await fetch(`${uploadUrl}/finalize`, {method: "POST"});
+            if (!response.ok) {
+                throw new Error(await response.text());
+            }
+            target.innerHTML = await response.text();
+            htmx.process(target);
+            setupVideo();
+        } catch (error) {
+            input.disabled = false;
+            progress.hidden = true;
+            input.setCustomValidity("Upload of the video failed");
+            input.reportValidity();
+            input.setCustomValidity("");
+        }
+    });
+}
+
+async function createUpload(file) {
+    const response = await fetch("/temp/upload", {
+        method: "POST",
+        headers: {"Content-Type": "application/x-www-form-urlencoded"},
+        body: new URLSearchParams({file_name: file.name, upload_length: file.size}),
+    });
+    if (!response.ok) {
+        throw new Error(await response.text());
+    }
+    return response.headers.get("Location");
+}
+
+async function sendChunkWithRetry(uploadUrl, file, offset, chunkSize) {
+    for (let attempt = 0; ; attempt++) {
+        try {
+            return await sendChunk(uploadUrl, file, offset, chunkSize);
+        } catch (error) {
+            if (attempt >= UPLOAD_RETRY_COUNT) {
+                throw error;
+            }
+            await new Promise(resolve => setTimeout(resolve, UPLOAD_RETRY_DELAY_MS));
+            // Server could receive only part of the data, so the offset must be synchronized
+            offset = await getUploadOffset(uploadUrl);
+        }
+    }
+}
+
+async function sendChunk(uploadUrl, file, offset, chunkSize) {
+    const response = await fetch(uploadUrl, {
+        method: "PATCH",
+        headers: {
+            "Content-Type": "application/offset+octet-stream",
+            "Upload-Offset": offset,
+        },
+        body: file.slice(offset, offset + chunkSize),
+    });
+    if (!response.ok) {
+        throw new Error(await response.text());
+    }
+    return Number(response.headers.get("Upload-Offset"));
+}
+
+async function getUploadOffset(uploadUrl) {
+    const response = await fetch(uploadUrl, {method: "HEAD"});
+    if (!response.ok) {
+        throw new Error("Upload doesn't exist");
+    }
+    return Number(response.headers.get("Upload-Offset"));
+}
@@ -0,0 +1,167 @@
+use crate::api::video::{create_upload_req, upload_video};
+use crate::common::users::{CHARLES_ARTIST, JOHN_ARTIST, JOHN_NOT_ARTIST};
+use crate::common::utils::{create_empty_cookie, extract_id, login_as};
+use actix_http::body::{BoxBody, EitherBody};
+use actix_http::Request;
+use actix_service::Service;
+use actix_web::cookie::Cookie;
+use actix_web::dev::ServiceResponse;
+use actix_web::{test, Error};
+use erotic_hub::business::models::video::VideoVisibility;
+use erotic_hub::common::tests::setup::AsyncContext;
+use http::{Method, StatusCode};
+use test_context::test_context;
+
+/// ID of the artist profile of John Artist
+pub const JOHN_ARTIST_ID: i32 = 1;
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test01_follow_artist(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let artist_cookie = login_as(JOHN_ARTIST, &app).await;
+    let fan_cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+
+    let response = follow(JOHN_ARTIST_ID, Method::POST, artist_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = follow(JOHN_ARTIST_ID, Method::POST, create_empty_cookie(), &app).await;
+    assert_ne!(response.status(), StatusCode::OK);
+    let response = follow(999, Method::POST, fan_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = follow(JOHN_ARTIST_ID, Method::POST, fan_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert!(body.contains("1 follower"));
+    assert!(body.contains("Following"));
+
+    let mut req = create_upload_req(String::from("Followed video"), VideoVisibility::All, None);
+    let video_id =
+        extract_id(upload_video(&mut req, None, None, artist_cookie.clone(), &app).await).await;
+    let mut req = create_upload_req(String::from("Paying video"), VideoVisibility::Paying, None);
+    upload_video(&mut req, None, None, artist_cookie, &app).await;
+
+    let body = get_feed(fan_cookie.clone(), &app).await;
+    assert!(body.contains("Followed video"));
+    assert!(body.contains(&format!("/video/{video_id}/watch")));
+    assert!(
+        !body.contains("Paying video"),
+        "Feed contains video, which the user can't watch"
+    );
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/{video_id}/watch"))
+        .cookie(create_empty_cookie());
+    let response = request.send_request(&app).await;
+    let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert!(body.contains("1 follower"));
+
+    let response = follow(JOHN_ARTIST_ID, Method::DELETE, fan_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert!(body.contains("0 followers"));
+
+    let body = get_feed(fan_cookie, &app).await;
+    assert!(!body.contains("Followed video"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test02_artist_profile(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let artist_cookie = login_as(JOHN_ARTIST, &app).await;
+    let other_artist_cookie = login_as(CHARLES_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Public video"), VideoVisibility::All, None);
+    upload_video(&mut req, None, None, artist_cookie.clone(), &app).await;
+    let mut req = create_upload_req(String::from("Paying video"), VideoVisibility::Paying, None);
+    upload_video(&mut req, None, None, artist_cookie.clone(), &app).await;
+
+    let body = get_profile(JOHN_ARTIST_ID, create_empty_cookie(), &app).await;
+    assert!(body.contains("Public video"));
+    assert!(
+        !body.contains("Paying video"),
+        "Profile contains video, which the user can't watch"
+    );
+    assert!(body.contains("1 video"));
+    assert!(!body.contains("Edit description"));
+
+    let body = get_profile(JOHN_ARTIST_ID, artist_cookie.clone(), &app).await;
+    assert!(body.contains("Paying video"));
+    assert!(body.contains("2 videos"));
+    assert!(body.contains("Edit description"));
+
+    let response = update_description(JOHN_ARTIST_ID, "Hacked", other_artist_cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = update_description(JOHN_ARTIST_ID, "Hello fans", artist_cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = get_profile(JOHN_ARTIST_ID, create_empty_cookie(), &app).await;
+    assert!(body.contains("Hello fans"));
+    assert!(!body.contains("Hacked"));
+
+    let request = test::TestRequest::default()
+        .uri("/artist/999")
+        .cookie(create_empty_cookie());
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+async fn follow(
+    artist_id: i32,
+    method: Method,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/artist/{artist_id}/follow"))
+        .method(method)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_feed(
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> String {
+    let request = test::TestRequest::default()
+        .uri("/feed/items")
+        .method(Method::GET)
+        .cookie(cookie);
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
+
+pub async fn get_profile(
+    artist_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> String {
+    let request = test::TestRequest::default()
+        .uri(&format!("/artist/{artist_id}"))
+        .method(Method::GET)
+        .cookie(cookie);
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
+
+async fn update_description(
+    artist_id: i32,
+    description: &str,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/artist/{artist_id}/description"))
+        .method(Method::PATCH)
+        .set_form([("description", description)])
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
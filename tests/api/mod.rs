@@ -1,5 +1,9 @@
+mod artist;
 mod example;
+mod playlist;
 mod stream;
 mod temp_file;
+mod two_factor;
 mod user_api;
+mod user_session;
 mod video;
@@ -0,0 +1,193 @@
+use crate::api::artist::{get_profile, JOHN_ARTIST_ID};
+use crate::api::video::{create_upload_req, upload_video};
+use crate::common::users::{CHARLES_ARTIST, JOHN_ARTIST, JOHN_PAYING};
+use crate::common::utils::{create_empty_cookie, extract_id, login_as};
+use actix_http::body::{BoxBody, EitherBody};
+use actix_http::Request;
+use actix_service::Service;
+use actix_web::cookie::Cookie;
+use actix_web::dev::ServiceResponse;
+use actix_web::{test, Error};
+use erotic_hub::business::models::video::VideoVisibility;
+use erotic_hub::common::tests::setup::AsyncContext;
+use http::{Method, StatusCode};
+use test_context::test_context;
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test01_playlist_visibility(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner_cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let private_id = create_playlist("Private list", "PRIVATE", owner_cookie.clone(), &app).await;
+    let unlisted_id =
+        create_playlist("Unlisted list", "UNLISTED", owner_cookie.clone(), &app).await;
+
+    let response = get_playlist(private_id, owner_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = get_playlist(private_id, create_empty_cookie(), &app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let other_cookie = login_as(CHARLES_ARTIST, &app).await;
+    let response = get_playlist(private_id, other_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = get_playlist(unlisted_id, create_empty_cookie(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/playlist/{unlisted_id}"))
+        .method(Method::DELETE)
+        .cookie(other_cookie);
+    let response = request.send_request(&app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "Playlist was deleted by another user"
+    );
+
+    let response = create_playlist_req("", "PUBLIC", owner_cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test02_playlist_video_permissions(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner_cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Free video"), VideoVisibility::All, None);
+    let free_id =
+        extract_id(upload_video(&mut req, None, None, owner_cookie.clone(), &app).await).await;
+    let mut req = create_upload_req(String::from("Paying video"), VideoVisibility::Paying, None);
+    let paying_id =
+        extract_id(upload_video(&mut req, None, None, owner_cookie.clone(), &app).await).await;
+
+    let playlist_id = create_playlist("Public list", "PUBLIC", owner_cookie.clone(), &app).await;
+    let response = add_item(playlist_id, paying_id, owner_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = add_item(playlist_id, free_id, owner_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = add_item(playlist_id, free_id, owner_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK, "Video was added twice");
+
+    let body = get_playlist_body(playlist_id, create_empty_cookie(), &app).await;
+    assert!(body.contains("Free video"));
+    assert!(
+        !body.contains("Paying video"),
+        "Paying video is listed to anonymous user"
+    );
+    assert!(!body.contains(&format!("/video/{paying_id}")));
+
+    let paying_cookie = login_as(JOHN_PAYING, &app).await;
+    let body = get_playlist_body(playlist_id, paying_cookie, &app).await;
+    let paying_pos = body.find("Paying video").expect("Paying video is missing");
+    let free_pos = body.find("Free video").expect("Free video is missing");
+    assert!(paying_pos < free_pos);
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/playlist/{playlist_id}/items/{free_id}"))
+        .method(Method::PATCH)
+        .set_form([("position", 0)])
+        .cookie(owner_cookie.clone());
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    let paying_pos = body.find("Paying video").unwrap();
+    let free_pos = body.find("Free video").unwrap();
+    assert!(free_pos < paying_pos, "Video was not moved");
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/playlist/{playlist_id}/items/{free_id}"))
+        .method(Method::DELETE)
+        .cookie(owner_cookie.clone());
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = String::from_utf8(test::read_body(response).await.to_vec()).unwrap();
+    assert!(!body.contains("Free video"), "Video was not removed");
+    assert!(body.contains("Paying video"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test03_public_playlists_on_profile(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner_cookie = login_as(JOHN_ARTIST, &app).await;
+
+    create_playlist("Public list", "PUBLIC", owner_cookie.clone(), &app).await;
+    create_playlist("Unlisted list", "UNLISTED", owner_cookie.clone(), &app).await;
+    create_playlist("Private list", "PRIVATE", owner_cookie, &app).await;
+
+    let body = get_profile(JOHN_ARTIST_ID, create_empty_cookie(), &app).await;
+    assert!(body.contains("Public list"));
+    assert!(
+        !body.contains("Unlisted list"),
+        "Unlisted playlist is listed on the profile"
+    );
+    assert!(!body.contains("Private list"));
+}
+
+async fn create_playlist(
+    name: &str,
+    visibility: &str,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> i32 {
+    let response = create_playlist_req(name, visibility, cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    extract_id(response).await
+}
+
+async fn create_playlist_req(
+    name: &str,
+    visibility: &str,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/playlist?get_template=false")
+        .method(Method::POST)
+        .set_form([("name", name), ("visibility", visibility)])
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn add_item(
+    playlist_id: i32,
+    video_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/playlist/{playlist_id}/items"))
+        .method(Method::POST)
+        .set_form([("video_id", video_id)])
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_playlist(
+    playlist_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/playlist/{playlist_id}"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_playlist_body(
+    playlist_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> String {
+    let response = get_playlist(playlist_id, cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
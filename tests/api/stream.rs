@@ -7,7 +7,9 @@ use actix_service::Service;
 use actix_web::cookie::Cookie;
 use actix_web::dev::ServiceResponse;
 use actix_web::{test, Error};
-use erotic_hub::business::models::stream::LiveStreamStart;
+use chrono::{DateTime, Duration, Local};
+use erotic_hub::business::models::stream::{LiveStreamSchedule, LiveStreamStart};
+use erotic_hub::business::models::stream_chat::ChatTimeoutReq;
 use erotic_hub::business::models::video::VideoVisibility;
 use erotic_hub::common::tests::setup::AsyncContext;
 use http::{Method, StatusCode};
@@ -17,6 +19,9 @@ use test_context::test_context;
 
 lazy_static! {
     static ref GET_ID_REGEX: Regex = Regex::new(r#"\/stream\/(\d+)\/watch"#).unwrap();
+    static ref STREAM_KEY_REGEX: Regex =
+        Regex::new(r#"id="ingestKey" [^>]*value="(\w+)""#).unwrap();
+    static ref CHAT_MESSAGE_REGEX: Regex = Regex::new(r#"id="chat-message-(\d+)""#).unwrap();
 }
 
 #[test_context(AsyncContext)]
@@ -252,6 +257,368 @@ async fn test07_watch_stream_visible_registered(ctx: &mut AsyncContext) {
     .await;
 }
 
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test08_publish_rtmp_stream(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+    let registered = login_as(JOHN_NOT_ARTIST, &app).await;
+
+    let response = publish_stream("unknown-key", &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = get_stream_key(registered.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let stream_key = extract_stream_key(get_stream_key(cookie.clone(), &app).await).await;
+    assert_eq!(
+        stream_key,
+        extract_stream_key(get_stream_key(cookie.clone(), &app).await).await,
+        "Stream key should not change without regeneration"
+    );
+
+    let response = publish_stream(&stream_key, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::FORBIDDEN,
+        "Publishing should be denied without a waiting stream"
+    );
+
+    let response = start_rtmp_stream(cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let stream_id = extract_stream_id(response).await;
+
+    let body = get_body(watch_stream(stream_id, cookie.clone(), &app).await).await;
+    assert!(
+        body.contains(&stream_key),
+        "Owner should see the stream key"
+    );
+    let body = get_body(watch_stream(stream_id, registered, &app).await).await;
+    assert!(!body.contains(&stream_key), "Stream key was leaked");
+
+    let response = publish_stream(&stream_key, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = get_body(watch_stream(stream_id, cookie.clone(), &app).await).await;
+    assert!(body.contains("stream-player"), "Stream should be running");
+
+    let response = publish_stream(&stream_key, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::FORBIDDEN,
+        "Running stream should not be published twice"
+    );
+
+    let request = test::TestRequest::default()
+        .uri("/stream/key")
+        .method(Method::POST)
+        .cookie(cookie);
+    let new_stream_key = extract_stream_key(request.send_request(&app).await).await;
+    assert_ne!(stream_key, new_stream_key);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test09_stream_chat(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner = login_as(JOHN_ARTIST, &app).await;
+    let viewer = login_as(JOHN_NOT_ARTIST, &app).await;
+    let other_artist = login_as(CHARLES_ARTIST, &app).await;
+
+    let stream_id = extract_stream_id(start_rtmp_stream(owner.clone(), &app).await).await;
+
+    let response = send_chat_message(stream_id, "Hello", create_empty_cookie(), &app).await;
+    assert_ne!(response.status(), StatusCode::NO_CONTENT);
+    let response = send_chat_message(stream_id, "   ", viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    set_email_verified("JohnNotArtist", false, ctx).await;
+    let response = send_chat_message(stream_id, "Unverified", viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    set_email_verified("JohnNotArtist", true, ctx).await;
+    let response = send_chat_message(stream_id, "Hello everyone", viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = get_chat_events(stream_id, viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers().get("Content-Type").unwrap(),
+        "text/event-stream"
+    );
+
+    let body = get_body(get_chat(stream_id, viewer.clone(), &app).await).await;
+    assert!(body.contains("Hello everyone"));
+    assert!(!body.contains("chat-moderator"), "Viewer can't moderate");
+    let body = get_body(get_chat(stream_id, owner.clone(), &app).await).await;
+    assert!(body.contains("chat-moderator"), "Owner should moderate");
+    let message_id: i32 = CHAT_MESSAGE_REGEX.captures(&body).unwrap()[1]
+        .parse()
+        .unwrap();
+
+    for content in ["Second", "Third"] {
+        let response = send_chat_message(stream_id, content, viewer.clone(), &app).await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    }
+    let response = send_chat_message(stream_id, "Fourth", viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    let response = delete_chat_message(stream_id, message_id, other_artist.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = delete_chat_message(stream_id, message_id, owner.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = delete_chat_message(stream_id, message_id, owner.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    let body = get_body(get_chat(stream_id, viewer.clone(), &app).await).await;
+    assert!(!body.contains("Hello everyone"), "Message was not deleted");
+
+    let response = timeout_chat_user(stream_id, 2, owner.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    let response = timeout_chat_user(stream_id, 1, owner.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = timeout_chat_user(stream_id, 2, other_artist.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = send_chat_message(stream_id, "Still here", viewer.clone(), &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::FORBIDDEN,
+        "Timed out user should not send messages"
+    );
+    let response = send_chat_message(stream_id, "Hi", other_artist.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = get_chat_transcript(stream_id, owner.clone(), &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::BAD_REQUEST,
+        "Transcript should be available after the stream ends"
+    );
+
+    sqlx::query("UPDATE live_stream SET status = 'ENDED' WHERE id = $1")
+        .bind(stream_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+
+    let response = send_chat_message(stream_id, "Bye", other_artist.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = get_chat_transcript(stream_id, other_artist, &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = get_chat_transcript(stream_id, owner, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let transcript = get_body(response).await;
+    let lines: Vec<&str> = transcript.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].ends_with("JohnNotArtist: Second"));
+    assert!(lines[2].ends_with("CharlesArtist: Hi"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test10_stream_stats(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner = login_as(JOHN_ARTIST, &app).await;
+    let viewer = login_as(JOHN_NOT_ARTIST, &app).await;
+    let other_artist = login_as(CHARLES_ARTIST, &app).await;
+
+    let stream_id = extract_stream_id(start_rtmp_stream(owner.clone(), &app).await).await;
+    sqlx::query("UPDATE live_stream SET status = 'RUNNING' WHERE id = $1")
+        .bind(stream_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+    sqlx::query(
+        "INSERT INTO stream_health_sample (stream_id, resolution, bitrate_kbps, dropped_frames, qos_events, warnings)
+        VALUES ($1, '720', 2500, 3, 1, 0)",
+    )
+    .bind(stream_id)
+    .execute(&ctx.pg_pool)
+    .await
+    .unwrap();
+
+    for cookie in [viewer.clone(), other_artist.clone(), viewer.clone()] {
+        let response = authenticate_stream(stream_id, cookie, &app).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let response = get_stream_stats(stream_id, viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = get_body(response).await;
+    assert!(
+        body.contains("2 watching"),
+        "Viewers should be counted once"
+    );
+    assert!(
+        !body.contains("2500 kbps"),
+        "Health is shown only to the owner"
+    );
+
+    let body = get_body(get_stream_stats(stream_id, owner.clone(), &app).await).await;
+    assert!(body.contains("2 watching"));
+    assert!(body.contains("2500 kbps"));
+
+    sqlx::query("UPDATE live_stream SET status = 'ENDED' WHERE id = $1")
+        .bind(stream_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+
+    let body = get_body(get_stream_stats(stream_id, viewer, &app).await).await;
+    assert!(!body.contains("watching"));
+    let body = get_body(get_stream_stats(stream_id, owner, &app).await).await;
+    assert!(body.contains("Health of the stream"));
+    assert!(body.contains("2500 kbps"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test11_schedule_stream(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner = login_as(JOHN_ARTIST, &app).await;
+    let viewer = login_as(JOHN_NOT_ARTIST, &app).await;
+    let mut req = create_upload_req(String::from("Vid"), VideoVisibility::All, None);
+    let video_id = extract_id(upload_video(&mut req, None, None, owner.clone(), &app).await).await;
+
+    let in_hour = Local::now() + Duration::hours(1);
+    let response = schedule_stream(
+        owner.clone(),
+        video_id,
+        Local::now() - Duration::minutes(1),
+        &app,
+    )
+    .await;
+    assert_eq!(
+        response.status(),
+        StatusCode::BAD_REQUEST,
+        "Stream should not be scheduled to the past"
+    );
+    let response = schedule_stream(viewer.clone(), video_id, in_hour, &app).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = schedule_stream(owner.clone(), video_id, in_hour, &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let stream_id = extract_stream_id(response).await;
+
+    let body = get_body(watch_stream(stream_id, create_empty_cookie(), &app).await).await;
+    assert!(
+        body.contains("stream-countdown"),
+        "Countdown should be shown"
+    );
+    assert!(
+        !body.contains("Notify me"),
+        "Anonymous user can't be notified"
+    );
+    let body = get_body(watch_stream(stream_id, viewer.clone(), &app).await).await;
+    assert!(body.contains("Notify me"));
+
+    let response = add_reminder(stream_id, viewer.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = get_body(response).await;
+    assert!(body.contains("You will be notified"));
+    assert!(body.contains("1 viewer is waiting"));
+
+    let body = get_body(get_live_reminders(viewer.clone(), &app).await).await;
+    assert!(!body.contains("is live now"), "Stream has not started yet");
+
+    sqlx::query("UPDATE live_stream SET status = 'RUNNING' WHERE id = $1")
+        .bind(stream_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+
+    let body = get_body(get_live_reminders(viewer.clone(), &app).await).await;
+    assert!(body.contains("Vid is live now"));
+    let response = add_reminder(stream_id, viewer.clone(), &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::BAD_REQUEST,
+        "Reminder of the running stream should not be added"
+    );
+
+    let response = schedule_stream(owner.clone(), video_id, in_hour, &app).await;
+    let stream_id = extract_stream_id(response).await;
+    let response = stop_stream(stream_id, owner, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NO_CONTENT,
+        "Owner should cancel the scheduled stream"
+    );
+    let body = get_body(watch_stream(stream_id, viewer, &app).await).await;
+    assert!(body.contains("Live stream already ended"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test12_stream_directory(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner = login_as(JOHN_ARTIST, &app).await;
+    let paying = login_as(JOHN_PAYING, &app).await;
+
+    let mut req = create_upload_req(String::from("Paying show"), VideoVisibility::Paying, None);
+    let video_id = extract_id(upload_video(&mut req, None, None, owner.clone(), &app).await).await;
+    let request = test::TestRequest::default()
+        .uri("/stream/start")
+        .method(Method::POST)
+        .set_form(LiveStreamStart { video_id })
+        .cookie(owner.clone());
+    assert_eq!(
+        request.send_request(&app).await.status(),
+        StatusCode::CREATED
+    );
+
+    let mut req = create_upload_req(String::from("Upcoming show"), VideoVisibility::All, None);
+    let video_id = extract_id(upload_video(&mut req, None, None, owner.clone(), &app).await).await;
+    let response = schedule_stream(
+        owner.clone(),
+        video_id,
+        Local::now() + Duration::hours(1),
+        &app,
+    )
+    .await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let response = get_stream_directory(create_empty_cookie(), &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = get_body(response).await;
+    assert!(
+        !body.contains("Paying show"),
+        "Stream for paying members was listed to anonymous user"
+    );
+    assert!(body.contains("Upcoming show"));
+
+    let body = get_body(get_stream_directory(paying, &app).await).await;
+    assert!(body.contains("Paying show"));
+    assert!(body.contains("stream-card-live"));
+    assert!(body.contains("stream-card-visibility"));
+    assert!(body.contains("Upcoming show"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test13_stop_stream_without_pipelines(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let owner = login_as(JOHN_ARTIST, &app).await;
+    let viewer = login_as(JOHN_NOT_ARTIST, &app).await;
+    let mut req = create_upload_req(String::from("Vid"), VideoVisibility::All, None);
+    let video_id = extract_id(upload_video(&mut req, None, None, owner.clone(), &app).await).await;
+
+    let in_hour = Local::now() + Duration::hours(1);
+    let response = schedule_stream(owner.clone(), video_id, in_hour, &app).await;
+    let stream_id = extract_stream_id(response).await;
+    // Stream was started by another instance of the app, so its pipelines don't run here
+    sqlx::query("UPDATE live_stream SET status = 'RUNNING' WHERE id = $1")
+        .bind(stream_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+
+    let response = stop_stream(stream_id, owner, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NO_CONTENT,
+        "Stream without pipelines should be stopped"
+    );
+    let body = get_body(watch_stream(stream_id, viewer, &app).await).await;
+    assert!(body.contains("Live stream already ended"));
+}
+
 async fn check_get_requests(
     stream_id: i32,
     cookie: Cookie<'_>,
@@ -351,3 +718,220 @@ async fn extract_stream_id(response: ServiceResponse<EitherBody<BoxBody>>) -> i3
 
     captures.get(1).unwrap().as_str().parse().unwrap()
 }
+
+async fn start_rtmp_stream(
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let mut req = create_upload_req(String::from("Vid"), VideoVisibility::All, None);
+    let video_id = extract_id(upload_video(&mut req, None, None, cookie.clone(), &app).await).await;
+
+    let request = test::TestRequest::default()
+        .uri("/stream/live")
+        .method(Method::POST)
+        .set_form(LiveStreamStart { video_id })
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn schedule_stream(
+    cookie: Cookie<'_>,
+    video_id: i32,
+    start_time: DateTime<Local>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/stream/schedule")
+        .method(Method::POST)
+        .set_form(LiveStreamSchedule {
+            video_id,
+            start_time,
+        })
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_stream_directory(
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/stream")
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn add_reminder(
+    stream_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/reminder"))
+        .method(Method::POST)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_live_reminders(
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/stream/reminders")
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn publish_stream(
+    stream_key: &str,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/stream/publish")
+        .method(Method::POST)
+        .set_form([("call", "publish"), ("app", "ingest"), ("name", stream_key)]);
+
+    request.send_request(&app).await
+}
+
+async fn get_stream_key(
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/stream/key")
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn extract_stream_key(response: ServiceResponse<EitherBody<BoxBody>>) -> String {
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = get_body(response).await;
+    let captures = STREAM_KEY_REGEX.captures(&body).unwrap();
+
+    captures.get(1).unwrap().as_str().to_string()
+}
+
+async fn send_chat_message(
+    stream_id: i32,
+    content: &str,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/chat"))
+        .method(Method::POST)
+        .set_form([("content", content)])
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_chat(
+    stream_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/chat"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_chat_events(
+    stream_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/chat/events"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn delete_chat_message(
+    stream_id: i32,
+    message_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/chat/{message_id}"))
+        .method(Method::DELETE)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn timeout_chat_user(
+    stream_id: i32,
+    user_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/chat/timeout"))
+        .method(Method::POST)
+        .set_form(ChatTimeoutReq {
+            user_id,
+            minutes: 5,
+        })
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_chat_transcript(
+    stream_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/chat/transcript"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_stream_stats(
+    stream_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/stream/{stream_id}/stats"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_body(response: ServiceResponse<EitherBody<BoxBody>>) -> String {
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
+
+async fn set_email_verified(username: &str, verified: bool, ctx: &AsyncContext) {
+    sqlx::query(
+        "UPDATE user_table SET email_verified_at = CASE WHEN $2 THEN now() END WHERE username = $1",
+    )
+    .bind(username)
+    .bind(verified)
+    .execute(&ctx.pg_pool)
+    .await
+    .unwrap();
+}
@@ -273,6 +273,129 @@ async fn test12_upload_thumbnail_different_mimetype(ctx: &mut AsyncContext) {
     );
 }
 
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test13_thumbnail_candidates(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie_john = login_as(JOHN_ARTIST, &app).await;
+    let cookie_charles = login_as(CHARLES_ARTIST, &app).await;
+
+    let video_id = extract_id(upload_temp_video(cookie_john.clone(), None, &app).await).await;
+
+    let response = create_thumbnail_candidates(video_id, cookie_charles, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "Thumbnails were created from video of a different user"
+    );
+
+    let response = create_thumbnail_candidates(video_id, cookie_john.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let candidate_ids: Vec<i32> = test::read_body_json(response).await;
+    assert!(
+        !candidate_ids.is_empty(),
+        "No thumbnail candidate was created"
+    );
+
+    for candidate_id in candidate_ids {
+        let response = get_temp_file_res(candidate_id, cookie_john.clone(), &app).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test14_resumable_upload(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let content = std::fs::read(files::VIDEO1.get_path_to_file()).unwrap();
+    let (first_chunk, second_chunk) = content.split_at(content.len() / 2);
+
+    let response = create_upload("video.mp4", content.len(), cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let upload_id = extract_id(response).await;
+
+    let response = patch_upload(upload_id, 0, first_chunk, cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(get_upload_offset(&response), first_chunk.len());
+
+    let response = finalize_upload(upload_id, cookie.clone(), &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::CONFLICT,
+        "Incomplete upload was finalized"
+    );
+
+    // Client lost the response and sends the first chunk again
+    let response = patch_upload(upload_id, 0, first_chunk, cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let req = test::TestRequest::default()
+        .uri(&format!("/temp/upload/{upload_id}"))
+        .method(Method::HEAD)
+        .cookie(cookie.clone());
+    let response = req.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let offset = get_upload_offset(&response);
+    assert_eq!(offset, first_chunk.len());
+
+    let response = patch_upload(upload_id, offset, second_chunk, cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(get_upload_offset(&response), content.len());
+
+    let response = finalize_upload(upload_id, cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let temp_file_id = extract_id(response).await;
+
+    let response = get_temp_file_res(temp_file_id, cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = test::read_body(response).await;
+    assert_eq!(body.to_vec(), content, "Uploaded file doesn't match");
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test15_resumable_upload_wrong_mimetype(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let content = std::fs::read(files::PLACEHOLDER_PNG.get_path_to_file()).unwrap();
+    let response = create_upload("video.mp4", content.len(), cookie.clone(), &app).await;
+    let upload_id = extract_id(response).await;
+
+    let response = patch_upload(upload_id, 0, &content, cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+    let response = finalize_upload(upload_id, cookie, &app).await;
+    assert_eq!(response.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test16_resumable_upload_different_user(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie_john = login_as(JOHN_ARTIST, &app).await;
+    let cookie_charles = login_as(CHARLES_ARTIST, &app).await;
+
+    let response = create_upload("video.mp4", 4, cookie_john, &app).await;
+    let upload_id = extract_id(response).await;
+
+    let response = patch_upload(upload_id, 0, &[0, 1, 2, 3], cookie_charles.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let req = test::TestRequest::default()
+        .uri(&format!("/temp/upload/{upload_id}"))
+        .method(Method::HEAD)
+        .cookie(cookie_charles.clone());
+    let response = req.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = finalize_upload(upload_id, cookie_charles, &app).await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
 pub async fn upload_temp_video(
     cookie: Cookie<'_>,
     file: Option<TestFile>,
@@ -311,6 +434,20 @@ pub async fn upload_temp_thumbnail(
     req.send_request(&app).await
 }
 
+async fn create_thumbnail_candidates(
+    temp_video_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let req = test::TestRequest::default()
+        .uri("/temp/thumbnail/candidates?get_template=false")
+        .method(Method::POST)
+        .set_form([("temp_video_id", temp_video_id)])
+        .cookie(cookie);
+
+    req.send_request(&app).await
+}
+
 async fn get_temp_file_res(
     temp_file_id: i32,
     cookie: Cookie<'_>,
@@ -323,3 +460,62 @@ async fn get_temp_file_res(
 
     req.send_request(&app).await
 }
+
+async fn create_upload(
+    file_name: &str,
+    upload_length: usize,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let req = test::TestRequest::default()
+        .uri("/temp/upload")
+        .method(Method::POST)
+        .set_form([
+            ("file_name", file_name.to_string()),
+            ("upload_length", upload_length.to_string()),
+        ])
+        .cookie(cookie);
+
+    req.send_request(&app).await
+}
+
+async fn patch_upload(
+    upload_id: i32,
+    offset: usize,
+    chunk: &[u8],
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let req = test::TestRequest::default()
+        .uri(&format!("/temp/upload/{upload_id}"))
+        .method(Method::PATCH)
+        .insert_header(("Upload-Offset", offset.to_string()))
+        .set_payload(chunk.to_vec())
+        .cookie(cookie);
+
+    req.send_request(&app).await
+}
+
+async fn finalize_upload(
+    upload_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let req = test::TestRequest::default()
+        .uri(&format!(
+            "/temp/upload/{upload_id}/finalize?get_template=false"
+        ))
+        .method(Method::POST)
+        .cookie(cookie);
+
+    req.send_request(&app).await
+}
+
+fn get_upload_offset(response: &ServiceResponse<EitherBody<BoxBody>>) -> usize {
+    response
+        .headers()
+        .get("Upload-Offset")
+        .and_then(|offset| offset.to_str().ok())
+        .and_then(|offset| offset.parse().ok())
+        .expect("Upload-Offset header is missing")
+}
@@ -0,0 +1,289 @@
+use crate::common::users::JOHN_NOT_ARTIST;
+use crate::common::utils::login_as;
+use actix_http::body::{BoxBody, EitherBody};
+use actix_http::Request;
+use actix_service::Service;
+use actix_web::cookie::Cookie;
+use actix_web::dev::ServiceResponse;
+use actix_web::Error;
+use actix_web::{http::StatusCode, test};
+use erotic_hub::business::models::two_factor::TwoFactorCode;
+use erotic_hub::common::tests::setup::AsyncContext;
+use http::Method;
+use regex::Regex;
+use test_context::test_context;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+fn code(code: &str) -> TwoFactorCode {
+    TwoFactorCode {
+        code: String::from(code),
+    }
+}
+
+fn get_header<'a>(response: &'a ServiceResponse<EitherBody<BoxBody>>, name: &str) -> &'a str {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// Enables two-factor authentication of the logged-in user and returns its recovery codes
+async fn enable_two_factor(
+    app: &impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+    cookie: &Cookie<'_>,
+) -> Vec<String> {
+    let request = test::TestRequest::default()
+        .uri("/user/two-factor/enable")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let secret_regex = Regex::new(r#"id="two-factor-secret"[^>]*>([A-Z2-7]+)<"#).unwrap();
+    let secret = secret_regex
+        .captures(&String::from_utf8_lossy(&body))
+        .unwrap()[1]
+        .to_string();
+    let totp = TOTP::new(
+        Algorithm::SHA1,
+        6,
+        1,
+        30,
+        Secret::Encoded(secret).to_bytes().unwrap(),
+        None,
+        String::from("JohnNotArtist"),
+    )
+    .unwrap();
+
+    let request = test::TestRequest::default()
+        .uri("/user/two-factor/confirm")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .set_form(code(&totp.generate_current().unwrap()))
+        .to_request();
+    let response = test::call_service(app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = test::read_body(response).await;
+    let recovery_code_regex = Regex::new(r"<code>([a-z0-9]{5}-[a-z0-9]{5})</code>").unwrap();
+    recovery_code_regex
+        .captures_iter(&String::from_utf8_lossy(&body))
+        .map(|recovery_code| recovery_code[1].to_string())
+        .collect()
+}
+
+/// Logs in by the password, which starts the two-factor challenge
+async fn login_with_password(
+    app: &impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> Cookie<'static> {
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(JOHN_NOT_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        get_header(&response, "HX-Redirect"),
+        "/user/login/two-factor"
+    );
+
+    response
+        .response()
+        .cookies()
+        .next()
+        .expect("Missing cookie in login response")
+        .into_owned()
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test01_two_factor_settings_not_logged_in(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/two-factor")
+        .method(Method::GET)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert!(response.status().is_redirection() || response.status() == StatusCode::UNAUTHORIZED);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test02_confirm_two_factor_wrong_code(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/two-factor/enable")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::default()
+        .uri("/user/two-factor/confirm")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .set_form(code("not-a-code"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Two-factor authentication stays disabled
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(JOHN_NOT_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_header(&response, "HX-Redirect"), "");
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test03_login_with_recovery_code(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let recovery_codes = enable_two_factor(&app, &cookie).await;
+    assert_eq!(recovery_codes.len(), 10);
+
+    let pending_cookie = login_with_password(&app).await;
+
+    // The password alone doesn't log the user in
+    let request = test::TestRequest::default()
+        .uri("/user/account")
+        .method(Method::GET)
+        .cookie(pending_cookie.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_redirection() || response.status() == StatusCode::UNAUTHORIZED);
+
+    let request = test::TestRequest::default()
+        .uri("/user/login/two-factor")
+        .method(Method::POST)
+        .cookie(pending_cookie.clone())
+        .set_form(code("wrong-code"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::default()
+        .uri("/user/login/two-factor")
+        .method(Method::POST)
+        .cookie(pending_cookie)
+        .set_form(code(&recovery_codes[0]))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_header(&response, "HX-Redirect"), "/");
+    let logged_in_cookie = response.response().cookies().next().unwrap().into_owned();
+
+    let request = test::TestRequest::default()
+        .uri("/user/account")
+        .method(Method::GET)
+        .cookie(logged_in_cookie)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Recovery codes can be used only once
+    let pending_cookie = login_with_password(&app).await;
+    let request = test::TestRequest::default()
+        .uri("/user/login/two-factor")
+        .method(Method::POST)
+        .cookie(pending_cookie)
+        .set_form(code(&recovery_codes[0]))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test04_two_factor_challenge_too_many_attempts(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let recovery_codes = enable_two_factor(&app, &cookie).await;
+    let pending_cookie = login_with_password(&app).await;
+
+    for _ in 0..4 {
+        let request = test::TestRequest::default()
+            .uri("/user/login/two-factor")
+            .method(Method::POST)
+            .cookie(pending_cookie.clone())
+            .set_form(code("wrong-code"))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    let request = test::TestRequest::default()
+        .uri("/user/login/two-factor")
+        .method(Method::POST)
+        .cookie(pending_cookie.clone())
+        .set_form(code("wrong-code"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // The user has to enter the password again
+    let request = test::TestRequest::default()
+        .uri("/user/login/two-factor")
+        .method(Method::POST)
+        .cookie(pending_cookie)
+        .set_form(code(&recovery_codes[0]))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test05_two_factor_challenge_without_password(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/login/two-factor")
+        .method(Method::POST)
+        .set_form(code("123456"))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test06_disable_two_factor(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let recovery_codes = enable_two_factor(&app, &cookie).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/two-factor/disable")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .set_form(code(&recovery_codes[1]))
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(JOHN_NOT_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(get_header(&response, "HX-Redirect"), "");
+}
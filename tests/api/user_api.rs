@@ -1,10 +1,22 @@
 use crate::common::users::{JOHN_ARTIST, JOHN_NOT_ARTIST};
 use crate::common::utils::login_as;
+use actix_http::body::{BoxBody, EitherBody};
+use actix_http::Request;
 use actix_multipart_test::MultiPartFormDataBuilder;
+use actix_service::Service;
+use actix_web::cookie::Cookie;
+use actix_web::dev::ServiceResponse;
+use actix_web::Error;
 use actix_web::{http::StatusCode, test};
-use erotic_hub::business::models::user::{UserDetailUpdate, UserPasswordUpdate};
+use erotic_hub::business::models::user::{
+    PasswordReset, PasswordResetRequest, UserDetailUpdate, UserLogin, UserPasswordUpdate,
+};
 use erotic_hub::common::tests::setup::AsyncContext;
 use http::Method;
+use regex::Regex;
+use std::fs;
+use std::net::SocketAddr;
+use std::time::Duration;
 use test_context::test_context;
 
 #[test_context(AsyncContext)]
@@ -508,6 +520,32 @@ async fn test24_change_password(ctx: &mut AsyncContext) {
     let response = test::call_service(&app, request).await;
 
     assert!(response.status().is_success());
+
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(JOHN_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::BAD_REQUEST,
+        "Old password should not work anymore"
+    );
+
+    let mut new_login = JOHN_ARTIST.get_login_req();
+    new_login.password = "tatra148".to_string();
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(&new_login)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::OK,
+        "New password should work"
+    );
 }
 
 #[test_context(AsyncContext)]
@@ -748,3 +786,365 @@ async fn test37_logout_not_logged_in(ctx: &mut AsyncContext) {
 
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 }
+
+async fn register_new_user(
+    app: &impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> Cookie<'static> {
+    let (header, body) = MultiPartFormDataBuilder::new()
+        .with_text("username", "new_user123")
+        .with_text("email", "new_user123@example.com")
+        .with_text("password", "password12345")
+        .with_text("password2", "password12345")
+        .build();
+
+    let request = test::TestRequest::default()
+        .uri("/user/register")
+        .method(Method::POST)
+        .insert_header(header)
+        .set_payload(body)
+        .to_request();
+
+    let response = test::call_service(app, request).await;
+    assert!(response.status().is_success());
+
+    response
+        .response()
+        .cookies()
+        .next()
+        .expect("Missing cookie in register response")
+        .into_owned()
+}
+
+/// Waits for the email to the address, since some emails are sent in the background,
+/// and returns the token from the link in it
+async fn read_mail_token(ctx: &AsyncContext, to: &str, link_path: &str) -> String {
+    let token_regex = Regex::new(&format!(
+        r"{}\?token=([0-9a-f.]+)",
+        regex::escape(link_path)
+    ))
+    .unwrap();
+
+    for _ in 0..50 {
+        let token = fs::read_dir(ctx.get_mail_dir())
+            .into_iter()
+            .flatten()
+            .map(|entry| fs::read_to_string(entry.unwrap().path()).unwrap())
+            .filter(|mail| mail.contains(to))
+            // Long lines of the body are encoded as quoted-printable
+            .map(|mail| mail.replace("=\r\n", "").replace("=3D", "="))
+            .find_map(|mail| {
+                token_regex
+                    .captures(&mail)
+                    .map(|token| token[1].to_string())
+            });
+        if let Some(token) = token {
+            return token;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    panic!("Email to {to} should be sent");
+}
+
+async fn read_verification_token(ctx: &AsyncContext) -> String {
+    read_mail_token(ctx, "new_user123@example.com", "/user/verify").await
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test38_verify_email(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = register_new_user(&app).await;
+    let token = read_verification_token(ctx).await;
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/user/verify?token={token}"))
+        .method(Method::GET)
+        .cookie(cookie.clone())
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let request = test::TestRequest::default()
+        .uri("/user/verify/resend")
+        .method(Method::POST)
+        .cookie(cookie)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test39_verify_email_invalid_token(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    register_new_user(&app).await;
+    let token = read_verification_token(ctx).await;
+    let tampered_token = format!("{}0", &token[..token.len() - 1]);
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/user/verify?token={tampered_token}"))
+        .method(Method::GET)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test40_resend_verification_too_soon(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = register_new_user(&app).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/verify/resend")
+        .method(Method::POST)
+        .cookie(cookie)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test41_resend_verification_not_logged_in(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/verify/resend")
+        .method(Method::POST)
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test42_forgot_password_unknown_email(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/forgot-password")
+        .method(Method::POST)
+        .set_form(PasswordResetRequest {
+            email: String::from("unknown@email.cz"),
+        })
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    // The response must not tell, that the email is not registered
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = test::read_body(response).await;
+    assert!(String::from_utf8_lossy(&body).contains("If an account with this email exists"));
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test43_reset_password(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/forgot-password")
+        .method(Method::POST)
+        .set_form(PasswordResetRequest {
+            email: String::from("john2@email.cz"),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let token = read_mail_token(ctx, "john2@email.cz", "/user/reset-password").await;
+    let password_reset = PasswordReset {
+        token,
+        password: String::from("new_password123"),
+        password2: String::from("new_password123"),
+    };
+
+    let request = test::TestRequest::default()
+        .uri("/user/reset-password")
+        .method(Method::POST)
+        .set_form(password_reset.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Existing sessions are logged out
+    let request = test::TestRequest::default()
+        .uri("/user/account")
+        .method(Method::GET)
+        .cookie(cookie)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_redirection() || response.status() == StatusCode::UNAUTHORIZED);
+
+    // The link can be used only once
+    let request = test::TestRequest::default()
+        .uri("/user/reset-password")
+        .method(Method::POST)
+        .set_form(password_reset)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(UserLogin {
+            username: String::from("JohnNotArtist"),
+            password: String::from("new_password123"),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test44_reset_password_invalid_token(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/reset-password")
+        .method(Method::POST)
+        .set_form(PasswordReset {
+            token: String::from("invalid"),
+            password: String::from("new_password123"),
+            password2: String::from("new_password123"),
+        })
+        .to_request();
+
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test45_login_unknown_user_same_error(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let mut bodies = Vec::new();
+
+    for username in ["UnknownUser", "JohnArtist"] {
+        let request = test::TestRequest::default()
+            .uri("/user/login")
+            .method(Method::POST)
+            .set_form(UserLogin {
+                username: String::from(username),
+                password: String::from("wrong_password"),
+            })
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        bodies.push(test::read_body(response).await);
+    }
+
+    assert_eq!(bodies[0], bodies[1]);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test46_login_locked_after_failures(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    for _ in 0..5 {
+        let request = test::TestRequest::default()
+            .uri("/user/login")
+            .method(Method::POST)
+            .set_form(UserLogin {
+                username: String::from("JohnArtist"),
+                password: String::from("wrong_password"),
+            })
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    // Even the correct password is refused, until the lock expires
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(JOHN_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // Other accounts are not affected
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(JOHN_NOT_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert!(response.status().is_success());
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test47_login_ip_lock_ignores_forged_header(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let client: SocketAddr = "203.0.113.5:40000".parse().unwrap();
+    for attempt in 0..20 {
+        let response = login_from(
+            client,
+            &format!("198.51.100.{attempt}"),
+            wrong_login(attempt),
+            &app,
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    let response = login_from(client, "198.51.100.200", JOHN_ARTIST.get_login_req(), &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::TOO_MANY_REQUESTS,
+        "Forged X-Real-IP header bypassed the lock of the IP address"
+    );
+
+    // The trusted proxy passes the address of its client, so its other clients are not locked
+    let proxy: SocketAddr = "10.0.0.1:40000".parse().unwrap();
+    for attempt in 0..20 {
+        let response = login_from(proxy, "198.51.100.7", wrong_login(attempt), &app).await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+    let response = login_from(proxy, "198.51.100.7", JOHN_ARTIST.get_login_req(), &app).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let response = login_from(proxy, "198.51.100.8", JOHN_ARTIST.get_login_req(), &app).await;
+    assert!(response.status().is_success());
+}
+
+fn wrong_login(attempt: u32) -> UserLogin {
+    UserLogin {
+        username: format!("nobody{attempt}"),
+        password: String::from("wrong_password"),
+    }
+}
+
+async fn login_from(
+    peer_addr: SocketAddr,
+    real_ip: &str,
+    user_login: UserLogin,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .peer_addr(peer_addr)
+        .insert_header(("X-Real-IP", real_ip))
+        .set_form(user_login)
+        .to_request();
+
+    test::call_service(&app, request).await
+}
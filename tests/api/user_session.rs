@@ -0,0 +1,217 @@
+use crate::common::users::JOHN_NOT_ARTIST;
+use crate::common::utils::login_as;
+use actix_http::body::{BoxBody, EitherBody};
+use actix_http::Request;
+use actix_service::Service;
+use actix_web::cookie::Cookie;
+use actix_web::dev::ServiceResponse;
+use actix_web::Error;
+use actix_web::{http::StatusCode, test};
+use erotic_hub::business::models::user::{UserLogin, UserPasswordUpdate};
+use erotic_hub::common::tests::setup::AsyncContext;
+use http::Method;
+use regex::Regex;
+use test_context::test_context;
+
+const FIREFOX_USER_AGENT: &str =
+    "Mozilla/5.0 (X11; Linux x86_64; rv:131.0) Gecko/20100101 Firefox/131.0";
+
+async fn login_with_user_agent(
+    app: &impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+    user_agent: &str,
+) -> Cookie<'static> {
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .insert_header(("User-Agent", user_agent))
+        .set_form(JOHN_NOT_ARTIST.get_login_req())
+        .to_request();
+    let response = test::call_service(app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    response
+        .response()
+        .cookies()
+        .next()
+        .expect("Missing cookie in login response")
+        .into_owned()
+}
+
+async fn is_logged_in(
+    app: &impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+    cookie: &Cookie<'_>,
+) -> bool {
+    let request = test::TestRequest::default()
+        .uri("/user/account")
+        .method(Method::GET)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(app, request).await;
+
+    response.status() == StatusCode::OK
+}
+
+/// Returns the page with the sessions and the public IDs of the sessions, the current one first
+async fn list_sessions(
+    app: &impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+    cookie: &Cookie<'_>,
+) -> (String, Vec<String>) {
+    let request = test::TestRequest::default()
+        .uri("/user/sessions")
+        .method(Method::GET)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = String::from_utf8_lossy(&test::read_body(response).await).to_string();
+    let session_id_regex = Regex::new(r"/user/sessions/([0-9a-f-]{36})/logout").unwrap();
+    let session_ids = session_id_regex
+        .captures_iter(&body)
+        .map(|session_id| session_id[1].to_string())
+        .collect();
+
+    (body, session_ids)
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test01_list_sessions(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_with_user_agent(&app, FIREFOX_USER_AGENT).await;
+    login_as(JOHN_NOT_ARTIST, &app).await;
+
+    let (body, session_ids) = list_sessions(&app, &cookie).await;
+
+    assert_eq!(session_ids.len(), 2);
+    assert!(body.contains("Firefox on Linux"));
+    assert!(body.contains("Unknown device"));
+    assert_eq!(body.matches("This device").count(), 1);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test02_list_sessions_not_logged_in(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/sessions")
+        .method(Method::GET)
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert!(response.status().is_redirection() || response.status() == StatusCode::UNAUTHORIZED);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test03_log_out_other_device(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_with_user_agent(&app, FIREFOX_USER_AGENT).await;
+    let other_cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let (_, session_ids) = list_sessions(&app, &cookie).await;
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/user/sessions/{}/logout", session_ids[1]))
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(is_logged_in(&app, &cookie).await);
+    assert!(!is_logged_in(&app, &other_cookie).await);
+    let (_, session_ids) = list_sessions(&app, &cookie).await;
+    assert_eq!(session_ids.len(), 1);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test04_log_out_this_device(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_with_user_agent(&app, FIREFOX_USER_AGENT).await;
+    let (_, session_ids) = list_sessions(&app, &cookie).await;
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/user/sessions/{}/logout", session_ids[0]))
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("HX-Redirect").unwrap(), "/");
+
+    assert!(!is_logged_in(&app, &cookie).await);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test05_log_out_unknown_session(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/sessions/00000000-0000-0000-0000-000000000000/logout")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert!(is_logged_in(&app, &cookie).await);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test06_log_out_everywhere(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let other_cookie = login_with_user_agent(&app, FIREFOX_USER_AGENT).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/sessions/logout-all")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get("HX-Redirect").unwrap(), "/");
+
+    assert!(!is_logged_in(&app, &cookie).await);
+    assert!(!is_logged_in(&app, &other_cookie).await);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test07_change_password_logs_out_other_devices(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let other_cookie = login_with_user_agent(&app, FIREFOX_USER_AGENT).await;
+
+    let request = test::TestRequest::default()
+        .uri("/user/change-password")
+        .method(Method::POST)
+        .cookie(cookie.clone())
+        .set_form(UserPasswordUpdate {
+            old_password: "12345678".to_string(),
+            password: "tatra148".to_string(),
+            password2: "tatra148".to_string(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    assert!(is_logged_in(&app, &cookie).await);
+    assert!(!is_logged_in(&app, &other_cookie).await);
+
+    let request = test::TestRequest::default()
+        .uri("/user/login")
+        .method(Method::POST)
+        .set_form(UserLogin {
+            username: "JohnNotArtist".to_string(),
+            password: "tatra148".to_string(),
+        })
+        .to_request();
+    let response = test::call_service(&app, request).await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
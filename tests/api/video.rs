@@ -14,6 +14,7 @@ use erotic_hub::api::templates::video::show::template::VideoNotAllowed;
 use erotic_hub::business::models::video::{VideoEditReq, VideoUploadReq, VideoVisibility};
 use erotic_hub::common::tests::setup::AsyncContext;
 use http::{Method, StatusCode};
+use std::time::Duration;
 use test_context::test_context;
 
 #[test_context(AsyncContext)]
@@ -391,6 +392,286 @@ async fn test13_delete_different_user(ctx: &mut AsyncContext) {
     )
 }
 
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test14_hls_playlist_visibility(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let artist_cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Video"), VideoVisibility::Paying, None);
+
+    let video_id =
+        extract_id(upload_video(&mut req, None, None, artist_cookie.clone(), &app).await).await;
+    // Transcoding runs in the background thread
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let owner_resp = get_hls_file(video_id, "master.m3u8", artist_cookie.clone(), &app).await;
+    assert_eq!(owner_resp.status(), StatusCode::OK);
+
+    let paying_cookie = login_as(JOHN_PAYING, &app).await;
+    let paying_resp = get_hls_file(video_id, "master.m3u8", paying_cookie, &app).await;
+    assert_eq!(paying_resp.status(), StatusCode::OK);
+
+    let anonymous_resp = get_hls_file(video_id, "master.m3u8", create_empty_cookie(), &app).await;
+    assert_eq!(anonymous_resp.status(), StatusCode::NOT_FOUND);
+
+    let traversal_resp = get_hls_file(
+        video_id,
+        "..%2F..%2Fvideos%2Fvideo.mp4",
+        artist_cookie.clone(),
+        &app,
+    )
+    .await;
+    assert_eq!(
+        traversal_resp.status(),
+        StatusCode::NOT_FOUND,
+        "Files outside of the HLS directory must not be served"
+    );
+
+    let missing_resp = get_hls_file(video_id, "360/playlist.m3u8", artist_cookie, &app).await;
+    assert_eq!(missing_resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test15_upload_without_thumbnail(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Video"), VideoVisibility::All, None);
+    req.temp_video_id = extract_id(upload_temp_video(cookie.clone(), None, &app).await).await;
+
+    let request = test::TestRequest::default()
+        .uri("/video?get_template=false")
+        .method(Method::POST)
+        .set_form(&req)
+        .cookie(cookie.clone());
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let video_id = extract_id(response).await;
+    let thumbnail_resp = get_thumbnail_file(video_id, cookie, &app).await;
+    assert_eq!(
+        thumbnail_resp.status(),
+        StatusCode::OK,
+        "Thumbnail was not created from the video"
+    );
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test16_video_metadata(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Video"), VideoVisibility::All, None);
+    let response = upload_video(&mut req, None, None, cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let video_id = extract_id(response).await;
+    let watch_res = watch_video(video_id, cookie, &app).await;
+    assert_eq!(watch_res.status(), StatusCode::OK);
+
+    let body = String::from_utf8(test::read_body(watch_res).await.to_vec()).unwrap();
+    assert!(body.contains("1:02"), "Duration of the video is not shown");
+    assert!(
+        body.contains("1920x1080"),
+        "Resolution of the video is not shown"
+    );
+    assert!(body.contains("H.264"), "Video codec is not shown");
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test17_search_visibility(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(
+        String::from("Sunset on the beach"),
+        VideoVisibility::All,
+        None,
+    );
+    let response = upload_video(&mut req, None, None, cookie.clone(), &app).await;
+    let public_video_id = extract_id(response).await;
+
+    let mut req = create_upload_req(
+        String::from("Private beach"),
+        VideoVisibility::Paying,
+        Some(String::from("Walk on the <b>sandy</b> beach")),
+    );
+    let response = upload_video(&mut req, None, None, cookie.clone(), &app).await;
+    let paying_video_id = extract_id(response).await;
+
+    let body = search_videos("beach", create_empty_cookie(), &app).await;
+    assert!(body.contains(&format!("/video/{public_video_id}/watch")));
+    assert!(body.contains("Sunset on the <mark>beach</mark>"));
+    assert!(
+        !body.contains(&format!("/video/{paying_video_id}/watch")),
+        "Paying video is visible to anonymous user"
+    );
+
+    let paying_cookie = login_as(JOHN_PAYING, &app).await;
+    let body = search_videos("beach", paying_cookie, &app).await;
+    assert!(body.contains(&format!("/video/{paying_video_id}/watch")));
+    assert!(
+        body.contains("&lt;b&gt;sandy&lt;/b&gt;"),
+        "Description in the snippet is not escaped"
+    );
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test18_views(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Video"), VideoVisibility::All, None);
+    let response = upload_video(&mut req, None, None, cookie.clone(), &app).await;
+    let video_id = extract_id(response).await;
+    let mut req = create_upload_req(String::from("Video"), VideoVisibility::Paying, None);
+    let response = upload_video(&mut req, None, None, cookie.clone(), &app).await;
+    let paying_video_id = extract_id(response).await;
+
+    let watch_res = watch_video(video_id, cookie.clone(), &app).await;
+    let body = String::from_utf8(test::read_body(watch_res).await.to_vec()).unwrap();
+    assert!(body.contains(&format!("data-view-url=\"/video/{video_id}/view\"")));
+    assert!(body.contains(&format!("/video/{video_id}/stats")));
+
+    let view_res = record_view(video_id, create_empty_cookie(), &app).await;
+    assert_eq!(view_res.status(), StatusCode::NO_CONTENT);
+    let view_res = record_view(paying_video_id, create_empty_cookie(), &app).await;
+    assert_eq!(
+        view_res.status(),
+        StatusCode::NOT_FOUND,
+        "View of the paying video was recorded for anonymous user"
+    );
+
+    let stats_res = get_view_stats(video_id, cookie, &app).await;
+    assert_eq!(stats_res.status(), StatusCode::OK);
+    let charles_cookie = login_as(CHARLES_ARTIST, &app).await;
+    let stats_res = get_view_stats(video_id, charles_cookie, &app).await;
+    assert_eq!(
+        stats_res.status(),
+        StatusCode::NOT_FOUND,
+        "Statistics are visible to another artist"
+    );
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test19_watch_history(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let cookie = login_as(JOHN_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Watched video"), VideoVisibility::All, None);
+    let response = upload_video(&mut req, None, None, cookie.clone(), &app).await;
+    let video_id = extract_id(response).await;
+
+    let position_res = save_position(video_id, -1, cookie.clone(), &app).await;
+    assert_eq!(position_res.status(), StatusCode::BAD_REQUEST);
+    let position_res = save_position(video_id, 30000, create_empty_cookie(), &app).await;
+    assert_ne!(
+        position_res.status(),
+        StatusCode::NO_CONTENT,
+        "Position was saved for anonymous user"
+    );
+    let position_res = save_position(video_id, 30000, cookie.clone(), &app).await;
+    assert_eq!(position_res.status(), StatusCode::NO_CONTENT);
+
+    let watch_res = watch_video(video_id, cookie.clone(), &app).await;
+    let body = String::from_utf8(test::read_body(watch_res).await.to_vec()).unwrap();
+    assert!(
+        body.contains("Continue watching from 0:30?"),
+        "Player doesn't offer to resume the playback"
+    );
+
+    let body = get_history(cookie.clone(), &app).await;
+    assert!(body.contains("Watched video"));
+    assert!(body.contains("stopped at 0:30"));
+
+    let request = test::TestRequest::default()
+        .uri(&format!("/user/history/{video_id}"))
+        .method(Method::DELETE)
+        .cookie(cookie.clone());
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = get_history(cookie.clone(), &app).await;
+    assert!(
+        !body.contains("Watched video"),
+        "Video was not removed from the history"
+    );
+    let watch_res = watch_video(video_id, cookie.clone(), &app).await;
+    let body = String::from_utf8(test::read_body(watch_res).await.to_vec()).unwrap();
+    assert!(!body.contains("Continue watching"));
+
+    let registered_cookie = login_as(JOHN_NOT_ARTIST, &app).await;
+    let position_res = save_position(video_id, 30000, registered_cookie.clone(), &app).await;
+    assert_eq!(position_res.status(), StatusCode::NO_CONTENT);
+    let body = get_history(registered_cookie.clone(), &app).await;
+    assert!(body.contains("Watched video"));
+
+    sqlx::query("UPDATE video SET visibility = 'PAYING' WHERE id = $1")
+        .bind(video_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+    let body = get_history(registered_cookie, &app).await;
+    assert!(
+        !body.contains("Watched video"),
+        "History shows a video, which the user can't watch"
+    );
+}
+
+#[test_context(AsyncContext)]
+#[actix_web::test]
+async fn test20_publish_draft(ctx: &mut AsyncContext) {
+    let app = ctx.create_app().await;
+    let artist_cookie = login_as(JOHN_ARTIST, &app).await;
+    let artist2_cookie = login_as(CHARLES_ARTIST, &app).await;
+
+    let mut req = create_upload_req(String::from("Recording"), VideoVisibility::All, None);
+    let video_id =
+        extract_id(upload_video(&mut req, None, None, artist_cookie.clone(), &app).await).await;
+    // Recordings of the live streams are saved as drafts
+    sqlx::query("UPDATE video SET draft = TRUE WHERE id = $1")
+        .bind(video_id)
+        .execute(&ctx.pg_pool)
+        .await
+        .unwrap();
+
+    let watch_res = watch_video(video_id, artist_cookie.clone(), &app).await;
+    let body = String::from_utf8(test::read_body(watch_res).await.to_vec()).unwrap();
+    assert!(body.contains("This video is a draft"));
+    assert_get_requests(video_id, StatusCode::NOT_FOUND, create_empty_cookie(), &app).await;
+    assert_get_requests(
+        video_id,
+        StatusCode::NOT_FOUND,
+        artist2_cookie.clone(),
+        &app,
+    )
+    .await;
+
+    let response = publish_video(video_id, artist2_cookie, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "Draft can be published only by its author"
+    );
+
+    let response = publish_video(video_id, artist_cookie.clone(), &app).await;
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_get_requests(video_id, StatusCode::OK, create_empty_cookie(), &app).await;
+
+    let response = publish_video(video_id, artist_cookie, &app).await;
+    assert_eq!(
+        response.status(),
+        StatusCode::NOT_FOUND,
+        "Published video is not a draft anymore"
+    );
+}
+
 async fn assert_get_requests(
     video_id: i32,
     expected_status: StatusCode,
@@ -427,7 +708,7 @@ pub fn create_upload_req(
         name,
         video_visibility,
         category_ids: None,
-        temp_thumbnail_id: -1,
+        temp_thumbnail_id: None,
         temp_video_id: -1,
         description,
     }
@@ -442,8 +723,10 @@ pub async fn upload_video(
 ) -> ServiceResponse<EitherBody<BoxBody>> {
     upload_video_form.temp_video_id = video_temp_id
         .unwrap_or(extract_id(upload_temp_video(cookie.clone(), None, &app).await).await);
-    upload_video_form.temp_thumbnail_id = thumbnail_temp_id
-        .unwrap_or(extract_id(upload_temp_thumbnail(cookie.clone(), None, &app).await).await);
+    upload_video_form.temp_thumbnail_id = Some(
+        thumbnail_temp_id
+            .unwrap_or(extract_id(upload_temp_thumbnail(cookie.clone(), None, &app).await).await),
+    );
 
     let request = test::TestRequest::default()
         .uri("/video?get_template=false")
@@ -498,6 +781,19 @@ async fn delete_video(
     request.send_request(&app).await
 }
 
+async fn publish_video(
+    video_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/{video_id}/publish"))
+        .method(Method::POST)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
 async fn watch_video(
     video_id: i32,
     cookie: Cookie<'_>,
@@ -524,6 +820,20 @@ async fn get_video_file(
     request.send_request(&app).await
 }
 
+async fn get_hls_file(
+    video_id: i32,
+    file: &str,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/{video_id}/hls/{file}"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
 async fn get_thumbnail_file(
     video_id: i32,
     cookie: Cookie<'_>,
@@ -536,3 +846,74 @@ async fn get_thumbnail_file(
 
     request.send_request(&app).await
 }
+
+async fn record_view(
+    video_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/{video_id}/view"))
+        .method(Method::POST)
+        .peer_addr("127.0.0.1:8000".parse().unwrap())
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_view_stats(
+    video_id: i32,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/{video_id}/stats"))
+        .method(Method::GET)
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn save_position(
+    video_id: i32,
+    position_ms: i64,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> ServiceResponse<EitherBody<BoxBody>> {
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/{video_id}/position"))
+        .method(Method::POST)
+        .set_form([("position_ms", position_ms)])
+        .cookie(cookie);
+
+    request.send_request(&app).await
+}
+
+async fn get_history(
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> String {
+    let request = test::TestRequest::default()
+        .uri("/user/history/items")
+        .method(Method::GET)
+        .cookie(cookie);
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
+
+async fn search_videos(
+    query: &str,
+    cookie: Cookie<'_>,
+    app: impl Service<Request, Response = ServiceResponse<EitherBody<BoxBody>>, Error = Error>,
+) -> String {
+    let request = test::TestRequest::default()
+        .uri(&format!("/video/search/results?q={query}"))
+        .method(Method::GET)
+        .cookie(cookie);
+    let response = request.send_request(&app).await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    String::from_utf8(test::read_body(response).await.to_vec()).unwrap()
+}
@@ -30,6 +30,9 @@ VALUES (1, 1, '1111222233334444', '2099-12-31', '123');
 UPDATE user_table SET artist_id = 1 WHERE id = 1;
 UPDATE user_table SET artist_id = 2 WHERE id = 3;
 
+-- Verify emails of all users
+UPDATE user_table SET email_verified_at = now();
+
 -- SET PAYING MEMBER REFERENCE FOR USERS
 UPDATE user_table SET paying_member_id = 1 WHERE id = 4;
 